@@ -0,0 +1,275 @@
+//! Banc d'essai de la chaîne d'analyse: `cargo bench -p feminizer-voice-core`.
+//!
+//! Rejoue des enregistrements de synthèse à travers `FrequencyProcessor`, puis
+//! chronomètre chaque étape séparément et compte ses allocations, pour repérer
+//! une régression avant une version.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use feminizer_voice_core::{
+    AnalysisConfig, FrequencyProcessor, PreFilter, PreFilterConfig, VadConfig,
+    VoiceActivityDetector, WindowFunction, estimate_formants, spectral_flatness,
+};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+const SAMPLE_RATE: f32 = 44100.0;
+/// Taille des blocs livrés par un pilote audio typique.
+const CALLBACK_BLOCK: usize = 512;
+/// Meilleur de plusieurs passages, pour écarter les interruptions du système.
+const REPEATS: usize = 5;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+struct Measure {
+    best: Duration,
+    allocations: usize,
+    bytes: usize,
+}
+
+/// Meilleur temps sur `REPEATS` passages; les allocations sont celles d'un passage.
+fn measure(mut run: impl FnMut()) -> Measure {
+    let mut best = Duration::MAX;
+    let mut allocations = 0;
+    let mut bytes = 0;
+    for _ in 0..REPEATS {
+        let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+        let bytes_before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let start = Instant::now();
+        run();
+        best = best.min(start.elapsed());
+        allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+        bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes_before;
+    }
+    Measure {
+        best,
+        allocations,
+        bytes,
+    }
+}
+
+// Générateur congruentiel: bruit reproductible sans dépendance
+struct Noise(u32);
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (self.0 >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+    }
+}
+
+/// Voyelle de synthèse: série harmonique décroissante suivant une
+/// trajectoire de f0, avec un léger souffle.
+fn voice(pitch: impl Fn(f32) -> f32, seconds: f32, seed: u32) -> Vec<f32> {
+    let mut noise = Noise(seed);
+    let mut phase = 0.0_f32;
+    (0..(seconds * SAMPLE_RATE) as usize)
+        .map(|i| {
+            let f0 = pitch(i as f32 / SAMPLE_RATE);
+            phase = (phase + f0 / SAMPLE_RATE).fract();
+            let tone: f32 = (1..=12)
+                .map(|k| (2.0 * std::f32::consts::PI * phase * k as f32).sin() / k as f32)
+                .sum();
+            0.15 * tone + 0.005 * noise.next()
+        })
+        .collect()
+}
+
+/// Enregistrements de référence, générés à l'identique à chaque exécution.
+fn fixtures() -> Vec<(&'static str, Vec<f32>)> {
+    let mut noise = Noise(0x5eed);
+    let background: Vec<f32> = (0..(5.0 * SAMPLE_RATE) as usize)
+        .map(|_| 0.01 * noise.next())
+        .collect();
+
+    let held = voice(
+        |t| 220.0 * (1.0 + 0.01 * (2.0 * std::f32::consts::PI * 5.5 * t).sin()),
+        5.0,
+        1,
+    );
+    let siren = voice(|t| 150.0 * 2.0_f32.powf(t / 2.5 * 1.4), 5.0, 2);
+    let mut speech = voice(
+        |t| 190.0 + 40.0 * (2.0 * std::f32::consts::PI * 0.7 * t).sin(),
+        5.0,
+        3,
+    );
+    // Syllabes de 180 ms séparées de 120 ms de silence
+    for (i, sample) in speech.iter_mut().enumerate() {
+        if (i as f32 / SAMPLE_RATE * 1000.0) as usize % 300 >= 180 {
+            *sample *= 0.02;
+        }
+    }
+
+    vec![
+        ("Voyelle tenue 220 Hz", held),
+        ("Sirène 150→400 Hz", siren),
+        ("Parole hachée", speech),
+        ("Bruit de fond", background),
+    ]
+}
+
+fn print_header(title: &str) {
+    println!("\n{}", title);
+    println!(
+        "{:<26} {:>12} {:>12} {:>10} {:>12}",
+        "", "temps", "par trame", "allocs", "octets"
+    );
+}
+
+fn print_row(name: &str, measure: &Measure, frames: usize) {
+    let frames = frames.max(1);
+    println!(
+        "{:<26} {:>9.2} ms {:>9.1} µs {:>10} {:>12}",
+        name,
+        measure.best.as_secs_f64() * 1000.0,
+        measure.best.as_secs_f64() * 1e6 / frames as f64,
+        measure.allocations,
+        measure.bytes,
+    );
+}
+
+fn bench_pipeline(name: &str, samples: &[f32], config: AnalysisConfig) {
+    let measure = measure(|| {
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        for block in samples.chunks(CALLBACK_BLOCK) {
+            black_box(processor.process_samples(block));
+        }
+    });
+    // Trames analysées, y compris celles qu'un même bloc ne renvoie pas
+    let config = config.sanitized();
+    let frames = samples.len().saturating_sub(config.window_size) / config.hop_size + 1;
+    let audio_secs = samples.len() as f64 / SAMPLE_RATE as f64;
+    print_row(name, &measure, frames);
+    println!(
+        "{:<26} {:>12.0}× temps réel, {} trames",
+        "",
+        audio_secs / measure.best.as_secs_f64(),
+        frames
+    );
+}
+
+/// Étapes de `FrequencyProcessor` rejouées une à une sur les mêmes fenêtres.
+fn bench_stages(samples: &[f32], config: AnalysisConfig) {
+    let window_size = config.window_size;
+    let fft_size = config.fft_size();
+    let windows: Vec<&[f32]> = samples.chunks_exact(window_size).collect();
+    let frames = windows.len();
+
+    let prefilter = measure(|| {
+        let mut filter = PreFilter::new(SAMPLE_RATE, PreFilterConfig::default());
+        for &sample in samples {
+            black_box(filter.process(sample));
+        }
+    });
+    print_row("Préfiltre", &prefilter, frames);
+
+    let coefficients = config.window.coefficients(window_size);
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(fft_size);
+    let mut spectra = Vec::with_capacity(frames);
+    let transform = measure(|| {
+        spectra.clear();
+        for window in &windows {
+            let mut buffer: Vec<Complex<f32>> = window
+                .iter()
+                .zip(&coefficients)
+                .map(|(&sample, &w)| Complex::new(sample * w, 0.0))
+                .collect();
+            buffer.resize(fft_size, Complex::new(0.0, 0.0));
+            fft.process(&mut buffer);
+            spectra.push(
+                buffer[..fft_size / 2]
+                    .iter()
+                    .map(|c| c.norm())
+                    .collect::<Vec<f32>>(),
+            );
+        }
+    });
+    print_row("Fenêtrage + FFT", &transform, frames);
+
+    let flatness_bins = (4000.0 * fft_size as f32 / SAMPLE_RATE) as usize;
+    let mut flatness = Vec::with_capacity(frames);
+    let planarity = measure(|| {
+        flatness.clear();
+        for spectrum in &spectra {
+            let power: Vec<f32> = spectrum[..flatness_bins].iter().map(|m| m * m).collect();
+            flatness.push(spectral_flatness(&power));
+        }
+    });
+    print_row("Planéité spectrale", &planarity, frames);
+
+    let vad = measure(|| {
+        let mut detector = VoiceActivityDetector::new(VadConfig::default());
+        for (window, &flatness) in windows.iter().zip(&flatness) {
+            let rms = (window.iter().map(|x| x * x).sum::<f32>() / window.len() as f32).sqrt();
+            black_box(detector.process(rms, flatness));
+        }
+    });
+    print_row("Détection de voix", &vad, frames);
+
+    let formants = measure(|| {
+        for window in &windows {
+            black_box(estimate_formants(window, SAMPLE_RATE));
+        }
+    });
+    print_row("Formants (LPC)", &formants, frames);
+}
+
+fn main() {
+    // `cargo bench` passe `--bench`; les autres arguments filtrent les enregistrements
+    let filters: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .collect();
+
+    let configs = [
+        ("Réglages par défaut", AnalysisConfig::default()),
+        (
+            "Fenêtre 2048, recouvrement ×4, bourrage ×2",
+            AnalysisConfig {
+                window_size: 2048,
+                hop_size: 512,
+                zero_padding: 2,
+                window: WindowFunction::Blackman,
+                ..AnalysisConfig::default()
+            },
+        ),
+    ];
+
+    for (name, samples) in fixtures() {
+        if !filters.is_empty() && !filters.iter().any(|f| name.contains(f.as_str())) {
+            continue;
+        }
+        for (config_name, config) in configs {
+            print_header(&format!("{} — {}", name, config_name));
+            bench_pipeline("Chaîne complète", &samples, config);
+            bench_stages(&samples, config);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/// Alignement temporel dynamique (DTW) de deux séquences de descripteurs.
+///
+/// Renvoie le chemin optimal sous forme de paires d'indices `(i, j)`, de
+/// `(0, 0)` à `(a.len() - 1, b.len() - 1)`, chaque indice étant monotone
+/// croissant. Le coût local est l'écart absolu. Vide si une séquence l'est.
+pub fn dtw_path(a: &[f32], b: &[f32]) -> Vec<(usize, usize)> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let (n, m) = (a.len(), b.len());
+    let mut cost = vec![f32::INFINITY; n * m];
+    let at = |i: usize, j: usize| i * m + j;
+
+    for i in 0..n {
+        for j in 0..m {
+            let local = (a[i] - b[j]).abs();
+            let best = if i == 0 && j == 0 {
+                0.0
+            } else {
+                let diagonal = if i > 0 && j > 0 { cost[at(i - 1, j - 1)] } else { f32::INFINITY };
+                let up = if i > 0 { cost[at(i - 1, j)] } else { f32::INFINITY };
+                let left = if j > 0 { cost[at(i, j - 1)] } else { f32::INFINITY };
+                diagonal.min(up).min(left)
+            };
+            cost[at(i, j)] = local + best;
+        }
+    }
+
+    // Remontée depuis la fin, en préférant la diagonale à égalité
+    let (mut i, mut j) = (n - 1, m - 1);
+    let mut path = vec![(i, j)];
+    while i > 0 || j > 0 {
+        if i == 0 {
+            j -= 1;
+        } else if j == 0 {
+            i -= 1;
+        } else {
+            let diagonal = cost[at(i - 1, j - 1)];
+            let up = cost[at(i - 1, j)];
+            let left = cost[at(i, j - 1)];
+            if diagonal <= up && diagonal <= left {
+                i -= 1;
+                j -= 1;
+            } else if up <= left {
+                i -= 1;
+            } else {
+                j -= 1;
+            }
+        }
+        path.push((i, j));
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_sequences_align_on_the_diagonal() {
+        let a = [0.0, 1.0, 2.0, 1.0, 0.0];
+        let path = dtw_path(&a, &a);
+        assert_eq!(path, (0..a.len()).map(|i| (i, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stretched_sequence_maps_back_to_its_source() {
+        let a = [0.0, 5.0, 0.0, 3.0, 0.0];
+        let b = [0.0, 0.0, 5.0, 5.0, 0.0, 0.0, 3.0, 0.0];
+        let path = dtw_path(&a, &b);
+
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(a.len() - 1, b.len() - 1)));
+        for &(i, j) in &path {
+            assert_eq!(a[i], b[j], "({}, {})", i, j);
+        }
+        assert!(path.windows(2).all(|w| w[1].0 >= w[0].0 && w[1].1 >= w[0].1));
+    }
+
+    #[test]
+    fn empty_sequence_has_no_path() {
+        assert!(dtw_path(&[], &[1.0]).is_empty());
+    }
+}
@@ -0,0 +1,953 @@
+use rustfft::{Fft, FftPlanner, num_complex::Complex};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::denoise::{DenoiseConfig, SpectralGate};
+use crate::filter::{PreFilter, PreFilterConfig};
+use crate::formants::FormantEstimator;
+use crate::loudness::{LoudnessConfig, LoudnessMeter, to_lufs};
+use crate::vad::{VadConfig, VoiceActivityDetector, spectral_flatness};
+use crate::weight::h1_h2_db;
+
+/// Résultat de l'analyse d'un bloc d'échantillons.
+pub struct FrequencyData {
+    /// Pic du spectre entre les bornes de [`AnalysisConfig`], 0 si le signal
+    /// est trop faible.
+    pub dominant_frequency: f32,
+    /// Fiabilité de `dominant_frequency` entre 0 et 1: émergence du pic
+    /// au-dessus du niveau médian de la bande de recherche.
+    pub confidence: f32,
+    /// Niveau RMS intégré sur la fenêtre de [`LoudnessConfig`], et non sur
+    /// le seul bloc: il ne dépend ni de la taille des tampons ni de celle
+    /// de la fenêtre d'analyse.
+    pub amplitude: f32,
+    /// Même niveau en LUFS (en dB sans pondération K).
+    pub loudness_lufs: f32,
+    /// Magnitudes normalisées (max = 1) des `fft_size / 2` premières raies,
+    /// vide quand le spectre est suspendu. Rendre ce vecteur avec
+    /// [`FrequencyProcessor::recycle_spectrum`] évite une allocation à la
+    /// trame suivante.
+    pub spectrum: Vec<f32>,
+    pub is_voiced: bool,
+    pub spectral_flatness: f32,
+    /// Centroïde spectral en Hz ("brillance").
+    pub spectral_centroid: f32,
+    /// Formants (F1, F2) en Hz, estimés uniquement sur les blocs voisés.
+    pub formants: Option<(f32, f32)>,
+    /// Poids vocal, voir [`h1_h2_db`](crate::h1_h2_db): uniquement sur les
+    /// blocs voisés.
+    pub h1_h2_db: Option<f32>,
+    /// Pic le plus fort entre 500 et 3500 Hz, en Hz: approximation grossière
+    /// de la résonance du conduit vocal, bien moins coûteuse que les
+    /// formants. Uniquement sur les blocs voisés.
+    pub resonance_hz: Option<f32>,
+    /// Méthode qui a produit `dominant_frequency` et `confidence`: le produit
+    /// harmonique cède la place au pic spectral sur un son sans harmoniques.
+    pub detector: PitchDetector,
+    pub sample_rate: f32,
+    /// Durée de signal écoulée depuis la trame précédente renvoyée par
+    /// [`FrequencyProcessor::process_samples`].
+    pub frame_duration: f32,
+    pub captured_at: Instant,
+}
+
+/// Fonction de pondération appliquée à chaque fenêtre avant la FFT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WindowFunction {
+    #[default]
+    Hann,
+    Hamming,
+    Blackman,
+    Rectangular,
+}
+
+impl WindowFunction {
+    pub const ALL: [WindowFunction; 4] = [
+        WindowFunction::Hann,
+        WindowFunction::Hamming,
+        WindowFunction::Blackman,
+        WindowFunction::Rectangular,
+    ];
+
+    pub fn coefficients(self, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let x = 2.0 * std::f32::consts::PI * i as f32 / (len.max(2) - 1) as f32;
+                match self {
+                    WindowFunction::Hann => 0.5 * (1.0 - x.cos()),
+                    WindowFunction::Hamming => 0.54 - 0.46 * x.cos(),
+                    WindowFunction::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                    WindowFunction::Rectangular => 1.0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Méthode de détection de la fondamentale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PitchDetector {
+    /// Raie la plus forte de la plage de recherche. Sur une voix grave dont
+    /// la deuxième harmonique domine, c'est elle qui est rapportée.
+    #[default]
+    SpectralPeak,
+    /// Produit spectral harmonique: chaque raie candidate est combinée à ses
+    /// multiples, la fondamentale commune aux harmoniques l'emporte même
+    /// quand elle est faible.
+    HarmonicProduct,
+}
+
+impl PitchDetector {
+    pub const ALL: [PitchDetector; 2] =
+        [PitchDetector::SpectralPeak, PitchDetector::HarmonicProduct];
+}
+
+/// Bande où chercher le pic de résonance.
+const RESONANCE_MIN_HZ: f32 = 500.0;
+const RESONANCE_MAX_HZ: f32 = 3500.0;
+
+/// Harmoniques combinées par le produit spectral, fondamentale comprise.
+const HPS_HARMONICS: usize = 4;
+/// Niveau minimal de la fondamentale retenue par le produit spectral, par
+/// rapport au pic du spectre: en dessous, il n'y a pas d'harmoniques à
+/// regrouper (son pur, sifflement) et le pic spectral reprend la main.
+const HPS_MIN_FUNDAMENTAL_RATIO: f32 = 0.05;
+
+/// Paramètres de découpage et de transformée.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AnalysisConfig {
+    /// Nombre d'échantillons analysés à chaque trame.
+    pub window_size: usize,
+    /// Échantillons entre deux trames; inférieur à `window_size` pour un
+    /// recouvrement, supérieur pour sauter des échantillons et calculer moins.
+    pub hop_size: usize,
+    /// Facteur de bourrage de zéros: la FFT fait `window_size * zero_padding` points.
+    pub zero_padding: usize,
+    pub window: WindowFunction,
+    pub prefilter: PreFilterConfig,
+    pub denoise: DenoiseConfig,
+    pub loudness: LoudnessConfig,
+    /// Plancher de la recherche de hauteur, en Hz.
+    pub min_frequency_hz: f32,
+    /// Plafond de la recherche de hauteur, en Hz: jusqu'à 800 Hz et plus pour
+    /// les sirènes en voix de tête.
+    pub max_frequency_hz: f32,
+    /// Taux d'analyse plafond, en Hz: un périphérique plus rapide est
+    /// rééchantillonné avant l'analyse (voir [`Resampler`](crate::Resampler)),
+    /// pour une résolution et un coût indépendants du matériel. `None` garde
+    /// le taux du périphérique.
+    pub resample_hz: Option<f32>,
+    pub detector: PitchDetector,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 1024,
+            hop_size: 1024,
+            zero_padding: 1,
+            window: WindowFunction::Hann,
+            prefilter: PreFilterConfig::default(),
+            denoise: DenoiseConfig::default(),
+            loudness: LoudnessConfig::default(),
+            min_frequency_hz: 50.0,
+            max_frequency_hz: 450.0,
+            resample_hz: Some(48000.0),
+            detector: PitchDetector::SpectralPeak,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// Le plafond reste toujours au-dessus du plancher.
+    pub const FLOOR_RANGE_HZ: std::ops::RangeInclusive<f32> = 50.0..=200.0;
+    pub const CEILING_RANGE_HZ: std::ops::RangeInclusive<f32> = 250.0..=1200.0;
+    /// Taux d'analyse proposés; en dessous de 16 kHz les formants sortent du spectre.
+    pub const RESAMPLE_RATES_HZ: [f32; 3] = [16000.0, 24000.0, 48000.0];
+    /// Pas maximal, en fenêtres: au-delà les trames ne se suivent plus assez
+    /// pour suivre une phrase.
+    pub const MAX_HOP_WINDOWS: usize = 4;
+
+    /// Ramène chaque paramètre dans un intervalle exploitable.
+    pub fn sanitized(self) -> Self {
+        let window_size = self.window_size.clamp(64, 16384);
+        Self {
+            window_size,
+            hop_size: self.hop_size.clamp(1, window_size * Self::MAX_HOP_WINDOWS),
+            zero_padding: self.zero_padding.clamp(1, 8),
+            window: self.window,
+            prefilter: PreFilterConfig {
+                high_pass_hz: self.prefilter.high_pass_hz.clamp(0.0, 150.0),
+                ..self.prefilter
+            },
+            denoise: DenoiseConfig {
+                strength: self.denoise.strength.clamp(
+                    *DenoiseConfig::STRENGTH_RANGE.start(),
+                    *DenoiseConfig::STRENGTH_RANGE.end(),
+                ),
+                ..self.denoise
+            },
+            loudness: LoudnessConfig {
+                window_ms: self.loudness.window_ms.clamp(
+                    *LoudnessConfig::WINDOW_RANGE_MS.start(),
+                    *LoudnessConfig::WINDOW_RANGE_MS.end(),
+                ),
+                ..self.loudness
+            },
+            min_frequency_hz: self
+                .min_frequency_hz
+                .clamp(*Self::FLOOR_RANGE_HZ.start(), *Self::FLOOR_RANGE_HZ.end()),
+            max_frequency_hz: self
+                .max_frequency_hz
+                .clamp(*Self::CEILING_RANGE_HZ.start(), *Self::CEILING_RANGE_HZ.end()),
+            resample_hz: self.resample_hz.map(|hz| hz.clamp(16000.0, 192000.0)),
+            detector: self.detector,
+        }
+    }
+
+    pub fn search_range(&self) -> std::ops::RangeInclusive<f32> {
+        self.min_frequency_hz..=self.max_frequency_hz
+    }
+
+    pub fn fft_size(&self) -> usize {
+        self.window_size * self.zero_padding
+    }
+}
+
+/// Spectres rendus gardés en réserve pour les trames suivantes.
+const MAX_SPARE_SPECTRA: usize = 4;
+
+/// Analyseur incrémental: accumule des échantillons mono et produit une
+/// [`FrequencyData`] tous les `hop_size` échantillons, sur les
+/// `window_size` derniers.
+///
+/// Tous les tampons de travail sont alloués à la construction: une fois
+/// lancé, l'analyseur n'alloue plus que le spectre renvoyé, et plus rien si
+/// l'appelant le rend.
+pub struct FrequencyProcessor {
+    sample_rate: f32,
+    config: AnalysisConfig,
+    buffer: VecDeque<f32>,
+    window: Vec<f32>,
+    prefilter: PreFilter,
+    loudness: LoudnessMeter,
+    /// Présent seulement si la réduction de bruit est activée.
+    denoise: Option<SpectralGate>,
+    fft: Arc<dyn Fft<f32>>,
+    fft_buffer: Vec<Complex<f32>>,
+    fft_scratch: Vec<Complex<f32>>,
+    magnitudes: Vec<f32>,
+    /// Tri de la médiane et spectre de puissance, tour à tour.
+    scratch: Vec<f32>,
+    /// Produit spectral harmonique de la plage de recherche.
+    harmonic_product: Vec<f32>,
+    formant_estimator: FormantEstimator,
+    spare_spectra: Vec<Vec<f32>>,
+    since_analysis: usize,
+    since_result: usize,
+    vad: VoiceActivityDetector,
+    /// Verdict de la trame précédente: le profil de bruit n'apprend que sur
+    /// les silences.
+    last_voiced: bool,
+    formants_enabled: bool,
+    spectrum_enabled: bool,
+}
+
+impl FrequencyProcessor {
+    pub fn new(sample_rate: f32, config: AnalysisConfig, vad_config: VadConfig) -> Self {
+        let config = config.sanitized();
+        let fft_size = config.fft_size();
+        let fft = FftPlanner::new().plan_fft_forward(fft_size);
+        let scratch_len = fft.get_inplace_scratch_len();
+
+        Self {
+            sample_rate,
+            config,
+            buffer: VecDeque::with_capacity(config.window_size + 1),
+            window: config.window.coefficients(config.window_size),
+            prefilter: PreFilter::new(sample_rate, config.prefilter),
+            loudness: LoudnessMeter::new(sample_rate, config.loudness),
+            denoise: config
+                .denoise
+                .enabled
+                .then(|| SpectralGate::new(config.denoise, config.hop_size as f32 / sample_rate)),
+            fft,
+            fft_buffer: vec![Complex::new(0.0, 0.0); fft_size],
+            fft_scratch: vec![Complex::new(0.0, 0.0); scratch_len],
+            magnitudes: Vec::with_capacity(fft_size / 2),
+            scratch: Vec::with_capacity(fft_size / 2),
+            harmonic_product: Vec::with_capacity(fft_size / 2),
+            formant_estimator: FormantEstimator::default(),
+            spare_spectra: Vec::with_capacity(MAX_SPARE_SPECTRA),
+            since_analysis: 0,
+            since_result: 0,
+            vad: VoiceActivityDetector::new(vad_config),
+            last_voiced: false,
+            formants_enabled: true,
+            spectrum_enabled: true,
+        }
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    pub fn config(&self) -> AnalysisConfig {
+        self.config
+    }
+
+    pub fn set_vad_config(&mut self, config: VadConfig) {
+        self.vad.set_config(config);
+    }
+
+    /// Reprend le spectre d'une trame déjà exploitée pour y écrire une
+    /// trame suivante.
+    pub fn recycle_spectrum(&mut self, spectrum: Vec<f32>) {
+        if self.spare_spectra.len() < MAX_SPARE_SPECTRA {
+            self.spare_spectra.push(spectrum);
+        }
+    }
+
+    /// Active ou suspend l'estimation des formants, l'étape la plus coûteuse.
+    pub fn set_formants_enabled(&mut self, enabled: bool) {
+        self.formants_enabled = enabled;
+    }
+
+    /// Active ou suspend la copie normalisée du spectre, qui ne sert qu'à
+    /// l'affichage: la détection de hauteur n'en dépend pas.
+    pub fn set_spectrum_enabled(&mut self, enabled: bool) {
+        self.spectrum_enabled = enabled;
+    }
+
+    /// Ajoute des échantillons mono dans `[-1, 1]`. Renvoie l'analyse de la
+    /// dernière trame terminée, ou `None` si aucune ne s'est terminée.
+    pub fn process_samples(&mut self, samples: &[f32]) -> Option<FrequencyData> {
+        let mut result = None;
+        let mut covered = 0;
+        for &sample in samples {
+            let filtered = self.prefilter.process(sample);
+            self.loudness.push(filtered);
+            self.buffer.push_back(filtered);
+            if self.buffer.len() > self.config.window_size {
+                self.buffer.pop_front();
+            }
+            self.since_analysis += 1;
+            self.since_result += 1;
+
+            if self.buffer.len() == self.config.window_size
+                && self.since_analysis >= self.config.hop_size
+            {
+                self.since_analysis = 0;
+                let mut data = self.analyze_frequency();
+                data.frame_duration = self.since_result as f32 / self.sample_rate;
+                covered = self.since_result;
+                if let Some(skipped) = result.replace(data) {
+                    self.recycle_spectrum(skipped.spectrum);
+                }
+            }
+        }
+        // Les trames intermédiaires d'un même appel ne sont pas renvoyées:
+        // leur durée est comptée dans celle de la dernière
+        self.since_result -= covered;
+        result
+    }
+
+    fn analyze_frequency(&mut self) -> FrequencyData {
+        let fft_size = self.config.fft_size();
+        let samples = self.buffer.make_contiguous();
+
+        let windowed = samples
+            .iter()
+            .zip(self.window.iter())
+            .map(|(&sample, &window_val)| Complex::new(sample * window_val, 0.0))
+            .chain(std::iter::repeat(Complex::new(0.0, 0.0)));
+        for (slot, value) in self.fft_buffer.iter_mut().zip(windowed) {
+            *slot = value;
+        }
+        self.fft
+            .process_with_scratch(&mut self.fft_buffer, &mut self.fft_scratch);
+
+        let spectrum = &mut self.magnitudes;
+        spectrum.clear();
+        spectrum.extend(self.fft_buffer[..fft_size / 2].iter().map(|c| c.norm()));
+        let kept_energy = match &mut self.denoise {
+            Some(gate) => gate.process(spectrum, !self.last_voiced),
+            None => 1.0,
+        };
+        let spectrum = &self.magnitudes;
+
+        let mut normalized_spectrum = self.spare_spectra.pop().unwrap_or_default();
+        normalized_spectrum.clear();
+        if self.spectrum_enabled {
+            let max_val = spectrum.iter().copied().fold(0.0_f32, f32::max);
+            if max_val > 0.0 {
+                normalized_spectrum.extend(spectrum.iter().map(|x| x / max_val));
+            } else {
+                normalized_spectrum.resize(spectrum.len(), 0.0);
+            }
+        }
+
+        let min_bin = (self.config.min_frequency_hz * fft_size as f32 / self.sample_rate) as usize;
+        let max_bin = (self.config.max_frequency_hz * fft_size as f32 / self.sample_rate) as usize;
+        let max_bin = max_bin.min(spectrum.len() - 1);
+
+        let mut max_magnitude = 0.0f32;
+        let mut dominant_bin = 0;
+
+        for i in min_bin..=max_bin {
+            if spectrum[i] > max_magnitude {
+                max_magnitude = spectrum[i];
+                dominant_bin = i;
+            }
+        }
+
+        let dominant_frequency = if dominant_bin > 0 && dominant_bin < spectrum.len() - 1 {
+            let y1 = spectrum[dominant_bin - 1];
+            let y2 = spectrum[dominant_bin];
+            let y3 = spectrum[dominant_bin + 1];
+
+            let a = (y1 - 2.0 * y2 + y3) / 2.0;
+            let b = (y3 - y1) / 2.0;
+
+            let x_offset = if a != 0.0 { -b / (2.0 * a) } else { 0.0 };
+            let bin_frequency = dominant_bin as f32 * self.sample_rate / fft_size as f32;
+            let frequency_resolution = self.sample_rate / fft_size as f32;
+
+            bin_frequency + x_offset * frequency_resolution
+        } else {
+            dominant_bin as f32 * self.sample_rate / fft_size as f32
+        };
+
+        let mut confidence = peak_confidence(
+            &spectrum[min_bin..=max_bin],
+            max_magnitude,
+            &mut self.scratch,
+        );
+
+        let mut detector = PitchDetector::SpectralPeak;
+        let mut dominant_frequency = dominant_frequency;
+        if self.config.detector == PitchDetector::HarmonicProduct {
+            let product = &mut self.harmonic_product;
+            harmonic_product(spectrum, min_bin..=max_bin, product);
+            let (offset, peak) = interpolated_peak(product);
+            let bin = min_bin + offset.round() as usize;
+            let fundamental = spectrum[bin.saturating_sub(1)..=(bin + 1).min(max_bin)]
+                .iter()
+                .copied()
+                .fold(0.0_f32, f32::max);
+            if fundamental >= HPS_MIN_FUNDAMENTAL_RATIO * max_magnitude {
+                detector = PitchDetector::HarmonicProduct;
+                confidence = peak_confidence(product, peak, &mut self.scratch);
+                dominant_frequency = (min_bin as f32 + offset) * self.sample_rate / fft_size as f32;
+            }
+        }
+
+        // Niveau du signal débruité (Parseval), pour que la détection
+        // d'activité ne se déclenche pas sur le bruit retiré
+        let mean_square = self.loudness.mean_square() * kept_energy;
+        let amplitude = mean_square.sqrt();
+
+        let flatness_max_bin =
+            ((4000.0 * fft_size as f32 / self.sample_rate) as usize).min(spectrum.len());
+        self.scratch.clear();
+        self.scratch
+            .extend(spectrum[min_bin..flatness_max_bin].iter().map(|m| m * m));
+        let flatness = spectral_flatness(&self.scratch);
+
+        let centroid_max_bin =
+            ((5000.0 * fft_size as f32 / self.sample_rate) as usize).min(spectrum.len());
+        let centroid = spectral_centroid(
+            &spectrum[min_bin..centroid_max_bin],
+            min_bin,
+            self.sample_rate / fft_size as f32,
+        );
+
+        let is_voiced = self.vad.process(amplitude, flatness) && max_magnitude > 0.001;
+        self.last_voiced = is_voiced;
+        let h1_h2 = if is_voiced {
+            h1_h2_db(spectrum, dominant_frequency, self.sample_rate / fft_size as f32)
+        } else {
+            None
+        };
+        let resonance = if is_voiced {
+            resonance_peak(spectrum, self.sample_rate / fft_size as f32)
+        } else {
+            None
+        };
+        let formants = if is_voiced && self.formants_enabled {
+            self.formant_estimator.estimate(samples, self.sample_rate)
+        } else {
+            None
+        };
+
+        FrequencyData {
+            dominant_frequency: if max_magnitude > 0.001 {
+                dominant_frequency
+            } else {
+                0.0
+            },
+            confidence,
+            amplitude,
+            loudness_lufs: to_lufs(mean_square),
+            spectrum: normalized_spectrum,
+            is_voiced,
+            spectral_flatness: flatness,
+            spectral_centroid: centroid,
+            formants,
+            h1_h2_db: h1_h2,
+            resonance_hz: resonance,
+            detector,
+            sample_rate: self.sample_rate,
+            frame_duration: 0.0,
+            captured_at: Instant::now(),
+        }
+    }
+}
+
+/// Moyenne géométrique de chaque raie de `bins` et de ses multiples, jusqu'à
+/// [`HPS_HARMONICS`]: garde l'échelle des magnitudes, donc les seuils de
+/// [`peak_confidence`]. Chaque multiple prend le maximum de son voisinage, la
+/// raie exacte pouvant tomber entre deux.
+fn harmonic_product(
+    spectrum: &[f32],
+    bins: std::ops::RangeInclusive<usize>,
+    product: &mut Vec<f32>,
+) {
+    product.clear();
+    product.extend(bins.map(|bin| {
+        let (mut log_sum, mut count) = (0.0_f32, 0);
+        for harmonic in 1..=HPS_HARMONICS {
+            let center = bin * harmonic;
+            let reach = harmonic / 2;
+            let Some(around) = spectrum.get(center.saturating_sub(reach)..=center + reach) else {
+                break;
+            };
+            let magnitude = around.iter().copied().fold(0.0_f32, f32::max);
+            log_sum += magnitude.max(1e-12).ln();
+            count += 1;
+        }
+        if count == 0 {
+            0.0
+        } else {
+            (log_sum / count as f32).exp()
+        }
+    }));
+}
+
+/// Indice du maximum de `curve`, affiné par interpolation parabolique, et sa valeur.
+fn interpolated_peak(curve: &[f32]) -> (f32, f32) {
+    let Some((index, &peak)) = curve.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)) else {
+        return (0.0, 0.0);
+    };
+    if index == 0 || index + 1 >= curve.len() {
+        return (index as f32, peak);
+    }
+    let (left, right) = (curve[index - 1], curve[index + 1]);
+    let curvature = left - 2.0 * peak + right;
+    let shift = if curvature < 0.0 {
+        (0.5 * (left - right) / curvature).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    (index as f32 + shift, peak)
+}
+
+/// Émergence du pic au-dessus de la médiane de la bande: 0 en deçà de
+/// `PROMINENCE_FLOOR_DB` (bruit), 1 au-delà de `PROMINENCE_FULL_DB`.
+fn peak_confidence(band: &[f32], peak: f32, sorted: &mut Vec<f32>) -> f32 {
+    const PROMINENCE_FLOOR_DB: f32 = 12.0;
+    const PROMINENCE_FULL_DB: f32 = 30.0;
+
+    if band.is_empty() || peak <= 0.0 {
+        return 0.0;
+    }
+    sorted.clear();
+    sorted.extend_from_slice(band);
+    let middle = sorted.len() / 2;
+    let median = *sorted.select_nth_unstable_by(middle, f32::total_cmp).1;
+    if median <= 0.0 {
+        return 1.0;
+    }
+    let prominence_db = 20.0 * (peak / median).log10();
+    ((prominence_db - PROMINENCE_FLOOR_DB) / (PROMINENCE_FULL_DB - PROMINENCE_FLOOR_DB))
+        .clamp(0.0, 1.0)
+}
+
+/// Fréquence du pic le plus fort de la bande de résonance, affinée par
+/// interpolation parabolique.
+fn resonance_peak(magnitudes: &[f32], bin_width: f32) -> Option<f32> {
+    let low = (RESONANCE_MIN_HZ / bin_width).ceil() as usize;
+    let high = ((RESONANCE_MAX_HZ / bin_width) as usize + 1).min(magnitudes.len());
+    let band = magnitudes.get(low..high)?;
+    let (offset, peak) = interpolated_peak(band);
+    (peak > 0.0).then_some((low as f32 + offset) * bin_width)
+}
+
+fn spectral_centroid(magnitudes: &[f32], first_bin: usize, bin_width: f32) -> f32 {
+    let total: f32 = magnitudes.iter().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let weighted: f32 = magnitudes
+        .iter()
+        .enumerate()
+        .map(|(i, &m)| (first_bin + i) as f32 * bin_width * m)
+        .sum();
+    weighted / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    fn sine(frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / SAMPLE_RATE).sin()
+            })
+            .collect()
+    }
+
+    // Générateur congruentiel: bruit reproductible sans dépendance
+    fn white_noise(amplitude: f32, len: usize) -> Vec<f32> {
+        let mut state = 0x1234_5678_u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn processor() -> FrequencyProcessor {
+        FrequencyProcessor::new(SAMPLE_RATE, AnalysisConfig::default(), VadConfig::default())
+    }
+
+    #[test]
+    fn denoising_quiets_steady_noise_but_keeps_the_voice() {
+        let config = AnalysisConfig {
+            denoise: DenoiseConfig {
+                enabled: true,
+                ..DenoiseConfig::default()
+            },
+            ..AnalysisConfig::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = processor
+            .process_samples(&white_noise(0.05, 44100))
+            .unwrap();
+        assert!(data.amplitude < 0.01, "bruit résiduel à {}", data.amplitude);
+
+        let voice: Vec<f32> = sine(220.0, 0.3, 4096)
+            .iter()
+            .zip(white_noise(0.05, 4096))
+            .map(|(tone, noise)| tone + noise)
+            .collect();
+        let data = processor.process_samples(&voice).unwrap();
+        assert!(data.is_voiced);
+        assert!((data.dominant_frequency - 220.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn partial_block_yields_nothing() {
+        assert!(processor().process_samples(&sine(220.0, 0.5, 1000)).is_none());
+    }
+
+    #[test]
+    fn detects_pitch_of_pure_tones() {
+        for frequency in [110.0, 196.0, 220.0, 330.0] {
+            let data = processor().process_samples(&sine(frequency, 0.5, 4096)).unwrap();
+            assert!(data.is_voiced, "{} Hz non voisé", frequency);
+            assert!(
+                (data.dominant_frequency - frequency).abs() < 10.0,
+                "{} Hz détecté à {}",
+                frequency,
+                data.dominant_frequency
+            );
+        }
+    }
+
+    #[test]
+    fn hop_size_controls_frame_rate() {
+        let config = AnalysisConfig {
+            hop_size: 256,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let frames = sine(220.0, 0.5, 4096)
+            .chunks(256)
+            .filter_map(|chunk| processor.process_samples(chunk))
+            .count();
+        assert_eq!(frames, (4096 - 1024) / 256 + 1);
+    }
+
+    #[test]
+    fn frame_duration_covers_skipped_frames() {
+        let config = AnalysisConfig {
+            hop_size: 256,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let first = processor.process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!((first.frame_duration - 4096.0 / SAMPLE_RATE).abs() < 1e-6);
+
+        let next = processor.process_samples(&sine(220.0, 0.5, 256)).unwrap();
+        assert!((next.frame_duration - 256.0 / SAMPLE_RATE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn recycled_spectrum_is_written_again() {
+        let mut processor = processor();
+        let first = processor.process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        let buffer = first.spectrum.as_ptr();
+        processor.recycle_spectrum(first.spectrum);
+
+        let next = processor.process_samples(&sine(220.0, 0.5, 1024)).unwrap();
+        assert_eq!(next.spectrum.as_ptr(), buffer);
+        assert_eq!(next.spectrum.len(), 512);
+        assert!((next.dominant_frequency - 220.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn zero_padding_refines_low_pitches() {
+        let config = AnalysisConfig {
+            window_size: 2048,
+            hop_size: 2048,
+            zero_padding: 4,
+            window: WindowFunction::Blackman,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = processor.process_samples(&sine(103.0, 0.5, 4096)).unwrap();
+        assert_eq!(data.spectrum.len(), 4096);
+        assert!((data.dominant_frequency - 103.0).abs() < 2.0, "{}", data.dominant_frequency);
+    }
+
+    #[test]
+    fn sanitized_config_bounds_hop() {
+        let config = AnalysisConfig {
+            window_size: 512,
+            hop_size: 4096,
+            zero_padding: 0,
+            ..Default::default()
+        }
+        .sanitized();
+        assert_eq!(config.hop_size, 512 * AnalysisConfig::MAX_HOP_WINDOWS);
+        assert_eq!(config.zero_padding, 1);
+    }
+
+    #[test]
+    fn hop_longer_than_window_skips_samples() {
+        let config = AnalysisConfig {
+            hop_size: 2048,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let frames: Vec<_> = sine(220.0, 0.5, 8192)
+            .chunks(256)
+            .filter_map(|chunk| processor.process_samples(chunk))
+            .collect();
+        assert_eq!(frames.len(), 4);
+        let last = frames.last().unwrap();
+        assert!((last.frame_duration - 2048.0 / SAMPLE_RATE).abs() < 1e-6);
+        assert!((last.dominant_frequency - 220.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn ceiling_bounds_pitch_search() {
+        let siren = sine(700.0, 0.5, 4096);
+        let data = processor().process_samples(&siren).unwrap();
+        assert!(data.dominant_frequency <= 450.0);
+
+        let config = AnalysisConfig {
+            max_frequency_hz: 800.0,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = processor.process_samples(&siren).unwrap();
+        assert!((data.dominant_frequency - 700.0).abs() < 10.0, "{}", data.dominant_frequency);
+    }
+
+    #[test]
+    fn sanitized_config_keeps_bounds_apart() {
+        let config = AnalysisConfig {
+            min_frequency_hz: 180.0,
+            max_frequency_hz: 100.0,
+            ..Default::default()
+        }
+        .sanitized();
+        assert_eq!(config.min_frequency_hz, 180.0);
+        assert_eq!(config.max_frequency_hz, 250.0);
+    }
+
+    #[test]
+    fn formants_can_be_suspended() {
+        let mut processor = processor();
+        processor.set_formants_enabled(false);
+        let data = processor.process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!(data.is_voiced);
+        assert!(data.formants.is_none());
+    }
+
+    #[test]
+    fn spectrum_can_be_suspended() {
+        let mut processor = processor();
+        processor.set_spectrum_enabled(false);
+        let data = processor.process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!(data.spectrum.is_empty());
+        assert!((data.dominant_frequency - 220.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn amplitude_is_rms() {
+        let data = processor().process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!((data.amplitude - 0.5 / 2.0_f32.sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn amplitude_is_integrated_beyond_the_block() {
+        // Trémolo à 5 Hz: chaque bloc de 23 ms voit un niveau différent, la
+        // fenêtre de 400 ms en couvre deux périodes entières
+        let samples: Vec<f32> = sine(220.0, 1.0, SAMPLE_RATE as usize * 2)
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let t = i as f32 / SAMPLE_RATE;
+                x * (0.3 + 0.2 * (2.0 * std::f32::consts::PI * 5.0 * t).sin())
+            })
+            .collect();
+        let mut processor = processor();
+        let levels: Vec<f32> = samples
+            .chunks(1024)
+            .filter_map(|chunk| processor.process_samples(chunk))
+            .skip(20)
+            .map(|data| data.amplitude)
+            .collect();
+        let (low, high) = levels
+            .iter()
+            .fold((f32::MAX, 0.0_f32), |(low, high), &x| (low.min(x), high.max(x)));
+        assert!(high - low < 0.01, "{} – {}", low, high);
+    }
+
+    #[test]
+    fn silence_is_unvoiced() {
+        let data = processor().process_samples(&[0.0; 2048]).unwrap();
+        assert!(!data.is_voiced);
+        assert_eq!(data.dominant_frequency, 0.0);
+        assert_eq!(data.spectrum.len(), 512);
+    }
+
+    #[test]
+    fn white_noise_is_unvoiced() {
+        let mut processor = processor();
+        let noise = white_noise(0.3, 1024 * 8);
+        for block in noise.chunks(1024) {
+            let data = processor.process_samples(block).unwrap();
+            assert!(!data.is_voiced);
+            assert!(data.spectral_flatness > 0.35);
+        }
+    }
+
+    #[test]
+    fn confidence_separates_tones_from_noise() {
+        let tone = processor().process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!(tone.confidence > 0.8, "{}", tone.confidence);
+
+        let mut processor = processor();
+        for block in white_noise(0.3, 1024 * 8).chunks(1024) {
+            let data = processor.process_samples(block).unwrap();
+            assert!(data.confidence < 0.4, "{}", data.confidence);
+        }
+    }
+
+    /// Voix grave dont la deuxième harmonique domine la fondamentale.
+    fn weak_fundamental(f0: f32, len: usize) -> Vec<f32> {
+        let partials = [(1.0, 0.1), (2.0, 0.5), (3.0, 0.3), (4.0, 0.2)];
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                partials
+                    .iter()
+                    .map(|&(h, a)| a * (2.0 * std::f32::consts::PI * h * f0 * t).sin())
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn harmonic_product_finds_a_weak_fundamental() {
+        let voice = weak_fundamental(110.0, 4096);
+        let config = AnalysisConfig {
+            window_size: 4096,
+            hop_size: 4096,
+            ..Default::default()
+        };
+
+        let mut peak = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = peak.process_samples(&voice).unwrap();
+        assert_eq!(data.detector, PitchDetector::SpectralPeak);
+        assert!((data.dominant_frequency - 220.0).abs() < 5.0, "{}", data.dominant_frequency);
+
+        let config = AnalysisConfig {
+            detector: PitchDetector::HarmonicProduct,
+            ..config
+        };
+        let mut hps = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = hps.process_samples(&voice).unwrap();
+        assert_eq!(data.detector, PitchDetector::HarmonicProduct);
+        assert!((data.dominant_frequency - 110.0).abs() < 3.0, "{}", data.dominant_frequency);
+        assert!(data.confidence > 0.5, "{}", data.confidence);
+    }
+
+    #[test]
+    fn harmonic_product_keeps_pure_tones_and_rejects_noise() {
+        let config = AnalysisConfig {
+            detector: PitchDetector::HarmonicProduct,
+            ..Default::default()
+        };
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        let data = processor.process_samples(&sine(220.0, 0.5, 4096)).unwrap();
+        assert!((data.dominant_frequency - 220.0).abs() < 10.0, "{}", data.dominant_frequency);
+        assert_eq!(data.detector, PitchDetector::SpectralPeak);
+
+        let mut processor = FrequencyProcessor::new(SAMPLE_RATE, config, VadConfig::default());
+        for block in white_noise(0.3, 1024 * 8).chunks(1024) {
+            let data = processor.process_samples(block).unwrap();
+            assert!(data.confidence < 0.4, "{}", data.confidence);
+        }
+    }
+
+    #[test]
+    fn resonance_is_the_strongest_peak_of_its_band() {
+        // Fondamentale forte sous la bande: seule l'harmonique à 1320 Hz compte
+        let samples: Vec<f32> = sine(220.0, 0.5, 4096)
+            .iter()
+            .zip(sine(1320.0, 0.2, 4096))
+            .zip(sine(4400.0, 0.4, 4096))
+            .map(|((a, b), c)| a + b + c)
+            .collect();
+        let data = processor().process_samples(&samples).unwrap();
+        let resonance = data.resonance_hz.unwrap();
+        assert!((resonance - 1320.0).abs() < 15.0, "{}", resonance);
+
+        let data = processor().process_samples(&vec![0.0; 4096]).unwrap();
+        assert!(data.resonance_hz.is_none());
+    }
+
+    #[test]
+    fn centroid_follows_tone() {
+        let data = processor().process_samples(&sine(1500.0, 0.5, 4096)).unwrap();
+        assert!((data.spectral_centroid - 1500.0).abs() < 100.0);
+    }
+}
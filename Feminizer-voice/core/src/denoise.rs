@@ -0,0 +1,118 @@
+/// Constante de temps du profil de bruit: il suit un ventilateur qui démarre
+/// en une à deux secondes de silence.
+const NOISE_TIME_CONSTANT_SECS: f32 = 0.5;
+/// Part de chaque raie toujours conservée: une soustraction totale laisse un
+/// spectre troué et un « bruit musical » qui trompe le détecteur.
+const SPECTRAL_FLOOR: f32 = 0.05;
+
+/// Réduction de bruit par soustraction spectrale, pour un bruit de fond
+/// stationnaire (ventilateur, climatisation, souffle du micro).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DenoiseConfig {
+    pub enabled: bool,
+    /// Multiple du profil de bruit retiré de chaque raie: au-delà de 1, le
+    /// bruit est sur-soustrait au prix d'une voix un peu amincie.
+    pub strength: f32,
+}
+
+impl Default for DenoiseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 2.0,
+        }
+    }
+}
+
+impl DenoiseConfig {
+    pub const STRENGTH_RANGE: std::ops::RangeInclusive<f32> = 1.0..=4.0;
+}
+
+/// Estime le spectre moyen du bruit de fond pendant les silences et le
+/// retire de chaque trame. Aucun apprentissage préalable: le profil part de
+/// la première trame et s'affine à chaque pause.
+pub struct SpectralGate {
+    strength: f32,
+    /// Part du profil renouvelée à chaque trame de silence.
+    smoothing: f32,
+    noise: Vec<f32>,
+}
+
+impl SpectralGate {
+    /// `frame_secs`: durée entre deux trames.
+    pub fn new(config: DenoiseConfig, frame_secs: f32) -> Self {
+        Self {
+            strength: config.strength,
+            smoothing: 1.0 - (-frame_secs / NOISE_TIME_CONSTANT_SECS).exp(),
+            noise: Vec::new(),
+        }
+    }
+
+    /// Débruite sur place les magnitudes d'une trame, après avoir mis le
+    /// profil à jour si `silent`. Renvoie la part d'énergie conservée, entre
+    /// 0 et 1.
+    pub fn process(&mut self, magnitudes: &mut [f32], silent: bool) -> f32 {
+        if self.noise.len() != magnitudes.len() {
+            self.noise.clear();
+            self.noise.extend_from_slice(magnitudes);
+        } else if silent {
+            for (noise, &magnitude) in self.noise.iter_mut().zip(magnitudes.iter()) {
+                *noise += self.smoothing * (magnitude - *noise);
+            }
+        }
+
+        let (mut before, mut after) = (0.0_f32, 0.0_f32);
+        for (magnitude, &noise) in magnitudes.iter_mut().zip(&self.noise) {
+            before += *magnitude * *magnitude;
+            let cleaned = *magnitude - self.strength * noise;
+            *magnitude = cleaned.max(SPECTRAL_FLOOR * *magnitude);
+            after += *magnitude * *magnitude;
+        }
+        if before > 0.0 { after / before } else { 1.0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bruit blanc déterministe entre 0 et 1.
+    fn noise(seed: &mut u32) -> f32 {
+        *seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (*seed >> 8) as f32 / (1 << 24) as f32
+    }
+
+    #[test]
+    fn removes_learned_noise_and_keeps_a_louder_peak() {
+        let mut gate = SpectralGate::new(DenoiseConfig::default(), 0.02);
+        let mut seed = 1;
+        let mut frame = vec![0.0; 64];
+        for _ in 0..200 {
+            frame.iter_mut().for_each(|m| *m = noise(&mut seed));
+            gate.process(&mut frame, true);
+        }
+
+        frame.iter_mut().for_each(|m| *m = noise(&mut seed));
+        frame[20] = 10.0;
+        let kept = gate.process(&mut frame, false);
+        assert!(frame[20] > 8.0, "pic réduit à {}", frame[20]);
+        let residual = frame.iter().enumerate().filter(|&(i, _)| i != 20);
+        assert!(residual.map(|(_, &m)| m).fold(0.0, f32::max) < 0.3);
+        assert!(kept > 0.5 && kept <= 1.0);
+    }
+
+    #[test]
+    fn profile_only_learns_during_silence() {
+        let mut gate = SpectralGate::new(DenoiseConfig::default(), 0.02);
+        gate.process(&mut [0.1; 16], true);
+        for _ in 0..50 {
+            let mut frame = [0.1; 16];
+            frame[4] = 5.0;
+            gate.process(&mut frame, false);
+        }
+        assert_eq!(gate.noise[4], 0.1);
+    }
+}
@@ -0,0 +1,183 @@
+use std::f32::consts::PI;
+
+/// Nombre d'harmoniques du secteur coupées, fondamentale comprise: le
+/// bourdonnement d'un secteur à 50 Hz est souvent plus fort à 100 ou 150 Hz.
+const NOTCH_HARMONICS: usize = 3;
+const NOTCH_Q: f32 = 30.0;
+
+/// Filtre biquadratique du second ordre (formules de l'« Audio EQ Cookbook »
+/// de R. Bristow-Johnson), en forme directe transposée II.
+#[derive(Clone, Copy, Debug)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    pub(crate) fn from_coefficients(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub fn low_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        let (cos, alpha) = prewarp(sample_rate, cutoff, q);
+        Self::from_coefficients(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub fn high_pass(sample_rate: f32, cutoff: f32, q: f32) -> Self {
+        let (cos, alpha) = prewarp(sample_rate, cutoff, q);
+        Self::from_coefficients(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub fn notch(sample_rate: f32, center: f32, q: f32) -> Self {
+        let (cos, alpha) = prewarp(sample_rate, center, q);
+        Self::from_coefficients(
+            [1.0, -2.0 * cos, 1.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub fn peaking(sample_rate: f32, center: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = prewarp(sample_rate, center, q);
+        let a = 10.0_f32.powf(gain_db / 40.0);
+        Self::from_coefficients(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+}
+
+fn prewarp(sample_rate: f32, frequency: f32, q: f32) -> (f32, f32) {
+    // Fréquence ramenée sous Nyquist pour les faibles taux d'échantillonnage
+    let omega = 2.0 * PI * frequency.min(sample_rate * 0.45) / sample_rate;
+    (omega.cos(), omega.sin() / (2.0 * q))
+}
+
+/// Filtrage appliqué avant la FFT pour que le grondement et le bourdonnement
+/// du secteur ne passent jamais pour la fréquence dominante.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PreFilterConfig {
+    pub enabled: bool,
+    /// Coupure du passe-haut (Hz).
+    pub high_pass_hz: f32,
+    /// Fréquence du secteur à rejeter (50 ou 60 Hz), ou `None` sans réjection.
+    pub notch_hz: Option<f32>,
+}
+
+impl Default for PreFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            high_pass_hz: 65.0,
+            notch_hz: Some(50.0),
+        }
+    }
+}
+
+/// Passe-haut du quatrième ordre suivi de réjecteurs sur le secteur et ses
+/// premières harmoniques.
+pub struct PreFilter {
+    stages: Vec<Biquad>,
+}
+
+impl PreFilter {
+    pub fn new(sample_rate: f32, config: PreFilterConfig) -> Self {
+        let mut stages = Vec::new();
+        if config.enabled {
+            if config.high_pass_hz > 0.0 {
+                // Deux Butterworth en cascade: pente de 24 dB/octave
+                stages.push(Biquad::high_pass(sample_rate, config.high_pass_hz, 0.541));
+                stages.push(Biquad::high_pass(sample_rate, config.high_pass_hz, 1.307));
+            }
+            if let Some(mains) = config.notch_hz.filter(|&hz| hz > 0.0) {
+                stages.extend(
+                    (1..=NOTCH_HARMONICS)
+                        .map(|k| mains * k as f32)
+                        .filter(|&hz| hz < sample_rate * 0.45)
+                        .map(|hz| Biquad::notch(sample_rate, hz, NOTCH_Q)),
+                );
+            }
+        }
+        Self { stages }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        self.stages
+            .iter_mut()
+            .fold(input, |sample, stage| stage.process(sample))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    // Amplitude crête en régime établi, après une seconde de transitoire
+    fn filtered_peak(config: PreFilterConfig, frequency: f32) -> f32 {
+        let mut filter = PreFilter::new(SAMPLE_RATE, config);
+        (0..(SAMPLE_RATE as usize * 2))
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                filter.process((2.0 * PI * frequency * t).sin())
+            })
+            .skip(SAMPLE_RATE as usize)
+            .fold(0.0_f32, |peak, sample| peak.max(sample.abs()))
+    }
+
+    #[test]
+    fn rejects_rumble_and_mains_hum() {
+        let config = PreFilterConfig::default();
+        assert!(filtered_peak(config, 20.0) < 0.02);
+        assert!(filtered_peak(config, 50.0) < 0.05);
+        assert!(filtered_peak(config, 100.0) < 0.05);
+    }
+
+    #[test]
+    fn keeps_voice_fundamentals() {
+        let config = PreFilterConfig::default();
+        for frequency in [120.0, 220.0, 440.0] {
+            let peak = filtered_peak(config, frequency);
+            assert!(peak > 0.8, "{} Hz atténué à {}", frequency, peak);
+        }
+    }
+
+    #[test]
+    fn disabled_filter_is_transparent() {
+        let config = PreFilterConfig {
+            enabled: false,
+            ..PreFilterConfig::default()
+        };
+        let mut filter = PreFilter::new(SAMPLE_RATE, config);
+        assert_eq!(filter.process(0.25), 0.25);
+    }
+}
@@ -0,0 +1,157 @@
+const TARGET_RATE: f32 = 11025.0;
+const LPC_ORDER: usize = 12;
+const ENVELOPE_POINTS: usize = 512;
+
+/// Estime (F1, F2) en Hz par LPC sur un bloc voisé, `None` si aucun pic
+/// plausible n'est trouvé.
+pub fn estimate_formants(samples: &[f32], sample_rate: f32) -> Option<(f32, f32)> {
+    FormantEstimator::default().estimate(samples, sample_rate)
+}
+
+/// Même estimation que [`estimate_formants`], en gardant le tampon de travail
+/// d'un bloc à l'autre: aucune allocation une fois la taille de bloc atteinte.
+#[derive(Default)]
+pub struct FormantEstimator {
+    signal: Vec<f32>,
+}
+
+impl FormantEstimator {
+    pub fn estimate(&mut self, samples: &[f32], sample_rate: f32) -> Option<(f32, f32)> {
+        let factor = ((sample_rate / TARGET_RATE).floor() as usize).max(1);
+        let rate = sample_rate / factor as f32;
+
+        // Décimation par moyenne, suffisante pour rester sous ~5 kHz
+        self.signal.clear();
+        self.signal.extend(
+            samples
+                .chunks_exact(factor)
+                .map(|chunk| chunk.iter().sum::<f32>() / factor as f32),
+        );
+        if self.signal.len() <= LPC_ORDER * 2 {
+            return None;
+        }
+
+        // Préaccentuation et fenêtre de Hamming, sur place
+        let n = self.signal.len();
+        let mut previous = 0.0;
+        for (i, x) in self.signal.iter_mut().enumerate() {
+            let current = *x;
+            let w = 0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos();
+            *x = (current - 0.97 * previous) * w;
+            previous = current;
+        }
+
+        let coefficients = lpc(&self.signal)?;
+
+        let mut envelope = [0.0_f32; ENVELOPE_POINTS];
+        for (k, value) in envelope.iter_mut().enumerate() {
+            let omega = std::f32::consts::PI * k as f32 / ENVELOPE_POINTS as f32;
+            let (mut re, mut im) = (1.0_f32, 0.0_f32);
+            for (j, &a) in coefficients.iter().enumerate() {
+                let phase = omega * (j + 1) as f32;
+                re += a * phase.cos();
+                im -= a * phase.sin();
+            }
+            *value = 1.0 / (re * re + im * im).max(1e-12);
+        }
+
+        let hz_per_point = rate / 2.0 / ENVELOPE_POINTS as f32;
+        let mut peaks = (1..ENVELOPE_POINTS - 1)
+            .filter(|&k| envelope[k] > envelope[k - 1] && envelope[k] >= envelope[k + 1])
+            .map(|k| k as f32 * hz_per_point)
+            .filter(|&hz| hz >= 200.0);
+
+        let f1 = peaks.next().filter(|&hz| hz <= 1200.0)?;
+        let f2 = peaks.find(|&hz| hz > f1 + 200.0).filter(|&hz| hz <= 3500.0)?;
+        Some((f1, f2))
+    }
+}
+
+fn lpc(signal: &[f32]) -> Option<[f32; LPC_ORDER]> {
+    let mut autocorrelation = [0.0_f32; LPC_ORDER + 1];
+    for (lag, value) in autocorrelation.iter_mut().enumerate() {
+        *value = signal[lag..]
+            .iter()
+            .zip(signal.iter())
+            .map(|(a, b)| a * b)
+            .sum();
+    }
+
+    if autocorrelation[0] <= 1e-9 {
+        return None;
+    }
+
+    // Levinson-Durbin
+    let mut a = [0.0_f32; LPC_ORDER];
+    let mut error = autocorrelation[0];
+    for i in 0..LPC_ORDER {
+        let mut acc = autocorrelation[i + 1];
+        for j in 0..i {
+            acc += a[j] * autocorrelation[i - j];
+        }
+        let k = -acc / error;
+
+        let previous = a;
+        a[i] = k;
+        for j in 0..i {
+            a[j] = previous[j] + k * previous[i - 1 - j];
+        }
+
+        error *= 1.0 - k * k;
+        if error <= 0.0 {
+            return None;
+        }
+    }
+
+    Some(a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Train d'impulsions filtré par deux résonateurs: voyelle synthétique.
+    fn synthetic_vowel(f1: f32, f2: f32, sample_rate: f32, len: usize) -> Vec<f32> {
+        let period = (sample_rate / 120.0) as usize;
+        let source: Vec<f32> = (0..len).map(|i| if i % period == 0 { 1.0 } else { 0.0 }).collect();
+        let first = resonate(&source, f1, 80.0, sample_rate);
+        resonate(&first, f2, 100.0, sample_rate)
+    }
+
+    fn resonate(input: &[f32], frequency: f32, bandwidth: f32, sample_rate: f32) -> Vec<f32> {
+        let r = (-std::f32::consts::PI * bandwidth / sample_rate).exp();
+        let theta = 2.0 * std::f32::consts::PI * frequency / sample_rate;
+        let (a1, a2) = (2.0 * r * theta.cos(), -r * r);
+
+        let mut output = vec![0.0; input.len()];
+        for i in 0..input.len() {
+            let y1 = if i >= 1 { output[i - 1] } else { 0.0 };
+            let y2 = if i >= 2 { output[i - 2] } else { 0.0 };
+            output[i] = input[i] + a1 * y1 + a2 * y2;
+        }
+        output
+    }
+
+    #[test]
+    fn finds_formants_of_synthetic_vowel() {
+        let samples = synthetic_vowel(700.0, 1200.0, 44100.0, 2048);
+        let (f1, f2) = estimate_formants(&samples, 44100.0).expect("formants");
+        assert!((f1 - 700.0).abs() < 100.0, "F1 = {}", f1);
+        assert!((f2 - 1200.0).abs() < 150.0, "F2 = {}", f2);
+    }
+
+    #[test]
+    fn estimator_reuses_its_buffer() {
+        let samples = synthetic_vowel(700.0, 1200.0, 44100.0, 2048);
+        let mut estimator = FormantEstimator::default();
+        let first = estimator.estimate(&samples, 44100.0);
+        let buffer = estimator.signal.as_ptr();
+        assert_eq!(estimator.estimate(&samples, 44100.0), first);
+        assert_eq!(estimator.signal.as_ptr(), buffer);
+    }
+
+    #[test]
+    fn silence_has_no_formants() {
+        assert!(estimate_formants(&[0.0; 1024], 44100.0).is_none());
+    }
+}
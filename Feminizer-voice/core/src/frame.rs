@@ -0,0 +1,89 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::analysis::FrequencyData;
+
+/// Mesures d'une trame, sans son spectre: ce que l'interface garde en
+/// historique et ce que la diffusion réseau et les exports transmettent.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AnalysisFrame {
+    /// Instant de la capture, en millisecondes depuis l'époque Unix.
+    pub timestamp_ms: u64,
+    /// Fondamentale en Hz, 0 hors voix.
+    pub frequency: f32,
+    /// Fiabilité de `frequency`, entre 0 et 1.
+    pub confidence: f32,
+    /// Niveau RMS de la trame.
+    pub amplitude: f32,
+    pub is_voiced: bool,
+    /// Centroïde spectral en Hz.
+    pub brightness: f32,
+    /// Formants (F1, F2) en Hz.
+    pub formants: Option<(f32, f32)>,
+    /// Poids vocal, voir [`h1_h2_db`](crate::h1_h2_db).
+    pub h1_h2_db: Option<f32>,
+    /// Pic de résonance entre 500 et 3500 Hz.
+    pub resonance_hz: Option<f32>,
+}
+
+impl AnalysisFrame {
+    /// Trame sans voix ni mesure, au même instant que `self`.
+    pub fn silent(&self) -> Self {
+        Self {
+            timestamp_ms: self.timestamp_ms,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&FrequencyData> for AnalysisFrame {
+    fn from(data: &FrequencyData) -> Self {
+        // L'horloge murale de la capture, retrouvée depuis son `Instant`
+        let captured = SystemTime::now()
+            .checked_sub(data.captured_at.elapsed())
+            .unwrap_or_else(SystemTime::now);
+        let timestamp_ms = captured
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        Self {
+            timestamp_ms,
+            frequency: if data.is_voiced { data.dominant_frequency } else { 0.0 },
+            confidence: data.confidence,
+            amplitude: data.amplitude,
+            is_voiced: data.is_voiced,
+            brightness: data.spectral_centroid,
+            formants: data.formants,
+            h1_h2_db: data.h1_h2_db,
+            resonance_hz: data.resonance_hz,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AnalysisConfig, FrequencyProcessor, VadConfig};
+
+    #[test]
+    fn frame_keeps_the_voiced_pitch_and_drops_the_spectrum() {
+        let sample_rate = 44100.0;
+        let mut processor =
+            FrequencyProcessor::new(sample_rate, AnalysisConfig::default(), VadConfig::default());
+        let samples: Vec<f32> = (0..4096)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / sample_rate).sin())
+            .collect();
+        let data = processor.process_samples(&samples).unwrap();
+
+        let frame = AnalysisFrame::from(&data);
+        assert!(frame.is_voiced);
+        assert_eq!(frame.frequency, data.dominant_frequency);
+        assert!(frame.timestamp_ms > 0);
+
+        let silent = frame.silent();
+        assert_eq!(silent.timestamp_ms, frame.timestamp_ms);
+        assert_eq!(silent.frequency, 0.0);
+        assert!(!silent.is_voiced);
+    }
+}
@@ -0,0 +1,53 @@
+//! Cœur d'analyse de Feminizer voice: détection de la fréquence dominante,
+//! du voisement, du niveau sonore à court terme, de la brillance, du poids
+//! vocal (H1–H2), d'un pic de résonance et des formants à partir
+//! d'échantillons mono, après un pré-filtrage du grondement et du
+//! bourdonnement secteur et une réduction facultative du bruit de fond,
+//! ainsi que la stabilité cycle à cycle d'une voyelle tenue (jitter,
+//! shimmer, HNR).
+//!
+//! Aucune dépendance audio ni graphique: la capture reste à la charge de
+//! l'appelant. Les fonctionnalités `serde` et `schemars` rendent la
+//! configuration sérialisable.
+//!
+//! ```
+//! use feminizer_voice_core::{AnalysisConfig, FrequencyProcessor, VadConfig};
+//!
+//! let sample_rate = 44100.0;
+//! let mut processor =
+//!     FrequencyProcessor::new(sample_rate, AnalysisConfig::default(), VadConfig::default());
+//!
+//! let samples: Vec<f32> = (0..4096)
+//!     .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / sample_rate).sin())
+//!     .collect();
+//!
+//! let data = processor.process_samples(&samples).unwrap();
+//! assert!(data.is_voiced);
+//! assert!((data.dominant_frequency - 220.0).abs() < 10.0);
+//! ```
+
+pub mod align;
+pub mod analysis;
+pub mod denoise;
+pub mod filter;
+pub mod formants;
+pub mod frame;
+pub mod loudness;
+pub mod perturbation;
+pub mod resample;
+pub mod vad;
+pub mod weight;
+
+pub use align::dtw_path;
+pub use analysis::{
+    AnalysisConfig, FrequencyData, FrequencyProcessor, PitchDetector, WindowFunction,
+};
+pub use denoise::{DenoiseConfig, SpectralGate};
+pub use filter::{Biquad, PreFilter, PreFilterConfig};
+pub use formants::{FormantEstimator, estimate_formants};
+pub use frame::AnalysisFrame;
+pub use loudness::{LoudnessConfig, LoudnessMeter, SILENCE_LUFS, to_lufs};
+pub use perturbation::{VoiceQuality, analyze_sustained};
+pub use resample::Resampler;
+pub use vad::{VadConfig, VoiceActivityDetector, spectral_flatness};
+pub use weight::h1_h2_db;
@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+use crate::filter::Biquad;
+
+/// Durée d'un bloc d'intégration: la fenêtre glisse par pas de 10 ms.
+const BLOCK_SECS: f32 = 0.01;
+/// Sonie d'un silence numérique, plancher de la mesure.
+pub const SILENCE_LUFS: f32 = -70.0;
+
+/// Mesure du niveau de la voix, indépendante de la taille des tampons et de
+/// la fenêtre d'analyse.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LoudnessConfig {
+    /// Durée sur laquelle l'énergie est intégrée, en ms; 400 ms pour la
+    /// sonie « momentanée » de l'UIT-R BS.1770.
+    pub window_ms: f32,
+    /// Pondération K de la BS.1770: le niveau suit l'oreille plutôt que
+    /// l'énergie brute, au prix de seuils à réajuster.
+    pub k_weighting: bool,
+}
+
+impl Default for LoudnessConfig {
+    fn default() -> Self {
+        Self {
+            window_ms: 400.0,
+            k_weighting: false,
+        }
+    }
+}
+
+impl LoudnessConfig {
+    pub const WINDOW_RANGE_MS: std::ops::RangeInclusive<f32> = 50.0..=3000.0;
+}
+
+/// Énergie moyenne des dernières `window_ms` d'un signal, échantillon par
+/// échantillon, éventuellement après pondération K.
+pub struct LoudnessMeter {
+    /// Plateau aigu puis passe-haut « RLB », absents sans pondération K.
+    k_filter: Vec<Biquad>,
+    block_len: usize,
+    /// Somme des carrés de chaque bloc complet de la fenêtre.
+    blocks: VecDeque<f32>,
+    max_blocks: usize,
+    /// Somme des blocs de la fenêtre, recalculée à chaque bloc.
+    window_sum: f32,
+    partial_sum: f32,
+    partial_len: usize,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: f32, config: LoudnessConfig) -> Self {
+        let range = LoudnessConfig::WINDOW_RANGE_MS;
+        let window_ms = config.window_ms.clamp(*range.start(), *range.end());
+        let max_blocks = ((window_ms / 1000.0 / BLOCK_SECS).round() as usize).max(1);
+        let k_filter = if config.k_weighting {
+            k_weighting(sample_rate).to_vec()
+        } else {
+            Vec::new()
+        };
+        Self {
+            k_filter,
+            block_len: ((BLOCK_SECS * sample_rate) as usize).max(1),
+            blocks: VecDeque::with_capacity(max_blocks),
+            max_blocks,
+            window_sum: 0.0,
+            partial_sum: 0.0,
+            partial_len: 0,
+        }
+    }
+
+    pub fn push(&mut self, sample: f32) {
+        let weighted = self
+            .k_filter
+            .iter_mut()
+            .fold(sample, |sample, stage| stage.process(sample));
+        self.partial_sum += weighted * weighted;
+        self.partial_len += 1;
+        if self.partial_len == self.block_len {
+            if self.blocks.len() == self.max_blocks {
+                self.blocks.pop_front();
+            }
+            self.blocks.push_back(self.partial_sum);
+            // Resommer plutôt que soustraire: pas de dérive d'arrondi
+            self.window_sum = self.blocks.iter().sum();
+            self.partial_sum = 0.0;
+            self.partial_len = 0;
+        }
+    }
+
+    /// Moyenne des carrés sur la fenêtre, ou sur ce qui a été reçu tant
+    /// qu'elle n'est pas pleine. Le bloc en cours remplace le plus ancien,
+    /// pour suivre le signal sans attendre la fin du bloc.
+    pub fn mean_square(&self) -> f32 {
+        let full = self.blocks.len() == self.max_blocks;
+        let (sum, len) = match self.blocks.front() {
+            Some(&oldest) if full && self.partial_len > 0 => {
+                let kept = 1.0 - self.partial_len as f32 / self.block_len as f32;
+                (
+                    self.window_sum - oldest * (1.0 - kept) + self.partial_sum,
+                    self.max_blocks * self.block_len,
+                )
+            }
+            _ => (
+                self.window_sum + self.partial_sum,
+                self.blocks.len() * self.block_len + self.partial_len,
+            ),
+        };
+        if len == 0 { 0.0 } else { (sum / len as f32).max(0.0) }
+    }
+
+    /// Niveau efficace sur la fenêtre, dans l'unité des échantillons.
+    pub fn rms(&self) -> f32 {
+        self.mean_square().sqrt()
+    }
+
+    /// Sonie sur la fenêtre, en LUFS avec la pondération K (en dB sinon).
+    pub fn lufs(&self) -> f32 {
+        to_lufs(self.mean_square())
+    }
+}
+
+/// Filtres de la pondération K, recalculés pour un taux quelconque à partir
+/// des pôles et zéros de la BS.1770 (donnés à 48 kHz dans la norme).
+fn k_weighting(sample_rate: f32) -> [Biquad; 2] {
+    let shelf = {
+        let k = (std::f32::consts::PI * 1681.9745 / sample_rate).tan();
+        let q = 0.707_175_2;
+        let vh = 10.0_f32.powf(3.999_844 / 20.0);
+        let vb = vh.powf(0.499_666_77);
+        Biquad::from_coefficients(
+            [vh + vb * k / q + k * k, 2.0 * (k * k - vh), vh - vb * k / q + k * k],
+            [1.0 + k / q + k * k, 2.0 * (k * k - 1.0), 1.0 - k / q + k * k],
+        )
+    };
+    let high_pass = {
+        let k = (std::f32::consts::PI * 38.135_47 / sample_rate).tan();
+        let q = 0.500_327;
+        Biquad::from_coefficients(
+            [1.0, -2.0, 1.0],
+            [1.0 + k / q + k * k, 2.0 * (k * k - 1.0), 1.0 - k / q + k * k],
+        )
+    };
+    [shelf, high_pass]
+}
+
+/// Sonie d'une moyenne des carrés, selon la BS.1770.
+pub fn to_lufs(mean_square: f32) -> f32 {
+    if mean_square <= 0.0 {
+        return SILENCE_LUFS;
+    }
+    (-0.691 + 10.0 * mean_square.log10()).max(SILENCE_LUFS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn sine(frequency: f32, amplitude: f32, secs: f32) -> Vec<f32> {
+        (0..(secs * SAMPLE_RATE) as usize)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / SAMPLE_RATE).sin()
+            })
+            .collect()
+    }
+
+    fn meter_after(config: LoudnessConfig, samples: &[f32]) -> LoudnessMeter {
+        let mut meter = LoudnessMeter::new(SAMPLE_RATE, config);
+        samples.iter().for_each(|&sample| meter.push(sample));
+        meter
+    }
+
+    #[test]
+    fn rms_of_a_sine() {
+        let meter = meter_after(LoudnessConfig::default(), &sine(220.0, 0.5, 1.0));
+        assert!((meter.rms() - 0.5 / 2.0_f32.sqrt()).abs() < 0.005, "{}", meter.rms());
+    }
+
+    #[test]
+    fn the_window_forgets_older_signal() {
+        let mut samples = sine(220.0, 0.5, 1.0);
+        samples.extend(std::iter::repeat_n(0.0, (0.5 * SAMPLE_RATE) as usize));
+        let meter = meter_after(LoudnessConfig::default(), &samples);
+        assert!(meter.rms() < 1e-3, "{}", meter.rms());
+        assert_eq!(meter.lufs(), SILENCE_LUFS);
+
+        // À mi-fenêtre, la moitié de l'énergie reste comptée
+        let half = &samples[..(1.2 * SAMPLE_RATE) as usize];
+        let meter = meter_after(LoudnessConfig::default(), half);
+        assert!((meter.mean_square() - 0.125 / 2.0).abs() < 0.003, "{}", meter.mean_square());
+    }
+
+    #[test]
+    fn full_scale_1khz_sine_reads_about_minus_3_lufs() {
+        let config = LoudnessConfig {
+            k_weighting: true,
+            ..LoudnessConfig::default()
+        };
+        let meter = meter_after(config, &sine(1000.0, 1.0, 1.0));
+        assert!((meter.lufs() + 3.01).abs() < 0.2, "{}", meter.lufs());
+    }
+
+    #[test]
+    fn k_weighting_favours_the_treble() {
+        let config = LoudnessConfig {
+            k_weighting: true,
+            ..LoudnessConfig::default()
+        };
+        let low = meter_after(config, &sine(40.0, 0.5, 1.0)).lufs();
+        let high = meter_after(config, &sine(4000.0, 0.5, 1.0)).lufs();
+        assert!(high - low > 5.0, "{} / {}", low, high);
+    }
+}
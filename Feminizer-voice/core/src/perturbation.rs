@@ -0,0 +1,299 @@
+/// Mesures de stabilité d'une voyelle tenue, à partir des cycles un par un.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceQuality {
+    /// Fréquence fondamentale moyenne des cycles retenus, en Hz.
+    pub mean_f0: f32,
+    /// Jitter local: écart moyen entre périodes successives, en % de la période.
+    pub jitter_percent: f32,
+    /// Shimmer local: écart moyen entre amplitudes successives, en % de l'amplitude.
+    pub shimmer_percent: f32,
+    /// Rapport harmoniques sur bruit, déduit de la corrélation d'un cycle au suivant.
+    pub hnr_db: f32,
+    /// Nombre de cycles mesurés.
+    pub cycles: usize,
+}
+
+/// Corrélation minimale entre deux cycles pour les compter comme voisés.
+const MIN_CYCLE_CORRELATION: f32 = 0.5;
+/// Variation de période admise d'un cycle au suivant.
+const MAX_PERIOD_CHANGE: f32 = 0.25;
+const MIN_CYCLES: usize = 10;
+/// Plafond du HNR, atteint par un signal parfaitement périodique.
+const MAX_HNR_DB: f32 = 60.0;
+
+struct Cycle {
+    period: f32,
+    amplitude: f32,
+    correlation: f32,
+    /// Premier cycle après une coupure: pas de comparaison avec le précédent.
+    after_break: bool,
+}
+
+/// Corrélation normalisée entre `x[start..start + len]` et le même bloc
+/// décalé de `lag` échantillons.
+fn correlation(x: &[f32], start: usize, lag: usize, len: usize) -> f32 {
+    let a = &x[start..start + len];
+    let b = &x[start + lag..start + lag + len];
+    let (mut ab, mut aa, mut bb) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for (&u, &v) in a.iter().zip(b) {
+        ab += u * v;
+        aa += u * u;
+        bb += v * v;
+    }
+    let norm = (aa * bb).sqrt();
+    if norm > 0.0 { ab / norm } else { 0.0 }
+}
+
+/// Maximum de `x[range]`, affiné par interpolation parabolique.
+fn peak(x: &[f32], range: std::ops::Range<usize>) -> Option<f32> {
+    let (offset, &best) = x[range.clone()]
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    let index = range.start + offset;
+    if index == 0 || index + 1 >= x.len() {
+        return Some(index as f32);
+    }
+    let (left, right) = (x[index - 1], x[index + 1]);
+    let curvature = left - 2.0 * best + right;
+    let shift = if curvature < 0.0 {
+        (0.5 * (left - right) / curvature).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    Some(index as f32 + shift)
+}
+
+/// Période de départ, sur un bloc pris au milieu de l'enregistrement. Le
+/// premier décalage proche du meilleur évite de prendre l'octave du dessous.
+fn initial_period(x: &[f32], min_lag: usize, max_lag: usize) -> Option<usize> {
+    let len = 2 * max_lag;
+    let start = x.len().checked_sub(len + max_lag + 1)? / 2;
+    let scores: Vec<f32> = (min_lag..=max_lag)
+        .map(|lag| correlation(x, start, lag, len))
+        .collect();
+    let best = scores.iter().copied().fold(f32::MIN, f32::max);
+    if best < MIN_CYCLE_CORRELATION {
+        return None;
+    }
+    let first = (1..scores.len().saturating_sub(1)).find(|&i| {
+        scores[i] >= 0.9 * best && scores[i] >= scores[i - 1] && scores[i] >= scores[i + 1]
+    })?;
+    Some(min_lag + first)
+}
+
+/// Repère le maximum de chaque cycle, en cherchant le suivant autour d'une
+/// période après le précédent: les périodes sont les écarts entre repères.
+fn track_cycles(x: &[f32], sample_rate: f32, min_hz: f32, max_hz: f32) -> Vec<Cycle> {
+    let min_lag = ((sample_rate / max_hz).floor() as usize).max(2);
+    let max_lag = (sample_rate / min_hz).ceil() as usize;
+    let Some(mut period) = initial_period(x, min_lag, max_lag).map(|lag| lag as f32) else {
+        return Vec::new();
+    };
+    let Some(mut mark) = peak(x, 0..(period as usize).min(x.len())) else {
+        return Vec::new();
+    };
+
+    let mut cycles = Vec::new();
+    let mut after_break = true;
+    loop {
+        let low = (mark + period * (1.0 - MAX_PERIOD_CHANGE)).round() as usize;
+        let high = (mark + period * (1.0 + MAX_PERIOD_CHANGE)).round() as usize;
+        if high + 1 >= x.len() {
+            break;
+        }
+        let Some(next) = peak(x, low..high + 1) else {
+            break;
+        };
+
+        let start = mark.round() as usize;
+        let length = ((next - mark).round() as usize).max(1);
+        let score = if start + 2 * length <= x.len() {
+            correlation(x, start, length, length)
+        } else {
+            0.0
+        };
+        let cycle_period = next - mark;
+        let plausible = (min_lag as f32..=max_lag as f32).contains(&cycle_period);
+        if score < MIN_CYCLE_CORRELATION || !plausible {
+            // Coupure de voisement: on reprend au repère suivant sans mesurer
+            after_break = true;
+            mark = next;
+            continue;
+        }
+
+        // Amplitude crête à crête sur une période centrée sur le repère
+        let centered = start.saturating_sub(length / 2);
+        let (low, high) = x[centered..centered + length]
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(low, high), &v| {
+                (low.min(v), high.max(v))
+            });
+        cycles.push(Cycle {
+            period: cycle_period / sample_rate,
+            amplitude: high - low,
+            correlation: score,
+            after_break,
+        });
+        after_break = false;
+        period = cycle_period;
+        mark = next;
+    }
+    cycles
+}
+
+/// Écart moyen entre valeurs successives, rapporté à la moyenne, en %.
+fn local_perturbation(cycles: &[Cycle], value: impl Fn(&Cycle) -> f32) -> f32 {
+    let mean = cycles.iter().map(&value).sum::<f32>() / cycles.len() as f32;
+    let differences: Vec<f32> = cycles
+        .windows(2)
+        .filter(|pair| !pair[1].after_break)
+        .map(|pair| (value(&pair[1]) - value(&pair[0])).abs())
+        .collect();
+    if differences.is_empty() || mean <= 0.0 {
+        return 0.0;
+    }
+    100.0 * differences.iter().sum::<f32>() / differences.len() as f32 / mean
+}
+
+/// Analyse cycle par cycle d'une voyelle tenue (un /a/ de quelques secondes),
+/// en cherchant la fondamentale entre `min_hz` et `max_hz`. `None` si le
+/// signal n'a pas assez de cycles voisés pour une mesure fiable.
+pub fn analyze_sustained(
+    samples: &[f32],
+    sample_rate: f32,
+    min_hz: f32,
+    max_hz: f32,
+) -> Option<VoiceQuality> {
+    if samples.is_empty() || min_hz <= 0.0 || max_hz <= min_hz {
+        return None;
+    }
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+    let centered: Vec<f32> = samples.iter().map(|&s| s - mean).collect();
+
+    let cycles = track_cycles(&centered, sample_rate, min_hz, max_hz);
+    if cycles.len() < MIN_CYCLES {
+        return None;
+    }
+
+    let mean_period = cycles.iter().map(|c| c.period).sum::<f32>() / cycles.len() as f32;
+    let mean_correlation = cycles.iter().map(|c| c.correlation).sum::<f32>() / cycles.len() as f32;
+    let hnr_db = if mean_correlation >= 1.0 {
+        MAX_HNR_DB
+    } else {
+        (10.0 * (mean_correlation / (1.0 - mean_correlation)).log10()).min(MAX_HNR_DB)
+    };
+
+    Some(VoiceQuality {
+        mean_f0: 1.0 / mean_period,
+        jitter_percent: local_perturbation(&cycles, |c| c.period),
+        shimmer_percent: local_perturbation(&cycles, |c| c.amplitude),
+        hnr_db,
+        cycles: cycles.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+
+    /// Une impulsion de largeur fixe par cycle, comme les fermetures de la
+    /// glotte, chacune avec sa période et son amplitude.
+    fn cycles(shape: impl Fn(usize) -> (f32, f32), count: usize) -> Vec<f32> {
+        let mut samples = Vec::new();
+        for k in 0..count {
+            let (frequency, amplitude) = shape(k);
+            let len = (SAMPLE_RATE / frequency).round() as usize;
+            samples.extend((0..len).map(|i| {
+                let t = (i as f32 - 40.0) / 12.0;
+                amplitude * (-t * t).exp()
+            }));
+        }
+        samples
+    }
+
+    // Générateur congruentiel: bruit reproductible sans dépendance
+    fn white_noise(amplitude: f32, len: usize) -> Vec<f32> {
+        let mut state = 0x1234_5678_u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                amplitude * ((state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn steady_tone_is_stable() {
+        let samples = cycles(|_| (220.0, 0.5), 440);
+        let quality = analyze_sustained(&samples, SAMPLE_RATE, 75.0, 600.0).unwrap();
+        assert!((quality.mean_f0 - 220.0).abs() < 2.0, "{:?}", quality);
+        assert!(quality.jitter_percent < 0.1, "{:?}", quality);
+        assert!(quality.shimmer_percent < 0.5, "{:?}", quality);
+        assert!(quality.hnr_db > 30.0, "{:?}", quality);
+        assert!(quality.cycles > 400, "{:?}", quality);
+    }
+
+    #[test]
+    fn alternating_periods_show_as_jitter() {
+        // ±2 % d'un cycle à l'autre: 4 % d'écart entre périodes successives
+        let samples = cycles(
+            |k| {
+                (
+                    if k % 2 == 0 {
+                        200.0 * 1.02
+                    } else {
+                        200.0 / 1.02
+                    },
+                    0.5,
+                )
+            },
+            400,
+        );
+        let quality = analyze_sustained(&samples, SAMPLE_RATE, 75.0, 600.0).unwrap();
+        assert!((quality.jitter_percent - 4.0).abs() < 0.5, "{:?}", quality);
+        assert!(quality.shimmer_percent < 0.5, "{:?}", quality);
+    }
+
+    #[test]
+    fn alternating_amplitudes_show_as_shimmer() {
+        let samples = cycles(|k| (200.0, if k % 2 == 0 { 0.5 } else { 0.45 }), 400);
+        let quality = analyze_sustained(&samples, SAMPLE_RATE, 75.0, 600.0).unwrap();
+        let expected = 100.0 * 0.05 / 0.475;
+        assert!(
+            (quality.shimmer_percent - expected).abs() < 1.0,
+            "{:?}",
+            quality
+        );
+        assert!(quality.jitter_percent < 0.1, "{:?}", quality);
+    }
+
+    #[test]
+    fn noise_lowers_the_hnr() {
+        let clean = cycles(|_| (220.0, 0.5), 440);
+        let noisy: Vec<f32> = clean
+            .iter()
+            .zip(white_noise(0.2, clean.len()))
+            .map(|(s, n)| s + n)
+            .collect();
+        let clean = analyze_sustained(&clean, SAMPLE_RATE, 75.0, 600.0).unwrap();
+        let noisy = analyze_sustained(&noisy, SAMPLE_RATE, 75.0, 600.0).unwrap();
+        assert!(
+            noisy.hnr_db < clean.hnr_db - 10.0,
+            "{:?} / {:?}",
+            noisy,
+            clean
+        );
+        assert!(noisy.hnr_db > 0.0, "{:?}", noisy);
+    }
+
+    #[test]
+    fn silence_and_noise_are_not_measured() {
+        let len = SAMPLE_RATE as usize;
+        assert!(analyze_sustained(&vec![0.0; len], SAMPLE_RATE, 75.0, 600.0).is_none());
+        assert!(analyze_sustained(&white_noise(0.3, len), SAMPLE_RATE, 75.0, 600.0).is_none());
+        assert!(analyze_sustained(&[], SAMPLE_RATE, 75.0, 600.0).is_none());
+    }
+}
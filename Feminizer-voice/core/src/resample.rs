@@ -0,0 +1,122 @@
+use crate::filter::Biquad;
+
+/// Coefficients de qualité d'un Butterworth d'ordre 6 en trois cellules.
+const BUTTERWORTH_Q: [f32; 3] = [0.5176, std::f32::consts::FRAC_1_SQRT_2, 1.9319];
+/// Coupure de l'anti-repliement, en fraction du taux de sortie.
+const CUTOFF_RATIO: f32 = 0.4;
+
+/// Ramène un flux mono à un taux plus bas (jamais plus haut): filtre
+/// anti-repliement puis interpolation linéaire, en gardant l'état d'un bloc
+/// à l'autre. Sans effet si l'entrée est déjà au taux visé ou en dessous.
+#[derive(Clone, Debug)]
+pub struct Resampler {
+    input_rate: f32,
+    output_rate: f32,
+    /// Pas entre deux échantillons de sortie, en échantillons d'entrée.
+    step: f64,
+    /// Position du prochain échantillon de sortie après `previous`.
+    position: f64,
+    previous: f32,
+    filters: [Biquad; 3],
+}
+
+impl Resampler {
+    /// `target_rate` à `None`: les échantillons passent tels quels.
+    pub fn new(input_rate: f32, target_rate: Option<f32>) -> Self {
+        let output_rate = target_rate.map_or(input_rate, |target| input_rate.min(target));
+        Self {
+            input_rate,
+            output_rate,
+            step: input_rate as f64 / output_rate as f64,
+            position: 0.0,
+            previous: 0.0,
+            filters: BUTTERWORTH_Q
+                .map(|q| Biquad::low_pass(input_rate, CUTOFF_RATIO * output_rate, q)),
+        }
+    }
+
+    pub fn output_rate(&self) -> f32 {
+        self.output_rate
+    }
+
+    pub fn is_passthrough(&self) -> bool {
+        self.output_rate >= self.input_rate
+    }
+
+    /// Ajoute à `output` les échantillons rééchantillonnés de `input`.
+    pub fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        if self.is_passthrough() {
+            output.extend_from_slice(input);
+            return;
+        }
+        for &sample in input {
+            let filtered = self
+                .filters
+                .iter_mut()
+                .fold(sample, |value, filter| filter.process(value));
+            while self.position <= 1.0 {
+                let t = self.position as f32;
+                output.push(self.previous + (filtered - self.previous) * t);
+                self.position += self.step;
+            }
+            self.position -= 1.0;
+            self.previous = filtered;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn never_raises_the_rate() {
+        let mut resampler = Resampler::new(16000.0, Some(24000.0));
+        assert!(resampler.is_passthrough());
+        assert_eq!(resampler.output_rate(), 16000.0);
+        let input = sine(220.0, 16000.0, 100);
+        let mut output = Vec::new();
+        resampler.process(&input, &mut output);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn output_length_follows_the_ratio_across_blocks() {
+        let mut resampler = Resampler::new(96000.0, Some(24000.0));
+        let input = sine(220.0, 96000.0, 96000);
+        let mut output = Vec::new();
+        for block in input.chunks(1000) {
+            resampler.process(block, &mut output);
+        }
+        assert!(output.len().abs_diff(24000) <= 1, "{}", output.len());
+
+        let mut resampler = Resampler::new(44100.0, Some(24000.0));
+        let mut output = Vec::new();
+        resampler.process(&sine(220.0, 44100.0, 44100), &mut output);
+        assert!(output.len().abs_diff(24000) <= 1, "{}", output.len());
+    }
+
+    #[test]
+    fn keeps_voice_and_removes_what_would_alias() {
+        let mut resampler = Resampler::new(96000.0, Some(24000.0));
+        let mut voice = Vec::new();
+        resampler.process(&sine(300.0, 96000.0, 96000), &mut voice);
+        assert!((rms(&voice[4800..]) - 0.707).abs() < 0.02, "{}", rms(&voice[4800..]));
+
+        // 30 kHz se replierait à 6 kHz après décimation par quatre
+        let mut resampler = Resampler::new(96000.0, Some(24000.0));
+        let mut alias = Vec::new();
+        resampler.process(&sine(30000.0, 96000.0, 96000), &mut alias);
+        assert!(rms(&alias[4800..]) < 0.01, "{}", rms(&alias[4800..]));
+    }
+}
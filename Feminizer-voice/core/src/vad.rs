@@ -0,0 +1,140 @@
+/// Réglages de la détection d'activité vocale.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VadConfig {
+    /// Niveau RMS minimal d'une trame voisée.
+    pub energy_threshold: f32,
+    /// Planéité spectrale maximale (0 = tonal, 1 = bruit blanc).
+    pub flatness_threshold: f32,
+    /// Multiple du plancher de bruit estimé à dépasser.
+    pub noise_margin: f32,
+    /// Trames gardées voisées après la fin de la voix.
+    pub hangover_frames: usize,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            energy_threshold: 0.0200,
+            flatness_threshold: 0.35,
+            noise_margin: 2.0,
+            hangover_frames: 4,
+        }
+    }
+}
+
+/// Détecteur énergie + planéité avec suivi du plancher de bruit.
+pub struct VoiceActivityDetector {
+    config: VadConfig,
+    noise_floor: f32,
+    hangover: usize,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(config: VadConfig) -> Self {
+        Self {
+            config,
+            noise_floor: 0.0,
+            hangover: 0,
+        }
+    }
+
+    pub fn set_config(&mut self, config: VadConfig) {
+        self.config = config;
+    }
+
+    /// Traite une trame et indique si elle est voisée.
+    pub fn process(&mut self, rms: f32, flatness: f32) -> bool {
+        let energy_gate = self
+            .config
+            .energy_threshold
+            .max(self.noise_floor * self.config.noise_margin);
+        let raw_voiced = rms >= energy_gate && flatness <= self.config.flatness_threshold;
+
+        if raw_voiced {
+            self.hangover = self.config.hangover_frames;
+            return true;
+        }
+
+        // Le plancher de bruit ne suit que les trames non voisées
+        if self.noise_floor == 0.0 {
+            self.noise_floor = rms;
+        } else if rms < self.noise_floor {
+            self.noise_floor = 0.7 * self.noise_floor + 0.3 * rms;
+        } else {
+            self.noise_floor = 0.98 * self.noise_floor + 0.02 * rms;
+        }
+
+        if self.hangover > 0 {
+            self.hangover -= 1;
+            return rms >= self.config.energy_threshold;
+        }
+
+        false
+    }
+}
+
+/// Rapport moyenne géométrique / moyenne arithmétique d'un spectre de puissance.
+pub fn spectral_flatness(power: &[f32]) -> f32 {
+    if power.is_empty() {
+        return 1.0;
+    }
+
+    let n = power.len() as f32;
+    let (log_sum, sum) = power.iter().fold((0.0_f32, 0.0_f32), |(log_sum, sum), &p| {
+        let p = p.max(1e-12);
+        (log_sum + p.ln(), sum + p)
+    });
+    let log_mean = log_sum / n;
+    let arithmetic_mean = sum / n;
+
+    if arithmetic_mean <= 1e-12 {
+        1.0
+    } else {
+        (log_mean.exp() / arithmetic_mean).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_spectrum_has_unit_flatness() {
+        let flatness = spectral_flatness(&[1.0; 64]);
+        assert!((flatness - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn single_peak_has_low_flatness() {
+        let mut power = vec![1e-6; 64];
+        power[10] = 1.0;
+        assert!(spectral_flatness(&power) < 0.05);
+    }
+
+    #[test]
+    fn empty_spectrum_is_considered_noise() {
+        assert_eq!(spectral_flatness(&[]), 1.0);
+    }
+
+    #[test]
+    fn hangover_keeps_voicing_after_loud_frames() {
+        let config = VadConfig::default();
+        let mut vad = VoiceActivityDetector::new(config);
+
+        assert!(vad.process(0.2, 0.1));
+        // Trame bruitée mais encore au-dessus du seuil: maintenue par le hangover
+        for _ in 0..config.hangover_frames {
+            assert!(vad.process(0.05, 0.9));
+        }
+        assert!(!vad.process(0.05, 0.9));
+    }
+
+    #[test]
+    fn quiet_frames_are_unvoiced() {
+        let mut vad = VoiceActivityDetector::new(VadConfig::default());
+        assert!(!vad.process(0.001, 0.1));
+    }
+}
@@ -0,0 +1,68 @@
+/// Écart H1–H2 en dB: niveau de la fondamentale moins celui de la deuxième
+/// harmonique, indice usuel du poids vocal. Une voix légère (fermeture
+/// glottique douce) a une fondamentale qui domine, donc un écart positif;
+/// une voix lourde, pressée, renforce les harmoniques et le fait baisser.
+///
+/// `magnitudes` sont les raies d'un spectre de `bin_hz` Hz de large. Chaque
+/// harmonique est le maximum à plus ou moins une demi-fondamentale de sa
+/// position théorique. `None` si la fondamentale est trop proche du bord
+/// pour mesurer H2.
+pub fn h1_h2_db(magnitudes: &[f32], f0: f32, bin_hz: f32) -> Option<f32> {
+    if f0 <= 0.0 || bin_hz <= 0.0 {
+        return None;
+    }
+    let harmonic = |rank: f32| -> Option<f32> {
+        let low = ((rank - 0.5) * f0 / bin_hz).round() as usize;
+        let high = ((rank + 0.5) * f0 / bin_hz).round() as usize;
+        let peak = magnitudes
+            .get(low..high)?
+            .iter()
+            .copied()
+            .fold(0.0_f32, f32::max);
+        (peak > 0.0).then_some(peak)
+    };
+    let h1 = harmonic(1.0)?;
+    let h2 = harmonic(2.0)?;
+    Some(20.0 * (h1 / h2).log10())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Spectre de raies: une harmonique de la hauteur donnée tous les `f0` Hz.
+    fn harmonics(f0: f32, bin_hz: f32, levels: &[f32]) -> Vec<f32> {
+        let mut magnitudes = vec![0.001; 512];
+        for (rank, &level) in levels.iter().enumerate() {
+            let bin = ((rank + 1) as f32 * f0 / bin_hz).round() as usize;
+            magnitudes[bin] = level;
+        }
+        magnitudes
+    }
+
+    #[test]
+    fn compares_the_first_two_harmonics() {
+        let light = harmonics(200.0, 10.0, &[1.0, 0.5, 0.2]);
+        let difference = h1_h2_db(&light, 200.0, 10.0).unwrap();
+        assert!((difference - 6.02).abs() < 0.1, "{}", difference);
+
+        let heavy = harmonics(200.0, 10.0, &[0.5, 1.0, 0.8]);
+        let difference = h1_h2_db(&heavy, 200.0, 10.0).unwrap();
+        assert!((difference + 6.02).abs() < 0.1, "{}", difference);
+    }
+
+    #[test]
+    fn tolerates_an_imprecise_fundamental() {
+        let magnitudes = harmonics(200.0, 10.0, &[1.0, 0.5]);
+        let difference = h1_h2_db(&magnitudes, 212.0, 10.0).unwrap();
+        assert!((difference - 6.02).abs() < 0.1, "{}", difference);
+    }
+
+    #[test]
+    fn needs_room_for_the_second_harmonic() {
+        let magnitudes = harmonics(200.0, 10.0, &[1.0]);
+        assert!(h1_h2_db(&magnitudes, 3000.0, 10.0).is_none());
+        assert!(h1_h2_db(&magnitudes, 0.0, 10.0).is_none());
+        assert!(h1_h2_db(&[0.0; 512], 200.0, 10.0).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::cues::CueCategory;
+
+/// Temps passé de l'autre côté d'une borne avant de signaler le changement:
+/// évite une rafale de bips autour de la limite.
+const TARGET_BEEP_HOLD_SECS: f32 = 0.2;
+const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.5;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Zoom global de l'interface.
+    pub ui_scale: f32,
+    /// Hauteur et niveau en gros caractères dans l'onglet direct.
+    pub large_readout: bool,
+    /// Bip à l'entrée et à la sortie de la plage cible.
+    pub target_beep: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            large_readout: false,
+            target_beep: false,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.ui_scale, UI_SCALE_RANGE)
+                        .step_by(0.05)
+                        .text("Taille de l'interface"),
+                )
+                .on_hover_text("Aussi avec Ctrl + et Ctrl −")
+                .changed();
+            if ui.button("100 %").clicked() {
+                self.ui_scale = 1.0;
+                changed = true;
+            }
+        });
+        changed |= ui
+            .checkbox(&mut self.large_readout, "Affichage en gros caractères de la hauteur")
+            .changed();
+        changed |= ui
+            .checkbox(
+                &mut self.target_beep,
+                "Bip à l'entrée et à la sortie de la plage cible",
+            )
+            .on_hover_text("Volumes réglables dans « Signaux sonores »")
+            .changed();
+        changed
+    }
+
+    /// Applique le zoom choisi, ou adopte celui changé au clavier.
+    pub fn sync_zoom(&mut self, ctx: &egui::Context, applied: &mut Option<f32>) -> bool {
+        if *applied != Some(self.ui_scale) {
+            self.ui_scale = self.ui_scale.clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
+            ctx.set_zoom_factor(self.ui_scale);
+            *applied = Some(self.ui_scale);
+            return false;
+        }
+        let zoom = ctx.zoom_factor();
+        if (zoom - self.ui_scale).abs() > f32::EPSILON {
+            self.ui_scale = zoom;
+            *applied = Some(zoom);
+            return true;
+        }
+        false
+    }
+}
+
+/// Nom lu par les lecteurs d'écran, pour un contrôle sans texte (icône seule)
+/// ou une zone dessinée à la main.
+pub fn describe(response: &egui::Response, typ: egui::WidgetType, label: &str) {
+    let enabled = response.enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(typ, enabled, label));
+}
+
+/// Détecte l'entrée et la sortie de la plage cible, pour un signal sonore.
+#[derive(Default)]
+pub struct TargetBeep {
+    inside: Option<bool>,
+    pending_secs: f32,
+}
+
+impl TargetBeep {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// `in_range` vaut `None` hors voix: l'état est conservé jusqu'à la
+    /// reprise, qui ne bipe que si elle se fait de l'autre côté.
+    pub fn push(&mut self, in_range: Option<bool>, frame_secs: f32) -> Option<CueCategory> {
+        let in_range = in_range?;
+        let Some(inside) = self.inside else {
+            self.inside = Some(in_range);
+            return None;
+        };
+        if in_range == inside {
+            self.pending_secs = 0.0;
+            return None;
+        }
+        self.pending_secs += frame_secs;
+        if self.pending_secs < TARGET_BEEP_HOLD_SECS {
+            return None;
+        }
+        self.inside = Some(in_range);
+        self.pending_secs = 0.0;
+        Some(if in_range {
+            CueCategory::TargetEnter
+        } else {
+            CueCategory::TargetLeave
+        })
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use feminizer_voice_core::{
+    AnalysisConfig, FrequencyData, FrequencyProcessor, Resampler, VadConfig,
+};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle, Thread};
+use std::time::{Duration, Instant};
+
+use crate::load::{Degradation, SharedLoad};
+
+/// Signal capturé que la file peut retenir pendant que l'analyse rattrape
+/// son retard.
+const RING_SECS: f32 = 1.0;
+/// Réveil de secours si un signal du callback se perd.
+const IDLE_WAIT: Duration = Duration::from_millis(50);
+
+/// File circulaire sans verrou entre le callback (seul producteur) et le
+/// thread d'analyse (seul consommateur). Les échantillons sont rangés bit à
+/// bit dans des atomiques: le callback n'attend ni n'alloue jamais.
+pub struct SampleRing {
+    slots: Box<[AtomicU32]>,
+    /// Échantillons écrits et lus depuis l'ouverture, modulo `usize`.
+    written: AtomicUsize,
+    read: AtomicUsize,
+    /// Échantillons écartés faute de place.
+    overflowed: AtomicU64,
+}
+
+impl SampleRing {
+    fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect(),
+            written: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            overflowed: AtomicU64::new(0),
+        }
+    }
+
+    /// Côté callback. Quand la file est pleine, la fin du bloc est écartée.
+    pub fn push(&self, samples: &[f32]) {
+        let capacity = self.slots.len();
+        let written = self.written.load(Ordering::Relaxed);
+        let read = self.read.load(Ordering::Acquire);
+        let free = capacity - written.wrapping_sub(read);
+        let accepted = samples.len().min(free);
+        for (offset, &sample) in samples[..accepted].iter().enumerate() {
+            let slot = written.wrapping_add(offset) % capacity;
+            self.slots[slot].store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.written.store(written.wrapping_add(accepted), Ordering::Release);
+        if accepted < samples.len() {
+            let lost = (samples.len() - accepted) as u64;
+            self.overflowed.fetch_add(lost, Ordering::Relaxed);
+        }
+    }
+
+    /// Côté analyse: ajoute à `out` tout ce qui est en attente.
+    fn pop_into(&self, out: &mut Vec<f32>) {
+        let capacity = self.slots.len();
+        let read = self.read.load(Ordering::Relaxed);
+        let written = self.written.load(Ordering::Acquire);
+        let pending = written.wrapping_sub(read);
+        out.extend((0..pending).map(|offset| {
+            let slot = read.wrapping_add(offset) % capacity;
+            f32::from_bits(self.slots[slot].load(Ordering::Relaxed))
+        }));
+        self.read.store(written, Ordering::Release);
+    }
+
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed.load(Ordering::Relaxed)
+    }
+}
+
+/// Destinations de l'analyse, partagées avec l'interface.
+pub struct WorkerTargets {
+    pub frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+    pub vad_config: Arc<Mutex<VadConfig>>,
+    pub analysis: Arc<Mutex<AnalysisConfig>>,
+    pub load: SharedLoad,
+    pub spare_spectra: Arc<Mutex<Vec<Vec<f32>>>>,
+}
+
+/// Thread d'analyse d'un flux: fenêtrage, FFT et détection de hauteur hors
+/// du callback audio, qui ne fait plus que remplir la file.
+pub struct AnalysisWorker {
+    ring: Arc<SampleRing>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AnalysisWorker {
+    pub fn spawn(sample_rate: f32, targets: WorkerTargets) -> Result<Self> {
+        let ring = Arc::new(SampleRing::new((sample_rate * RING_SECS) as usize));
+        let running = Arc::new(AtomicBool::new(true));
+        let handle = thread::Builder::new().name("analyse audio".to_string()).spawn({
+            let ring = ring.clone();
+            let running = running.clone();
+            move || analysis_loop(&ring, &running, sample_rate, targets)
+        })?;
+        Ok(Self {
+            ring,
+            running,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn ring(&self) -> Arc<SampleRing> {
+        self.ring.clone()
+    }
+
+    /// À réveiller après chaque bloc poussé dans la file.
+    pub fn thread(&self) -> Option<Thread> {
+        self.handle.as_ref().map(|handle| handle.thread().clone())
+    }
+}
+
+impl Drop for AnalysisWorker {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+    }
+}
+
+fn analysis_loop(
+    ring: &SampleRing,
+    running: &AtomicBool,
+    sample_rate: f32,
+    targets: WorkerTargets,
+) {
+    let initial = targets
+        .analysis
+        .lock()
+        .map(|config| *config)
+        .unwrap_or_default()
+        .sanitized();
+    // Un périphérique à 96 kHz est analysé au taux visé, comme les autres
+    let mut resampler = Resampler::new(sample_rate, initial.resample_hz);
+    let mut processor =
+        FrequencyProcessor::new(resampler.output_rate(), initial, VadConfig::default());
+    // Tampons réutilisés d'un bloc à l'autre: pas d'allocation une fois la
+    // taille de bloc atteinte
+    let mut samples = Vec::new();
+    let mut resampled = Vec::new();
+    let mut level = Degradation::Full;
+
+    while running.load(Ordering::Relaxed) {
+        thread::park_timeout(IDLE_WAIT);
+        samples.clear();
+        ring.pop_into(&mut samples);
+        if samples.is_empty() {
+            continue;
+        }
+
+        let started = Instant::now();
+        // Paramètres modifiés depuis les réglages: on repart d'un analyseur neuf
+        if let Ok(config) = targets.analysis.lock() {
+            let config = degraded(config.sanitized(), level);
+            if config != processor.config() {
+                resampler = Resampler::new(sample_rate, config.resample_hz);
+                processor =
+                    FrequencyProcessor::new(resampler.output_rate(), config, VadConfig::default());
+            }
+        }
+        if let Ok(config) = targets.vad_config.lock() {
+            processor.set_vad_config(*config);
+        }
+        processor.set_spectrum_enabled(level < Degradation::NoSpectrogram);
+        processor.set_formants_enabled(level < Degradation::NoFormants);
+        if let Ok(mut spare) = targets.spare_spectra.lock() {
+            while let Some(spectrum) = spare.pop() {
+                processor.recycle_spectrum(spectrum);
+            }
+        }
+        resampled.clear();
+        resampler.process(&samples, &mut resampled);
+
+        let (mut produced, mut dropped) = (false, false);
+        if let Some(result) = processor.process_samples(&resampled) {
+            produced = true;
+            // Trame précédente jamais lue: son spectre resservira
+            if let Ok(mut data_guard) = targets.frequency_data.lock()
+                && let Some(unread) = data_guard.replace(result)
+            {
+                dropped = true;
+                processor.recycle_spectrum(unread.spectrum);
+            }
+        }
+
+        let block = Duration::from_secs_f32(samples.len() as f32 / sample_rate);
+        if let Ok(mut load) = targets.load.lock() {
+            load.record(started.elapsed(), block);
+            load.frames += produced as u64;
+            load.dropped_frames += dropped as u64;
+            load.lost_samples = ring.overflowed();
+            level = load.level();
+        }
+    }
+}
+
+/// Réglages effectivement appliqués au palier de délestage courant.
+fn degraded(config: AnalysisConfig, level: Degradation) -> AnalysisConfig {
+    if level < Degradation::WiderHop {
+        return config;
+    }
+    AnalysisConfig {
+        hop_size: (config.hop_size * 2).min(config.window_size).max(config.hop_size),
+        ..config
+    }
+}
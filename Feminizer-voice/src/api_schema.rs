@@ -0,0 +1,31 @@
+use anyhow::Result;
+use schemars::{JsonSchema, schema_for};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::broadcast::{LiveMessage, PROTOCOL_VERSION};
+use crate::headless::HeadlessLine;
+use crate::schema::Versioned;
+use crate::session::{SESSION_SCHEMA, SessionSummary};
+use crate::settings::{SETTINGS_SCHEMA, Settings};
+
+fn write_schema<T: JsonSchema>(dir: &Path, name: &str, version: u32) -> Result<PathBuf> {
+    let mut schema = schema_for!(T);
+    schema.insert("x-version".to_string(), version.into());
+
+    let path = dir.join(format!("{}.schema.json", name));
+    fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+    Ok(path)
+}
+
+/// Écrit les schémas JSON des interfaces publiques (WebSocket, sortie
+/// headless, fichiers de session et de réglages) dans `dir`.
+pub fn export_schemas(dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    Ok(vec![
+        write_schema::<LiveMessage>(dir, "live-message", PROTOCOL_VERSION)?,
+        write_schema::<HeadlessLine>(dir, "headless-line", PROTOCOL_VERSION)?,
+        write_schema::<Versioned<'static, SessionSummary>>(dir, "session", SESSION_SCHEMA.version())?,
+        write_schema::<Versioned<'static, Settings>>(dir, "settings", SETTINGS_SCHEMA.version())?,
+    ])
+}
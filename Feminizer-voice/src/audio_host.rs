@@ -0,0 +1,45 @@
+use anyhow::{Result, anyhow};
+use cpal::HostId;
+use std::sync::Mutex;
+
+/// Pilote choisi pour tous les flux audio de l'application; `None` pour
+/// celui du système. Un seul pour tout le processus: ASIO ou JACK ne
+/// partagent pas toujours une carte avec le pilote par défaut.
+static SELECTED: Mutex<Option<HostId>> = Mutex::new(None);
+
+/// Pilotes utilisables sur cette machine avec les fonctions compilées
+/// (`jack`, `asio`).
+pub fn available() -> Vec<&'static str> {
+    cpal::available_hosts().into_iter().map(|id| id.name()).collect()
+}
+
+pub fn default_name() -> &'static str {
+    cpal::default_host().id().name()
+}
+
+/// Choisit le pilote des prochains flux ouverts. S'il est introuvable ou ne
+/// démarre pas, le pilote du système le remplace et l'erreur est renvoyée.
+pub fn select(name: Option<&str>) -> Result<()> {
+    let id = name.map(find).transpose();
+    if let Ok(mut selected) = SELECTED.lock() {
+        *selected = id.as_ref().ok().copied().flatten();
+    }
+    id.map(|_| ())
+}
+
+fn find(name: &str) -> Result<HostId> {
+    let id = cpal::available_hosts()
+        .into_iter()
+        .find(|id| id.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow!("pilote « {} » non disponible", name))?;
+    cpal::host_from_id(id).map_err(|e| anyhow!("pilote « {} »: {}", name, e))?;
+    Ok(id)
+}
+
+/// Pilote à utiliser pour ouvrir un flux, celui du système en repli.
+pub fn host() -> cpal::Host {
+    let selected = SELECTED.lock().ok().and_then(|selected| *selected);
+    selected
+        .and_then(|id| cpal::host_from_id(id).ok())
+        .unwrap_or_else(cpal::default_host)
+}
@@ -1,33 +1,217 @@
 use anyhow::Result;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Stream, StreamConfig};
-use rustfft::{FftPlanner, num_complex::Complex};
+use cpal::{Device, I24, SampleRate, Stream, StreamConfig, SupportedStreamConfig};
+use feminizer_voice_core::{AnalysisConfig, FrequencyData, VadConfig};
 use std::sync::{Arc, Mutex};
+use tracing::{error, info, warn};
+
+use crate::analysis_worker::{AnalysisWorker, WorkerTargets};
+use crate::audio_host;
+use crate::load::SharedLoad;
+use crate::monitor::{MonitorTap, push_to_tap};
+use crate::session_audio::{AudioTap, push_to_audio_tap};
+
+const FORMAT_PREFERENCE: [cpal::SampleFormat; 11] = [
+    cpal::SampleFormat::F32,
+    cpal::SampleFormat::I16,
+    cpal::SampleFormat::I32,
+    cpal::SampleFormat::I24,
+    cpal::SampleFormat::F64,
+    cpal::SampleFormat::U16,
+    cpal::SampleFormat::I8,
+    cpal::SampleFormat::U8,
+    cpal::SampleFormat::U32,
+    cpal::SampleFormat::I64,
+    cpal::SampleFormat::U64,
+];
+
+const LEVEL_DECAY: f32 = 0.9;
+const CLIP_LEVEL: f32 = 0.999;
+/// Spectres rendus par l'interface en attente de réutilisation.
+const MAX_SPARE_SPECTRA: usize = 4;
+
+/// Mesures de l'entrée brute, tous canaux confondus, accumulées jusqu'à ce
+/// que l'interface les relève.
+#[derive(Clone, Copy, Default)]
+pub struct InputMeter {
+    pub peak: f32,
+    pub sum_squares: f32,
+    pub samples: usize,
+    pub clipped: u64,
+}
+
+#[derive(Default)]
+pub struct InputChannels {
+    /// Canal analysé, `None` pour la moyenne de tous les canaux.
+    pub selected: Option<usize>,
+    /// Crête récente de chaque canal, pour aider à choisir.
+    pub levels: Vec<f32>,
+    pub meter: InputMeter,
+}
+
+pub type SharedInputChannels = Arc<Mutex<InputChannels>>;
+type SpareSpectra = Arc<Mutex<Vec<Vec<f32>>>>;
 
-pub struct FrequencyData {
-    pub dominant_frequency: f32,
-    pub amplitude: f32,
-    pub spectrum: Vec<f32>,
+/// Source d'un second flux analysé à côté du micro.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecondaryInput {
+    Input(String),
+    /// Ce que joue une sortie, capturé en boucle (WASAPI, sous Windows).
+    Loopback(String),
 }
 
+impl SecondaryInput {
+    pub fn label(&self) -> String {
+        match self {
+            SecondaryInput::Input(name) => name.clone(),
+            SecondaryInput::Loopback(name) => format!("🔁 {}", name),
+        }
+    }
+}
+
+// Destinations partagées entre le callback audio et l'interface
+struct StreamTargets {
+    frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+    vad_config: Arc<Mutex<VadConfig>>,
+    /// Retour casque et audio de session: pour le micro seulement.
+    monitor_tap: Option<MonitorTap>,
+    audio_tap: Option<AudioTap>,
+    channels: SharedInputChannels,
+    analysis: Arc<Mutex<AnalysisConfig>>,
+    stream_error: Arc<Mutex<Option<String>>>,
+    load: SharedLoad,
+    spare_spectra: SpareSpectra,
+}
+
+// Le flux est fermé avant l'arrêt du thread qu'il alimente
 pub struct AudioProcessor {
     _stream: Stream,
+    _worker: AnalysisWorker,
+    sample_rate: f32,
+    stream_error: Arc<Mutex<Option<String>>>,
+    load: SharedLoad,
+    spare_spectra: SpareSpectra,
 }
 
 impl AudioProcessor {
-    pub fn new(frequency_data: Arc<Mutex<Option<FrequencyData>>>) -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique d'entrée audio trouvé"))?;
+    pub fn new(
+        device_name: Option<&str>,
+        frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+        vad_config: Arc<Mutex<VadConfig>>,
+        monitor_tap: MonitorTap,
+        audio_tap: AudioTap,
+        channels: SharedInputChannels,
+        analysis: Arc<Mutex<AnalysisConfig>>,
+    ) -> Result<Self> {
+        let host = audio_host::host();
+        let device = match device_name {
+            Some(name) => host
+                .input_devices()?
+                .find(|device| device.name().is_ok_and(|n| n == name))
+                .ok_or_else(|| anyhow::anyhow!("Périphérique d'entrée introuvable: {}", name))?,
+            None => host
+                .default_input_device()
+                .ok_or_else(|| anyhow::anyhow!("Aucun périphérique d'entrée audio trouvé"))?,
+        };
 
-        let config = device.default_input_config()?;
+        let targets = StreamTargets {
+            frequency_data,
+            vad_config,
+            monitor_tap: Some(monitor_tap),
+            audio_tap: Some(audio_tap),
+            channels,
+            analysis,
+            stream_error: Default::default(),
+            load: Default::default(),
+            spare_spectra: Arc::new(Mutex::new(Vec::with_capacity(MAX_SPARE_SPECTRA))),
+        };
+
+        let default_config = device.default_input_config()?;
+        Self::start(&device, &default_config, targets)
+    }
+
+    /// Second flux, analysé à part: sa hauteur se superpose à celle du micro
+    /// sans passer par le retour casque ni l'audio de session.
+    pub fn secondary(
+        source: &SecondaryInput,
+        frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+        vad_config: Arc<Mutex<VadConfig>>,
+        analysis: Arc<Mutex<AnalysisConfig>>,
+    ) -> Result<Self> {
+        let host = audio_host::host();
+        let (device, default_config) = match source {
+            SecondaryInput::Input(name) => {
+                let device = host
+                    .input_devices()?
+                    .find(|device| device.name().is_ok_and(|n| &n == name))
+                    .ok_or_else(|| anyhow::anyhow!("Périphérique d'entrée introuvable: {}", name))?;
+                let config = device.default_input_config()?;
+                (device, config)
+            }
+            // Un flux d'entrée sur une sortie capture ce qu'elle joue
+            SecondaryInput::Loopback(name) => {
+                let device = host
+                    .output_devices()?
+                    .find(|device| device.name().is_ok_and(|n| &n == name))
+                    .ok_or_else(|| anyhow::anyhow!("Sortie introuvable: {}", name))?;
+                let config = device.default_output_config()?;
+                (device, config)
+            }
+        };
+        let targets = StreamTargets {
+            frequency_data,
+            vad_config,
+            monitor_tap: None,
+            audio_tap: None,
+            channels: Default::default(),
+            analysis,
+            stream_error: Default::default(),
+            load: Default::default(),
+            spare_spectra: Arc::new(Mutex::new(Vec::with_capacity(MAX_SPARE_SPECTRA))),
+        };
+        Self::start(&device, &default_config, targets)
+    }
+
+    fn start(
+        device: &Device,
+        default_config: &SupportedStreamConfig,
+        targets: StreamTargets,
+    ) -> Result<Self> {
+        let opened = Self::open_stream(device, default_config, &targets);
+        let (stream, worker, sample_rate) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                warn!(
+                    "Configuration par défaut inutilisable ({}), recherche d'une alternative",
+                    e
+                );
+                let fallback = Self::fallback_config(device)?;
+                Self::open_stream(device, &fallback, &targets)?
+            }
+        };
+
+        Ok(AudioProcessor {
+            _stream: stream,
+            _worker: worker,
+            sample_rate,
+            stream_error: targets.stream_error,
+            load: targets.load,
+            spare_spectra: targets.spare_spectra,
+        })
+    }
+
+    fn open_stream(
+        device: &Device,
+        config: &SupportedStreamConfig,
+        targets: &StreamTargets,
+    ) -> Result<(Stream, AnalysisWorker, f32)> {
         let sample_rate = config.sample_rate().0 as f32;
-        let channels = config.channels() as usize;
 
-        println!(
-            "Configuration audio: {} Hz, {} canaux",
-            sample_rate, channels
+        info!(
+            "Configuration audio: {} Hz, {} canaux, {:?}",
+            sample_rate,
+            config.channels(),
+            config.sample_format()
         );
 
         let stream_config = StreamConfig {
@@ -36,182 +220,226 @@ impl AudioProcessor {
             buffer_size: cpal::BufferSize::Fixed(1024),
         };
 
-        let processor = FrequencyProcessor::new(sample_rate, 1024);
-        let processor = Arc::new(Mutex::new(processor));
-
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => {
-                Self::build_stream::<f32>(&device, &stream_config, processor, frequency_data)?
-            }
-            cpal::SampleFormat::I16 => {
-                Self::build_stream::<i16>(&device, &stream_config, processor, frequency_data)?
-            }
-            cpal::SampleFormat::U16 => {
-                Self::build_stream::<u16>(&device, &stream_config, processor, frequency_data)?
-            }
+        let (stream, worker) = match config.sample_format() {
+            cpal::SampleFormat::I8 => Self::build_stream::<i8>(device, &stream_config, targets)?,
+            cpal::SampleFormat::I16 => Self::build_stream::<i16>(device, &stream_config, targets)?,
+            cpal::SampleFormat::I24 => Self::build_stream::<I24>(device, &stream_config, targets)?,
+            cpal::SampleFormat::I32 => Self::build_stream::<i32>(device, &stream_config, targets)?,
+            cpal::SampleFormat::I64 => Self::build_stream::<i64>(device, &stream_config, targets)?,
+            cpal::SampleFormat::U8 => Self::build_stream::<u8>(device, &stream_config, targets)?,
+            cpal::SampleFormat::U16 => Self::build_stream::<u16>(device, &stream_config, targets)?,
+            cpal::SampleFormat::U32 => Self::build_stream::<u32>(device, &stream_config, targets)?,
+            cpal::SampleFormat::U64 => Self::build_stream::<u64>(device, &stream_config, targets)?,
+            cpal::SampleFormat::F32 => Self::build_stream::<f32>(device, &stream_config, targets)?,
+            cpal::SampleFormat::F64 => Self::build_stream::<f64>(device, &stream_config, targets)?,
             format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
         };
 
         stream.play()?;
+        Ok((stream, worker, sample_rate))
+    }
 
-        Ok(AudioProcessor { _stream: stream })
+    // Meilleure configuration exposée par le périphérique: format courant, 44,1/48 kHz si possible
+    fn fallback_config(device: &Device) -> Result<SupportedStreamConfig> {
+        let preferred_rates = [48000, 44100];
+
+        device
+            .supported_input_configs()?
+            .filter_map(|range| {
+                let rank = FORMAT_PREFERENCE
+                    .iter()
+                    .position(|&format| format == range.sample_format())?;
+                let (min, max) = (range.min_sample_rate().0, range.max_sample_rate().0);
+                let rate = preferred_rates
+                    .iter()
+                    .copied()
+                    .find(|rate| (min..=max).contains(rate))
+                    .unwrap_or(max.min(96000).max(min));
+                Some((rank, range.with_sample_rate(SampleRate(rate))))
+            })
+            .min_by_key(|(rank, config)| (*rank, config.channels()))
+            .map(|(_, config)| config)
+            .ok_or_else(|| anyhow::anyhow!("Aucune configuration d'entrée utilisable"))
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Erreur signalée par le flux (périphérique débranché...), une seule fois.
+    pub fn take_error(&self) -> Option<String> {
+        self.stream_error.lock().ok().and_then(|mut error| error.take())
+    }
+
+    pub fn load(&self) -> SharedLoad {
+        self.load.clone()
+    }
+
+    /// Rend le spectre d'une trame exploitée: le callback y écrira une trame
+    /// suivante au lieu d'allouer.
+    pub fn recycle_spectrum(&self, spectrum: Vec<f32>) {
+        if let Ok(mut spare) = self.spare_spectra.lock()
+            && spare.len() < MAX_SPARE_SPECTRA
+        {
+            spare.push(spectrum);
+        }
+    }
+
+    pub fn input_device_names() -> Result<Vec<String>> {
+        let host = audio_host::host();
+        Ok(host
+            .input_devices()?
+            .filter_map(|device| device.name().ok())
+            .collect())
+    }
+
+    /// Nom du périphérique d'entrée par défaut du système.
+    pub fn default_input_name() -> Option<String> {
+        audio_host::host().default_input_device()?.name().ok()
+    }
+
+    /// Sources possibles d'un second flux. Sous Linux, les moniteurs
+    /// PulseAudio ou PipeWire apparaissent déjà parmi les entrées.
+    pub fn secondary_sources() -> Result<Vec<SecondaryInput>> {
+        let mut sources: Vec<SecondaryInput> = Self::input_device_names()?
+            .into_iter()
+            .map(SecondaryInput::Input)
+            .collect();
+        if cfg!(target_os = "windows") {
+            let host = audio_host::host();
+            sources.extend(
+                host.output_devices()?
+                    .filter_map(|device| device.name().ok())
+                    .map(SecondaryInput::Loopback),
+            );
+        }
+        Ok(sources)
     }
 
     fn build_stream<T>(
         device: &Device,
         config: &StreamConfig,
-        processor: Arc<Mutex<FrequencyProcessor>>,
-        frequency_data: Arc<Mutex<Option<FrequencyData>>>,
-    ) -> Result<Stream>
+        targets: &StreamTargets,
+    ) -> Result<(Stream, AnalysisWorker)>
     where
         T: cpal::Sample + cpal::SizedSample + Send + 'static,
         f32: cpal::FromSample<T>,
     {
         let channels = config.channels as usize;
+        let sample_rate = config.sample_rate.0 as f32;
 
+        let worker = AnalysisWorker::spawn(
+            sample_rate,
+            WorkerTargets {
+                frequency_data: targets.frequency_data.clone(),
+                vad_config: targets.vad_config.clone(),
+                analysis: targets.analysis.clone(),
+                load: targets.load.clone(),
+                spare_spectra: targets.spare_spectra.clone(),
+            },
+        )?;
+        let ring = worker.ring();
+        let analysis_thread = worker.thread();
+        // Tampon réutilisé d'un callback à l'autre: pas d'allocation une fois
+        // la taille de bloc atteinte
+        let mut samples = Vec::new();
+        let monitor_tap = targets.monitor_tap.clone();
+        let audio_tap = targets.audio_tap.clone();
+        let input_channels = targets.channels.clone();
+        let stream_error = targets.stream_error.clone();
+        let load = targets.load.clone();
+        let mut selected = None;
+
+        // Le callback ne fait que répartir le signal: l'analyse tourne sur son
+        // propre thread, sans risque de faire décrocher le son
         let stream = device.build_input_stream(
             config,
-            move |data: &[T], _: &cpal::InputCallbackInfo| {
-                let samples: Vec<f32> = if channels == 1 {
-                    data.iter()
-                        .map(|&s| cpal::Sample::to_sample::<f32>(s))
-                        .collect()
-                } else {
-                    data.chunks(channels)
-                        .map(|chunk| {
-                            let sum: f32 = chunk
-                                .iter()
-                                .map(|&s| cpal::Sample::to_sample::<f32>(s))
-                                .sum();
-                            sum / channels as f32
-                        })
-                        .collect()
-                };
-
-                if let Ok(mut proc) = processor.try_lock() {
-                    if let Some(result) = proc.process_samples(&samples) {
-                        if let Ok(mut data_guard) = frequency_data.try_lock() {
-                            *data_guard = Some(result);
-                        }
-                    }
+            move |data: &[T], info: &cpal::InputCallbackInfo| {
+                if let Ok(mut shared) = input_channels.try_lock() {
+                    update_levels(&mut shared, data, channels);
+                    selected = shared.selected.filter(|&channel| channel < channels);
+                }
+
+                to_mono(data, channels, selected, &mut samples);
+
+                if let Some(tap) = &monitor_tap {
+                    push_to_tap(tap, &samples, sample_rate);
+                }
+                if let Some(tap) = &audio_tap {
+                    push_to_audio_tap(tap, &samples);
+                }
+                ring.push(&samples);
+                if let Some(thread) = &analysis_thread {
+                    thread.unpark();
+                }
+
+                let timestamp = info.timestamp();
+                if let Ok(mut load) = load.try_lock() {
+                    load.input_latency = timestamp.callback.duration_since(&timestamp.capture);
+                }
+            },
+            move |err| {
+                error!("Erreur du flux audio: {}", err);
+                if let Ok(mut error) = stream_error.lock() {
+                    *error = Some(err.to_string());
                 }
             },
-            |err| eprintln!("Erreur du stream audio: {}", err),
             None,
         )?;
 
-        Ok(stream)
+        Ok((stream, worker))
     }
 }
 
-struct FrequencyProcessor {
-    sample_rate: f32,
-    buffer_size: usize,
-    buffer: Vec<f32>,
-    window: Vec<f32>,
-    fft_planner: FftPlanner<f32>,
-    buffer_pos: usize,
-}
-
-impl FrequencyProcessor {
-    fn new(sample_rate: f32, buffer_size: usize) -> Self {
-        let window: Vec<f32> = (0..buffer_size)
-            .map(|i| {
-                let angle = 2.0 * std::f32::consts::PI * i as f32 / (buffer_size - 1) as f32;
-                0.5 * (1.0 - angle.cos())
-            })
-            .collect();
-
-        Self {
-            sample_rate,
-            buffer_size,
-            buffer: vec![0.0; buffer_size],
-            window,
-            fft_planner: FftPlanner::new(),
-            buffer_pos: 0,
+/// Convertit le bloc entrelacé en mono dans `out`, sans allouer tant que sa
+/// capacité suffit.
+fn to_mono<T>(data: &[T], channels: usize, selected: Option<usize>, out: &mut Vec<f32>)
+where
+    T: cpal::Sample,
+    f32: cpal::FromSample<T>,
+{
+    out.clear();
+    match selected {
+        Some(channel) => out.extend(
+            data.iter()
+                .skip(channel)
+                .step_by(channels)
+                .map(|&s| cpal::Sample::to_sample::<f32>(s)),
+        ),
+        None if channels == 1 => {
+            out.extend(data.iter().map(|&s| cpal::Sample::to_sample::<f32>(s)))
         }
+        None => out.extend(data.chunks(channels).map(|chunk| {
+            let sum: f32 = chunk
+                .iter()
+                .map(|&s| cpal::Sample::to_sample::<f32>(s))
+                .sum();
+            sum / channels as f32
+        })),
     }
+}
 
-    fn process_samples(&mut self, samples: &[f32]) -> Option<FrequencyData> {
-        for &sample in samples {
-            self.buffer[self.buffer_pos] = sample;
-            self.buffer_pos = (self.buffer_pos + 1) % self.buffer_size;
-
-            if self.buffer_pos == 0 {
-                return Some(self.analyze_frequency());
-            }
-        }
-        None
+fn update_levels<T>(shared: &mut InputChannels, data: &[T], channels: usize)
+where
+    T: cpal::Sample,
+    f32: cpal::FromSample<T>,
+{
+    shared.levels.resize(channels, 0.0);
+    for level in shared.levels.iter_mut() {
+        *level *= LEVEL_DECAY;
     }
-
-
-
-    fn analyze_frequency(&mut self) -> FrequencyData {
-        let windowed: Vec<Complex<f32>> = self
-            .buffer
-            .iter()
-            .zip(self.window.iter())
-            .map(|(&sample, &window_val)| Complex::new(sample * window_val, 0.0))
-            .collect();
-
-        let mut fft_input = windowed;
-        let fft = self.fft_planner.plan_fft_forward(self.buffer_size);
-        fft.process(&mut fft_input);
-
-        let spectrum: Vec<f32> = fft_input[..self.buffer_size / 2]
-            .iter()
-            .map(|c| c.norm())
-            .collect();
-
-        let max_val = spectrum.iter().copied().fold(0.0_f32, f32::max);
-        let normalized_spectrum = if max_val > 0.0 {
-            spectrum.iter().map(|x| x / max_val).collect()
-        } else {
-            vec![0.0; spectrum.len()]
-        };
-
-        let min_bin = (50.0 * self.buffer_size as f32 / self.sample_rate) as usize;
-        let max_bin = (450.0 * self.buffer_size as f32 / self.sample_rate) as usize;
-        let max_bin = max_bin.min(spectrum.len() - 1);
-
-        let mut max_magnitude = 0.0f32;
-        let mut dominant_bin = 0;
-
-        for i in min_bin..=max_bin {
-            if spectrum[i] > max_magnitude {
-                max_magnitude = spectrum[i];
-                dominant_bin = i;
+    let meter = &mut shared.meter;
+    for frame in data.chunks(channels) {
+        for (level, &sample) in shared.levels.iter_mut().zip(frame) {
+            let value = cpal::Sample::to_sample::<f32>(sample);
+            let magnitude = value.abs();
+            *level = level.max(magnitude);
+            meter.peak = meter.peak.max(magnitude);
+            meter.sum_squares += value * value;
+            meter.samples += 1;
+            if magnitude >= CLIP_LEVEL {
+                meter.clipped += 1;
             }
         }
-
-        let dominant_frequency = if dominant_bin > 0 && dominant_bin < spectrum.len() - 1 {
-            let y1 = spectrum[dominant_bin - 1];
-            let y2 = spectrum[dominant_bin];
-            let y3 = spectrum[dominant_bin + 1];
-
-            let a = (y1 - 2.0 * y2 + y3) / 2.0;
-            let b = (y3 - y1) / 2.0;
-
-            let x_offset = if a != 0.0 { -b / (2.0 * a) } else { 0.0 };
-            let bin_frequency = dominant_bin as f32 * self.sample_rate / self.buffer_size as f32;
-            let frequency_resolution = self.sample_rate / self.buffer_size as f32;
-
-            bin_frequency + x_offset * frequency_resolution
-        } else {
-            dominant_bin as f32 * self.sample_rate / self.buffer_size as f32
-        };
-
-        let rms: f32 = self.buffer.iter().map(|&x| x * x).sum::<f32>() / self.buffer.len() as f32;
-        let amplitude = rms.sqrt();
-
-        FrequencyData {
-            dominant_frequency: if max_magnitude > 0.001 {
-                dominant_frequency
-            } else {
-                0.0
-            },
-            amplitude,
-            spectrum: normalized_spectrum,
-        }
     }
 }
@@ -0,0 +1,101 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AutoCaptureSettings {
+    /// Lancer l'enregistrement dès l'ouverture de l'application.
+    pub start_on_launch: bool,
+    pub stop_on_silence: bool,
+    /// Silence continu, en minutes, avant l'arrêt automatique.
+    pub silence_minutes: u32,
+}
+
+impl Default for AutoCaptureSettings {
+    fn default() -> Self {
+        Self {
+            start_on_launch: false,
+            stop_on_silence: false,
+            silence_minutes: 5,
+        }
+    }
+}
+
+impl AutoCaptureSettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = ui
+            .checkbox(&mut self.start_on_launch, "Démarrer l'enregistrement à l'ouverture")
+            .changed();
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.stop_on_silence, "Arrêter après")
+                .on_hover_text("La session est enregistrée comme avec le bouton « Arrêter »")
+                .changed();
+            changed |= ui
+                .add_enabled(
+                    self.stop_on_silence,
+                    egui::DragValue::new(&mut self.silence_minutes)
+                        .range(1..=60)
+                        .suffix(" min"),
+                )
+                .changed();
+            ui.label("de silence");
+        });
+        changed
+    }
+
+    fn silence_limit(&self) -> Option<Duration> {
+        self.stop_on_silence
+            .then(|| Duration::from_secs(60 * self.silence_minutes.max(1) as u64))
+    }
+}
+
+/// Durée du silence en cours pendant l'enregistrement.
+pub struct SilenceWatch {
+    last_voice: Instant,
+    /// Silence qui a provoqué le dernier arrêt automatique.
+    stopped_after: Option<Duration>,
+}
+
+impl Default for SilenceWatch {
+    fn default() -> Self {
+        Self {
+            last_voice: Instant::now(),
+            stopped_after: None,
+        }
+    }
+}
+
+impl SilenceWatch {
+    /// À appeler au démarrage de l'enregistrement.
+    pub fn reset(&mut self) {
+        self.last_voice = Instant::now();
+        self.stopped_after = None;
+    }
+
+    /// Une trame voisée a été captée à `captured_at`.
+    pub fn heard(&mut self, captured_at: Instant) {
+        self.last_voice = self.last_voice.max(captured_at);
+    }
+
+    /// Renvoie `true` quand le silence dépasse la limite réglée: l'appelant
+    /// arrête alors l'enregistrement.
+    pub fn expired(&mut self, settings: &AutoCaptureSettings) -> bool {
+        let Some(limit) = settings.silence_limit() else {
+            return false;
+        };
+        let silent = self.last_voice.elapsed();
+        if silent < limit {
+            return false;
+        }
+        self.stopped_after = Some(silent);
+        true
+    }
+
+    pub fn stopped_after(&self) -> Option<Duration> {
+        self.stopped_after
+    }
+}
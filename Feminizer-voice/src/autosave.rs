@@ -0,0 +1,234 @@
+use anyhow::Result;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::dates::DateTime;
+use crate::paths;
+use crate::report::format_duration;
+use crate::session::SessionStats;
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+const FILE_NAME: &str = "session_autosave.json";
+
+fn autosave_path() -> Result<PathBuf> {
+    Ok(paths::profile_dir()?.join(FILE_NAME))
+}
+
+/// Écrit à côté puis renomme: un plantage pendant l'écriture laisse la
+/// sauvegarde précédente intacte.
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let partial = path.with_extension("json.tmp");
+    fs::write(&partial, contents)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[derive(Serialize)]
+struct DraftRef<'a> {
+    saved_at: u64,
+    elapsed_secs: f32,
+    stats: &'a SessionStats,
+}
+
+/// Séance en cours telle qu'elle était à la dernière sauvegarde.
+#[derive(Deserialize)]
+struct SessionDraft {
+    saved_at: u64,
+    elapsed_secs: f32,
+    stats: SessionStats,
+}
+
+impl SessionDraft {
+    fn into_stats(self) -> SessionStats {
+        self.stats.resume(self.elapsed_secs)
+    }
+}
+
+pub enum RecoveryAction {
+    /// Clore la séance retrouvée et l'ajouter à l'historique.
+    Save(SessionStats),
+    /// La poursuivre en démarrant l'enregistrement.
+    Resume(SessionStats),
+    Discard,
+}
+
+/// Sauvegarde régulière de la séance en cours: après un plantage, une panne
+/// du périphérique ou une fermeture de la fenêtre, elle est proposée au
+/// lancement suivant au lieu d'être perdue.
+#[derive(Default)]
+pub struct SessionAutosave {
+    last_saved: Option<Instant>,
+    /// Une seule écriture à la fois, en arrière-plan.
+    writing: Option<JoinHandle<()>>,
+    /// Séance interrompue retrouvée, en attente d'une décision.
+    recovered: Option<SessionDraft>,
+    /// Reprise demandée, pas encore commencée: date de la sauvegarde, dont le
+    /// fichier reste la seule copie de la séance.
+    resuming: Option<u64>,
+}
+
+impl SessionAutosave {
+    /// Cherche une séance interrompue du profil actif.
+    pub fn recover(&mut self) {
+        self.recovered = None;
+        let Ok(path) = autosave_path() else {
+            return;
+        };
+        let json = match fs::read_to_string(&path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return,
+            Err(e) => {
+                warn!("Séance sauvegardée illisible {}: {}", path.display(), e);
+                return;
+            }
+        };
+        match serde_json::from_str::<SessionDraft>(&json) {
+            Ok(draft) => {
+                info!("Séance interrompue retrouvée: {}", path.display());
+                self.recovered = Some(draft);
+            }
+            Err(e) => {
+                // Gardée à part: elle ne sera plus proposée, mais pas perdue
+                warn!("Séance sauvegardée illisible {}: {}", path.display(), e);
+                let _ = fs::rename(&path, path.with_extension("json.bak"));
+            }
+        }
+    }
+
+    /// À appeler à chaque image pendant l'enregistrement.
+    pub fn poll(&mut self, stats: &SessionStats) {
+        let due = self
+            .last_saved
+            .is_none_or(|at| at.elapsed() >= AUTOSAVE_INTERVAL);
+        let busy = self.writing.as_ref().is_some_and(|writing| !writing.is_finished());
+        // La séance retrouvée n'est pas écrasée tant qu'elle attend une décision
+        if !due || busy || self.recovered.is_some() || stats.voiced_frames() == 0 {
+            return;
+        }
+        self.last_saved = Some(Instant::now());
+
+        let draft = DraftRef {
+            saved_at: now_secs(),
+            elapsed_secs: stats.elapsed_secs(),
+            stats,
+        };
+        let saved = autosave_path().and_then(|path| Ok((path, serde_json::to_string(&draft)?)));
+        match saved {
+            Ok((path, json)) => {
+                self.writing = Some(thread::spawn(move || {
+                    if let Err(e) = write_atomically(&path, &json) {
+                        warn!("Sauvegarde automatique de la séance: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!("Sauvegarde automatique de la séance: {}", e),
+        }
+    }
+
+    /// Séance terminée normalement: sa sauvegarde n'a plus lieu d'être.
+    pub fn clear(&mut self) {
+        // Une écriture encore en cours recréerait le fichier après coup
+        if let Some(writing) = self.writing.take() {
+            let _ = writing.join();
+        }
+        self.last_saved = None;
+        if self.recovered.is_some() || self.resuming.is_some() {
+            return;
+        }
+        if let Ok(path) = autosave_path()
+            && let Err(e) = fs::remove_file(&path)
+            && e.kind() != ErrorKind::NotFound
+        {
+            warn!("Suppression de {}: {}", path.display(), e);
+        }
+    }
+
+    /// La séance reprise est en cours: sa prochaine sauvegarde, immédiate,
+    /// remplace celle de la séance interrompue.
+    pub fn resumed(&mut self) {
+        self.resuming = None;
+        self.last_saved = None;
+    }
+
+    /// La reprise n'a pas pu démarrer: la séance est de nouveau proposée.
+    pub fn offer_again(&mut self, stats: SessionStats) {
+        self.recovered = Some(SessionDraft {
+            saved_at: self.resuming.take().unwrap_or_else(now_secs),
+            elapsed_secs: stats.elapsed_secs(),
+            stats,
+        });
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<RecoveryAction> {
+        let draft = self.recovered.as_ref()?;
+        let mut choice = None;
+        egui::Window::new("♻ Séance interrompue")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    "La dernière séance ne s'est pas terminée normalement (plantage, panne du \
+                     périphérique ou fermeture de l'application). Ses mesures ont été \
+                     sauvegardées.",
+                );
+                egui::Grid::new("recovered_session").num_columns(2).show(ui, |ui| {
+                    ui.label("Commencée le");
+                    ui.label(DateTime::from_unix(draft.stats.started_at()).to_string());
+                    ui.end_row();
+                    ui.label("Enregistrée");
+                    ui.label(format_duration(draft.elapsed_secs));
+                    ui.end_row();
+                    ui.label("Voix");
+                    ui.label(format_duration(draft.stats.voiced_secs()));
+                    ui.end_row();
+                    ui.label("Dernière sauvegarde");
+                    ui.label(DateTime::from_unix(draft.saved_at).to_string());
+                    ui.end_row();
+                });
+                ui.small(
+                    "Une reprise poursuit les mesures, mais pas l'enregistrement audio de la \
+                     séance; ses repères restent dans son bilan sans être affichés sur le tracé.",
+                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("💾 L'ajouter à l'historique").clicked() {
+                        choice = Some(0);
+                    }
+                    if ui
+                        .button("⏺ La reprendre")
+                        .on_hover_text("L'enregistrement démarre et poursuit cette séance")
+                        .clicked()
+                    {
+                        choice = Some(1);
+                    }
+                    if ui.button("🗑 L'abandonner").clicked() {
+                        choice = Some(2);
+                    }
+                });
+            });
+
+        let choice = choice?;
+        let draft = self.recovered.take()?;
+        Some(match choice {
+            0 => RecoveryAction::Save(draft.into_stats()),
+            1 => {
+                self.resuming = Some(draft.saved_at);
+                RecoveryAction::Resume(draft.into_stats())
+            }
+            _ => RecoveryAction::Discard,
+        })
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::{Context, Result};
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Component, Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+use crate::paths;
+use crate::zip::{self, ZipWriter};
+
+const PREFIX: &str = "sauvegarde-";
+const SECS_PER_DAY: u64 = 86_400;
+/// Intervalle entre deux vérifications de l'échéance pendant que l'app tourne.
+const CHECK_EVERY: Duration = Duration::from_secs(3600);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Sauvegardes automatiques du dossier de données (réglages, sessions,
+/// référence, textes de lecture).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_days: u32,
+    /// Nombre de sauvegardes conservées, les plus anciennes sont supprimées.
+    pub keep: usize,
+    /// Dossier de destination, `None` pour `backups` dans le dossier de données.
+    pub destination: Option<PathBuf>,
+    /// Inclure l'audio conservé des sessions, de loin le plus volumineux.
+    pub include_audio: bool,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_days: 7,
+            keep: 4,
+            destination: None,
+            include_audio: true,
+        }
+    }
+}
+
+impl BackupSettings {
+    pub fn destination(&self) -> Result<PathBuf> {
+        let dir = match &self.destination {
+            Some(dir) => dir.clone(),
+            None => paths::data_dir()?.join("backups"),
+        };
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+}
+
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// Sauvegardes présentes dans la destination, de la plus récente à la plus ancienne.
+pub fn list(destination: &Path) -> Result<Vec<BackupFile>> {
+    let mut backups: Vec<BackupFile> = fs::read_dir(destination)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_name()?.to_str()?;
+            let stem = name.strip_prefix(PREFIX)?.strip_suffix(".zip")?;
+            let created_at = stem.split('-').next()?.parse().ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some(BackupFile {
+                path,
+                created_at,
+                size,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
+    Ok(backups)
+}
+
+fn collect_files(dir: &Path, skip: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path == skip {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, skip, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Archive le dossier de données dans `destination`, puis supprime les
+/// sauvegardes en trop. `label` distingue les sauvegardes manuelles.
+pub fn create(settings: &BackupSettings, label: Option<&str>) -> Result<PathBuf> {
+    let root = paths::data_dir()?;
+    let destination = settings.destination()?;
+    let created_at = now_secs();
+
+    let mut files = Vec::new();
+    collect_files(&root, &destination, &mut files)?;
+
+    let name = match label {
+        Some(label) => format!("{}{}-{}.zip", PREFIX, created_at, label),
+        None => format!("{}{}.zip", PREFIX, created_at),
+    };
+    let path = destination.join(name);
+    // Écriture dans un fichier temporaire: jamais de sauvegarde à moitié écrite
+    let partial = path.with_extension("zip.partial");
+    let written = write_archive(&partial, &root, &files, settings.include_audio, created_at);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, &path)?;
+
+    for old in list(&destination)?.iter().skip(settings.keep.max(1)) {
+        if let Err(e) = fs::remove_file(&old.path) {
+            warn!("Suppression de {}: {}", old.path.display(), e);
+        }
+    }
+    Ok(path)
+}
+
+/// Écrit les fichiers un par un dans l'archive: un seul est en mémoire à la
+/// fois, même avec l'audio des sessions.
+fn write_archive(
+    path: &Path,
+    root: &Path,
+    files: &[PathBuf],
+    include_audio: bool,
+    created_at: u64,
+) -> Result<()> {
+    let mut archive = ZipWriter::new(BufWriter::new(File::create(path)?), created_at);
+    for path in files {
+        if !include_audio && path.extension().is_some_and(|ext| ext == "wav") {
+            continue;
+        }
+        let name = path
+            .strip_prefix(root)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let data = fs::read(path).with_context(|| format!("Lecture de {}", path.display()))?;
+        archive.add(&name, &data)?;
+    }
+    archive.finish()?.into_inner()?.sync_all()?;
+    Ok(())
+}
+
+/// Remet le contenu d'une sauvegarde dans le dossier de données. Les
+/// fichiers absents de l'archive (sessions plus récentes...) sont conservés.
+pub fn restore(archive: &Path) -> Result<usize> {
+    restore_into(&paths::data_dir()?, archive)
+}
+
+fn restore_into(root: &Path, archive: &Path) -> Result<usize> {
+    let entries = zip::read_all(&fs::read(archive)?)?;
+    // Tout est vérifié avant d'écrire quoi que ce soit
+    for entry in &entries {
+        let relative = Path::new(&entry.name);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            anyhow::bail!("Chemin refusé dans l'archive: {}", entry.name);
+        }
+    }
+    for entry in &entries {
+        let path = root.join(&entry.name);
+        // Dossier créé par un autre outil d'archive
+        if entry.name.ends_with('/') {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &entry.data)?;
+    }
+    Ok(entries.len())
+}
+
+/// Planification des sauvegardes et écran de restauration.
+pub struct BackupManager {
+    job: Option<JoinHandle<Result<PathBuf>>>,
+    next_check: Instant,
+    status: Option<String>,
+    destination_input: String,
+    /// Sauvegarde dont la restauration attend confirmation.
+    confirm_restore: Option<PathBuf>,
+}
+
+impl Default for BackupManager {
+    fn default() -> Self {
+        Self {
+            job: None,
+            next_check: Instant::now(),
+            status: None,
+            destination_input: String::new(),
+            confirm_restore: None,
+        }
+    }
+}
+
+impl BackupManager {
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    fn start(&mut self, settings: &BackupSettings, label: Option<&'static str>) {
+        if self.job.is_some() {
+            return;
+        }
+        let settings = settings.clone();
+        self.status = Some("Sauvegarde en cours…".to_string());
+        self.job = Some(thread::spawn(move || create(&settings, label)));
+    }
+
+    /// À appeler à chaque image: lance la sauvegarde quand elle est due et
+    /// relève le résultat de celle en cours.
+    pub fn poll(&mut self, settings: &BackupSettings) {
+        if self.job.as_ref().is_some_and(JoinHandle::is_finished)
+            && let Some(job) = self.job.take()
+        {
+            self.status = Some(match job.join() {
+                Ok(Ok(path)) => format!("✅ Sauvegardé dans {}", path.display()),
+                Ok(Err(e)) => format!("❌ Sauvegarde: {}", e),
+                Err(_) => "❌ Sauvegarde interrompue".to_string(),
+            });
+        }
+
+        if !settings.enabled || self.job.is_some() || Instant::now() < self.next_check {
+            return;
+        }
+        self.next_check = Instant::now() + CHECK_EVERY;
+        let latest = settings
+            .destination()
+            .and_then(|dir| list(&dir))
+            .ok()
+            .and_then(|backups| backups.first().map(|b| b.created_at));
+        let due = latest.is_none_or(|at| {
+            now_secs().saturating_sub(at) >= settings.interval_days.max(1) as u64 * SECS_PER_DAY
+        });
+        if due {
+            self.start(settings, None);
+        }
+    }
+
+    /// Renvoie `(réglages modifiés, données restaurées)`.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        settings: &mut BackupSettings,
+        can_restore: bool,
+    ) -> (bool, bool) {
+        let mut changed = false;
+        let mut restored = false;
+
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut settings.enabled, "Sauvegarde automatique")
+                .changed();
+            ui.add_enabled_ui(settings.enabled, |ui| {
+                ui.label("tous les");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.interval_days).range(1..=90))
+                    .changed();
+                ui.label("jours, garder les");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.keep).range(1..=52))
+                    .changed();
+                ui.label("dernières");
+            });
+        });
+        changed |= ui
+            .checkbox(&mut settings.include_audio, "Inclure l'audio des sessions")
+            .changed();
+
+        let current = settings.destination.as_ref().map_or_else(
+            || "dossier de données/backups".to_string(),
+            |d| d.display().to_string(),
+        );
+        ui.horizontal(|ui| {
+            ui.label(format!("Destination: {}", current));
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.destination_input)
+                    .hint_text("Autre dossier (disque externe, dossier synchronisé…)")
+                    .desired_width(320.0),
+            );
+            if ui
+                .add_enabled(
+                    !self.destination_input.trim().is_empty(),
+                    egui::Button::new("Choisir"),
+                )
+                .clicked()
+            {
+                settings.destination = Some(PathBuf::from(self.destination_input.trim()));
+                self.destination_input.clear();
+                changed = true;
+            }
+            if settings.destination.is_some() && ui.button("Par défaut").clicked() {
+                settings.destination = None;
+                changed = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.is_running(),
+                    egui::Button::new("💾 Sauvegarder maintenant"),
+                )
+                .clicked()
+            {
+                self.start(settings, Some("manuelle"));
+            }
+            if let Some(status) = &self.status {
+                ui.small(status);
+            }
+        });
+
+        let backups = match settings.destination().and_then(|dir| list(&dir)) {
+            Ok(backups) => backups,
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, format!("❌ Destination: {}", e));
+                return (changed, false);
+            }
+        };
+        if backups.is_empty() {
+            ui.small("Aucune sauvegarde pour l'instant.");
+            return (changed, false);
+        }
+
+        egui::CollapsingHeader::new(format!("♻ Restaurer ({} sauvegardes)", backups.len()))
+            .id_salt("backup_restore")
+            .show(ui, |ui| {
+                ui.small(
+                    "La restauration remplace les réglages et les sessions présents dans la \
+                     sauvegarde; les sessions plus récentes sont conservées. Une sauvegarde de \
+                     l'état actuel est faite juste avant.",
+                );
+                if !can_restore {
+                    ui.small("Arrêtez l'enregistrement pour restaurer.");
+                }
+                let now = now_secs();
+                egui::Grid::new("backups_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for backup in &backups {
+                            let days = now.saturating_sub(backup.created_at) / SECS_PER_DAY;
+                            ui.label(match days {
+                                0 => "aujourd'hui".to_string(),
+                                1 => "hier".to_string(),
+                                days => format!("il y a {} jours", days),
+                            });
+                            ui.label(
+                                backup
+                                    .path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().into_owned())
+                                    .unwrap_or_default(),
+                            );
+                            ui.label(format!("{:.1} Mo", backup.size as f64 / 1_048_576.0));
+
+                            let enabled = can_restore && !self.is_running();
+                            if self.confirm_restore.as_ref() == Some(&backup.path) {
+                                if ui
+                                    .add_enabled(enabled, egui::Button::new("Confirmer"))
+                                    .clicked()
+                                {
+                                    self.confirm_restore = None;
+                                    match self.restore_with_safety_copy(settings, &backup.path) {
+                                        Ok(count) => {
+                                            self.status = Some(format!(
+                                                "✅ {} fichier(s) restauré(s)",
+                                                count
+                                            ));
+                                            restored = true;
+                                        }
+                                        Err(e) => {
+                                            self.status = Some(format!("❌ Restauration: {}", e))
+                                        }
+                                    }
+                                }
+                                if ui.button("Annuler").clicked() {
+                                    self.confirm_restore = None;
+                                }
+                            } else if ui
+                                .add_enabled(enabled, egui::Button::new("Restaurer"))
+                                .clicked()
+                            {
+                                self.confirm_restore = Some(backup.path.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        (changed, restored)
+    }
+
+    fn restore_with_safety_copy(
+        &mut self,
+        settings: &BackupSettings,
+        path: &Path,
+    ) -> Result<usize> {
+        let safety = create(
+            &BackupSettings {
+                // La copie de sécurité ne doit pas pousser la sauvegarde choisie dehors
+                keep: settings.keep.max(1) + 1,
+                ..settings.clone()
+            },
+            Some("avant-restauration"),
+        )
+        .context("Copie de sécurité impossible, restauration annulée")?;
+        info!("Copie de sécurité: {}", safety.display());
+        restore(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dossier vide propre au test, dans le dossier temporaire du système.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("feminizer-backup-{}-{}", name, now_secs()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut archive = ZipWriter::new(BufWriter::new(File::create(path).unwrap()), 0);
+        for (name, data) in entries {
+            archive.add(name, data).unwrap();
+        }
+        archive.finish().unwrap();
+    }
+
+    #[test]
+    fn a_backup_restores_its_files() {
+        let dir = scratch_dir("round-trip");
+        let root = dir.join("data");
+        fs::create_dir_all(root.join("profiles/Élodie")).unwrap();
+        fs::write(root.join("settings.json"), "{}").unwrap();
+        fs::write(root.join("profiles/Élodie/take.wav"), [1_u8, 2, 3]).unwrap();
+        let mut files = Vec::new();
+        collect_files(&root, &dir.join("backups"), &mut files).unwrap();
+
+        let archive = dir.join("backup.zip");
+        write_archive(&archive, &root, &files, false, 0).unwrap();
+        let restored = dir.join("restored");
+        assert_eq!(restore_into(&restored, &archive).unwrap(), 1);
+        assert_eq!(fs::read_to_string(restored.join("settings.json")).unwrap(), "{}");
+        assert!(!restored.join("profiles/Élodie/take.wav").exists());
+
+        write_archive(&archive, &root, &files, true, 0).unwrap();
+        assert_eq!(restore_into(&restored, &archive).unwrap(), 2);
+        assert_eq!(fs::read(restored.join("profiles/Élodie/take.wav")).unwrap(), [1, 2, 3]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn paths_leaving_the_data_folder_are_refused() {
+        let dir = scratch_dir("traversal");
+        let root = dir.join("data");
+        let archive = dir.join("evil.zip");
+        write_zip(&archive, &[("settings.json", b"{}"), ("../evil.txt", b"!")]);
+        assert!(restore_into(&root, &archive).is_err());
+        // Rien n'est écrit, pas même les entrées valides
+        assert!(!root.join("settings.json").exists());
+        assert!(!dir.join("evil.txt").exists());
+
+        write_zip(&archive, &[("/etc/evil.txt", b"!")]);
+        assert!(restore_into(&root, &archive).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn folder_entries_are_created() {
+        let dir = scratch_dir("folders");
+        let archive = dir.join("folders.zip");
+        write_zip(&archive, &[("profiles/", b""), ("profiles/Élodie/", b"")]);
+        let root = dir.join("data");
+        restore_into(&root, &archive).unwrap();
+        assert!(root.join("profiles/Élodie").is_dir());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use anyhow::Result;
+use feminizer_voice_core::AnalysisFrame;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tungstenite::http::StatusCode;
+use tungstenite::{Message, WebSocket};
+
+use crate::permissions::NetworkPermissions;
+
+/// Version du protocole WebSocket; un client peut l'exiger avec `?protocol=N`.
+pub const PROTOCOL_VERSION: u32 = 1;
+const SUPPORTED_PROTOCOLS: [u32; 1] = [PROTOCOL_VERSION];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct BroadcastSettings {
+    pub websocket_enabled: bool,
+    pub websocket_port: u16,
+    pub osc_enabled: bool,
+    pub osc_target: String,
+}
+
+impl Default for BroadcastSettings {
+    fn default() -> Self {
+        Self {
+            websocket_enabled: false,
+            websocket_port: 9001,
+            osc_enabled: false,
+            osc_target: "127.0.0.1:9000".to_string(),
+        }
+    }
+}
+
+impl BroadcastSettings {
+    pub fn is_active(&self, permissions: &NetworkPermissions) -> bool {
+        (self.websocket_enabled && permissions.websocket.allowed)
+            || (self.osc_enabled && permissions.osc.allowed)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveMessage {
+    /// Premier message envoyé à chaque client après la poignée de main.
+    Hello {
+        protocol_version: u32,
+        supported_versions: Vec<u32>,
+        app_version: String,
+    },
+    /// Les champs d'[`AnalysisFrame`] à plat, plus `in_target`.
+    Frame {
+        #[serde(flatten)]
+        frame: AnalysisFrame,
+        in_target: bool,
+    },
+    Stats {
+        voiced_secs: f32,
+        median_pitch: f32,
+        in_range_percent: f32,
+    },
+}
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+pub struct Broadcaster {
+    clients: Clients,
+    running: Arc<AtomicBool>,
+    sender: Option<Sender<String>>,
+    websocket_address: Option<SocketAddr>,
+    osc: Option<(UdpSocket, SocketAddr)>,
+}
+
+impl Broadcaster {
+    /// Ne démarre que les fonctions activées et autorisées dans `permissions`.
+    pub fn start(settings: &BroadcastSettings, permissions: &NetworkPermissions) -> Result<Self> {
+        let clients: Clients = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let mut websocket_address = None;
+        let sender = if settings.websocket_enabled && permissions.websocket.allowed {
+            let address = SocketAddr::new(
+                permissions.websocket.scope.bind_ip(),
+                settings.websocket_port,
+            );
+            let listener = TcpListener::bind(address)?;
+            listener.set_nonblocking(true)?;
+            info!("Serveur WebSocket en écoute sur ws://{}", address);
+            websocket_address = Some(address);
+
+            let accept_clients = clients.clone();
+            let accept_running = running.clone();
+            thread::spawn(move || accept_loop(listener, accept_clients, accept_running));
+
+            let (sender, receiver) = mpsc::channel::<String>();
+            let send_clients = clients.clone();
+            thread::spawn(move || {
+                for text in receiver {
+                    if let Ok(mut clients) = send_clients.lock() {
+                        clients.retain_mut(|ws| ws.send(Message::text(text.clone())).is_ok());
+                    }
+                }
+            });
+            Some(sender)
+        } else {
+            None
+        };
+
+        let osc = if settings.osc_enabled && permissions.osc.allowed {
+            let target: SocketAddr = settings
+                .osc_target
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Adresse OSC invalide: {}", e))?;
+            if !permissions.osc.scope.permits(&target) {
+                anyhow::bail!(
+                    "destination OSC {} hors de cette machine, non autorisée",
+                    target
+                );
+            }
+            let socket = UdpSocket::bind((permissions.osc.scope.bind_ip(), 0))?;
+            Some((socket, target))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            clients,
+            running,
+            sender,
+            websocket_address,
+            osc,
+        })
+    }
+
+    /// Une ligne par service réseau en cours, pour l'indicateur global.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(address) = self.websocket_address {
+            lines.push(format!(
+                "WebSocket ws://{} — {} client(s)",
+                address,
+                self.client_count()
+            ));
+        }
+        if let Some((_, target)) = &self.osc {
+            lines.push(format!("OSC → {}", target));
+        }
+        lines
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().map(|clients| clients.len()).unwrap_or(0)
+    }
+
+    pub fn send(&self, message: &LiveMessage) {
+        if let Some(sender) = &self.sender
+            && let Ok(json) = serde_json::to_string(message)
+        {
+            let _ = sender.send(json);
+        }
+
+        if let Some((socket, target)) = &self.osc {
+            for packet in osc_packets(message) {
+                let _ = socket.send_to(&packet, target);
+            }
+        }
+    }
+}
+
+impl Drop for Broadcaster {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+fn accept_loop(listener: TcpListener, clients: Clients, running: Arc<AtomicBool>) {
+    while running.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, address)) => {
+                let _ = stream.set_nonblocking(false);
+                let _ = stream.set_write_timeout(Some(Duration::from_millis(200)));
+                match tungstenite::accept_hdr(stream, negotiate_protocol) {
+                    Ok(mut ws) => {
+                        info!("Client WebSocket connecté: {}", address);
+                        let hello = LiveMessage::Hello {
+                            protocol_version: PROTOCOL_VERSION,
+                            supported_versions: SUPPORTED_PROTOCOLS.to_vec(),
+                            app_version: env!("CARGO_PKG_VERSION").to_string(),
+                        };
+                        if let Ok(json) = serde_json::to_string(&hello)
+                            && ws.send(Message::text(json)).is_ok()
+                            && let Ok(mut clients) = clients.lock()
+                        {
+                            clients.push(ws);
+                        }
+                    }
+                    Err(e) => warn!("Poignée de main WebSocket échouée: {}", e),
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => {
+                error!("Erreur du serveur WebSocket: {}", e);
+                thread::sleep(Duration::from_millis(500));
+            }
+        }
+    }
+
+    if let Ok(mut clients) = clients.lock() {
+        for ws in clients.iter_mut() {
+            let _ = ws.close(None);
+        }
+        clients.clear();
+    }
+}
+
+// Signature imposée par tungstenite::accept_hdr
+#[allow(clippy::result_large_err)]
+fn negotiate_protocol(request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+    let requested = request
+        .uri()
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("protocol="));
+
+    match requested {
+        None => Ok(response),
+        Some(version) if version.parse().is_ok_and(|v| SUPPORTED_PROTOCOLS.contains(&v)) => {
+            Ok(response)
+        }
+        Some(version) => {
+            let mut error = ErrorResponse::new(Some(format!(
+                "Protocole {} non supporté, versions disponibles: {:?}",
+                version, SUPPORTED_PROTOCOLS
+            )));
+            *error.status_mut() = StatusCode::BAD_REQUEST;
+            Err(error)
+        }
+    }
+}
+
+fn osc_packets(message: &LiveMessage) -> Vec<Vec<u8>> {
+    match message {
+        LiveMessage::Hello { .. } => Vec::new(),
+        LiveMessage::Frame { frame, in_target } => vec![
+            osc_message("/feminizer/pitch", frame.frequency),
+            osc_message("/feminizer/amplitude", frame.amplitude),
+            osc_message("/feminizer/voiced", if frame.is_voiced { 1.0 } else { 0.0 }),
+            osc_message("/feminizer/brightness", frame.brightness),
+            osc_message("/feminizer/in_target", if *in_target { 1.0 } else { 0.0 }),
+        ],
+        LiveMessage::Stats {
+            voiced_secs,
+            median_pitch,
+            in_range_percent,
+        } => vec![
+            osc_message("/feminizer/stats/voiced_secs", *voiced_secs),
+            osc_message("/feminizer/stats/median_pitch", *median_pitch),
+            osc_message("/feminizer/stats/in_range_percent", *in_range_percent),
+        ],
+    }
+}
+
+fn osc_message(address: &str, value: f32) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(48);
+    push_osc_string(&mut packet, address);
+    push_osc_string(&mut packet, ",f");
+    packet.extend_from_slice(&value.to_be_bytes());
+    packet
+}
+
+fn push_osc_string(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(value.as_bytes());
+    packet.push(0);
+    while !packet.len().is_multiple_of(4) {
+        packet.push(0);
+    }
+}
@@ -0,0 +1,226 @@
+use eframe::egui;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dates::DateTime;
+use crate::session::SessionSummary;
+
+const SECS_PER_DAY: u64 = 86_400;
+/// Un an de pratique, comme un calendrier de contributions.
+const WEEKS: i64 = 53;
+const CELL: f32 = 11.0;
+const GAP: f32 = 2.0;
+const LABEL_WIDTH: f32 = 18.0;
+const LABEL_HEIGHT: f32 = 14.0;
+/// Paliers de couleur, en minutes de pratique par jour.
+const LEVELS_MIN: [f32; 4] = [1.0, 10.0, 20.0, 40.0];
+const MONTHS: [&str; 12] = [
+    "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov", "déc",
+];
+
+fn today() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| (d.as_secs() / SECS_PER_DAY) as i64)
+        .unwrap_or(0)
+}
+
+// Le 1er janvier 1970 était un jeudi: décalage pour des semaines du lundi
+fn week_start(day: i64) -> i64 {
+    (day + 3).div_euclid(7) * 7 - 3
+}
+
+fn date_of(day: i64) -> DateTime {
+    DateTime::from_unix(day.max(0) as u64 * SECS_PER_DAY)
+}
+
+/// Minutes enregistrées par jour (UTC), toutes sessions confondues.
+fn minutes_per_day(sessions: &[SessionSummary]) -> HashMap<i64, f32> {
+    let mut days = HashMap::new();
+    for session in sessions {
+        let day = (session.started_at / SECS_PER_DAY) as i64;
+        *days.entry(day).or_insert(0.0) += session.duration_secs / 60.0;
+    }
+    days
+}
+
+/// Série en cours et plus longue série de jours consécutifs avec au moins une
+/// session. La série en cours tient encore tant que la veille a été pratiquée.
+fn streaks(days: &HashMap<i64, f32>, today: i64) -> (u32, u32) {
+    let mut practiced: Vec<i64> = days.keys().copied().collect();
+    practiced.sort_unstable();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for &day in &practiced {
+        run = if previous == Some(day - 1) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains_key(&today) { today } else { today - 1 };
+    while days.contains_key(&day) {
+        current += 1;
+        day -= 1;
+    }
+    (current, longest)
+}
+
+fn cell_color(minutes: f32) -> egui::Color32 {
+    let level = LEVELS_MIN.iter().filter(|&&floor| minutes >= floor).count();
+    match level {
+        0 => egui::Color32::from_gray(45),
+        1 => egui::Color32::from_rgb(90, 30, 90),
+        2 => egui::Color32::from_rgb(150, 40, 150),
+        3 => egui::Color32::from_rgb(210, 30, 210),
+        _ => egui::Color32::from_rgb(255, 0, 255),
+    }
+}
+
+/// Calendrier de la dernière année, une case par jour colorée selon les
+/// minutes de pratique, avec les séries de jours consécutifs.
+pub fn show(ui: &mut egui::Ui, sessions: &[SessionSummary]) {
+    let days = minutes_per_day(sessions);
+    let today = today();
+    let (current, longest) = streaks(&days, today);
+
+    ui.horizontal(|ui| {
+        ui.label(format!("🔥 Série en cours: {} j", current));
+        ui.separator();
+        ui.label(format!("Plus longue série: {} j", longest));
+        ui.separator();
+        let year: f32 = days
+            .iter()
+            .filter(|(day, _)| today - **day < 7 * WEEKS)
+            .map(|(_, minutes)| minutes)
+            .sum();
+        ui.label(format!("{:.0} min sur l'année", year));
+    });
+
+    let first_week = week_start(today) - 7 * (WEEKS - 1);
+    let size = egui::vec2(
+        LABEL_WIDTH + WEEKS as f32 * (CELL + GAP),
+        LABEL_HEIGHT + 7.0 * (CELL + GAP),
+    );
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let grid = rect.min + egui::vec2(LABEL_WIDTH, LABEL_HEIGHT);
+    let font = egui::FontId::proportional(9.0);
+    let text_color = ui.visuals().weak_text_color();
+
+    for (row, name) in [(0, "L"), (2, "M"), (4, "V")] {
+        painter.text(
+            rect.min + egui::vec2(0.0, LABEL_HEIGHT + row as f32 * (CELL + GAP) + CELL / 2.0),
+            egui::Align2::LEFT_CENTER,
+            name,
+            font.clone(),
+            text_color,
+        );
+    }
+
+    let cell_rect = |week: i64, weekday: i64| {
+        let min = grid + egui::vec2(week as f32, weekday as f32) * (CELL + GAP);
+        egui::Rect::from_min_size(min, egui::vec2(CELL, CELL))
+    };
+    let mut last_month = None;
+    for week in 0..WEEKS {
+        let monday = first_week + 7 * week;
+        let month = date_of(monday).month;
+        if last_month.is_some_and(|last| last != month) {
+            painter.text(
+                egui::pos2(cell_rect(week, 0).min.x, rect.min.y),
+                egui::Align2::LEFT_TOP,
+                MONTHS[month as usize - 1],
+                font.clone(),
+                text_color,
+            );
+        }
+        last_month = Some(month);
+
+        for weekday in 0..7 {
+            let day = monday + weekday;
+            if day > today {
+                break;
+            }
+            let minutes = days.get(&day).copied().unwrap_or(0.0);
+            let cell = cell_rect(week, weekday);
+            painter.rect_filled(cell, 2.0, cell_color(minutes));
+            if day == today {
+                let stroke = egui::Stroke::new(1.0, ui.visuals().strong_text_color());
+                painter.rect_stroke(cell, 2.0, stroke, egui::StrokeKind::Inside);
+            }
+        }
+    }
+
+    if let Some(pointer) = response.hover_pos() {
+        let offset = (pointer - grid) / (CELL + GAP);
+        let (week, weekday) = (offset.x.floor() as i64, offset.y.floor() as i64);
+        let day = first_week + 7 * week + weekday;
+        if (0..WEEKS).contains(&week) && (0..7).contains(&weekday) && day <= today {
+            let date = date_of(day);
+            let minutes = days.get(&day).copied().unwrap_or(0.0);
+            response.on_hover_text_at_pointer(format!(
+                "{:04}-{:02}-{:02}: {:.0} min",
+                date.year, date.month, date.day, minutes
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_summary;
+
+    fn practiced(days: &[i64]) -> HashMap<i64, f32> {
+        days.iter().map(|&day| (day, 10.0)).collect()
+    }
+
+    #[test]
+    fn a_missed_day_breaks_the_streak() {
+        let days = practiced(&[10, 11, 13, 14, 15]);
+        assert_eq!(streaks(&days, 15), (3, 3));
+        // Pas encore pratiqué aujourd'hui: la série d'hier tient toujours
+        assert_eq!(streaks(&days, 16), (3, 3));
+        assert_eq!(streaks(&days, 17), (0, 3));
+        assert_eq!(streaks(&HashMap::new(), 17), (0, 0));
+    }
+
+    #[test]
+    fn sessions_either_side_of_midnight_are_consecutive_days() {
+        let midnight = 19_723 * SECS_PER_DAY;
+        let mut before = test_summary(midnight - 1);
+        before.duration_secs = 600.0;
+        let mut after = test_summary(midnight);
+        after.duration_secs = 300.0;
+        let days = minutes_per_day(&[before, after]);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[&19_722], 10.0);
+        assert_eq!(days[&19_723], 5.0);
+        assert_eq!(streaks(&days, 19_723), (2, 2));
+    }
+
+    #[test]
+    fn days_are_utc_and_ignore_daylight_saving() {
+        // 30 mars 2024 23:30 UTC puis 31 mars 22:30 UTC: le passage à l'heure
+        // d'été de la nuit ne raccourcit ni ne fusionne les jours
+        let sessions = [test_summary(1_711_841_400), test_summary(1_711_924_200)];
+        let days = minutes_per_day(&sessions);
+        let mut keys: Vec<i64> = days.keys().copied().collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [19_812, 19_813]);
+        assert_eq!(date_of(19_813).day, 31);
+        assert_eq!(streaks(&days, 19_813).0, 2);
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        // 1er janvier 2024, un lundi
+        assert_eq!(week_start(19_723), 19_723);
+        assert_eq!(week_start(19_729), 19_723);
+        assert_eq!(week_start(19_730), 19_730);
+        assert_eq!(week_start(0), -3);
+    }
+}
@@ -0,0 +1,232 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::playback::ClipPlayer;
+
+const FLOOR_DBFS: f32 = -100.0;
+const MEASURE_SECS: f32 = 3.0;
+const TONE_HZ: f32 = 1000.0;
+const TONE_RATE: f32 = 48000.0;
+const TONE_SECS: f32 = 6.0;
+/// Crête de la tonalité de référence, assez bas pour ne pas saturer le micro.
+const TONE_AMPLITUDE: f32 = 0.25;
+
+pub fn to_dbfs(rms: f32) -> f32 {
+    if rms > 0.0 {
+        (20.0 * rms.log10()).max(FLOOR_DBFS)
+    } else {
+        FLOOR_DBFS
+    }
+}
+
+/// Référence des niveaux affichés.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelUnit {
+    /// Relatif à la pleine échelle numérique du micro: 0 dB = saturation.
+    #[default]
+    Dbfs,
+    /// Estimation en dB SPL, après calibration.
+    Spl,
+}
+
+/// Passage des dBFS mesurés à une estimation du niveau acoustique.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LevelCalibration {
+    pub unit: LevelUnit,
+    /// dB SPL = dBFS + `offset_db`; `None` tant que rien n'est calibré.
+    pub offset_db: Option<f32>,
+    /// Micro utilisé lors de la calibration: elle ne vaut que pour lui.
+    pub device: Option<String>,
+}
+
+impl LevelCalibration {
+    fn spl_offset(&self) -> Option<f32> {
+        self.offset_db.filter(|_| self.unit == LevelUnit::Spl)
+    }
+
+    /// Niveau affichable d'une mesure en dBFS.
+    pub fn level(&self, dbfs: f32) -> f32 {
+        dbfs + self.spl_offset().unwrap_or(0.0)
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self.spl_offset() {
+            Some(_) => "dB SPL (est.)",
+            None => "dBFS",
+        }
+    }
+
+    pub fn format(&self, dbfs: f32) -> String {
+        format!("{:.1} {}", self.level(dbfs), self.suffix())
+    }
+}
+
+/// Calibration en cours: moyenne du niveau pendant quelques secondes d'un
+/// son dont le niveau réel est connu (sonomètre, calibreur ou tonalité
+/// jouée par l'app et mesurée).
+pub struct LevelCalibrator {
+    known_spl: f32,
+    play_tone: bool,
+    /// Énergie cumulée et durée de la mesure en cours.
+    measuring: Option<(f32, f32)>,
+    player: ClipPlayer,
+    error: Option<String>,
+}
+
+impl Default for LevelCalibrator {
+    fn default() -> Self {
+        Self {
+            known_spl: 70.0,
+            play_tone: false,
+            measuring: None,
+            player: ClipPlayer::default(),
+            error: None,
+        }
+    }
+}
+
+impl LevelCalibrator {
+    pub fn push_frame(&mut self, rms: f32, frame_duration: f32) {
+        if let Some((energy, secs)) = &mut self.measuring {
+            *energy += rms * rms * frame_duration;
+            *secs += frame_duration;
+        }
+    }
+
+    fn tone() -> Arc<Vec<f32>> {
+        let samples = (0..(TONE_SECS * TONE_RATE) as usize)
+            .map(|i| {
+                let t = i as f32 / TONE_RATE;
+                // Fondus de 50 ms pour éviter les clics
+                let fade = (t / 0.05).min((TONE_SECS - t) / 0.05).clamp(0.0, 1.0);
+                TONE_AMPLITUDE * fade * (2.0 * std::f32::consts::PI * TONE_HZ * t).sin()
+            })
+            .collect();
+        Arc::new(samples)
+    }
+
+    /// Renvoie `true` quand la calibration a changé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        calibration: &mut LevelCalibration,
+        is_recording: bool,
+        device: Option<&str>,
+    ) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Niveaux en:");
+            changed |= ui
+                .selectable_value(&mut calibration.unit, LevelUnit::Dbfs, "dBFS")
+                .on_hover_text("Relatif au maximum du micro: 0 dB = saturation")
+                .changed();
+            let spl =
+                egui::Button::selectable(calibration.unit == LevelUnit::Spl, "dB SPL estimés");
+            if ui
+                .add_enabled(calibration.offset_db.is_some(), spl)
+                .on_disabled_hover_text("Calibrez d'abord le micro ci-dessous")
+                .clicked()
+            {
+                calibration.unit = LevelUnit::Spl;
+                changed = true;
+            }
+        });
+
+        match (calibration.offset_db, &calibration.device) {
+            (Some(offset), calibrated_on) => {
+                ui.small(format!(
+                    "Calibré: dB SPL ≈ dBFS {:+.1} dB{}",
+                    offset,
+                    calibrated_on
+                        .as_ref()
+                        .map(|d| format!(" (micro « {} »)", d))
+                        .unwrap_or_default()
+                ));
+                if let (Some(calibrated_on), Some(device)) = (calibrated_on, device)
+                    && calibrated_on != device
+                {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "⚠ Calibration faite avec un autre micro: les niveaux SPL sont faux",
+                    );
+                }
+            }
+            (None, _) => {
+                ui.small("Non calibré: les niveaux sont relatifs au micro (dBFS).");
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Niveau réel mesuré:");
+            ui.add(
+                egui::DragValue::new(&mut self.known_spl)
+                    .range(30.0..=120.0)
+                    .speed(0.5)
+                    .suffix(" dB SPL"),
+            );
+            ui.checkbox(&mut self.play_tone, "Jouer une tonalité de 1 kHz")
+                .on_hover_text(
+                    "Mesurez son niveau au micro avec un sonomètre, \
+                     puis saisissez-le avant de lancer la mesure",
+                );
+        });
+
+        ui.horizontal(|ui| match self.measuring {
+            None => {
+                if ui
+                    .add_enabled(is_recording, egui::Button::new("🎯 Mesurer 3 s"))
+                    .on_disabled_hover_text("Démarrez l'enregistrement d'abord")
+                    .clicked()
+                {
+                    self.error = None;
+                    self.measuring = Some((0.0, 0.0));
+                    if self.play_tone
+                        && let Err(e) = self.player.play(Self::tone(), TONE_RATE, 0.0)
+                    {
+                        self.error = Some(format!("Tonalité: {}", e));
+                    }
+                }
+                if calibration.offset_db.is_some() && ui.button("Oublier la calibration").clicked()
+                {
+                    calibration.offset_db = None;
+                    calibration.device = None;
+                    calibration.unit = LevelUnit::Dbfs;
+                    changed = true;
+                }
+            }
+            Some((energy, secs)) => {
+                ui.add(egui::ProgressBar::new(secs / MEASURE_SECS).desired_width(150.0));
+                if !is_recording || ui.button("Annuler").clicked() {
+                    self.measuring = None;
+                    self.player.stop();
+                } else if secs >= MEASURE_SECS {
+                    self.measuring = None;
+                    self.player.stop();
+                    let dbfs = to_dbfs((energy / secs).sqrt());
+                    if dbfs <= -70.0 {
+                        self.error = Some("Signal trop faible pour calibrer".to_string());
+                    } else {
+                        calibration.offset_db = Some(self.known_spl - dbfs);
+                        calibration.device = device.map(str::to_string);
+                        calibration.unit = LevelUnit::Spl;
+                        changed = true;
+                    }
+                }
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        ui.small(
+            "Estimation seulement: elle suppose le même micro, le même gain et la même \
+             distance qu'au moment de la calibration.",
+        );
+        changed
+    }
+}
@@ -0,0 +1,218 @@
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+
+/// Écart au-delà duquel deux points ne sont pas reliés: un silence ou une
+/// consonne sourde entre eux.
+const MAX_GAP_SECS: f32 = 0.05;
+
+/// Contour de hauteur importé d'un autre logiciel, pour le superposer aux
+/// contours de l'application.
+pub struct ImportedContour {
+    pub name: String,
+    /// Points voisés (temps en s, hauteur en Hz), par temps croissant.
+    pub points: Vec<[f32; 2]>,
+}
+
+impl ImportedContour {
+    /// Lit un PitchTier de Praat (texte long ou court), une liste de hauteurs
+    /// de Praat ou un CSV dont les deux premières colonnes sont le temps et la
+    /// hauteur.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Lecture de {}", path.display()))?;
+        let points = parse(&text).with_context(|| format!("Lecture de {}", path.display()))?;
+        if points.is_empty() {
+            bail!("Aucun point de hauteur dans {}", path.display());
+        }
+        let name = path
+            .file_stem()
+            .map_or("Import".to_string(), |stem| stem.to_string_lossy().into_owned());
+        Ok(Self { name, points })
+    }
+
+    pub fn duration_secs(&self) -> f32 {
+        self.points.last().map_or(0.0, |&[time, _]| time)
+    }
+
+    pub fn median_hz(&self) -> f32 {
+        let mut values: Vec<f32> = self.points.iter().map(|&[_, hz]| hz).collect();
+        values.sort_by(f32::total_cmp);
+        values[values.len() / 2]
+    }
+
+    /// Passages continus du contour décalés de `offset_secs`, à tracer chacun
+    /// comme une ligne.
+    pub fn runs(&self, offset_secs: f32) -> Vec<Vec<[f64; 2]>> {
+        let mut runs: Vec<Vec<[f64; 2]>> = Vec::new();
+        let mut previous = None;
+        for &[time, hz] in &self.points {
+            if previous.is_none_or(|before| time - before > MAX_GAP_SECS) {
+                runs.push(Vec::new());
+            }
+            if let Some(run) = runs.last_mut() {
+                run.push([(time + offset_secs) as f64, hz as f64]);
+            }
+            previous = Some(time);
+        }
+        runs
+    }
+}
+
+/// Points voisés d'un fichier, par temps croissant.
+fn parse(text: &str) -> Result<Vec<[f32; 2]>> {
+    let mut points = if text.contains("\"PitchTier\"") {
+        parse_pitch_tier(text)?
+    } else {
+        parse_table(text)?
+    };
+    points.retain(|&[time, hz]| time.is_finite() && hz.is_finite() && hz > 0.0);
+    points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+    Ok(points)
+}
+
+/// `number = 0.1` ou `value = 200` → la valeur, pour le format texte long.
+fn field(line: &str, name: &str) -> Option<Result<f32>> {
+    let (key, value) = line.split_once('=')?;
+    (key.trim() == name).then(|| {
+        let value = value.trim();
+        value
+            .parse()
+            .with_context(|| format!("{} invalide: « {} »", name, value))
+    })
+}
+
+fn parse_pitch_tier(text: &str) -> Result<Vec<[f32; 2]>> {
+    // Texte long: des paires « number = … » / « value = … »
+    if text.contains("number =") {
+        let mut points = Vec::new();
+        let mut time = None;
+        for line in text.lines() {
+            if let Some(number) = field(line, "number") {
+                time = Some(number?);
+            } else if let Some(value) = field(line, "value") {
+                let Some(time) = time.take() else {
+                    bail!("Valeur sans temps: « {} »", line.trim());
+                };
+                points.push([time, value?]);
+            }
+        }
+        return Ok(points);
+    }
+
+    // Texte court: xmin, xmax, nombre de points puis les paires
+    let numbers = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains('"'))
+        .map(|line| {
+            line.parse::<f32>()
+                .with_context(|| format!("Nombre attendu: « {} »", line))
+        })
+        .collect::<Result<Vec<f32>>>()?;
+    let Some(&count) = numbers.get(2) else {
+        bail!("PitchTier incomplet");
+    };
+    let pairs = &numbers[3..];
+    if pairs.len() != 2 * count as usize {
+        bail!("PitchTier incomplet: {} points annoncés", count);
+    }
+    Ok(pairs.chunks(2).map(|pair| [pair[0], pair[1]]).collect())
+}
+
+/// Valeur non définie d'une liste de Praat: trame non voisée.
+const UNDEFINED: &str = "--undefined--";
+
+/// Lignes « temps, hauteur[, …] », après une éventuelle ligne d'en-tête. Les
+/// valeurs non définies (`--undefined--` chez Praat) sont ignorées. Avec le
+/// point-virgule ou la tabulation comme séparateur, la virgule peut servir de
+/// séparateur décimal.
+fn parse_table(text: &str) -> Result<Vec<[f32; 2]>> {
+    let mut points = Vec::new();
+    let mut first = true;
+    for (index, line) in text.lines().enumerate() {
+        let mut fields: Vec<String> = match [';', '\t'].into_iter().find(|&s| line.contains(s)) {
+            Some(separator) => line
+                .split(separator)
+                .map(|f| f.trim().replace(',', "."))
+                .collect(),
+            None => line.split([',', ' ']).map(|f| f.trim().to_string()).collect(),
+        };
+        fields.retain(|f| !f.is_empty());
+        let Some(time) = fields.first() else {
+            continue;
+        };
+        let is_header = first && time.parse::<f32>().is_err();
+        first = false;
+        if is_header || fields.iter().take(2).any(|f| f == UNDEFINED) {
+            continue;
+        }
+        let (Some(Ok(time)), Some(Ok(hz))) = (
+            fields.first().map(|f| f.parse()),
+            fields.get(1).map(|f| f.parse()),
+        ) else {
+            bail!("Ligne {}: temps et hauteur attendus, « {} »", index + 1, line.trim());
+        };
+        points.push([time, hz]);
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_pitch_tier() {
+        let text = r#"File type = "ooTextFile"
+Object class = "PitchTier"
+
+xmin = 0
+xmax = 1.2
+points: size = 2
+points [1]:
+    number = 0.5
+    value = 210.5
+points [2]:
+    number = 0.1
+    value = 180
+"#;
+        assert_eq!(parse(text).unwrap(), [[0.1, 180.0], [0.5, 210.5]]);
+        assert!(parse(&text.replace("210.5", "haut")).is_err());
+    }
+
+    #[test]
+    fn short_pitch_tier() {
+        let text = "File type = \"ooTextFile\"\nObject class = \"PitchTier\"\n\n0\n1.2\n2\n\
+                    0.1\n180\n0.5\n210.5\n";
+        assert_eq!(parse(text).unwrap(), [[0.1, 180.0], [0.5, 210.5]]);
+        // Un point annoncé manque
+        assert!(parse(&text.replace("\n2\n", "\n3\n")).is_err());
+        assert!(parse(&text.replace("210.5", "2l0.5")).is_err());
+    }
+
+    #[test]
+    fn csv_with_and_without_header() {
+        let expected = [[0.1, 180.0], [0.2, 195.5]];
+        assert_eq!(parse("0.1,180\n0.2,195.5\n").unwrap(), expected);
+        assert_eq!(parse("time,f0\n0.2,195.5\n0.1,180\n").unwrap(), expected);
+        // Liste de hauteurs de Praat: trames non voisées ignorées
+        let listing = "Time_s   F0_Hz\n0.1   180\n0.15   --undefined--\n0.2   195.5\n";
+        assert_eq!(parse(listing).unwrap(), expected);
+    }
+
+    #[test]
+    fn comma_decimal_separator() {
+        let expected = [[0.1, 180.0], [0.2, 195.5]];
+        assert_eq!(parse("temps;hauteur\n0,1;180\n0,2;195,5\n").unwrap(), expected);
+        assert_eq!(parse("0,1\t180\n0,2\t195,5\n").unwrap(), expected);
+    }
+
+    #[test]
+    fn malformed_lines_are_errors() {
+        let error = parse("0.1,180\n0.2,abc\n").unwrap_err();
+        assert!(error.to_string().contains("Ligne 2"), "{}", error);
+        assert!(parse("0.1,180\n0.2\n").is_err());
+        assert!(parse("time,f0\nheader,again\n").is_err());
+        assert!(parse("").unwrap().is_empty());
+    }
+}
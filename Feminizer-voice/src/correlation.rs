@@ -0,0 +1,209 @@
+use eframe::egui;
+use egui_plot::{Plot, Points};
+
+use crate::session::SessionSummary;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Metric {
+    MeanPitch,
+    MedianPitch,
+    Variability,
+    PitchSpan,
+    InRange,
+    VoicedTime,
+    Duration,
+    Loudness,
+    Brightness,
+    Weight,
+    SelfRating,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 11] = [
+        Metric::MeanPitch,
+        Metric::MedianPitch,
+        Metric::Variability,
+        Metric::PitchSpan,
+        Metric::InRange,
+        Metric::VoicedTime,
+        Metric::Duration,
+        Metric::Loudness,
+        Metric::Brightness,
+        Metric::Weight,
+        Metric::SelfRating,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::MeanPitch => "Fréquence moyenne (Hz)",
+            Metric::MedianPitch => "Fréquence médiane (Hz)",
+            Metric::Variability => "Variabilité (demi-tons)",
+            Metric::PitchSpan => "Étendue (Hz)",
+            Metric::InRange => "Dans la cible (%)",
+            Metric::VoicedTime => "Temps voisé (s)",
+            Metric::Duration => "Durée (s)",
+            Metric::Loudness => "Amplitude moyenne (dBFS)",
+            Metric::Brightness => "Brillance moyenne (Hz)",
+            Metric::Weight => "Poids vocal H1–H2 (dB)",
+            Metric::SelfRating => "Auto-évaluation",
+        }
+    }
+
+    pub fn value(self, session: &SessionSummary) -> Option<f64> {
+        let value = match self {
+            Metric::MeanPitch => session.mean_pitch,
+            Metric::MedianPitch => session.median_pitch,
+            Metric::Variability => session.pitch_variability_st,
+            Metric::PitchSpan => session.max_pitch - session.min_pitch,
+            Metric::InRange => session.in_range_percent,
+            Metric::VoicedTime => session.voiced_secs,
+            Metric::Duration => session.duration_secs,
+            Metric::Loudness => session.mean_amplitude_db,
+            Metric::Brightness => session.mean_brightness,
+            Metric::Weight => session.mean_h1_h2_db?,
+            Metric::SelfRating => session.self_rating? as f32,
+        };
+
+        if session.voiced_secs <= 0.0 && self != Metric::Duration {
+            return None;
+        }
+        Some(value as f64)
+    }
+}
+
+pub fn pearson(points: &[[f64; 2]]) -> Option<f64> {
+    if points.len() < 3 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p[0]).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p[1]).sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+    for p in points {
+        let dx = p[0] - mean_x;
+        let dy = p[1] - mean_y;
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x <= f64::EPSILON || var_y <= f64::EPSILON {
+        return None;
+    }
+    Some(cov / (var_x.sqrt() * var_y.sqrt()))
+}
+
+pub struct CorrelationExplorer {
+    x_metric: Metric,
+    y_metric: Metric,
+}
+
+impl Default for CorrelationExplorer {
+    fn default() -> Self {
+        Self {
+            x_metric: Metric::Variability,
+            y_metric: Metric::SelfRating,
+        }
+    }
+}
+
+impl CorrelationExplorer {
+    pub fn show(&mut self, ui: &mut egui::Ui, sessions: &[SessionSummary]) {
+        ui.horizontal(|ui| {
+            metric_combo(ui, "correlation_x", "X:", &mut self.x_metric);
+            metric_combo(ui, "correlation_y", "Y:", &mut self.y_metric);
+        });
+
+        let points: Vec<[f64; 2]> = sessions
+            .iter()
+            .filter_map(|s| Some([self.x_metric.value(s)?, self.y_metric.value(s)?]))
+            .collect();
+
+        match pearson(&points) {
+            Some(r) => ui.label(format!(
+                "Corrélation (Pearson r): {:.2} — {} sessions",
+                r,
+                points.len()
+            )),
+            None => ui.label(format!(
+                "Pas assez de données pour une corrélation ({} sessions)",
+                points.len()
+            )),
+        };
+
+        Plot::new("correlation_plot")
+            .height(300.0)
+            .x_axis_label(self.x_metric.label())
+            .y_axis_label(self.y_metric.label())
+            .show(ui, |plot_ui| {
+                plot_ui.points(
+                    Points::new("sessions", points)
+                        .radius(4.0)
+                        .color(egui::Color32::from_rgb(255, 0, 255)),
+                );
+            });
+    }
+}
+
+fn metric_combo(ui: &mut egui::Ui, id: &str, label: &str, metric: &mut Metric) {
+    ui.label(label);
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(metric.label())
+        .show_ui(ui, |ui| {
+            for candidate in Metric::ALL {
+                ui.selectable_value(metric, candidate, candidate.label());
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_summary;
+
+    #[test]
+    fn constant_series_have_no_correlation() {
+        assert_eq!(pearson(&[[1.0, 5.0], [2.0, 5.0], [3.0, 5.0]]), None);
+        assert_eq!(pearson(&[[4.0, 1.0], [4.0, 2.0], [4.0, 3.0]]), None);
+        assert_eq!(pearson(&[[190.1, 3.0]; 20]), None);
+    }
+
+    #[test]
+    fn too_few_sessions() {
+        assert_eq!(pearson(&[]), None);
+        assert_eq!(pearson(&[[1.0, 2.0], [2.0, 4.0]]), None);
+    }
+
+    #[test]
+    fn linear_series() {
+        let r = pearson(&[[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]).unwrap();
+        assert!((r - 1.0).abs() < 1e-12, "{}", r);
+        let r = pearson(&[[1.0, 6.0], [2.0, 4.0], [3.0, 2.0], [4.0, 0.0]]).unwrap();
+        assert!((r + 1.0).abs() < 1e-12, "{}", r);
+    }
+
+    #[test]
+    fn the_coefficient_stays_between_minus_one_and_one() {
+        let points: Vec<[f64; 2]> = (0..50)
+            .map(|i| {
+                let x = 180.0 + i as f64 * 0.37;
+                [x, 1e6 + 3.0 * x + (i % 7) as f64 * 1e-3]
+            })
+            .collect();
+        let r = pearson(&points).unwrap();
+        assert!(r.is_finite() && (-1.0..=1.0).contains(&r), "{}", r);
+    }
+
+    #[test]
+    fn unvoiced_sessions_only_count_for_their_duration() {
+        let mut session = test_summary(0);
+        session.duration_secs = 60.0;
+        assert_eq!(Metric::MeanPitch.value(&session), None);
+        assert_eq!(Metric::Weight.value(&session), None);
+        assert_eq!(Metric::Duration.value(&session), Some(60.0));
+    }
+}
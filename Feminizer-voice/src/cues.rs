@@ -0,0 +1,390 @@
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, Stream, StreamConfig};
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+use crate::accessibility;
+use crate::audio_host;
+use crate::paths;
+use crate::wav;
+
+const BUILTIN_RATE: f32 = 44100.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CueCategory {
+    Start,
+    Success,
+    Metronome,
+    Warning,
+    Nudge,
+    TargetEnter,
+    TargetLeave,
+    BelowFloor,
+    Reference,
+}
+
+impl CueCategory {
+    pub const ALL: [CueCategory; 9] = [
+        CueCategory::Start,
+        CueCategory::Success,
+        CueCategory::Metronome,
+        CueCategory::Warning,
+        CueCategory::Nudge,
+        CueCategory::TargetEnter,
+        CueCategory::TargetLeave,
+        CueCategory::BelowFloor,
+        CueCategory::Reference,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CueCategory::Start => "Début",
+            CueCategory::Success => "Réussite",
+            CueCategory::Metronome => "Métronome",
+            CueCategory::Warning => "Alerte",
+            CueCategory::Nudge => "Rappel discret",
+            CueCategory::TargetEnter => "Entrée dans la cible",
+            CueCategory::TargetLeave => "Sortie de la cible",
+            CueCategory::BelowFloor => "Sous la cible",
+            CueCategory::Reference => "Note de référence",
+        }
+    }
+
+    fn file_stem(self) -> &'static str {
+        match self {
+            CueCategory::Start => "start",
+            CueCategory::Success => "success",
+            CueCategory::Metronome => "metronome",
+            CueCategory::Warning => "warning",
+            CueCategory::Nudge => "nudge",
+            CueCategory::TargetEnter => "target_enter",
+            CueCategory::TargetLeave => "target_leave",
+            CueCategory::BelowFloor => "below_floor",
+            CueCategory::Reference => "reference",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn builtin(self) -> Vec<f32> {
+        match self {
+            CueCategory::Start => tone(&[(880.0, 0.12)]),
+            CueCategory::Success => tone(&[(660.0, 0.1), (990.0, 0.18)]),
+            CueCategory::Metronome => tone(&[(1500.0, 0.03)]),
+            CueCategory::Warning => tone(&[(330.0, 0.1), (262.0, 0.15)]),
+            CueCategory::Nudge => tone(&[(523.0, 0.06), (659.0, 0.09)]),
+            CueCategory::TargetEnter => tone(&[(784.0, 0.05), (1047.0, 0.07)]),
+            CueCategory::TargetLeave => tone(&[(622.0, 0.05), (466.0, 0.07)]),
+            CueCategory::BelowFloor => tone(&[(1200.0, 0.02)]),
+            CueCategory::Reference => held_tone(440.0, 0.5),
+        }
+    }
+}
+
+fn tone(notes: &[(f32, f32)]) -> Vec<f32> {
+    let mut samples = Vec::new();
+    for &(freq, duration) in notes {
+        let count = (duration * BUILTIN_RATE) as usize;
+        samples.extend((0..count).map(|i| {
+            let t = i as f32 / BUILTIN_RATE;
+            let envelope = (1.0 - i as f32 / count as f32).powi(2);
+            0.5 * envelope * (2.0 * std::f32::consts::PI * freq * t).sin()
+        }));
+    }
+    samples
+}
+
+/// Note tenue, sans décroissance, avec des fondus brefs pour éviter les clics.
+fn held_tone(freq: f32, duration: f32) -> Vec<f32> {
+    let count = (duration * BUILTIN_RATE) as usize;
+    let fade = (0.02 * BUILTIN_RATE) as usize;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / BUILTIN_RATE;
+            let envelope = (i.min(count - i) as f32 / fade as f32).min(1.0);
+            0.4 * envelope * (2.0 * std::f32::consts::PI * freq * t).sin()
+        })
+        .collect()
+}
+
+struct CueSound {
+    samples: Arc<Vec<f32>>,
+    sample_rate: f32,
+}
+
+struct Voice {
+    samples: Arc<Vec<f32>>,
+    position: f32,
+    step: f32,
+    gain: f32,
+}
+
+pub struct CuePlayer {
+    stream: Option<Stream>,
+    output_rate: f32,
+    voices: Arc<Mutex<Vec<Voice>>>,
+    sounds: Vec<CueSound>,
+    volumes: [f32; CueCategory::ALL.len()],
+    pack: Option<String>,
+}
+
+impl Default for CuePlayer {
+    fn default() -> Self {
+        Self {
+            stream: None,
+            output_rate: BUILTIN_RATE,
+            voices: Default::default(),
+            sounds: CueCategory::ALL
+                .iter()
+                .map(|category| CueSound {
+                    samples: Arc::new(category.builtin()),
+                    sample_rate: BUILTIN_RATE,
+                })
+                .collect(),
+            volumes: [0.8, 0.8, 0.8, 0.8, 0.3, 0.5, 0.5, 0.3, 0.6],
+            pack: None,
+        }
+    }
+}
+
+impl CuePlayer {
+    pub fn available_packs() -> Vec<String> {
+        let Ok(dir) = paths::data_subdir("cues") else {
+            return Vec::new();
+        };
+        let mut packs: Vec<String> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        packs.sort();
+        packs
+    }
+
+    pub fn load_pack(&mut self, pack: Option<&str>) -> Result<()> {
+        let dir = match pack {
+            Some(name) => Some(paths::data_subdir("cues")?.join(name)),
+            None => None,
+        };
+
+        let mut sounds = Vec::with_capacity(CueCategory::ALL.len());
+        for category in CueCategory::ALL {
+            let path = dir
+                .as_ref()
+                .map(|dir| dir.join(format!("{}.wav", category.file_stem())))
+                .filter(|path| path.exists());
+
+            let sound = match path {
+                Some(path) => load_wav(&path)?,
+                None => CueSound {
+                    samples: Arc::new(category.builtin()),
+                    sample_rate: BUILTIN_RATE,
+                },
+            };
+            sounds.push(sound);
+        }
+
+        self.sounds = sounds;
+        self.pack = pack.map(str::to_string);
+        Ok(())
+    }
+
+    pub fn volume(&self, category: CueCategory) -> f32 {
+        self.volumes[category.index()]
+    }
+
+    pub fn set_volume(&mut self, category: CueCategory, volume: f32) {
+        self.volumes[category.index()] = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn play(&mut self, category: CueCategory) -> Result<()> {
+        let gain = self.volume(category);
+        if gain <= 0.0 {
+            return Ok(());
+        }
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+
+        let sound = &self.sounds[category.index()];
+        self.push_voice(sound.samples.clone(), sound.sample_rate, gain);
+        Ok(())
+    }
+
+    /// Note brève à la fréquence donnée, au volume de la catégorie: le signal
+    /// renseigne sur la hauteur au lieu d'un son fixe.
+    pub fn play_pitch(&mut self, category: CueCategory, frequency: f32) -> Result<()> {
+        let gain = self.volume(category);
+        if gain <= 0.0 {
+            return Ok(());
+        }
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+        self.push_voice(Arc::new(tone(&[(frequency, 0.15)])), BUILTIN_RATE, gain);
+        Ok(())
+    }
+
+    /// Note tenue à la fréquence donnée, pour la reprendre à l'oreille.
+    pub fn play_note(&mut self, category: CueCategory, frequency: f32, secs: f32) -> Result<()> {
+        let gain = self.volume(category);
+        if gain <= 0.0 {
+            return Ok(());
+        }
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+        self.push_voice(Arc::new(held_tone(frequency, secs)), BUILTIN_RATE, gain);
+        Ok(())
+    }
+
+    fn push_voice(&self, samples: Arc<Vec<f32>>, sample_rate: f32, gain: f32) {
+        if let Ok(mut voices) = self.voices.lock() {
+            voices.push(Voice {
+                samples,
+                position: 0.0,
+                step: sample_rate / self.output_rate,
+                gain,
+            });
+        }
+    }
+
+    fn open_stream(&mut self) -> Result<()> {
+        let host = audio_host::host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique de sortie audio trouvé"))?;
+        let config = device.default_output_config()?;
+        let stream_config: StreamConfig = config.clone().into();
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                Self::build_stream::<f32>(&device, &stream_config, self.voices.clone())?
+            }
+            cpal::SampleFormat::I16 => {
+                Self::build_stream::<i16>(&device, &stream_config, self.voices.clone())?
+            }
+            cpal::SampleFormat::U16 => {
+                Self::build_stream::<u16>(&device, &stream_config, self.voices.clone())?
+            }
+            format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
+        };
+        stream.play()?;
+
+        self.output_rate = stream_config.sample_rate.0 as f32;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn build_stream<T>(
+        device: &Device,
+        config: &StreamConfig,
+        voices: Arc<Mutex<Vec<Voice>>>,
+    ) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
+    {
+        let channels = config.channels as usize;
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let Ok(mut voices) = voices.try_lock() else {
+                    data.fill(T::EQUILIBRIUM);
+                    return;
+                };
+
+                for frame in data.chunks_mut(channels) {
+                    let mut mix = 0.0;
+                    for voice in voices.iter_mut() {
+                        let index = voice.position as usize;
+                        if let Some(&sample) = voice.samples.get(index) {
+                            mix += sample * voice.gain;
+                        }
+                        voice.position += voice.step;
+                    }
+                    let value = T::from_sample(mix.clamp(-1.0, 1.0));
+                    frame.fill(value);
+                }
+
+                voices.retain(|voice| (voice.position as usize) < voice.samples.len());
+            },
+            |err| error!("Erreur du flux audio des signaux: {}", err),
+            None,
+        )?;
+
+        Ok(stream)
+    }
+
+    pub fn show_settings(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let mut error = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Pack de signaux:");
+            let current = self.pack.clone();
+            egui::ComboBox::from_id_salt("cue_pack")
+                .selected_text(current.as_deref().unwrap_or("Intégré"))
+                .show_ui(ui, |ui| {
+                    let mut selected = current.clone();
+                    ui.selectable_value(&mut selected, None, "Intégré");
+                    for pack in Self::available_packs() {
+                        ui.selectable_value(&mut selected, Some(pack.clone()), pack);
+                    }
+                    if selected != current
+                        && let Err(e) = self.load_pack(selected.as_deref())
+                    {
+                        error = Some(format!("Pack de signaux: {}", e));
+                    }
+                });
+
+            if let Ok(dir) = paths::data_subdir("cues") {
+                ui.label("ℹ").on_hover_text(format!(
+                    "Un sous-dossier par pack dans {}\n(start.wav, success.wav, metronome.wav, \
+                     warning.wav, nudge.wav, target_enter.wav, target_leave.wav, below_floor.wav)",
+                    dir.display()
+                ));
+            }
+        });
+
+        for category in CueCategory::ALL {
+            ui.horizontal(|ui| {
+                let mut volume = self.volume(category);
+                if ui
+                    .add(egui::Slider::new(&mut volume, 0.0..=1.0).text(category.label()))
+                    .changed()
+                {
+                    self.set_volume(category, volume);
+                }
+                let preview = ui.small_button("▶").on_hover_text("Écouter");
+                accessibility::describe(
+                    &preview,
+                    egui::WidgetType::Button,
+                    &format!("Écouter le signal « {} »", category.label()),
+                );
+                if preview.clicked()
+                    && let Err(e) = self.play(category)
+                {
+                    error = Some(format!("Signal sonore: {}", e));
+                }
+            });
+        }
+
+        error
+    }
+}
+
+fn load_wav(path: &Path) -> Result<CueSound> {
+    let (samples, sample_rate) = wav::read_mono(path)?;
+    Ok(CueSound {
+        samples: Arc::new(samples),
+        sample_rate,
+    })
+}
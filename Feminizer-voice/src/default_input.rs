@@ -0,0 +1,56 @@
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::audio_processor::AudioProcessor;
+
+/// Interroger le système plus souvent ne rend pas le changement plus fluide:
+/// le nouveau flux met lui-même un moment à démarrer.
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Suit le périphérique d'entrée par défaut du système pendant
+/// l'enregistrement: brancher un casque le change sur la plupart des systèmes.
+pub struct DefaultInputWatch {
+    /// Périphérique par défaut au dernier relevé, `None` avant le premier.
+    known: Option<String>,
+    check: Option<JoinHandle<Option<String>>>,
+    checked_at: Instant,
+}
+
+impl Default for DefaultInputWatch {
+    fn default() -> Self {
+        Self {
+            known: None,
+            check: None,
+            checked_at: Instant::now(),
+        }
+    }
+}
+
+impl DefaultInputWatch {
+    /// À appeler quand le périphérique par défaut n'est pas suivi: le
+    /// prochain relevé repart de zéro sans signaler de changement.
+    pub fn reset(&mut self) {
+        self.known = None;
+        self.check = None;
+    }
+
+    /// Renvoie le nom du nouveau périphérique par défaut quand il change.
+    /// L'énumération peut être lente: elle tourne dans un thread à part.
+    pub fn poll(&mut self) -> Option<String> {
+        if self.check.is_none() {
+            if self.checked_at.elapsed() >= CHECK_INTERVAL {
+                self.checked_at = Instant::now();
+                self.check = Some(std::thread::spawn(AudioProcessor::default_input_name));
+            }
+            return None;
+        }
+        if !self.check.as_ref().is_some_and(JoinHandle::is_finished) {
+            return None;
+        }
+        let name = self.check.take()?.join().ok().flatten()?;
+        match self.known.replace(name.clone()) {
+            Some(previous) if previous != name => Some(name),
+            _ => None,
+        }
+    }
+}
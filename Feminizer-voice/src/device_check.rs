@@ -0,0 +1,322 @@
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, Stream, StreamConfig};
+use rustfft::{FftPlanner, num_complex::Complex};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::error;
+
+use crate::audio_host;
+
+const TONE_HZ: f32 = 1000.0;
+const TONE_AMPLITUDE: f32 = 0.3;
+const TONE_START: f32 = 0.5;
+const TONE_END: f32 = 1.5;
+const CHECK_DURATION: Duration = Duration::from_millis(2000);
+
+pub struct DeviceCheckReport {
+    pub input_sample_rate: u32,
+    pub output_sample_rate: u32,
+    pub noise_db: f32,
+    pub tone_db: f32,
+    pub latency_ms: Option<f32>,
+    pub measured_tone_hz: Option<f32>,
+    pub issues: Vec<String>,
+}
+
+#[derive(Default)]
+struct Capture {
+    samples: Vec<f32>,
+    first_callback: Option<Instant>,
+    tone_started: Option<Instant>,
+}
+
+pub struct DeviceCheck {
+    _input: Stream,
+    _output: Stream,
+    capture: Arc<Mutex<Capture>>,
+    input_sample_rate: u32,
+    output_sample_rate: u32,
+    started: Instant,
+}
+
+impl DeviceCheck {
+    pub fn start() -> Result<Self> {
+        let host = audio_host::host();
+        let input_device = host
+            .default_input_device()
+            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique d'entrée audio trouvé"))?;
+        let output_device = host
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique de sortie audio trouvé"))?;
+
+        let input_config = input_device.default_input_config()?;
+        let output_config = output_device.default_output_config()?;
+        let capture = Arc::new(Mutex::new(Capture::default()));
+
+        let input_stream_config: StreamConfig = input_config.clone().into();
+        let input = match input_config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                Self::build_input::<f32>(&input_device, &input_stream_config, capture.clone())?
+            }
+            cpal::SampleFormat::I16 => {
+                Self::build_input::<i16>(&input_device, &input_stream_config, capture.clone())?
+            }
+            cpal::SampleFormat::U16 => {
+                Self::build_input::<u16>(&input_device, &input_stream_config, capture.clone())?
+            }
+            format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
+        };
+
+        let output_stream_config: StreamConfig = output_config.clone().into();
+        let output = match output_config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                Self::build_output::<f32>(&output_device, &output_stream_config, capture.clone())?
+            }
+            cpal::SampleFormat::I16 => {
+                Self::build_output::<i16>(&output_device, &output_stream_config, capture.clone())?
+            }
+            cpal::SampleFormat::U16 => {
+                Self::build_output::<u16>(&output_device, &output_stream_config, capture.clone())?
+            }
+            format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
+        };
+
+        input.play()?;
+        output.play()?;
+
+        Ok(Self {
+            _input: input,
+            _output: output,
+            capture,
+            input_sample_rate: input_config.sample_rate().0,
+            output_sample_rate: output_config.sample_rate().0,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn progress(&self) -> f32 {
+        (self.started.elapsed().as_secs_f32() / CHECK_DURATION.as_secs_f32()).min(1.0)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= CHECK_DURATION
+    }
+
+    pub fn report(&self) -> DeviceCheckReport {
+        let capture = self.capture.lock().unwrap_or_else(|e| e.into_inner());
+        analyze_capture(&capture, self.input_sample_rate, self.output_sample_rate)
+    }
+
+    fn build_input<T>(
+        device: &Device,
+        config: &StreamConfig,
+        capture: Arc<Mutex<Capture>>,
+    ) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + Send + 'static,
+        f32: cpal::FromSample<T>,
+    {
+        let channels = config.channels as usize;
+
+        let stream = device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut capture) = capture.try_lock() {
+                    capture.first_callback.get_or_insert_with(Instant::now);
+                    capture.samples.extend(data.chunks(channels).map(|chunk| {
+                        chunk
+                            .iter()
+                            .map(|&s| cpal::Sample::to_sample::<f32>(s))
+                            .sum::<f32>()
+                            / channels as f32
+                    }));
+                }
+            },
+            |err| error!("Erreur du flux audio (test du périphérique): {}", err),
+            None,
+        )?;
+
+        Ok(stream)
+    }
+
+    fn build_output<T>(
+        device: &Device,
+        config: &StreamConfig,
+        capture: Arc<Mutex<Capture>>,
+    ) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
+    {
+        let channels = config.channels as usize;
+        let sample_rate = config.sample_rate.0 as f32;
+        let mut sample_index: u64 = 0;
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    let t = sample_index as f32 / sample_rate;
+                    let value = if (TONE_START..TONE_END).contains(&t) {
+                        if let Ok(mut capture) = capture.try_lock() {
+                            capture.tone_started.get_or_insert_with(Instant::now);
+                        }
+                        TONE_AMPLITUDE * (2.0 * std::f32::consts::PI * TONE_HZ * t).sin()
+                    } else {
+                        0.0
+                    };
+
+                    for sample in frame.iter_mut() {
+                        *sample = T::from_sample(value);
+                    }
+                    sample_index += 1;
+                }
+            },
+            |err| error!("Erreur du flux audio (test du périphérique): {}", err),
+            None,
+        )?;
+
+        Ok(stream)
+    }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|x| x * x).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn to_db(value: f32) -> f32 {
+    if value > 0.0 {
+        (20.0 * value.log10()).max(-100.0)
+    } else {
+        -100.0
+    }
+}
+
+fn analyze_capture(capture: &Capture, input_rate: u32, output_rate: u32) -> DeviceCheckReport {
+    let rate = input_rate as f32;
+    let slice = |from: f32, to: f32| {
+        let start = ((from * rate) as usize).min(capture.samples.len());
+        let end = ((to * rate) as usize).min(capture.samples.len());
+        &capture.samples[start..end]
+    };
+
+    let noise = rms(slice(0.05, TONE_START - 0.1));
+    let tone_section = slice(TONE_START + 0.2, TONE_END - 0.1);
+    let tone = rms(tone_section);
+    let peak = capture.samples.iter().fold(0.0_f32, |m, &x| m.max(x.abs()));
+
+    let mut issues = Vec::new();
+
+    if capture.samples.is_empty() {
+        issues.push("Aucun échantillon reçu du micro".to_string());
+    } else if peak < 1e-5 {
+        issues.push("Le micro semble muet ou débranché (signal nul)".to_string());
+    }
+
+    let tone_heard = tone > (noise * 4.0).max(1e-4);
+    if !capture.samples.is_empty() && peak >= 1e-5 && !tone_heard {
+        issues.push(
+            "Tonalité de test non captée: vérifiez le volume de sortie ou utilisez des haut-parleurs"
+                .to_string(),
+        );
+    }
+
+    if peak >= 0.99 {
+        issues.push("Saturation détectée: baissez le gain du micro".to_string());
+    }
+
+    let latency_ms = if tone_heard {
+        detect_onset_latency(capture, rate, noise)
+    } else {
+        None
+    };
+
+    let measured_tone_hz = if tone_heard {
+        measure_tone(tone_section, rate)
+    } else {
+        None
+    };
+
+    if let Some(measured) = measured_tone_hz {
+        let ratio = measured / TONE_HZ;
+        if (ratio - 1.0).abs() > 0.02 {
+            let hint = if (ratio - 44100.0 / 48000.0).abs() < 0.01
+                || (ratio - 48000.0 / 44100.0).abs() < 0.01
+            {
+                " (symptôme typique d'un conflit 44,1 / 48 kHz)"
+            } else {
+                ""
+            };
+            issues.push(format!(
+                "Tonalité mesurée à {:.0} Hz au lieu de {:.0} Hz{}",
+                measured, TONE_HZ, hint
+            ));
+        }
+    }
+
+    if input_rate != output_rate {
+        issues.push(format!(
+            "Fréquences d'échantillonnage différentes: entrée {} Hz, sortie {} Hz",
+            input_rate, output_rate
+        ));
+    }
+
+    DeviceCheckReport {
+        input_sample_rate: input_rate,
+        output_sample_rate: output_rate,
+        noise_db: to_db(noise),
+        tone_db: to_db(tone),
+        latency_ms,
+        measured_tone_hz,
+        issues,
+    }
+}
+
+fn detect_onset_latency(capture: &Capture, rate: f32, noise: f32) -> Option<f32> {
+    let first_callback = capture.first_callback?;
+    let tone_started = capture.tone_started?;
+    let threshold = (noise * 4.0).max(1e-3);
+
+    let window = 64;
+    let onset = capture
+        .samples
+        .chunks(window)
+        .position(|chunk| rms(chunk) > threshold)?;
+
+    let onset_time = first_callback + Duration::from_secs_f32(onset as f32 * window as f32 / rate);
+    let latency = onset_time.checked_duration_since(tone_started)?;
+    Some(latency.as_secs_f32() * 1000.0)
+}
+
+fn measure_tone(section: &[f32], rate: f32) -> Option<f32> {
+    let size = section.len().next_power_of_two() / 2;
+    if size < 1024 {
+        return None;
+    }
+
+    let mut buffer: Vec<Complex<f32>> = section[..size]
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let w = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (size - 1) as f32).cos());
+            Complex::new(x * w, 0.0)
+        })
+        .collect();
+    FftPlanner::new().plan_fft_forward(size).process(&mut buffer);
+
+    let bin_width = rate / size as f32;
+    let low = ((TONE_HZ * 0.8) / bin_width) as usize;
+    let high = (((TONE_HZ * 1.2) / bin_width) as usize).min(size / 2 - 2);
+    let magnitudes: Vec<f32> = buffer[..size / 2].iter().map(|c| c.norm()).collect();
+
+    let peak = (low..=high).max_by(|&a, &b| magnitudes[a].total_cmp(&magnitudes[b]))?;
+    let (y1, y2, y3) = (magnitudes[peak - 1], magnitudes[peak], magnitudes[peak + 1]);
+    let a = (y1 - 2.0 * y2 + y3) / 2.0;
+    let b = (y3 - y1) / 2.0;
+    let offset = if a != 0.0 { -b / (2.0 * a) } else { 0.0 };
+
+    Some((peak as f32 + offset) * bin_width)
+}
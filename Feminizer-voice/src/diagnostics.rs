@@ -0,0 +1,210 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::load::LoadMonitor;
+
+/// Intervalle de calcul des débits.
+const RATE_INTERVAL: Duration = Duration::from_secs(1);
+const SMOOTHING: f32 = 0.1;
+
+/// Compteurs relevés au dernier calcul des débits.
+struct Counters {
+    at: Instant,
+    frames: u64,
+    received: u64,
+    dropped: u64,
+    lost: u64,
+    overruns: u64,
+}
+
+/// Débits par seconde sur le dernier intervalle.
+#[derive(Default)]
+struct Rates {
+    frames: f32,
+    received: f32,
+    dropped: f32,
+    lost: f32,
+    overruns: f32,
+}
+
+/// Ce qui est propre au flux ouvert, relevé par l'application à chaque image.
+pub struct StreamInfo {
+    pub load: LoadMonitor,
+    pub device_rate: f32,
+    pub analysis_rate: f32,
+    /// Durée de la fenêtre d'analyse: une hauteur décrit le milieu de sa fenêtre.
+    pub window_secs: f32,
+}
+
+/// Fenêtre de diagnostic: trames analysées et perdues, latence estimée de
+/// bout en bout et occupation du thread d'analyse.
+#[derive(Default)]
+pub struct Diagnostics {
+    pub open: bool,
+    /// Trames lues par l'interface.
+    received: u64,
+    /// Délai entre la fin d'une trame et sa lecture par l'interface, lissé.
+    queue_secs: f32,
+    last: Option<Counters>,
+    rates: Rates,
+}
+
+impl Diagnostics {
+    pub fn push_frame(&mut self, captured_at: Instant) {
+        self.received += 1;
+        let delay = captured_at.elapsed().as_secs_f32();
+        self.queue_secs += SMOOTHING * (delay - self.queue_secs);
+    }
+
+    fn update_rates(&mut self, load: &LoadMonitor) {
+        let now = Instant::now();
+        let counters = Counters {
+            at: now,
+            frames: load.frames,
+            received: self.received,
+            dropped: load.dropped_frames,
+            lost: load.lost_samples,
+            overruns: load.overruns,
+        };
+        let Some(last) = &self.last else {
+            self.last = Some(counters);
+            return;
+        };
+        let elapsed = now.duration_since(last.at);
+        if elapsed < RATE_INTERVAL {
+            return;
+        }
+        // Un nouveau flux repart de zéro: pas de débit négatif
+        let rate = |current: u64, before: u64| {
+            current.saturating_sub(before) as f32 / elapsed.as_secs_f32()
+        };
+        self.rates = Rates {
+            frames: rate(counters.frames, last.frames),
+            received: rate(counters.received, last.received),
+            dropped: rate(counters.dropped, last.dropped),
+            lost: rate(counters.lost, last.lost),
+            overruns: rate(counters.overruns, last.overruns),
+        };
+        self.last = Some(counters);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, stream: Option<&StreamInfo>) {
+        if !self.open {
+            self.last = None;
+            return;
+        }
+        if let Some(stream) = stream {
+            self.update_rates(&stream.load);
+        }
+
+        let mut open = self.open;
+        egui::Window::new("🩺 Diagnostics")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| match stream {
+                Some(stream) => self.show_stream(ui, stream),
+                None => {
+                    ui.weak("Démarrez l'enregistrement pour mesurer l'analyse.");
+                }
+            });
+        self.open = open;
+    }
+
+    fn show_stream(&self, ui: &mut egui::Ui, stream: &StreamInfo) {
+        let load = &stream.load;
+        let warning = egui::Color32::from_rgb(255, 170, 60);
+        let count = |ui: &mut egui::Ui, total: u64, per_sec: f32| {
+            let text = format!("{} ({:.1}/s)", total, per_sec);
+            if per_sec > 0.0 {
+                ui.colored_label(warning, text);
+            } else {
+                ui.label(text);
+            }
+        };
+
+        egui::Grid::new("diagnostics_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Échantillonnage");
+                if stream.analysis_rate < stream.device_rate {
+                    ui.label(format!(
+                        "{:.0} Hz, analysé à {:.0} Hz",
+                        stream.device_rate, stream.analysis_rate
+                    ));
+                } else {
+                    ui.label(format!("{:.0} Hz", stream.device_rate));
+                }
+                ui.end_row();
+
+                ui.label("Trames analysées");
+                ui.label(format!("{:.1}/s", self.rates.frames));
+                ui.end_row();
+
+                ui.label("Trames affichées");
+                ui.label(format!("{:.1}/s", self.rates.received));
+                ui.end_row();
+
+                ui.label("Trames perdues")
+                    .on_hover_text("Écrasées par la suivante avant d'avoir été affichées");
+                count(ui, load.dropped_frames, self.rates.dropped);
+                ui.end_row();
+
+                ui.label("Échantillons perdus").on_hover_text(
+                    "Écartés par le callback audio: l'analyse avait plus d'une seconde \
+                     de retard",
+                );
+                count(ui, load.lost_samples, self.rates.lost);
+                ui.end_row();
+
+                ui.label("Blocs en retard").on_hover_text(
+                    "Blocs analysés en plus de temps qu'ils n'en couvrent: l'analyse \
+                     prend du retard sur la capture",
+                );
+                count(ui, load.overruns, self.rates.overruns);
+                ui.end_row();
+
+                ui.label("Occupation du thread d'analyse");
+                ui.label(format!(
+                    "{:.0} % (pointe {:.0} %)",
+                    100.0 * load.load(),
+                    100.0 * load.peak_load()
+                ));
+                ui.end_row();
+
+                ui.label("Délestage");
+                ui.label(load.level().label());
+                ui.end_row();
+            });
+
+        ui.separator();
+        ui.label("⏱ Latence estimée");
+        let input = load.input_latency.map(|latency| latency.as_secs_f32());
+        let half_window = stream.window_secs / 2.0;
+        egui::Grid::new("diagnostics_latency")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Tampon d'entrée");
+                ui.label(match input {
+                    Some(secs) => format!("{:.1} ms", 1000.0 * secs),
+                    None => "non fourni par le pilote".to_string(),
+                });
+                ui.end_row();
+
+                ui.label("Demi-fenêtre d'analyse");
+                ui.label(format!("{:.1} ms", 1000.0 * half_window));
+                ui.end_row();
+
+                ui.label("Attente avant affichage");
+                ui.label(format!("{:.1} ms", 1000.0 * self.queue_secs));
+                ui.end_row();
+
+                ui.strong("Total");
+                ui.strong(format!(
+                    "{:.0} ms",
+                    1000.0 * (input.unwrap_or(0.0) + half_window + self.queue_secs)
+                ));
+                ui.end_row();
+            });
+    }
+}
@@ -0,0 +1,104 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Signal sonore quand la hauteur reste sous le plancher de la cible.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FloorCueSettings {
+    pub enabled: bool,
+    /// Temps continu sous le plancher avant le signal.
+    pub hold_ms: f32,
+    /// Silence minimal entre deux signaux.
+    pub cooldown_secs: f32,
+    /// Joue une note à la hauteur chantée plutôt qu'un simple tic.
+    pub pitch_tone: bool,
+}
+
+impl Default for FloorCueSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hold_ms: 500.0,
+            cooldown_secs: 3.0,
+            pitch_tone: false,
+        }
+    }
+}
+
+impl FloorCueSettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.enabled, "🔉 Signal sous la cible")
+                .on_hover_text(
+                    "Un tic dans le casque quand la voix reste sous le bas de la plage cible \
+                     (volume: « Sous la cible » dans les signaux sonores)",
+                )
+                .changed();
+            ui.add_enabled_ui(self.enabled, |ui| {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.hold_ms, 100.0..=3000.0)
+                            .step_by(50.0)
+                            .text("ms"),
+                    )
+                    .on_hover_text("Temps sous la cible avant le signal")
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.cooldown_secs, 0.5..=30.0)
+                            .text("s entre deux signaux"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.pitch_tone, "Note à la hauteur chantée")
+                    .changed();
+            });
+        });
+        changed
+    }
+}
+
+/// Temps passé sous le plancher et délai depuis le dernier signal.
+#[derive(Default)]
+pub struct FloorCue {
+    below_secs: f32,
+    since_cue: Option<f32>,
+}
+
+impl FloorCue {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// `frequency` vaut 0 hors voix, ce qui interrompt l'épisode. Renvoie la
+    /// hauteur fautive quand il faut jouer le signal.
+    pub fn push(
+        &mut self,
+        settings: &FloorCueSettings,
+        frequency: f32,
+        floor_hz: f32,
+        frame_duration: f32,
+    ) -> Option<f32> {
+        if let Some(since) = &mut self.since_cue {
+            *since += frame_duration;
+        }
+        if !settings.enabled || frequency <= 0.0 || frequency >= floor_hz {
+            self.below_secs = 0.0;
+            return None;
+        }
+        self.below_secs += frame_duration;
+
+        let cooled_down = self
+            .since_cue
+            .is_none_or(|since| since >= settings.cooldown_secs);
+        if self.below_secs * 1000.0 >= settings.hold_ms && cooled_down {
+            self.since_cue = Some(0.0);
+            return Some(frequency);
+        }
+        None
+    }
+}
@@ -0,0 +1,148 @@
+use eframe::egui;
+
+use crate::accessibility;
+use crate::pitch_unit::PitchScale;
+use crate::theme::Palette;
+
+pub struct GaugeReading {
+    pub frequency: f32,
+    pub is_voiced: bool,
+    pub target_min: f32,
+    pub target_max: f32,
+    pub scale_min: f32,
+    pub scale_max: f32,
+    pub scale: PitchScale,
+    pub palette: Palette,
+}
+
+#[derive(Default)]
+pub struct GaugeWindow {
+    pub open: bool,
+    fullscreen: bool,
+}
+
+impl GaugeWindow {
+    pub fn show(&mut self, ctx: &egui::Context, reading: &GaugeReading) {
+        if !self.open {
+            return;
+        }
+
+        let builder = egui::ViewportBuilder::default()
+            .with_title("Feminizer voice — Jauge")
+            .with_inner_size([800.0, 600.0]);
+
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("gauge_viewport"),
+            builder,
+            |ctx, _class| {
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    self.open = false;
+                    self.fullscreen = false;
+                }
+
+                let toggle = ctx.input(|i| {
+                    i.key_pressed(egui::Key::F11)
+                        || i.pointer.button_double_clicked(egui::PointerButton::Primary)
+                });
+                let leave = ctx.input(|i| i.key_pressed(egui::Key::Escape)) && self.fullscreen;
+                if toggle || leave {
+                    self.fullscreen = !self.fullscreen;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(self.fullscreen));
+                }
+
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::new().fill(egui::Color32::BLACK))
+                    .show(ctx, |ui| draw_gauge(ui, reading));
+            },
+        );
+    }
+}
+
+/// Position de la hauteur par rapport à la cible, pour les lecteurs d'écran.
+fn status_label(reading: &GaugeReading) -> String {
+    if !reading.is_voiced || reading.frequency <= 0.0 {
+        return "Jauge de hauteur: aucune voix".to_string();
+    }
+    let position = if reading.frequency < reading.target_min {
+        "sous la cible"
+    } else if reading.frequency > reading.target_max {
+        "au-dessus de la cible"
+    } else {
+        "dans la cible"
+    };
+    format!(
+        "Jauge de hauteur: {}, {}",
+        reading.scale.format(reading.frequency),
+        position
+    )
+}
+
+fn status_color(reading: &GaugeReading) -> egui::Color32 {
+    if !reading.is_voiced || reading.frequency <= 0.0 {
+        egui::Color32::DARK_GRAY
+    } else if reading.frequency < reading.target_min {
+        reading.palette.low_range
+    } else if reading.frequency > reading.target_max {
+        reading.palette.warning
+    } else {
+        reading.palette.in_range
+    }
+}
+
+pub fn draw_gauge(ui: &mut egui::Ui, reading: &GaugeReading) {
+    let rect = ui.available_rect_before_wrap();
+    let painter = ui.painter_at(rect);
+    let color = status_color(reading);
+
+    let bar_height = rect.height() * 0.12;
+    let bar = egui::Rect::from_min_size(
+        egui::pos2(rect.left() + rect.width() * 0.05, rect.bottom() - bar_height * 2.0),
+        egui::vec2(rect.width() * 0.9, bar_height),
+    );
+    let x_for = |freq: f32| {
+        let t = ((freq - reading.scale_min) / (reading.scale_max - reading.scale_min)).clamp(0.0, 1.0);
+        bar.left() + t * bar.width()
+    };
+
+    painter.rect_filled(bar, 8.0, egui::Color32::from_gray(30));
+    let target = egui::Rect::from_x_y_ranges(
+        x_for(reading.target_min)..=x_for(reading.target_max),
+        bar.y_range(),
+    );
+    painter.rect_filled(target, 0.0, reading.palette.in_range.gamma_multiply(0.3));
+
+    let text = if reading.is_voiced && reading.frequency > 0.0 {
+        let x = x_for(reading.frequency);
+        painter.line_segment(
+            [egui::pos2(x, bar.top() - 10.0), egui::pos2(x, bar.bottom() + 10.0)],
+            egui::Stroke::new(6.0, color),
+        );
+        reading.scale.format(reading.frequency)
+    } else {
+        "—".to_string()
+    };
+
+    let font_size = (rect.height() * 0.35).min(rect.width() * 0.22);
+    painter.text(
+        egui::pos2(rect.center().x, rect.top() + rect.height() * 0.38),
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::proportional(font_size),
+        color,
+    );
+
+    painter.text(
+        egui::pos2(bar.left(), bar.bottom() + 6.0),
+        egui::Align2::LEFT_TOP,
+        format!(
+            "Cible {} – {}   (F11 / double-clic: plein écran)",
+            reading.scale.format(reading.target_min),
+            reading.scale.format(reading.target_max)
+        ),
+        egui::FontId::proportional((bar_height * 0.3).max(12.0)),
+        egui::Color32::GRAY,
+    );
+
+    let response = ui.allocate_rect(rect, egui::Sense::hover());
+    accessibility::describe(&response, egui::WidgetType::Label, &status_label(reading));
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::session::SessionSummary;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+fn day_of(timestamp: u64) -> u64 {
+    timestamp / SECS_PER_DAY
+}
+
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| day_of(d.as_secs()))
+        .unwrap_or(0)
+}
+
+/// Objectif de pratique quotidien, en temps de voix.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PracticeGoal {
+    pub enabled: bool,
+    pub minutes: f32,
+    /// Ne compter que le temps de voix dans la cible.
+    pub in_range_only: bool,
+    /// Notification du bureau quand l'objectif est atteint.
+    pub notify: bool,
+}
+
+impl Default for PracticeGoal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            minutes: 15.0,
+            in_range_only: true,
+            notify: true,
+        }
+    }
+}
+
+/// État de l'objectif à la fin d'une session, gardé dans l'historique.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoalRecord {
+    pub target_secs: f32,
+    /// Temps compté sur la journée, cette session comprise.
+    pub day_secs: f32,
+    pub reached: bool,
+}
+
+impl PracticeGoal {
+    pub fn target_secs(&self) -> f32 {
+        self.minutes * 60.0
+    }
+
+    pub fn counted_secs(&self, voiced_secs: f32, in_range_secs: f32) -> f32 {
+        if self.in_range_only {
+            in_range_secs
+        } else {
+            voiced_secs
+        }
+    }
+
+    fn session_secs(&self, session: &SessionSummary) -> f32 {
+        let in_range = session.voiced_secs * session.in_range_percent / 100.0;
+        self.counted_secs(session.voiced_secs, in_range)
+    }
+
+    /// Temps déjà compté aujourd'hui dans les sessions enregistrées.
+    pub fn today_secs(&self, sessions: &[SessionSummary]) -> f32 {
+        let today = today();
+        sessions
+            .iter()
+            .filter(|session| day_of(session.started_at) == today)
+            .map(|session| self.session_secs(session))
+            .sum()
+    }
+
+    pub fn record(&self, day_secs: f32) -> GoalRecord {
+        GoalRecord {
+            target_secs: self.target_secs(),
+            day_secs,
+            reached: day_secs >= self.target_secs(),
+        }
+    }
+
+    pub fn show_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui.checkbox(&mut self.enabled, "🎯 Objectif quotidien").changed();
+            ui.add_enabled_ui(self.enabled, |ui| {
+                changed |= ui
+                    .add(egui::Slider::new(&mut self.minutes, 1.0..=120.0).text("min"))
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.in_range_only, "dans la cible uniquement")
+                    .changed();
+                changed |= ui.checkbox(&mut self.notify, "notification").changed();
+            });
+        });
+        changed
+    }
+}
+
+/// Se souvient du jour où l'objectif a été annoncé, pour ne le faire qu'une fois.
+#[derive(Default)]
+pub struct GoalTracker {
+    announced_day: Option<u64>,
+}
+
+impl GoalTracker {
+    /// Renvoie vrai au moment où l'objectif du jour vient d'être atteint.
+    pub fn check(&mut self, goal: &PracticeGoal, day_secs: f32) -> bool {
+        let today = today();
+        if !goal.enabled || day_secs < goal.target_secs() || self.announced_day == Some(today) {
+            return false;
+        }
+        self.announced_day = Some(today);
+        true
+    }
+}
+
+/// Notification du bureau par l'outil natif de chaque système.
+pub fn notify_desktop(title: &str, body: &str) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification {:?} with title {:?}",
+                body, title
+            ))
+            .status()?
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, \
+             ContentType = WindowsRuntime] | Out-Null; \
+             $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent(1); \
+             $text = $xml.GetElementsByTagName('text'); \
+             $text.Item(0).AppendChild($xml.CreateTextNode('{}')) | Out-Null; \
+             $text.Item(1).AppendChild($xml.CreateTextNode('{}')) | Out-Null; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Feminizer voice')\
+             .Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+            title.replace('\'', "''"),
+            body.replace('\'', "''")
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-Command", &script])
+            .status()?
+    } else {
+        Command::new("notify-send").arg(title).arg(body).status()?
+    };
+
+    if !status.success() {
+        anyhow::bail!("la notification a échoué ({})", status);
+    }
+    Ok(())
+}
+
+/// Anneau de progression avec le temps restant au centre.
+pub fn draw_ring(ui: &mut egui::Ui, goal: &PracticeGoal, day_secs: f32) {
+    let size = 90.0;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let center = rect.center();
+    let radius = size / 2.0 - 6.0;
+
+    let fraction = (day_secs / goal.target_secs().max(1.0)).clamp(0.0, 1.0);
+    let done = fraction >= 1.0;
+    let color = if done {
+        egui::Color32::GREEN
+    } else {
+        egui::Color32::from_rgb(255, 0, 255)
+    };
+
+    painter.circle_stroke(center, radius, egui::Stroke::new(8.0, egui::Color32::from_gray(45)));
+    let steps = (64.0 * fraction).ceil().max(1.0) as usize;
+    let arc: Vec<egui::Pos2> = (0..=steps)
+        .map(|i| {
+            // Départ en haut, sens horaire
+            let angle = -std::f32::consts::FRAC_PI_2
+                + std::f32::consts::TAU * fraction * i as f32 / steps as f32;
+            center + radius * egui::vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    if fraction > 0.0 {
+        painter.add(egui::Shape::line(arc, egui::Stroke::new(8.0, color)));
+    }
+
+    let remaining = (goal.target_secs() - day_secs).max(0.0) as u32;
+    let text = if done {
+        "✔".to_string()
+    } else {
+        format!("{}:{:02}", remaining / 60, remaining % 60)
+    };
+    painter.text(
+        center,
+        egui::Align2::CENTER_CENTER,
+        text,
+        egui::FontId::proportional(18.0),
+        egui::Color32::WHITE,
+    );
+}
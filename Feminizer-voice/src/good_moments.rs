@@ -0,0 +1,468 @@
+use anyhow::Result;
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use crate::dates::DateTime;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+use crate::playback::ClipPlayer;
+use crate::session_audio::SessionAudioWriter;
+use crate::wav;
+
+/// Un bon moment plus long est enregistré en plusieurs extraits.
+const MAX_CLIP_SECS: f32 = 30.0;
+/// Coupure de voix tolérée au milieu d'un bon moment: une consonne sourde,
+/// une respiration brève.
+const MAX_GAP_SECS: f32 = 0.25;
+/// Audio pris de part et d'autre avant de retirer les silences: l'analyse
+/// arrive un peu après le son.
+const MARGIN_SECS: f32 = 0.3;
+/// Blocs du rognage des silences, et seuil relatif au bloc le plus fort.
+const TRIM_BLOCK_SECS: f32 = 0.01;
+const TRIM_THRESHOLD: f32 = 0.1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GoodMomentSettings {
+    /// Garder automatiquement l'audio des passages tenus dans la cible.
+    pub enabled: bool,
+    /// Durée minimale d'un bon moment, en secondes.
+    pub min_secs: f32,
+}
+
+impl Default for GoodMomentSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_secs: 3.0,
+        }
+    }
+}
+
+impl GoodMomentSettings {
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.enabled, "Garder les passages tenus dans la cible pendant")
+                .on_hover_text("L'audio est enregistré sur cette machine, dans le profil actif")
+                .changed();
+            changed |= ui
+                .add_enabled(
+                    self.enabled,
+                    egui::Slider::new(&mut self.min_secs, 1.0..=10.0).suffix(" s"),
+                )
+                .changed();
+        });
+        changed
+    }
+}
+
+/// Bon moment enregistré: retrouvé au prochain lancement.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Clip {
+    /// Fichier WAV, dans le dossier des bons moments.
+    file: String,
+    /// Horodatage Unix de l'enregistrement.
+    at: u64,
+    /// Session pendant laquelle il a été capté.
+    session_started_at: Option<u64>,
+    duration_secs: f32,
+    median_hz: f32,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ClipIndex {
+    clips: Vec<Clip>,
+}
+
+fn clips_dir() -> Result<PathBuf> {
+    paths::profile_subdir("good_moments")
+}
+
+fn load_clips() -> Result<Vec<Clip>> {
+    let path = clips_dir()?.join("clips.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let index: ClipIndex = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(index.clips)
+}
+
+fn write_wav(file: &str, samples: &[f32], sample_rate: f32) -> Result<()> {
+    let mut writer = SessionAudioWriter::create(&clips_dir()?.join(file), sample_rate)?;
+    writer.write(samples)?;
+    writer.finish()
+}
+
+/// Retire les silences du début et de la fin, par blocs de
+/// [`TRIM_BLOCK_SECS`] comparés au bloc le plus fort.
+fn trim_silence(samples: &[f32], sample_rate: f32) -> &[f32] {
+    let block = ((TRIM_BLOCK_SECS * sample_rate) as usize).max(1);
+    let levels: Vec<f32> = samples
+        .chunks(block)
+        .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect();
+    let threshold = levels.iter().copied().fold(0.0, f32::max) * TRIM_THRESHOLD;
+    let Some(first) = levels.iter().position(|&level| level > threshold) else {
+        return &[];
+    };
+    let last = levels.iter().rposition(|&level| level > threshold).unwrap_or(first);
+    &samples[first * block..((last + 1) * block).min(samples.len())]
+}
+
+/// Échantillons de la fin du tampon qui couvrent un passage de `run_secs`
+/// terminé il y a `trailing_secs`, marges comprises.
+fn clip_range(audio_len: usize, run_secs: f32, trailing_secs: f32, rate: f32) -> Range<usize> {
+    let skipped = (((trailing_secs - MARGIN_SECS).max(0.0)) * rate) as usize;
+    let end = audio_len.saturating_sub(skipped);
+    let start = end.saturating_sub(((run_secs + 2.0 * MARGIN_SECS) * rate) as usize);
+    start..end
+}
+
+/// Suite de trames dans la cible en cours.
+#[derive(Default)]
+struct Run {
+    secs: f32,
+    /// Coupure de voix en cours, pas encore comptée dans `secs`.
+    gap_secs: f32,
+    pitches: Vec<f32>,
+}
+
+/// Bon moment qui vient d'être enregistré, pour le repérer dans la session.
+pub struct SavedClip {
+    pub duration_secs: f32,
+    pub median_hz: f32,
+    /// Temps écoulé depuis le début du passage.
+    pub started_secs_ago: f32,
+}
+
+/// Bibliothèque des bons moments: les passages tenus dans la cible sont
+/// gardés automatiquement pour retrouver plus tard la sensation de la voix.
+#[derive(Default)]
+pub struct GoodMoments {
+    clips: Vec<Clip>,
+    loaded: bool,
+    /// Dernières secondes d'audio brut, de quoi couvrir le plus long extrait.
+    audio: VecDeque<f32>,
+    audio_rate: f32,
+    run: Option<Run>,
+    player: ClipPlayer,
+    playing: Option<String>,
+    error: Option<String>,
+}
+
+impl GoodMoments {
+    /// Vrai quand l'audio brut doit lui parvenir pendant l'enregistrement.
+    pub fn is_listening(&self, settings: &GoodMomentSettings) -> bool {
+        settings.enabled
+    }
+
+    pub fn push_samples(&mut self, samples: &[f32], sample_rate: f32) {
+        if sample_rate != self.audio_rate {
+            self.audio.clear();
+            self.run = None;
+            self.audio_rate = sample_rate;
+        }
+        self.audio.extend(samples);
+        let capacity = ((MAX_CLIP_SECS + 4.0 * MARGIN_SECS + MAX_GAP_SECS) * sample_rate) as usize;
+        let excess = self.audio.len().saturating_sub(capacity);
+        self.audio.drain(..excess);
+    }
+
+    /// Trame analysée; `frequency` vaut 0 hors voix. Renvoie l'extrait
+    /// enregistré quand un bon moment se termine.
+    pub fn push_frame(
+        &mut self,
+        settings: &GoodMomentSettings,
+        frequency: f32,
+        in_target: bool,
+        frame_duration: f32,
+        session_started_at: Option<u64>,
+    ) -> Option<SavedClip> {
+        if !settings.enabled {
+            self.run = None;
+            return None;
+        }
+        if frequency > 0.0 && in_target {
+            let run = self.run.get_or_insert_default();
+            run.secs += run.gap_secs + frame_duration;
+            run.gap_secs = 0.0;
+            run.pitches.push(frequency);
+            if run.secs >= MAX_CLIP_SECS {
+                return self.finish_run(settings, 0.0, session_started_at);
+            }
+            return None;
+        }
+
+        let run = self.run.as_mut()?;
+        run.gap_secs += frame_duration;
+        // Une voix hors de la cible termine le passage aussitôt
+        if frequency > 0.0 || run.gap_secs > MAX_GAP_SECS {
+            let trailing = run.gap_secs;
+            return self.finish_run(settings, trailing, session_started_at);
+        }
+        None
+    }
+
+    /// À l'arrêt de l'enregistrement: le passage en cours compte s'il est
+    /// assez long.
+    pub fn finish(
+        &mut self,
+        settings: &GoodMomentSettings,
+        session_started_at: Option<u64>,
+    ) -> Option<SavedClip> {
+        let trailing = self.run.as_ref().map_or(0.0, |run| run.gap_secs);
+        let saved = self.finish_run(settings, trailing, session_started_at);
+        self.audio.clear();
+        saved
+    }
+
+    /// `trailing_secs`: temps écoulé depuis la dernière trame dans la cible.
+    fn finish_run(
+        &mut self,
+        settings: &GoodMomentSettings,
+        trailing_secs: f32,
+        session_started_at: Option<u64>,
+    ) -> Option<SavedClip> {
+        let mut run = self.run.take()?;
+        if run.secs < settings.min_secs || self.audio_rate <= 0.0 {
+            return None;
+        }
+        let rate = self.audio_rate;
+        let range = clip_range(self.audio.len(), run.secs, trailing_secs, rate);
+        let samples: Vec<f32> = self.audio.range(range).copied().collect();
+        let samples = trim_silence(&samples, rate);
+        if samples.is_empty() {
+            return None;
+        }
+
+        run.pitches.sort_by(f32::total_cmp);
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let clip = Clip {
+            file: format!("{}.wav", at),
+            at: at / 1000,
+            session_started_at,
+            duration_secs: samples.len() as f32 / rate,
+            median_hz: run.pitches[run.pitches.len() / 2],
+        };
+        if let Err(e) = write_wav(&clip.file, samples, rate) {
+            self.error = Some(format!("Enregistrement du bon moment: {}", e));
+            return None;
+        }
+        let saved = SavedClip {
+            duration_secs: clip.duration_secs,
+            median_hz: clip.median_hz,
+            started_secs_ago: run.secs + trailing_secs,
+        };
+        self.ensure_loaded();
+        self.clips.push(clip);
+        self.save();
+        Some(saved)
+    }
+
+    fn save(&mut self) {
+        let index = ClipIndex {
+            clips: self.clips.clone(),
+        };
+        let saved = clips_dir().and_then(|dir| {
+            fs::write(dir.join("clips.json"), serde_json::to_string_pretty(&index)?)?;
+            Ok(())
+        });
+        if let Err(e) = saved {
+            self.error = Some(format!("Enregistrement des bons moments: {}", e));
+        }
+    }
+
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        match load_clips() {
+            Ok(clips) => self.clips = clips,
+            Err(e) => self.error = Some(format!("Bons moments illisibles: {}", e)),
+        }
+    }
+
+    fn play(&mut self, clip: &Clip) {
+        let loaded = clips_dir().and_then(|dir| wav::read_mono(&dir.join(&clip.file)));
+        match loaded {
+            Ok((samples, rate)) => match self.player.play(Arc::new(samples), rate, 0.0) {
+                Ok(()) => {
+                    self.playing = Some(clip.file.clone());
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(format!("Lecture: {}", e)),
+            },
+            Err(e) => self.error = Some(format!("Lecture du bon moment: {}", e)),
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        let clip = self.clips.remove(index);
+        if let Ok(dir) = clips_dir()
+            && let Err(e) = fs::remove_file(dir.join(&clip.file))
+        {
+            warn!("Suppression de {}: {}", clip.file, e);
+        }
+        self.save();
+    }
+
+    /// Renvoie `true` si les réglages ont changé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        settings: &mut GoodMomentSettings,
+        scale: PitchScale,
+    ) -> bool {
+        self.ensure_loaded();
+        if self.player.position_secs().is_none() {
+            self.playing = None;
+        } else {
+            ui.ctx().request_repaint();
+        }
+
+        ui.heading("🌟 Bons moments");
+        ui.label(
+            "Quand la voix reste dans la cible assez longtemps, l'extrait est gardé, sans les \
+             silences autour, et repéré dans la session. Réécoutez-les pour retrouver le \
+             placement.",
+        );
+        let changed = settings.show(ui);
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        ui.separator();
+
+        if self.clips.is_empty() {
+            ui.weak("Aucun bon moment pour ce profil.");
+            return changed;
+        }
+        let mut action = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("good_moment_list")
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["", "Date", "Durée", "Hauteur", ""] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for (i, clip) in self.clips.iter().enumerate().rev() {
+                        let playing = self.playing.as_ref() == Some(&clip.file);
+                        let (icon, hint) = if playing {
+                            ("⏹", "Arrêter")
+                        } else {
+                            ("▶", "Écouter")
+                        };
+                        if ui.small_button(icon).on_hover_text(hint).clicked() {
+                            action = Some((i, playing, false));
+                        }
+                        ui.label(DateTime::from_unix(clip.at).to_string());
+                        ui.label(format!("{:.1} s", clip.duration_secs));
+                        ui.label(scale.format(clip.median_hz));
+                        if ui.small_button("🗑").on_hover_text("Supprimer").clicked() {
+                            action = Some((i, playing, true));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        match action {
+            Some((i, playing, true)) => {
+                if playing {
+                    self.player.stop();
+                }
+                self.remove(i);
+            }
+            Some((_, true, false)) => self.player.stop(),
+            Some((i, false, false)) => {
+                let clip = self.clips[i].clone();
+                self.play(&clip);
+            }
+            None => {}
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.01;
+    const RATE: f32 = 1000.0;
+
+    fn settings() -> GoodMomentSettings {
+        GoodMomentSettings {
+            enabled: true,
+            min_secs: 3.0,
+        }
+    }
+
+    /// Trames de voix dans la cible, ou de silence si `frequency` vaut 0.
+    fn push(moments: &mut GoodMoments, frequency: f32, secs: f32) {
+        for _ in 0..(secs / FRAME).round() as usize {
+            let saved = moments.push_frame(&settings(), frequency, frequency > 0.0, FRAME, None);
+            assert!(saved.is_none());
+        }
+    }
+
+    #[test]
+    fn a_silence_longer_than_a_gap_ends_the_run() {
+        let mut moments = GoodMoments::default();
+        push(&mut moments, 220.0, 2.0);
+        push(&mut moments, 0.0, 0.3);
+        assert!(moments.run.is_none());
+
+        // Deux passages de 2 s ne font pas un bon moment de 3 s
+        push(&mut moments, 220.0, 2.0);
+        let run = moments.run.as_ref().unwrap();
+        assert!((run.secs - 2.0).abs() < 0.05, "{}", run.secs);
+    }
+
+    #[test]
+    fn a_short_gap_is_bridged() {
+        let mut moments = GoodMoments::default();
+        push(&mut moments, 220.0, 1.0);
+        push(&mut moments, 0.0, 0.1);
+        push(&mut moments, 220.0, 1.0);
+        let run = moments.run.as_ref().unwrap();
+        assert!((run.secs - 2.1).abs() < 0.05, "{}", run.secs);
+    }
+
+    #[test]
+    fn the_clip_skips_the_trailing_silence() {
+        // 10 s de tampon, passage de 3 s terminé il y a 2 s
+        let range = clip_range(10_000, 3.0, 2.0, RATE);
+        let end = 10_000 - ((2.0 - MARGIN_SECS) * RATE) as usize;
+        assert_eq!(range.end, end);
+        assert_eq!(range.start, end - ((3.0 + 2.0 * MARGIN_SECS) * RATE) as usize);
+
+        // Le tampon ne remonte pas assez loin: l'extrait commence au début
+        assert_eq!(clip_range(1_000, 3.0, 0.0, RATE).start, 0);
+    }
+
+    #[test]
+    fn silences_are_trimmed_from_both_ends() {
+        let mut samples = vec![0.0; 500];
+        samples.extend((0..1000).map(|i| (i as f32 * 0.3).sin() * 0.5));
+        samples.extend(vec![0.001; 700]);
+        let trimmed = trim_silence(&samples, RATE);
+        assert_eq!(trimmed.len(), 1000);
+        assert_eq!(trimmed.as_ptr(), samples[500..].as_ptr());
+        assert!(trim_silence(&[0.0; 100], RATE).is_empty());
+    }
+}
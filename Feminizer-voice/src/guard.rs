@@ -0,0 +1,142 @@
+use eframe::egui;
+use std::collections::VecDeque;
+
+const BASELINE_SECS: f32 = 120.0;
+const MIN_BASELINE_SECS: f32 = 10.0;
+const MEDIAN_REFRESH_SECS: f32 = 1.0;
+const COOLDOWN_SECS: f32 = 30.0;
+
+/// Garde-fou de conversation: repère une descente prolongée sous la médiane
+/// récente (la voix fatiguée qui retombe) et ne donne qu'un seul rappel par
+/// épisode.
+pub struct PitchGuard {
+    pub enabled: bool,
+    drop_semitones: f32,
+    sustain_secs: f32,
+    // Trames voisées hors descente: (fréquence, durée)
+    baseline: VecDeque<(f32, f32)>,
+    baseline_secs: f32,
+    median: Option<f32>,
+    since_median: f32,
+    below_secs: f32,
+    armed: bool,
+    since_nudge: f32,
+    nudges: usize,
+}
+
+impl Default for PitchGuard {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drop_semitones: 3.0,
+            sustain_secs: 4.0,
+            baseline: VecDeque::new(),
+            baseline_secs: 0.0,
+            median: None,
+            since_median: 0.0,
+            below_secs: 0.0,
+            armed: true,
+            since_nudge: COOLDOWN_SECS,
+            nudges: 0,
+        }
+    }
+}
+
+impl PitchGuard {
+    pub fn reset(&mut self) {
+        *self = Self {
+            enabled: self.enabled,
+            drop_semitones: self.drop_semitones,
+            sustain_secs: self.sustain_secs,
+            ..Self::default()
+        };
+    }
+
+    /// Renvoie vrai quand il faut donner le rappel.
+    pub fn push(&mut self, frequency: f32, frame_duration: f32) -> bool {
+        if !self.enabled || frequency <= 0.0 {
+            return false;
+        }
+        self.since_nudge += frame_duration;
+        self.since_median += frame_duration;
+
+        let dropped = self
+            .median
+            .is_some_and(|median| 12.0 * (median / frequency).log2() > self.drop_semitones);
+
+        if dropped {
+            self.below_secs += frame_duration;
+        } else {
+            // La référence reste figée pendant une descente pour ne pas la suivre
+            self.push_baseline(frequency, frame_duration);
+            // Un bref retour au-dessus du seuil n'efface pas toute la descente
+            self.below_secs = (self.below_secs - frame_duration).max(0.0);
+            if self.below_secs == 0.0 {
+                self.armed = true;
+            }
+        }
+
+        if self.armed && self.below_secs >= self.sustain_secs && self.since_nudge >= COOLDOWN_SECS
+        {
+            self.armed = false;
+            self.since_nudge = 0.0;
+            self.nudges += 1;
+            return true;
+        }
+        false
+    }
+
+    fn push_baseline(&mut self, frequency: f32, frame_duration: f32) {
+        self.baseline.push_back((frequency, frame_duration));
+        self.baseline_secs += frame_duration;
+        while self.baseline_secs > BASELINE_SECS
+            && let Some((_, duration)) = self.baseline.pop_front()
+        {
+            self.baseline_secs -= duration;
+        }
+
+        if self.since_median >= MEDIAN_REFRESH_SECS && self.baseline_secs >= MIN_BASELINE_SECS {
+            self.since_median = 0.0;
+            let mut frequencies: Vec<f32> = self.baseline.iter().map(|&(f, _)| f).collect();
+            frequencies.sort_by(|a, b| a.total_cmp(b));
+            self.median = Some(frequencies[frequencies.len() / 2]);
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, warning: egui::Color32) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.enabled, "🛡 Garde-fou de hauteur").on_hover_text(
+                "Un rappel discret quand la voix reste nettement sous sa médiane récente",
+            );
+            ui.add_enabled(
+                self.enabled,
+                egui::Slider::new(&mut self.drop_semitones, 1.0..=6.0)
+                    .step_by(0.5)
+                    .text("demi-tons"),
+            );
+            ui.add_enabled(
+                self.enabled,
+                egui::Slider::new(&mut self.sustain_secs, 1.0..=15.0).text("s"),
+            );
+
+            if !self.enabled {
+                return;
+            }
+            match self.median {
+                Some(median) => {
+                    let floor = median / 2.0_f32.powf(self.drop_semitones / 12.0);
+                    ui.small(format!("Médiane {:.0} Hz, plancher {:.0} Hz", median, floor));
+                }
+                None => {
+                    ui.small("Calibrage sur les premières secondes de parole…");
+                }
+            }
+            if self.below_secs > 0.0 {
+                ui.colored_label(warning, "⬇ En dessous");
+            }
+            if self.nudges > 0 {
+                ui.small(format!("{} rappel(s)", self.nudges));
+            }
+        });
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{Context, Result};
+use feminizer_voice_core::{AnalysisConfig, PitchDetector, VadConfig};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::audio_host;
+use crate::audio_processor::{AudioProcessor, InputChannels};
+use crate::{MIN_CONFIDENCE, TARGET_MAX_HZ, TARGET_MIN_HZ};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+pub const USAGE: &str = "\
+Utilisation: Feminizer-voice --headless [options]
+
+Options:
+  --host <nom>         Pilote audio: ALSA, JACK, WASAPI, ASIO… (défaut: pilote système)
+  --device <nom>       Périphérique d'entrée (défaut: périphérique système)
+  --channel <n>        Canal analysé, à partir de 1 (défaut: moyenne des canaux)
+  --list-devices       Affiche les périphériques d'entrée et quitte
+  --target <min-max>   Plage cible en Hz (défaut: 180-310)
+  --search <min-max>   Bornes de recherche de la hauteur en Hz (défaut: 50-450)
+  --detector <méthode> peak (pic spectral) ou hps (produit harmonique) (défaut: peak)
+  --rate <Hz>          Lignes émises par seconde (défaut: 10)
+  --format <format>    text, csv ou json (défaut: text)
+  --duration <s>       Arrête après cette durée (défaut: illimité)
+  --help               Affiche cette aide";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutputFormat {
+    Text,
+    Csv,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct HeadlessOptions {
+    host: Option<String>,
+    device: Option<String>,
+    channel: Option<usize>,
+    list_devices: bool,
+    show_help: bool,
+    target_min: f32,
+    target_max: f32,
+    analysis: AnalysisConfig,
+    rate: f32,
+    format: OutputFormat,
+    duration: Option<Duration>,
+}
+
+impl Default for HeadlessOptions {
+    fn default() -> Self {
+        Self {
+            host: None,
+            device: None,
+            channel: None,
+            list_devices: false,
+            show_help: false,
+            target_min: TARGET_MIN_HZ,
+            target_max: TARGET_MAX_HZ,
+            analysis: AnalysisConfig::default(),
+            rate: 10.0,
+            format: OutputFormat::Text,
+            duration: None,
+        }
+    }
+}
+
+impl HeadlessOptions {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("Valeur manquante pour {}", arg))
+            };
+
+            match arg.as_str() {
+                "--headless" => {}
+                "--list-devices" => options.list_devices = true,
+                "--help" | "-h" => options.show_help = true,
+                "--host" => options.host = Some(value()?.clone()),
+                "--device" => options.device = Some(value()?.clone()),
+                "--channel" => {
+                    let channel: usize = value()?.parse().context("Canal invalide")?;
+                    if channel == 0 {
+                        anyhow::bail!("Les canaux sont numérotés à partir de 1");
+                    }
+                    options.channel = Some(channel - 1);
+                }
+                "--target" => {
+                    let range = value()?;
+                    let (min, max) = range
+                        .split_once('-')
+                        .ok_or_else(|| anyhow::anyhow!("Plage cible invalide: {}", range))?;
+                    options.target_min = min.trim().parse().context("Plage cible invalide")?;
+                    options.target_max = max.trim().parse().context("Plage cible invalide")?;
+                    if options.target_min >= options.target_max {
+                        anyhow::bail!("Plage cible invalide: {}", range);
+                    }
+                }
+                "--search" => {
+                    let range = value()?;
+                    let (min, max) = range
+                        .split_once('-')
+                        .ok_or_else(|| anyhow::anyhow!("Bornes invalides: {}", range))?;
+                    options.analysis.min_frequency_hz =
+                        min.trim().parse().context("Bornes invalides")?;
+                    options.analysis.max_frequency_hz =
+                        max.trim().parse().context("Bornes invalides")?;
+                    options.analysis = options.analysis.sanitized();
+                }
+                "--detector" => {
+                    options.analysis.detector = match value()?.as_str() {
+                        "peak" => PitchDetector::SpectralPeak,
+                        "hps" => PitchDetector::HarmonicProduct,
+                        other => anyhow::bail!("Méthode de détection inconnue: {}", other),
+                    }
+                }
+                "--rate" => {
+                    options.rate = value()?.parse().context("Fréquence d'émission invalide")?;
+                    if options.rate <= 0.0 {
+                        anyhow::bail!("La fréquence d'émission doit être positive");
+                    }
+                }
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "text" => OutputFormat::Text,
+                        "csv" => OutputFormat::Csv,
+                        "json" => OutputFormat::Json,
+                        other => anyhow::bail!("Format de sortie inconnu: {}", other),
+                    }
+                }
+                "--duration" => {
+                    let secs: f32 = value()?.parse().context("Durée invalide")?;
+                    options.duration = Some(Duration::from_secs_f32(secs.max(0.0)));
+                }
+                other => anyhow::bail!("Option inconnue: {}\n\n{}", other, USAGE),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Une ligne de la sortie `--format json`.
+#[derive(Serialize, JsonSchema)]
+pub struct HeadlessLine {
+    time: f32,
+    frequency: f32,
+    amplitude: f32,
+    is_voiced: bool,
+    in_target: bool,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    voiced_frequencies: Vec<f32>,
+    amplitude_sum: f32,
+    frames: usize,
+}
+
+impl Accumulator {
+    fn push(&mut self, frequency: f32, amplitude: f32, is_voiced: bool) {
+        if is_voiced && frequency > 0.0 {
+            self.voiced_frequencies.push(frequency);
+        }
+        self.amplitude_sum += amplitude;
+        self.frames += 1;
+    }
+
+    fn take_line(&mut self, time: f32, options: &HeadlessOptions) -> HeadlessLine {
+        let frequency = if self.voiced_frequencies.is_empty() {
+            0.0
+        } else {
+            self.voiced_frequencies.sort_by(|a, b| a.total_cmp(b));
+            self.voiced_frequencies[self.voiced_frequencies.len() / 2]
+        };
+        let amplitude = if self.frames > 0 {
+            self.amplitude_sum / self.frames as f32
+        } else {
+            0.0
+        };
+        *self = Self::default();
+
+        HeadlessLine {
+            time,
+            frequency,
+            amplitude,
+            is_voiced: frequency > 0.0,
+            in_target: (options.target_min..=options.target_max).contains(&frequency),
+        }
+    }
+}
+
+pub fn run(options: &HeadlessOptions) -> Result<()> {
+    if options.show_help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if let Err(e) = audio_host::select(options.host.as_deref()) {
+        warn!("{}: pilote {} utilisé à la place", e, audio_host::default_name());
+    }
+    if options.list_devices {
+        for name in AudioProcessor::input_device_names()? {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let frequency_data = Arc::new(Mutex::new(None));
+    let processor = AudioProcessor::new(
+        options.device.as_deref(),
+        frequency_data.clone(),
+        Arc::new(Mutex::new(VadConfig::default())),
+        Arc::new(Mutex::new(VecDeque::new())),
+        Default::default(),
+        Arc::new(Mutex::new(InputChannels {
+            selected: options.channel,
+            ..Default::default()
+        })),
+        Arc::new(Mutex::new(options.analysis)),
+    )?;
+    eprintln!(
+        "Analyse en cours ({} Hz), cible {:.0}–{:.0} Hz. Ctrl+C pour arrêter.",
+        processor.sample_rate(),
+        options.target_min,
+        options.target_max
+    );
+
+    let mut stdout = std::io::stdout().lock();
+    if options.format == OutputFormat::Csv {
+        writeln!(stdout, "time,frequency,amplitude,is_voiced,in_target")?;
+    }
+
+    let started = Instant::now();
+    let interval = Duration::from_secs_f32(1.0 / options.rate);
+    let mut next_line = started + interval;
+    let mut accumulator = Accumulator::default();
+
+    loop {
+        let data = frequency_data.lock().ok().and_then(|mut guard| guard.take());
+        if let Some(data) = data {
+            let reliable = data.is_voiced && data.confidence >= MIN_CONFIDENCE;
+            accumulator.push(data.dominant_frequency, data.amplitude, reliable);
+            processor.recycle_spectrum(data.spectrum);
+        }
+
+        let now = Instant::now();
+        if now >= next_line {
+            next_line += interval;
+            let time = now.duration_since(started).as_secs_f32();
+            let line = accumulator.take_line(time, options);
+            // Un tube fermé (ex: `| head`) termine simplement l'analyse
+            if write_line(&mut stdout, &line, options.format).is_err() {
+                return Ok(());
+            }
+        }
+
+        if options.duration.is_some_and(|duration| now.duration_since(started) >= duration) {
+            return Ok(());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn write_line(out: &mut impl Write, line: &HeadlessLine, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => writeln!(
+            out,
+            "{:8.2} s  {:6.1} Hz  amp {:.4}  {}",
+            line.time,
+            line.frequency,
+            line.amplitude,
+            match (line.is_voiced, line.in_target) {
+                (false, _) => "silence",
+                (true, true) => "dans la cible",
+                (true, false) => "hors cible",
+            }
+        )?,
+        OutputFormat::Csv => writeln!(
+            out,
+            "{:.3},{:.2},{:.5},{},{}",
+            line.time, line.frequency, line.amplitude, line.is_voiced, line.in_target
+        )?,
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string(line)?)?,
+    }
+    out.flush()?;
+    Ok(())
+}
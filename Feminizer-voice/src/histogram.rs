@@ -0,0 +1,159 @@
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints, Polygon};
+
+use crate::pitch_unit::PitchScale;
+use crate::session::SessionSummary;
+
+pub const MIN_HZ: f32 = 50.0;
+pub const MAX_HZ: f32 = 1200.0;
+/// Largeur d'une classe en demi-tons: des classes égales à l'oreille.
+const BIN_ST: f32 = 0.5;
+
+pub fn bin_count() -> usize {
+    (12.0 * (MAX_HZ / MIN_HZ).log2() / BIN_ST).ceil() as usize
+}
+
+pub fn bin_of(frequency: f32) -> Option<usize> {
+    if !(MIN_HZ..MAX_HZ).contains(&frequency) {
+        return None;
+    }
+    Some((12.0 * (frequency / MIN_HZ).log2() / BIN_ST) as usize)
+}
+
+pub fn bin_edge(bin: usize) -> f64 {
+    MIN_HZ as f64 * 2.0_f64.powf(bin as f64 * BIN_ST as f64 / 12.0)
+}
+
+/// Convertit des durées par classe en pourcentages du temps voisé.
+pub fn normalized(seconds: &[f32]) -> Vec<f32> {
+    let total: f32 = seconds.iter().sum();
+    if total <= 0.0 {
+        return vec![0.0; seconds.len()];
+    }
+    seconds.iter().map(|s| 100.0 * s / total).collect()
+}
+
+#[derive(Default)]
+pub struct PitchHistogram {
+    /// `started_at` de la session superposée.
+    compare_with: Option<u64>,
+}
+
+impl PitchHistogram {
+    /// `current` en pourcentages par classe, comme `SessionSummary::pitch_histogram`.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        current: &[f32],
+        sessions: &[SessionSummary],
+        target_min: f32,
+        target_max: f32,
+        scale: PitchScale,
+    ) {
+        let comparable: Vec<&SessionSummary> =
+            sessions.iter().filter(|s| !s.pitch_histogram.is_empty()).collect();
+        let options: Vec<(u64, String)> = comparable
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, session)| {
+                let before = comparable.len() - index;
+                let label = if before == 1 {
+                    "La précédente".to_string()
+                } else {
+                    format!("{} sessions avant", before)
+                };
+                (
+                    session.started_at,
+                    format!("{} (médiane {})", label, scale.format(session.median_pitch)),
+                )
+            })
+            .collect();
+
+        ui.horizontal(|ui| {
+            ui.label("Comparer avec:");
+            let selected_text = options
+                .iter()
+                .find(|(started_at, _)| Some(*started_at) == self.compare_with)
+                .map_or("Aucune", |(_, label)| label.as_str());
+            egui::ComboBox::from_id_salt("histogram_compare")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.compare_with, None, "Aucune");
+                    for (started_at, label) in &options {
+                        ui.selectable_value(&mut self.compare_with, Some(*started_at), label);
+                    }
+                });
+            if options.is_empty() {
+                ui.small("Aucune session enregistrée avec sa distribution.");
+            }
+        });
+
+        let previous = self
+            .compare_with
+            .and_then(|started_at| comparable.iter().find(|s| s.started_at == started_at));
+
+        let bars: Vec<Bar> = current
+            .iter()
+            .enumerate()
+            .filter(|&(_, &percent)| percent > 0.0)
+            .map(|(bin, &percent)| {
+                let (low, high) = (bin_edge(bin), bin_edge(bin + 1));
+                let in_target =
+                    (target_min as f64..=target_max as f64).contains(&((low + high) / 2.0));
+                Bar::new((low + high) / 2.0, percent as f64)
+                    .width(high - low)
+                    .fill(if in_target {
+                        egui::Color32::from_rgb(255, 0, 255)
+                    } else {
+                        egui::Color32::from_rgb(120, 120, 180)
+                    })
+            })
+            .collect();
+        let peak = current
+            .iter()
+            .chain(previous.iter().flat_map(|s| s.pitch_histogram.iter()))
+            .copied()
+            .fold(1.0_f32, f32::max) as f64;
+
+        let plot = Plot::new("pitch_histogram")
+            .height(200.0)
+            .legend(Legend::default())
+            .y_axis_label("% du temps voisé")
+            .include_y(0.0);
+        scale.x_axis(plot)
+            .show(ui, |plot_ui| {
+                plot_ui.polygon(
+                    Polygon::new(
+                        "Zone cible",
+                        PlotPoints::from(vec![
+                            [target_min as f64, 0.0],
+                            [target_max as f64, 0.0],
+                            [target_max as f64, peak * 1.1],
+                            [target_min as f64, peak * 1.1],
+                        ]),
+                    )
+                    .fill_color(egui::Color32::from_rgba_unmultiplied(0, 200, 0, 25))
+                    .stroke(egui::Stroke::NONE),
+                );
+
+                plot_ui.bar_chart(BarChart::new("Session en cours", bars));
+
+                if let Some(previous) = previous {
+                    let points: PlotPoints = previous
+                        .pitch_histogram
+                        .iter()
+                        .enumerate()
+                        .map(|(bin, &percent)| {
+                            [(bin_edge(bin) + bin_edge(bin + 1)) / 2.0, percent as f64]
+                        })
+                        .collect();
+                    plot_ui.line(
+                        Line::new("Session comparée", points)
+                            .color(egui::Color32::from_rgb(255, 200, 0))
+                            .width(2.0),
+                    );
+                }
+            });
+    }
+}
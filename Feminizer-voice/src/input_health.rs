@@ -0,0 +1,148 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::audio_processor::InputMeter;
+
+const PEAK_HOLD: Duration = Duration::from_millis(1500);
+const PEAK_DECAY_DB_PER_SEC: f32 = 20.0;
+const CLIP_WARNING: Duration = Duration::from_secs(3);
+const QUIET_DB: f32 = -50.0;
+const QUIET_AFTER_SECS: f32 = 5.0;
+const METER_FLOOR_DB: f32 = -60.0;
+
+fn to_db(value: f32) -> f32 {
+    20.0 * value.max(1e-6).log10()
+}
+
+/// État de l'entrée brute (avant détection de voix): crête maintenue,
+/// saturation et signal trop faible.
+pub struct InputHealth {
+    level_db: f32,
+    peak_db: f32,
+    peak_at: Instant,
+    last_update: Instant,
+    last_clip: Option<Instant>,
+    clipped_samples: u64,
+    quiet_secs: f32,
+}
+
+impl Default for InputHealth {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            level_db: METER_FLOOR_DB,
+            peak_db: METER_FLOOR_DB,
+            peak_at: now,
+            last_update: now,
+            last_clip: None,
+            clipped_samples: 0,
+            quiet_secs: 0.0,
+        }
+    }
+}
+
+impl InputHealth {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn update(&mut self, meter: InputMeter) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        if meter.samples == 0 {
+            return;
+        }
+
+        let peak_db = to_db(meter.peak);
+        self.level_db = peak_db;
+        if peak_db >= self.peak_db {
+            self.peak_db = peak_db;
+            self.peak_at = now;
+        } else if now.duration_since(self.peak_at) > PEAK_HOLD {
+            self.peak_db = (self.peak_db - PEAK_DECAY_DB_PER_SEC * elapsed).max(peak_db);
+        }
+
+        if meter.clipped > 0 {
+            self.last_clip = Some(now);
+            self.clipped_samples += meter.clipped;
+        }
+
+        let rms_db = to_db((meter.sum_squares / meter.samples as f32).sqrt());
+        if rms_db < QUIET_DB {
+            self.quiet_secs += elapsed;
+        } else {
+            self.quiet_secs = 0.0;
+        }
+    }
+
+    pub fn is_clipping(&self) -> bool {
+        self.last_clip.is_some_and(|at| at.elapsed() < CLIP_WARNING)
+    }
+
+    pub fn is_too_quiet(&self) -> bool {
+        self.quiet_secs >= QUIET_AFTER_SECS
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Entrée:");
+            self.draw_meter(ui);
+            ui.small(format!("crête {:.0} dBFS", self.peak_db));
+
+            if self.is_clipping() {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    "⚠ Saturation: baissez le gain du micro",
+                )
+                .on_hover_text(format!(
+                    "{} échantillon(s) écrêté(s) depuis le début",
+                    self.clipped_samples
+                ));
+            } else if self.is_too_quiet() {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "⚠ Signal très faible: micro coupé ou gain trop bas ?",
+                );
+            }
+        });
+    }
+
+    fn draw_meter(&self, ui: &mut egui::Ui) {
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(200.0, 12.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, egui::Color32::from_gray(30));
+
+        let position = |db: f32| {
+            let t = ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0);
+            rect.left() + t * rect.width()
+        };
+
+        let level_x = position(self.level_db);
+        let color = if self.level_db > -3.0 {
+            egui::Color32::RED
+        } else if self.level_db > -12.0 {
+            egui::Color32::YELLOW
+        } else {
+            egui::Color32::GREEN
+        };
+        painter.rect_filled(
+            egui::Rect::from_min_max(rect.min, egui::pos2(level_x, rect.bottom())),
+            2.0,
+            color,
+        );
+
+        let peak_x = position(self.peak_db);
+        painter.line_segment(
+            [egui::pos2(peak_x, rect.top()), egui::pos2(peak_x, rect.bottom())],
+            egui::Stroke::new(2.0, egui::Color32::WHITE),
+        );
+
+        response.on_hover_text(format!(
+            "Niveau {:.0} dBFS, crête maintenue {:.0} dBFS",
+            self.level_db, self.peak_db
+        ));
+    }
+}
@@ -0,0 +1,515 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{HLine, Line, Plot, PlotPoints, VLine};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+
+/// Décompte avant la première ligne.
+const COUNT_IN_SECS: f32 = 3.0;
+/// Durée de la dernière ligne, qu'aucune autre ne vient clore.
+const LAST_LINE_SECS: f32 = 4.0;
+/// Écart au-delà duquel deux points de la courbe ne sont pas reliés.
+const MAX_GAP_SECS: f32 = 0.1;
+pub const OFFSET_RANGE_MS: std::ops::RangeInclusive<f32> = -500.0..=500.0;
+
+const BUNDLED: &[(&str, &str)] = &[(
+    "La bise et le soleil",
+    "[00:00.00]La bise et le soleil se disputaient,
+     [00:03.50]chacun assurant qu'il était le plus fort.
+     [00:07.00]Quand ils virent un voyageur qui s'avançait,
+     [00:10.50]enveloppé dans son manteau,
+     [00:13.50]ils convinrent que celui qui arriverait le premier à le lui faire ôter
+     [00:18.50]serait regardé comme le plus fort.
+     [00:22.00]Alors la bise se mit à souffler de toute sa force,
+     [00:26.00]mais plus elle soufflait, plus le voyageur serrait son manteau autour de lui.
+     [00:31.50]Finalement, elle renonça à le lui faire ôter.
+     [00:35.00]Alors le soleil commença à briller et, au bout d'un moment,
+     [00:39.50]le voyageur, réchauffé, ôta son manteau.
+     [00:43.00]Ainsi, la bise dut reconnaître que le soleil était le plus fort des deux.",
+)];
+
+#[derive(Clone)]
+struct TimedLine {
+    /// Début de la ligne, en secondes depuis le début du texte.
+    start_secs: f32,
+    text: String,
+}
+
+struct LyricSet {
+    name: String,
+    lines: Vec<TimedLine>,
+}
+
+/// `12.5`, `1:02.5` ou `1:02:03` → secondes.
+fn parse_clock(text: &str) -> Option<f32> {
+    text.split(':').try_fold(0.0, |secs: f32, part| {
+        let value: f32 = part.trim().parse().ok()?;
+        (value >= 0.0).then_some(secs * 60.0 + value)
+    })
+}
+
+/// Lit un fichier LRC (`[mm:ss.xx]texte`, plusieurs repères par ligne et
+/// `[offset:ms]` compris) ou un texte minuté simple: un temps, un espace,
+/// puis la ligne. Les lignes sans temps sont ignorées.
+fn parse_timed_text(text: &str) -> Vec<TimedLine> {
+    let mut lines = Vec::new();
+    let mut offset_secs = 0.0;
+    for line in text.lines().map(str::trim) {
+        let mut rest = line;
+        let mut starts = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[')
+            && let Some((tag, after)) = tag.split_once(']')
+        {
+            rest = after;
+            if let Some(start) = parse_clock(tag) {
+                starts.push(start);
+            } else if let Some(ms) = tag.strip_prefix("offset:") {
+                // Un décalage positif fait apparaître les paroles plus tôt
+                offset_secs = ms.trim().parse::<f32>().unwrap_or(0.0) / 1000.0;
+            }
+        }
+        if starts.is_empty()
+            && let Some((clock, after)) = line.split_once(char::is_whitespace)
+            && let Some(start) = parse_clock(clock)
+        {
+            starts.push(start);
+            rest = after;
+        }
+        for start in starts {
+            lines.push(TimedLine {
+                start_secs: start,
+                text: rest.trim().to_string(),
+            });
+        }
+    }
+    for line in &mut lines {
+        line.start_secs = (line.start_secs - offset_secs).max(0.0);
+    }
+    lines.sort_by(|a, b| a.start_secs.total_cmp(&b.start_secs));
+    lines
+}
+
+fn lyrics_dir() -> Result<PathBuf> {
+    paths::data_subdir("lyrics")
+}
+
+fn load_sets() -> (Vec<LyricSet>, Option<String>) {
+    let mut sets: Vec<LyricSet> = BUNDLED
+        .iter()
+        .map(|(name, text)| LyricSet {
+            name: name.to_string(),
+            lines: parse_timed_text(text),
+        })
+        .collect();
+
+    let imported = lyrics_dir().and_then(|dir| {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "lrc" || ext == "txt") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        for path in files {
+            let lines = parse_timed_text(&fs::read_to_string(&path)?);
+            if !lines.is_empty() {
+                let name = path.file_stem().map_or_else(String::new, |s| {
+                    format!("📄 {}", s.to_string_lossy())
+                });
+                sets.push(LyricSet { name, lines });
+            }
+        }
+        Ok(())
+    });
+
+    let error = imported.err().map(|e| format!("Textes minutés: {}", e));
+    (sets, error)
+}
+
+/// Une prise: le texte suivi et la hauteur captée sur sa chronologie.
+struct Take {
+    lines: Vec<TimedLine>,
+    /// (temps en s sur la chronologie du texte, hauteur en Hz, 0 sans voix).
+    trace: Vec<[f32; 2]>,
+}
+
+impl Take {
+    fn end_secs(&self) -> f32 {
+        self.lines.last().map_or(0.0, |line| line.start_secs + LAST_LINE_SECS)
+    }
+
+    fn line_at(&self, secs: f32) -> Option<usize> {
+        let next = self.lines.partition_point(|line| line.start_secs <= secs);
+        next.checked_sub(1)
+    }
+
+    fn line_end(&self, index: usize) -> f32 {
+        self.lines
+            .get(index + 1)
+            .map_or(self.end_secs(), |next| next.start_secs)
+    }
+
+    /// Hauteur médiane et part dans la cible des trames voisées d'une ligne.
+    fn line_stats(&self, index: usize, (min, max): (f32, f32)) -> Option<(f32, f32)> {
+        let (start, end) = (self.lines[index].start_secs, self.line_end(index));
+        let mut voiced: Vec<f32> = self
+            .trace
+            .iter()
+            .filter(|&&[t, hz]| hz > 0.0 && (start..end).contains(&t))
+            .map(|&[_, hz]| hz)
+            .collect();
+        if voiced.is_empty() {
+            return None;
+        }
+        let in_range = voiced.iter().filter(|&&hz| (min..=max).contains(&hz)).count();
+        let share = in_range as f32 / voiced.len() as f32;
+        voiced.sort_by(f32::total_cmp);
+        Some((voiced[voiced.len() / 2], share))
+    }
+
+    fn runs(&self) -> Vec<Vec<[f64; 2]>> {
+        let mut runs: Vec<Vec<[f64; 2]>> = Vec::new();
+        let mut previous: Option<f32> = None;
+        for &[t, hz] in self.trace.iter().filter(|&&[_, hz]| hz > 0.0) {
+            if previous.is_none_or(|before| t - before > MAX_GAP_SECS) {
+                runs.push(Vec::new());
+            }
+            if let Some(run) = runs.last_mut() {
+                run.push([t as f64, hz as f64]);
+            }
+            previous = Some(t);
+        }
+        runs
+    }
+}
+
+struct Run {
+    started: Instant,
+    take: Take,
+}
+
+impl Run {
+    /// Temps sur la chronologie du texte, négatif pendant le décompte.
+    fn position_secs(&self, at: Instant) -> f32 {
+        let elapsed = match at.checked_duration_since(self.started) {
+            Some(elapsed) => elapsed.as_secs_f32(),
+            None => -self.started.duration_since(at).as_secs_f32(),
+        };
+        elapsed - COUNT_IN_SECS
+    }
+}
+
+/// Texte minuté qui défile pendant l'enregistrement, ligne après ligne; la
+/// hauteur captée est recalée sur la chronologie du texte pour la relecture.
+pub struct KaraokePrompt {
+    sets: Vec<LyricSet>,
+    selected: usize,
+    /// Décalage ajouté à la latence mesurée, pour un temps de réaction.
+    offset_ms: f32,
+    run: Option<Run>,
+    last: Option<Take>,
+    error: Option<String>,
+}
+
+impl Default for KaraokePrompt {
+    fn default() -> Self {
+        let (sets, error) = load_sets();
+        Self {
+            sets,
+            selected: 0,
+            offset_ms: 0.0,
+            run: None,
+            last: None,
+            error,
+        }
+    }
+}
+
+impl KaraokePrompt {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// `capture_delay`: latence de l'entrée et demi-fenêtre d'analyse, soit
+    /// l'écart entre `captured_at` et l'instant où la voix a été émise.
+    pub fn push_frame(&mut self, captured_at: Instant, capture_delay: Duration, frequency: f32) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let offset = Duration::from_secs_f32(self.offset_ms.abs() / 1000.0);
+        let spoken = captured_at.checked_sub(capture_delay).unwrap_or(captured_at);
+        let spoken = if self.offset_ms >= 0.0 {
+            spoken.checked_sub(offset).unwrap_or(spoken)
+        } else {
+            spoken + offset
+        };
+        let position = run.position_secs(spoken);
+        if position >= 0.0 {
+            run.take.trace.push([position, frequency]);
+        }
+    }
+
+    fn start(&mut self) {
+        self.error = None;
+        self.run = Some(Run {
+            started: Instant::now(),
+            take: Take {
+                lines: self.sets[self.selected].lines.clone(),
+                trace: Vec::new(),
+            },
+        });
+    }
+
+    fn stop(&mut self) {
+        if let Some(run) = self.run.take() {
+            self.last = Some(run.take);
+        }
+    }
+
+    fn reload(&mut self) {
+        let name = self.sets.get(self.selected).map(|set| set.name.clone());
+        (self.sets, self.error) = load_sets();
+        self.selected = name
+            .and_then(|name| self.sets.iter().position(|set| set.name == name))
+            .unwrap_or(0);
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        target: (f32, f32),
+        scale: PitchScale,
+    ) {
+        if self.run.is_some() && !is_recording {
+            self.stop();
+        }
+        if let Some(run) = &self.run
+            && run.position_secs(Instant::now()) >= run.take.end_secs()
+        {
+            self.stop();
+        }
+
+        ui.heading("🎼 Texte minuté");
+        ui.label(
+            "Le texte défile à son rythme: dites chaque ligne quand elle s'allume, d'une voix \
+             posée et régulière. La courbe de hauteur est ensuite recalée ligne par ligne, \
+             latence du micro déduite.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.add_enabled_ui(self.run.is_none(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Texte:");
+                egui::ComboBox::from_id_salt("karaoke_set")
+                    .selected_text(&self.sets[self.selected].name)
+                    .show_ui(ui, |ui| {
+                        for (index, set) in self.sets.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected, index, &set.name);
+                        }
+                    });
+                if ui.button("🔄 Recharger").clicked() {
+                    self.reload();
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.offset_ms, OFFSET_RANGE_MS)
+                        .text("ms de décalage"),
+                )
+                .on_hover_text(
+                    "Ajouté à la latence mesurée: positif si votre voix arrive régulièrement \
+                     après le début des lignes",
+                );
+            });
+        });
+        if let Ok(dir) = lyrics_dir() {
+            ui.small(format!(
+                "Ajoutez vos textes (.lrc, ou .txt avec un temps en début de ligne) dans {}",
+                dir.display()
+            ));
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if self.run.is_some() {
+                if ui.button("⏹ Arrêter").clicked() {
+                    self.stop();
+                }
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("▶ Lancer le texte"))
+                .on_disabled_hover_text("Démarrez l'enregistrement pour suivre le texte")
+                .clicked()
+            {
+                self.start();
+            }
+        });
+
+        if let Some(run) = &self.run {
+            show_prompt(ui, run);
+            ui.ctx().request_repaint();
+        } else if let Some(take) = &self.last {
+            show_review(ui, take, target, scale);
+        }
+    }
+}
+
+fn show_prompt(ui: &mut egui::Ui, run: &Run) {
+    let take = &run.take;
+    let position = run.position_secs(Instant::now());
+    if position < 0.0 {
+        ui.label(egui::RichText::new(format!("{:.0}…", -position.floor())).size(32.0));
+    }
+    let current = take.line_at(position);
+    let first = current.map_or(0, |index| index.saturating_sub(1));
+    for (index, line) in take.lines.iter().enumerate().skip(first).take(4) {
+        let text = if line.text.is_empty() { "…" } else { &line.text };
+        if Some(index) == current {
+            ui.label(
+                egui::RichText::new(text)
+                    .size(24.0)
+                    .strong()
+                    .color(egui::Color32::from_rgb(255, 0, 255)),
+            );
+            let (start, end) = (line.start_secs, take.line_end(index));
+            let progress = (position - start) / (end - start).max(0.1);
+            ui.add(egui::ProgressBar::new(progress.clamp(0.0, 1.0)).desired_height(4.0));
+        } else if current.is_some_and(|current| index < current) {
+            ui.weak(text);
+        } else {
+            ui.label(egui::RichText::new(text).size(18.0));
+        }
+    }
+}
+
+fn show_review(ui: &mut egui::Ui, take: &Take, target: (f32, f32), scale: PitchScale) {
+    ui.label("📈 Dernière prise, sur la chronologie du texte");
+    let plot = Plot::new("karaoke_review")
+        .height(220.0)
+        .include_x(0.0)
+        .include_x(take.end_secs())
+        .x_axis_label("Temps (s)");
+    scale.y_axis(plot).show(ui, |plot_ui| {
+        plot_ui.hline(HLine::new("Cible min", target.0).color(egui::Color32::GREEN));
+        plot_ui.hline(HLine::new("Cible max", target.1).color(egui::Color32::GREEN));
+        for (index, line) in take.lines.iter().enumerate() {
+            plot_ui.vline(
+                VLine::new(format!("{}. {}", index + 1, line.text), line.start_secs)
+                    .color(egui::Color32::GRAY)
+                    .style(egui_plot::LineStyle::dashed_loose()),
+            );
+        }
+        for run in take.runs() {
+            plot_ui.line(
+                Line::new("Hauteur", PlotPoints::from(run))
+                    .color(egui::Color32::from_rgb(255, 0, 255))
+                    .width(2.0),
+            );
+        }
+    });
+
+    let stats: Vec<Option<(f32, f32)>> =
+        (0..take.lines.len()).map(|index| take.line_stats(index, target)).collect();
+    let medians: Vec<f32> = stats.iter().flatten().map(|&(median, _)| median).collect();
+    if medians.len() >= 2 {
+        // Régularité d'une ligne à l'autre: écart entre médianes extrêmes
+        let (low, high) = medians
+            .iter()
+            .fold((f32::MAX, 0.0_f32), |(low, high), &m| (low.min(m), high.max(m)));
+        ui.label(format!(
+            "Écart entre les lignes: {:.1} demi-tons ({} – {})",
+            12.0 * (high / low).log2(),
+            scale.format(low),
+            scale.format(high)
+        ));
+    }
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("karaoke_lines").striped(true).show(ui, |ui| {
+            for header in ["#", "Ligne", "Hauteur médiane", "Dans la cible"] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for (index, (line, stats)) in take.lines.iter().zip(&stats).enumerate() {
+                ui.label(format!("{}", index + 1));
+                let short: String = line.text.chars().take(50).collect();
+                if short.len() < line.text.len() {
+                    ui.label(format!("{}…", short));
+                } else {
+                    ui.label(short);
+                }
+                match stats {
+                    Some((median, share)) => {
+                        ui.label(scale.format(*median));
+                        ui.label(format!("{:.0}%", 100.0 * share));
+                    }
+                    None => {
+                        ui.weak("—");
+                        ui.weak("—");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(text: &str) -> Vec<(f32, String)> {
+        parse_timed_text(text)
+            .into_iter()
+            .map(|line| (line.start_secs, line.text))
+            .collect()
+    }
+
+    #[test]
+    fn minutes_seconds_and_hundredths() {
+        assert_eq!(
+            starts("[00:03.50]chacun\n[01:02.25]le plus fort"),
+            [(3.5, "chacun".to_string()), (62.25, "le plus fort".to_string())]
+        );
+        assert_eq!(parse_clock("1:02:03"), Some(3723.0));
+        assert_eq!(parse_clock("00:-01"), None);
+    }
+
+    #[test]
+    fn several_timestamps_repeat_the_line() {
+        assert_eq!(
+            starts("[00:01.00][00:05.00]refrain\n[00:03.00]couplet"),
+            [
+                (1.0, "refrain".to_string()),
+                (3.0, "couplet".to_string()),
+                (5.0, "refrain".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_are_sorted_and_offset() {
+        assert_eq!(
+            starts("[offset:500]\n[00:04.00]deux\n[00:02.00]un\n[00:00.20]zéro"),
+            [
+                (0.0, "zéro".to_string()),
+                (1.5, "un".to_string()),
+                (3.5, "deux".to_string()),
+            ]
+        );
+        // Texte minuté simple
+        assert_eq!(starts("2.5 bonjour\n1 salut")[0], (1.0, "salut".to_string()));
+    }
+
+    #[test]
+    fn malformed_tags_are_ignored() {
+        let text = "[ar:Quelqu'un]\n[ti:Titre]\n[00:xx.00]illisible\n[00:01.00 ouvert\n\
+                    [offset:abc]\n[bof][00:02.00]gardée\nsans repère";
+        assert_eq!(starts(text), [(2.0, "gardée".to_string())]);
+    }
+}
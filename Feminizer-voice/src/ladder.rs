@@ -0,0 +1,419 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::cues::CueCategory;
+use crate::pitch_unit::PitchScale;
+use crate::settings::TargetRange;
+
+/// Durée de la note de référence jouée avant chaque barreau.
+pub const TONE_SECS: f32 = 1.2;
+/// Voix ignorée juste après la note: le haut-parleur résonne encore dans le micro.
+const ECHO_GRACE_SECS: f32 = 0.4;
+const MAX_RUNGS: usize = 36;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LadderSettings {
+    /// Premier barreau: une note grave et confortable.
+    pub low_hz: f32,
+    /// Écart entre deux barreaux, en demi-tons.
+    pub step_semitones: f32,
+    /// Écart toléré avec la note, en cents.
+    pub tolerance_cents: f32,
+    /// Temps à tenir la note pour valider le barreau.
+    pub hold_secs: f32,
+    /// Au-delà, la note est comptée manquée et l'échelle continue.
+    pub give_up_secs: f32,
+}
+
+impl Default for LadderSettings {
+    fn default() -> Self {
+        Self {
+            low_hz: 120.0,
+            step_semitones: 2.0,
+            tolerance_cents: 50.0,
+            hold_secs: 1.0,
+            give_up_secs: 15.0,
+        }
+    }
+}
+
+impl LadderSettings {
+    /// Notes de l'échelle, de la note grave jusqu'au haut de la cible.
+    fn rungs(&self, target: TargetRange) -> Vec<f32> {
+        (0..MAX_RUNGS)
+            .map(|i| self.low_hz * 2.0_f32.powf(i as f32 * self.step_semitones / 12.0))
+            .take_while(|&hz| hz <= target.max_hz)
+            .collect()
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui, scale: PitchScale) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(egui::Slider::new(&mut self.low_hz, 70.0..=250.0).text("Hz de départ"))
+                .changed();
+            ui.weak(scale.format(self.low_hz));
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.step_semitones, 1.0..=4.0)
+                        .step_by(0.5)
+                        .text("demi-tons par barreau"),
+                )
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.tolerance_cents, 10.0..=100.0)
+                        .text("cents de tolérance"),
+                )
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut self.hold_secs, 0.3..=5.0).text("s à tenir"))
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut self.give_up_secs, 5.0..=60.0).text("s max par note"))
+                .changed();
+        });
+        changed
+    }
+}
+
+/// Ce que l'application doit jouer pour l'échelle.
+pub enum LadderEvent {
+    Tone(f32),
+    Cue(CueCategory),
+}
+
+#[derive(Clone, Copy)]
+struct RungResult {
+    hz: f32,
+    /// Temps mis à tenir la note, `None` si elle a été manquée.
+    matched_after: Option<f32>,
+    /// Écart moyen pendant la tenue validée, en cents.
+    mean_cents: f32,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Attempt {
+    elapsed_secs: f32,
+    held_secs: f32,
+    /// Somme des écarts pondérés par la durée, sur la tenue en cours.
+    cents_sum: f32,
+    last_cents: Option<f32>,
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Listen { remaining_secs: f32 },
+    Match(Attempt),
+}
+
+struct Run {
+    settings: LadderSettings,
+    rungs: Vec<f32>,
+    index: usize,
+    phase: Phase,
+    results: Vec<RungResult>,
+}
+
+impl Run {
+    fn hz(&self) -> f32 {
+        self.rungs[self.index]
+    }
+}
+
+/// Échauffement en échelle: une note de référence par barreau, de la note
+/// grave jusqu'à la cible; chaque note tenue dans la tolérance fait monter
+/// d'un barreau.
+#[derive(Default)]
+pub struct LadderWarmup {
+    run: Option<Run>,
+    /// Dernière échelle, terminée ou interrompue.
+    summary: Option<(Vec<RungResult>, usize)>,
+    events: VecDeque<LadderEvent>,
+    error: Option<String>,
+}
+
+impl LadderWarmup {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    pub fn next_event(&mut self) -> Option<LadderEvent> {
+        self.events.pop_front()
+    }
+
+    fn start(&mut self, settings: &LadderSettings, target: TargetRange) {
+        let rungs = settings.rungs(target);
+        if rungs.is_empty() {
+            self.error = Some("La note de départ est au-dessus de la cible".to_string());
+            return;
+        }
+        self.error = None;
+        self.summary = None;
+        self.run = Some(Run {
+            settings: settings.clone(),
+            rungs,
+            index: 0,
+            phase: Phase::Listen {
+                remaining_secs: 0.0,
+            },
+            results: Vec::new(),
+        });
+        self.replay();
+    }
+
+    fn replay(&mut self) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        run.phase = Phase::Listen {
+            remaining_secs: TONE_SECS + ECHO_GRACE_SECS,
+        };
+        self.events.push_back(LadderEvent::Tone(run.hz()));
+    }
+
+    fn stop(&mut self) {
+        if let Some(run) = self.run.take() {
+            self.summary = Some((run.results, run.rungs.len()));
+        }
+    }
+
+    /// Clôt le barreau en cours et passe au suivant.
+    fn advance(&mut self, matched_after: Option<f32>, mean_cents: f32) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        run.results.push(RungResult {
+            hz: run.hz(),
+            matched_after,
+            mean_cents,
+        });
+        run.index += 1;
+        let cue = if matched_after.is_some() {
+            CueCategory::Success
+        } else {
+            CueCategory::Warning
+        };
+        self.events.push_back(LadderEvent::Cue(cue));
+        if run.index == run.rungs.len() {
+            self.stop();
+        } else {
+            self.replay();
+        }
+    }
+
+    /// `frequency`: hauteur retenue, 0 hors voix ou si la trame est peu fiable.
+    pub fn push_frame(&mut self, frequency: f32, frame_duration: f32) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let hz = run.hz();
+        let settings = &run.settings;
+        match &mut run.phase {
+            Phase::Listen { remaining_secs } => {
+                *remaining_secs -= frame_duration;
+                if *remaining_secs <= 0.0 {
+                    run.phase = Phase::Match(Attempt::default());
+                }
+            }
+            Phase::Match(attempt) => {
+                attempt.elapsed_secs += frame_duration;
+                attempt.last_cents = None;
+                if frequency > 0.0 {
+                    let cents = 1200.0 * (frequency / hz).log2();
+                    attempt.last_cents = Some(cents);
+                    if cents.abs() <= settings.tolerance_cents {
+                        attempt.held_secs += frame_duration;
+                        attempt.cents_sum += cents * frame_duration;
+                    } else {
+                        attempt.held_secs = 0.0;
+                        attempt.cents_sum = 0.0;
+                    }
+                }
+                let attempt = *attempt;
+                if attempt.held_secs >= settings.hold_secs {
+                    let mean_cents = attempt.cents_sum / attempt.held_secs;
+                    self.advance(Some(attempt.elapsed_secs), mean_cents);
+                } else if attempt.elapsed_secs >= settings.give_up_secs {
+                    self.advance(None, 0.0);
+                }
+            }
+        }
+    }
+
+    /// Renvoie `true` si les réglages ont changé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        settings: &mut LadderSettings,
+        target: TargetRange,
+        scale: PitchScale,
+    ) -> bool {
+        if self.run.is_some() && !is_recording {
+            self.stop();
+            self.error = Some("Enregistrement arrêté: échauffement interrompu".to_string());
+        }
+
+        ui.heading("📶 Échauffement en échelle");
+        ui.label(
+            "Chaque barreau joue une note de référence: reprenez-la et tenez-la dans la \
+             tolérance pour monter d'un cran, de votre grave confortable jusqu'au haut de la \
+             cible. Un casque évite que le micro capte la note jouée.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        let mut changed = false;
+        ui.add_enabled_ui(self.run.is_none(), |ui| {
+            changed = settings.show(ui, scale);
+        });
+        let planned = settings.rungs(target);
+        if let (Some(first), Some(last)) = (planned.first(), planned.last()) {
+            ui.weak(format!(
+                "{} barreaux, de {} à {}",
+                planned.len(),
+                scale.format(*first),
+                scale.format(*last)
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            if self.run.is_some() {
+                if ui.button("🔊 Rejouer la note").clicked() {
+                    self.replay();
+                }
+                if ui.button("⏭ Passer cette note").clicked() {
+                    self.advance(None, 0.0);
+                }
+                if ui.button("⏹ Arrêter").clicked() {
+                    self.stop();
+                }
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("▶ Commencer l'échelle"))
+                .on_disabled_hover_text("Démarrez l'enregistrement pour commencer")
+                .clicked()
+            {
+                self.start(settings, target);
+            }
+        });
+        ui.separator();
+
+        if let Some(run) = &self.run {
+            Self::show_run(ui, run, scale);
+        } else if let Some((results, total)) = &self.summary {
+            Self::show_summary(ui, results, *total, scale);
+        }
+        changed
+    }
+
+    fn show_run(ui: &mut egui::Ui, run: &Run, scale: PitchScale) {
+        ui.horizontal_wrapped(|ui| {
+            for (i, &hz) in run.rungs.iter().enumerate() {
+                let text = egui::RichText::new(scale.format(hz));
+                match run.results.get(i) {
+                    Some(result) if result.matched_after.is_some() => {
+                        ui.colored_label(egui::Color32::GREEN, text);
+                    }
+                    Some(_) => {
+                        ui.colored_label(egui::Color32::RED, text);
+                    }
+                    None if i == run.index => {
+                        ui.label(text.strong().underline());
+                    }
+                    None => {
+                        ui.weak(text);
+                    }
+                }
+            }
+        });
+        ui.add_space(6.0);
+        ui.label(
+            egui::RichText::new(format!(
+                "Barreau {}/{}: {}",
+                run.index + 1,
+                run.rungs.len(),
+                scale.format(run.hz())
+            ))
+            .size(22.0),
+        );
+        match run.phase {
+            Phase::Listen { .. } => {
+                ui.label("🔊 Écoutez la note…");
+            }
+            Phase::Match(attempt) => {
+                let progress = (attempt.held_secs / run.settings.hold_secs).min(1.0);
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .text(format!("Tenue {:.1} s", attempt.held_secs))
+                        .desired_width(240.0),
+                );
+                match attempt.last_cents {
+                    Some(cents) if cents.abs() <= run.settings.tolerance_cents => {
+                        ui.colored_label(egui::Color32::GREEN, format!("{:+.0} cents", cents));
+                    }
+                    Some(cents) => {
+                        let hint = if cents < 0.0 { "plus haut" } else { "plus bas" };
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 170, 60),
+                            format!("{:+.0} cents: {}", cents, hint),
+                        );
+                    }
+                    None => {
+                        ui.weak("À vous…");
+                    }
+                }
+                let left = run.settings.give_up_secs - attempt.elapsed_secs;
+                ui.weak(format!("Note suivante dans {:.0} s au plus", left.max(0.0)));
+            }
+        }
+    }
+
+    fn show_summary(ui: &mut egui::Ui, results: &[RungResult], total: usize, scale: PitchScale) {
+        let matched: Vec<&RungResult> =
+            results.iter().filter(|r| r.matched_after.is_some()).collect();
+        let title = if results.len() == total {
+            "🏁 Échelle terminée"
+        } else {
+            "⏹ Échelle interrompue"
+        };
+        ui.strong(title);
+        ui.label(format!("{} notes tenues sur {}", matched.len(), total));
+        if let Some(highest) = matched.iter().map(|r| r.hz).reduce(f32::max) {
+            ui.label(format!("Plus haute note tenue: {}", scale.format(highest)));
+        }
+        if results.is_empty() {
+            return;
+        }
+        egui::Grid::new("ladder_summary")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Note", "Résultat", "Temps", "Écart moyen"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for result in results {
+                    ui.label(scale.format(result.hz));
+                    match result.matched_after {
+                        Some(secs) => {
+                            ui.label("✅");
+                            ui.label(format!("{:.1} s", secs));
+                            ui.label(format!("{:+.0} cents", result.mean_cents));
+                        }
+                        None => {
+                            ui.label("❌");
+                            ui.label("—");
+                            ui.label("—");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
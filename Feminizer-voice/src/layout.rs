@@ -0,0 +1,175 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Tab {
+    Live,
+    Vowels,
+    Harmonics,
+    Snapshots,
+    Takes,
+    Moments,
+    Prosody,
+    Passage,
+    Reading,
+    Reference,
+    Rhythm,
+    Sustain,
+    Range,
+    Ladder,
+    Karaoke,
+    Scripts,
+    Tuning,
+    Analytics,
+    Settings,
+}
+
+impl Tab {
+    /// Ordre de la barre d'onglets.
+    pub const ALL: [Tab; 19] = [
+        Tab::Live,
+        Tab::Vowels,
+        Tab::Harmonics,
+        Tab::Snapshots,
+        Tab::Takes,
+        Tab::Moments,
+        Tab::Prosody,
+        Tab::Passage,
+        Tab::Reading,
+        Tab::Reference,
+        Tab::Rhythm,
+        Tab::Sustain,
+        Tab::Range,
+        Tab::Ladder,
+        Tab::Karaoke,
+        Tab::Scripts,
+        Tab::Tuning,
+        Tab::Analytics,
+        Tab::Settings,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tab::Live => "🎤 Direct",
+            Tab::Vowels => "🗣 Voyelles",
+            Tab::Harmonics => "🌈 Harmoniques",
+            Tab::Snapshots => "📸 Spectres",
+            Tab::Takes => "🎞 Prises",
+            Tab::Moments => "🌟 Bons moments",
+            Tab::Prosody => "🎵 Intonation",
+            Tab::Passage => "📖 Passages",
+            Tab::Reading => "🗒 Lecture",
+            Tab::Reference => "🆚 Référence",
+            Tab::Rhythm => "🥁 Rythme",
+            Tab::Sustain => "🅰 Voyelle tenue",
+            Tab::Range => "📏 Étendue",
+            Tab::Ladder => "📶 Échauffement",
+            Tab::Karaoke => "🎼 Texte minuté",
+            Tab::Scripts => "📜 Scripts",
+            Tab::Tuning => "🎚 Seuils",
+            Tab::Analytics => "📊 Analyses",
+            Tab::Settings => "⚙ Réglages",
+        }
+    }
+
+    /// Les réglages restent au centre: c'est là qu'on défait la disposition.
+    fn can_dock(self) -> bool {
+        self != Tab::Settings
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Dock {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl Dock {
+    pub const ALL: [Dock; 3] = [Dock::Left, Dock::Right, Dock::Bottom];
+
+    fn label(self) -> &'static str {
+        match self {
+            Dock::Left => "À gauche",
+            Dock::Right => "À droite",
+            Dock::Bottom => "En bas",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DockedTab {
+    pub tab: Tab,
+    pub dock: Dock,
+}
+
+/// Disposition de la fenêtre: les onglets ancrés restent affichés dans un
+/// panneau autour de l'onglet central, dans l'ordre où ils ont été ancrés.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LayoutSettings {
+    pub docked: Vec<DockedTab>,
+}
+
+impl LayoutSettings {
+    pub fn dock_of(&self, tab: Tab) -> Option<Dock> {
+        self.docked
+            .iter()
+            .find(|docked| docked.tab == tab && tab.can_dock())
+            .map(|docked| docked.dock)
+    }
+
+    pub fn tabs_in(&self, dock: Dock) -> Vec<Tab> {
+        let mut tabs = Vec::new();
+        for docked in &self.docked {
+            if docked.dock == dock && docked.tab.can_dock() && !tabs.contains(&docked.tab) {
+                tabs.push(docked.tab);
+            }
+        }
+        tabs
+    }
+
+    /// Onglets restés au centre, dans l'ordre de la barre.
+    pub fn central_tabs(&self) -> impl Iterator<Item = Tab> + '_ {
+        Tab::ALL.into_iter().filter(|&tab| self.dock_of(tab).is_none())
+    }
+
+    /// Renvoie `true` si la disposition a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.small(
+            "Un onglet ancré reste visible à côté de l'onglet central, pour composer son \
+             propre tableau de bord. Les panneaux se redimensionnent à la souris.",
+        );
+        let mut changed = false;
+        egui::Grid::new("layout_docks").num_columns(2).show(ui, |ui| {
+            for tab in Tab::ALL.into_iter().filter(|tab| tab.can_dock()) {
+                let mut dock = self.dock_of(tab);
+                ui.label(tab.label());
+                egui::ComboBox::from_id_salt(("layout_dock", tab))
+                    .selected_text(dock.map_or("Au centre", Dock::label))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut dock, None, "Au centre");
+                        for choice in Dock::ALL {
+                            ui.selectable_value(&mut dock, Some(choice), choice.label());
+                        }
+                    });
+                ui.end_row();
+                if dock != self.dock_of(tab) {
+                    self.docked.retain(|docked| docked.tab != tab);
+                    if let Some(dock) = dock {
+                        self.docked.push(DockedTab { tab, dock });
+                    }
+                    changed = true;
+                }
+            }
+        });
+        if !self.docked.is_empty() && ui.button("↺ Tout remettre au centre").clicked() {
+            self.docked.clear();
+            changed = true;
+        }
+        changed
+    }
+}
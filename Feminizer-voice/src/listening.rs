@@ -0,0 +1,194 @@
+use feminizer_voice_core::Biquad;
+
+/// Situation d'écoute simulée sur le retour casque.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListeningContext {
+    #[default]
+    Direct,
+    Phone,
+    Laptop,
+    Room,
+}
+
+impl ListeningContext {
+    pub const ALL: [ListeningContext; 4] = [
+        ListeningContext::Direct,
+        ListeningContext::Phone,
+        ListeningContext::Laptop,
+        ListeningContext::Room,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ListeningContext::Direct => "Direct",
+            ListeningContext::Phone => "📞 Téléphone",
+            ListeningContext::Laptop => "💻 Haut-parleur d'ordinateur",
+            ListeningContext::Room => "🏛 Grande salle",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ListeningContext::Direct => "Aucun filtre",
+            ListeningContext::Phone => "Bande passante téléphonique 300–3400 Hz",
+            ListeningContext::Laptop => "Petit haut-parleur: pas de graves, médiums en avant",
+            ListeningContext::Room => "Réverbération d'une pièce vaste et peu meublée",
+        }
+    }
+}
+
+struct Comb {
+    buffer: Vec<f32>,
+    pos: usize,
+    feedback: f32,
+    damping: f32,
+    filtered: f32,
+}
+
+impl Comb {
+    fn new(length: usize, feedback: f32, damping: f32) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            pos: 0,
+            feedback,
+            damping,
+            filtered: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = self.buffer[self.pos];
+        self.filtered = output * (1.0 - self.damping) + self.filtered * self.damping;
+        self.buffer[self.pos] = input + self.filtered * self.feedback;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+struct AllPass {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl AllPass {
+    const GAIN: f32 = 0.5;
+
+    fn new(length: usize) -> Self {
+        Self {
+            buffer: vec![0.0; length.max(1)],
+            pos: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.pos];
+        let output = delayed - input;
+        self.buffer[self.pos] = input + delayed * Self::GAIN;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+// Réverbération de Schroeder: peignes parallèles puis passe-tout en série.
+// Longueurs de Freeverb, données à 44,1 kHz.
+struct Reverb {
+    combs: Vec<Comb>,
+    all_passes: Vec<AllPass>,
+}
+
+impl Reverb {
+    const COMB_LENGTHS: [usize; 4] = [1116, 1188, 1277, 1356];
+    const ALL_PASS_LENGTHS: [usize; 2] = [556, 441];
+    const WET: f32 = 0.35;
+
+    fn new(sample_rate: f32) -> Self {
+        let scale = sample_rate / 44_100.0;
+        let scaled = |length: usize| (length as f32 * scale) as usize;
+        Self {
+            combs: Self::COMB_LENGTHS
+                .iter()
+                .map(|&length| Comb::new(scaled(length), 0.84, 0.2))
+                .collect(),
+            all_passes: Self::ALL_PASS_LENGTHS
+                .iter()
+                .map(|&length| AllPass::new(scaled(length)))
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let mut wet = self.combs.iter_mut().map(|comb| comb.process(input)).sum::<f32>()
+            / self.combs.len() as f32;
+        for all_pass in &mut self.all_passes {
+            wet = all_pass.process(wet);
+        }
+        input * (1.0 - Self::WET) + wet * Self::WET
+    }
+}
+
+/// Chaîne de filtres d'une situation d'écoute, à la fréquence de sortie.
+pub struct ListeningFilter {
+    context: ListeningContext,
+    filters: Vec<Biquad>,
+    reverb: Option<Reverb>,
+    gain: f32,
+}
+
+impl ListeningFilter {
+    pub fn new(context: ListeningContext, sample_rate: f32) -> Self {
+        let (filters, reverb, gain) = match context {
+            ListeningContext::Direct => (Vec::new(), None, 1.0),
+            ListeningContext::Phone => (
+                vec![
+                    Biquad::high_pass(sample_rate, 300.0, 0.707),
+                    Biquad::high_pass(sample_rate, 300.0, 0.707),
+                    Biquad::low_pass(sample_rate, 3400.0, 0.707),
+                    Biquad::low_pass(sample_rate, 3400.0, 0.707),
+                ],
+                None,
+                1.4,
+            ),
+            ListeningContext::Laptop => (
+                vec![
+                    Biquad::high_pass(sample_rate, 450.0, 0.9),
+                    Biquad::peaking(sample_rate, 2500.0, 1.0, 6.0),
+                    Biquad::low_pass(sample_rate, 9000.0, 0.707),
+                ],
+                None,
+                1.1,
+            ),
+            ListeningContext::Room => (
+                vec![Biquad::low_pass(sample_rate, 7000.0, 0.707)],
+                Some(Reverb::new(sample_rate)),
+                1.0,
+            ),
+        };
+        Self {
+            context,
+            filters,
+            reverb,
+            gain,
+        }
+    }
+
+    pub fn context(&self) -> ListeningContext {
+        self.context
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let mut sample = input;
+        for filter in &mut self.filters {
+            sample = filter.process(sample);
+        }
+        if let Some(reverb) = &mut self.reverb {
+            sample = reverb.process(sample);
+        }
+        // Le téléphone sature un peu: écrêtage doux plutôt que coupure nette
+        if self.context == ListeningContext::Phone {
+            sample = (sample * self.gain).tanh();
+        } else {
+            sample *= self.gain;
+        }
+        sample
+    }
+}
@@ -0,0 +1,156 @@
+use eframe::egui;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Part du temps d'un bloc passée à l'analyser au-delà de laquelle on déleste.
+const HIGH_LOAD: f32 = 0.7;
+/// En dessous, on rétablit un palier.
+const LOW_LOAD: f32 = 0.3;
+/// Blocs consécutifs en surcharge avant de délester: un pic isolé ne compte pas.
+const BLOCKS_BEFORE_SHEDDING: u32 = 8;
+/// Blocs consécutifs au calme avant de rétablir, nettement plus long pour ne
+/// pas osciller entre deux paliers.
+const BLOCKS_BEFORE_RESTORING: u32 = 200;
+const SMOOTHING: f32 = 0.2;
+
+/// Paliers de délestage, dans l'ordre où ils s'appliquent: chacun inclut
+/// les précédents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Degradation {
+    #[default]
+    Full,
+    /// Le spectre n'est plus recopié pour l'affichage: le spectrogramme, la
+    /// cascade et les instantanés spectraux sont suspendus. Le gain est
+    /// surtout côté interface, l'analyse n'économisant qu'une normalisation.
+    NoSpectrogram,
+    /// Une trame sur deux: pas d'analyse doublé.
+    WiderHop,
+    /// Plus d'estimation des formants, l'étape la plus coûteuse.
+    NoFormants,
+}
+
+impl Degradation {
+    const LADDER: [Degradation; 4] = [
+        Degradation::Full,
+        Degradation::NoSpectrogram,
+        Degradation::WiderHop,
+        Degradation::NoFormants,
+    ];
+
+    fn step(self, up: bool) -> Self {
+        let index = Self::LADDER.iter().position(|&d| d == self).unwrap_or(0);
+        let index = if up {
+            (index + 1).min(Self::LADDER.len() - 1)
+        } else {
+            index.saturating_sub(1)
+        };
+        Self::LADDER[index]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Degradation::Full => "Analyse complète",
+            Degradation::NoSpectrogram => "Spectrogramme suspendu",
+            Degradation::WiderHop => "Spectrogramme suspendu, moins de trames",
+            Degradation::NoFormants => "Spectrogramme, trames et formants réduits",
+        }
+    }
+}
+
+/// Charge du thread d'analyse, avec délestage progressif quand il ne suit
+/// plus le rythme des blocs.
+#[derive(Clone, Default)]
+pub struct LoadMonitor {
+    /// Temps d'analyse / durée du bloc, lissé.
+    load: f32,
+    /// Pire rapport temps d'analyse / durée du bloc depuis l'ouverture du flux.
+    peak_load: f32,
+    level: Degradation,
+    over_blocks: u32,
+    under_blocks: u32,
+    /// Blocs analysés en plus de temps qu'ils n'en couvrent.
+    pub overruns: u64,
+    /// Trames écrasées avant que l'interface ne les lise.
+    pub dropped_frames: u64,
+    /// Échantillons écartés par le callback, la file vers l'analyse étant pleine.
+    pub lost_samples: u64,
+    /// Trames produites par l'analyse.
+    pub frames: u64,
+    /// Délai entre la capture d'un bloc et son arrivée dans le callback,
+    /// quand le pilote le fournit.
+    pub input_latency: Option<Duration>,
+}
+
+pub type SharedLoad = Arc<Mutex<LoadMonitor>>;
+
+impl LoadMonitor {
+    pub fn record(&mut self, busy: Duration, block: Duration) {
+        if block.is_zero() {
+            return;
+        }
+        let ratio = busy.as_secs_f32() / block.as_secs_f32();
+        if ratio > 1.0 {
+            self.overruns += 1;
+        }
+        self.peak_load = self.peak_load.max(ratio);
+        self.load += SMOOTHING * (ratio - self.load);
+
+        if self.load > HIGH_LOAD {
+            self.under_blocks = 0;
+            self.over_blocks += 1;
+            if self.over_blocks >= BLOCKS_BEFORE_SHEDDING {
+                self.over_blocks = 0;
+                self.level = self.level.step(true);
+            }
+        } else if self.load < LOW_LOAD {
+            self.over_blocks = 0;
+            self.under_blocks += 1;
+            if self.under_blocks >= BLOCKS_BEFORE_RESTORING {
+                self.under_blocks = 0;
+                self.level = self.level.step(false);
+            }
+        } else {
+            self.over_blocks = 0;
+            self.under_blocks = 0;
+        }
+    }
+
+    pub fn level(&self) -> Degradation {
+        self.level
+    }
+
+    /// Part du temps réel passée à analyser: l'occupation du thread d'analyse.
+    pub fn load(&self) -> f32 {
+        self.load
+    }
+
+    pub fn peak_load(&self) -> f32 {
+        self.peak_load
+    }
+
+    /// Indicateur affiché seulement quand il y a quelque chose à signaler.
+    pub fn show(&self, ui: &mut egui::Ui) {
+        if self.level == Degradation::Full
+            && self.overruns == 0
+            && self.dropped_frames == 0
+            && self.lost_samples == 0
+        {
+            return;
+        }
+        let color = if self.level == Degradation::Full {
+            egui::Color32::GRAY
+        } else {
+            egui::Color32::from_rgb(255, 170, 60)
+        };
+        ui.colored_label(
+            color,
+            format!("⚙ Charge {:.0} %: {}", 100.0 * self.load, self.level.label()),
+        )
+        .on_hover_text(format!(
+            "L'analyse prend trop de temps pour cet ordinateur: les tâches les plus lourdes \
+             sont suspendues, puis rétablies quand la charge baisse.\n\
+             {} bloc(s) en retard, {} trame(s) non affichée(s)",
+            self.overruns, self.dropped_frames
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use eframe::egui;
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, fmt as tracing_fmt};
+
+use crate::dates::DateTime;
+use crate::paths;
+
+/// Entrées gardées en mémoire pour le panneau; le fichier garde tout.
+const MAX_ENTRIES: usize = 500;
+/// Un fichier par jour, les plus anciens supprimés au-delà.
+const KEPT_LOG_FILES: usize = 7;
+
+struct LogEntry {
+    at: DateTime,
+    level: Level,
+    target: String,
+    message: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02} {:>5} {}: {}",
+            self.at.hour, self.at.minute, self.at.second, self.level, self.target, self.message
+        )
+    }
+}
+
+static ENTRIES: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+/// Avertissements et erreurs arrivés depuis la dernière ouverture du panneau.
+static UNSEEN: AtomicUsize = AtomicUsize::new(0);
+
+pub fn logs_dir() -> Result<PathBuf> {
+    paths::data_subdir("logs")
+}
+
+/// Texte d'un événement: son message, suivi de ses autres champs.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Garde les derniers événements pour le panneau du journal.
+struct PanelLayer;
+
+impl<S: Subscriber> Layer<S> for PanelLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let entry = LogEntry {
+            at: DateTime::from_unix(now),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: format!("{}{}", visitor.message, visitor.fields),
+        };
+        if entry.level <= Level::WARN {
+            UNSEEN.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Ok(mut entries) = ENTRIES.lock() {
+            if entries.len() == MAX_ENTRIES {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+}
+
+/// Journal dans la console, dans un fichier par jour et dans le panneau de
+/// l'application. À appeler une fois, au démarrage.
+pub fn init() {
+    let file = logs_dir().and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("feminizer-voice")
+            .filename_suffix("log")
+            .max_log_files(KEPT_LOG_FILES)
+            .build(dir)
+            .map_err(anyhow::Error::from)
+    });
+    let (file_layer, file_error) = match file {
+        Ok(appender) => (
+            Some(tracing_fmt::layer().with_ansi(false).with_writer(appender)),
+            None,
+        ),
+        Err(e) => (None, Some(e)),
+    };
+    let initialized = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(tracing_fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .with(PanelLayer)
+        .try_init();
+    if let Err(e) = initialized {
+        eprintln!("Journal indisponible: {}", e);
+    }
+    if let Some(e) = file_error {
+        tracing::warn!("Journal sur disque indisponible: {}", e);
+    }
+}
+
+/// Fenêtre du journal: ce qui s'affichait dans le terminal, pour comprendre
+/// et partager la raison d'une panne sans lancer l'application en console.
+#[derive(Default)]
+pub struct LogWindow {
+    pub open: bool,
+    warnings_only: bool,
+}
+
+impl LogWindow {
+    /// Avertissements et erreurs pas encore vus, pour l'indicateur.
+    pub fn unseen(&self) -> usize {
+        UNSEEN.load(Ordering::Relaxed)
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        UNSEEN.store(0, Ordering::Relaxed);
+
+        let mut open = self.open;
+        egui::Window::new("📃 Journal")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                let lines: Vec<(Level, String)> = ENTRIES
+                    .lock()
+                    .map(|entries| {
+                        entries
+                            .iter()
+                            .filter(|entry| !self.warnings_only || entry.level <= Level::WARN)
+                            .map(|entry| (entry.level, entry.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.warnings_only, "Avertissements et erreurs seulement");
+                    if ui.button("📋 Copier").clicked() {
+                        let text: Vec<&str> = lines.iter().map(|(_, line)| line.as_str()).collect();
+                        ui.ctx().copy_text(text.join("\n"));
+                    }
+                });
+                if let Ok(dir) = logs_dir() {
+                    ui.small(format!("Journal complet, un fichier par jour: {}", dir.display()));
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        if lines.is_empty() {
+                            ui.weak("Rien à signaler.");
+                        }
+                        for (level, line) in &lines {
+                            let text = egui::RichText::new(line).monospace();
+                            match *level {
+                                Level::ERROR => ui.colored_label(egui::Color32::RED, text),
+                                Level::WARN => {
+                                    ui.colored_label(egui::Color32::from_rgb(255, 170, 60), text)
+                                }
+                                _ => ui.label(text),
+                            };
+                        }
+                    });
+            });
+        self.open = open;
+    }
+}
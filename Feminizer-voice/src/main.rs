@@ -2,427 +2,3956 @@ use eframe::egui;
 use egui_plot::{Line, Plot, PlotItem, PlotPoints, Text};
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
 use egui::ecolor::Hsva;
 use egui::StrokeKind;
-
+use feminizer_voice_core::{
+    AnalysisConfig, AnalysisFrame, DenoiseConfig, FrequencyData, LoudnessConfig, PitchDetector,
+    SILENCE_LUFS, VadConfig, WindowFunction,
+};
+
+mod accessibility;
+mod analysis_worker;
+mod api_schema;
+mod audio_host;
 mod audio_processor;
-use audio_processor::{AudioProcessor, FrequencyData};
+mod auto_capture;
+mod autosave;
+mod backup;
+mod broadcast;
+mod calendar;
+mod calibration;
+mod contour_import;
+mod correlation;
+mod cues;
+mod dates;
+mod default_input;
+mod device_check;
+mod diagnostics;
+mod floor_cue;
+mod gauge;
+mod goal;
+mod good_moments;
+mod guard;
+mod headless;
+mod histogram;
+mod input_health;
+mod karaoke;
+mod ladder;
+mod layout;
+mod listening;
+mod logging;
+mod load;
+mod markers;
+mod metronome;
+mod midi;
+mod mode;
+mod monitor;
+mod palette;
+mod passage;
+mod peer;
+mod perception;
+mod paths;
+mod permissions;
+mod piano;
+mod pitch_unit;
+mod playback;
+mod plot_image;
+mod power;
+mod profiles;
+mod prosody;
+mod range_test;
+mod reading;
+mod reconnect;
+mod reference;
+mod report;
+mod schema;
+mod scripting;
+mod second_stream;
+mod sequencer;
+mod session;
+mod session_audio;
+mod session_end;
+mod session_review;
+mod settings;
+mod setup_wizard;
+mod shortcuts;
+mod spectrum_snapshot;
+mod strain;
+mod sustain;
+mod takes;
+mod theme;
+mod tray;
+mod trend;
+mod tuning;
+mod vowel_chart;
+mod waterfall;
+mod wav;
+mod zip;
+use accessibility::TargetBeep;
+use audio_processor::{AudioProcessor, SharedInputChannels};
+use auto_capture::SilenceWatch;
+use autosave::{RecoveryAction, SessionAutosave};
+use backup::BackupManager;
+use broadcast::{Broadcaster, LiveMessage};
+use calibration::{LevelCalibrator, to_dbfs};
+use correlation::CorrelationExplorer;
+use cues::{CueCategory, CuePlayer};
+use default_input::DefaultInputWatch;
+use device_check::{DeviceCheck, DeviceCheckReport};
+use diagnostics::{Diagnostics, StreamInfo};
+use floor_cue::FloorCue;
+use gauge::{GaugeReading, GaugeWindow};
+use goal::GoalTracker;
+use good_moments::{GoodMoments, SavedClip};
+use guard::PitchGuard;
+use histogram::PitchHistogram;
+use input_health::InputHealth;
+use karaoke::KaraokePrompt;
+use ladder::{LadderEvent, LadderWarmup};
+use layout::{Dock, Tab};
+use listening::ListeningContext;
+use logging::LogWindow;
+use load::Degradation;
+use markers::{MarkerEditor, MarkerPlayback};
+use metronome::Metronome;
+use midi::MidiOut;
+use mode::{AppMode, Exercise, ModeKind};
+use monitor::{Monitor, MonitorConfig, MonitorTap};
+use palette::CommandPalette;
+use passage::PassagePractice;
+use peer::PeerLink;
+use perception::PerceptionGauge;
+use pitch_unit::PitchUnit;
+use plot_image::{Figure, Item, PlotImageExport};
+use profiles::ProfilePicker;
+use prosody::{PhraseGrade, UtteranceTracker, segment_phrases};
+use range_test::RangeExploration;
+use reading::ReadingPractice;
+use reconnect::Reconnect;
+use reference::ReferenceComparison;
+use report::{ReportExporter, ReportOptions};
+use scripting::ScriptRunner;
+use second_stream::SecondStream;
+use sequencer::DrillSequencer;
+use session_end::SessionEndSummary;
+use session_review::SessionReview;
+use session::{SessionStats, SessionStore, SessionSummary};
+use session_audio::{AudioTap, ReanalysisParams, SessionAudioWriter};
+use settings::Settings;
+use setup_wizard::{SetupResult, SetupWizard, WizardAction};
+use shortcuts::{Shortcut, ShortcutHelp};
+use spectrum_snapshot::SpectrumSnapshots;
+use strain::StrainMonitor;
+use sustain::SustainedVowel;
+use takes::TakeManager;
+use theme::ThemeMode;
+use tray::{Tray, TrayCommand, TrayState};
+use trend::TrendChart;
+use tuning::ThresholdTuner;
+use vowel_chart::VowelChart;
+use waterfall::HarmonicWaterfall;
+
+const TARGET_MIN_HZ: f32 = 180.0;
+const TARGET_MAX_HZ: f32 = 310.0;
+/// Confiance de hauteur en deçà de laquelle une trame est ignorée des statistiques.
+const MIN_CONFIDENCE: f32 = 0.5;
+const APP_TITLE: &str = "Feminizer voice";
+const TITLE_REFRESH: Duration = Duration::from_millis(250);
+const STATS_BROADCAST_INTERVAL: Duration = Duration::from_secs(1);
+const BRIGHTNESS_DARK_HZ: f32 = 500.0;
+const BRIGHTNESS_BRIGHT_HZ: f32 = 3000.0;
+/// Bornes sombre et claire de l'échelle de couleur du pic de résonance.
+const RESONANCE_DARK_HZ: f32 = 700.0;
+const RESONANCE_BRIGHT_HZ: f32 = 2000.0;
+/// Paliers de couleur du tracé de résonance, un nuage de points par palier.
+const RESONANCE_COLOR_STEPS: usize = 8;
+/// Repères du poids vocal (H1–H2): fondamentale nettement dominante pour une
+/// voix légère, dominée par la deuxième harmonique pour une voix lourde.
+const WEIGHT_LIGHT_DB: f32 = 8.0;
+const WEIGHT_HEAVY_DB: f32 = -2.0;
+const WEIGHT_SMOOTHING: f32 = 0.15;
+const DEVICE_SWITCH_TIMEOUT: Duration = Duration::from_secs(3);
+const STALE_STREAM_TIMEOUT: Duration = Duration::from_secs(2);
+const SPECTROGRAM_MARKS: [f32; 10] =
+    [50.0, 100.0, 200.0, 300.0, 400.0, 500.0, 600.0, 800.0, 1000.0, 1200.0];
+const LARGE_READOUT_SIZE: f32 = 48.0;
+/// Avance du contour importé sur l'historique en direct, en trames.
+const CONTOUR_LOOKAHEAD_FRAMES: f64 = 20.0;
+const MARKER_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 0);
+
+#[derive(Clone, Copy)]
+enum Action {
+    ShowTab(Tab),
+    StartRecording,
+    StopRecording,
+    DeviceCheck,
+    ToggleMonitor,
+    ToggleGauge,
+    TogglePitchGuard,
+    TogglePitchInTitle,
+    ExportSchemas,
+    SetupWizard,
+    ToggleRecording,
+    TogglePlots,
+    ExportSessions,
+    ShowDiagnostics,
+    ShowLog,
+    AddMarker,
+    NudgeTarget { shift_hz: f32, widen_hz: f32 },
+}
+
+// Nouveau flux ouvert en parallèle de l'ancien jusqu'à sa première trame analysée
+struct PendingDeviceSwitch {
+    processor: AudioProcessor,
+    frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+    monitor_tap: MonitorTap,
+    audio_tap: AudioTap,
+    label: String,
+    requested_at: Instant,
+}
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "--export-schemas") {
+        let dir = std::path::PathBuf::from(args.get(1).map_or(".", String::as_str));
+        match api_schema::export_schemas(&dir) {
+            Ok(paths) => paths.iter().for_each(|path| println!("{}", path.display())),
+            Err(e) => {
+                eprintln!("Erreur: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    logging::init();
+    if args.iter().any(|arg| arg == "--headless") {
+        let result = headless::HeadlessOptions::parse(&args).and_then(|o| headless::run(&o));
+        if let Err(e) = result {
+            eprintln!("Erreur: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions {
         ..Default::default()
     };
 
     eframe::run_native(
-        "Feminizer voice",
+        APP_TITLE,
         options,
-        Box::new(|cc| {
-            cc.egui_ctx.set_visuals(egui::Visuals::dark());
-            Ok(Box::new(VoiceFrequencyApp::new()))
-        }),
+        Box::new(|_cc| Ok(Box::new(VoiceFrequencyApp::new()))),
     )
 }
 
 struct VoiceFrequencyApp {
     audio_processor: Option<AudioProcessor>,
-    is_recording: bool,
-    frequency_history: VecDeque<f32>,
-    amplitude_history: VecDeque<f32>,
+    mode: AppMode,
+    /// Dernières trames analysées, une par pas d'analyse; une trame sans voix
+    /// fiable n'a aucune mesure.
+    history: VecDeque<AnalysisFrame>,
     current_frequency: f32,
     current_amplitude: f32,
+    /// Même niveau en LUFS, affiché avec la pondération K.
+    current_loudness: f32,
     frequency_data: Arc<Mutex<Option<FrequencyData>>>,
     error_message: Option<String>,
-    min_amplitude_threshold: f32,
-    spectrum_history: VecDeque<Vec<f32>>
+    vad_config: Arc<Mutex<VadConfig>>,
+    is_voiced: bool,
+    current_flatness: f32,
+    /// Méthode qui a produit la dernière hauteur.
+    current_detector: PitchDetector,
+    current_brightness: f32,
+    /// H1–H2 lissé, `None` tant qu'aucune trame voisée ne l'a mesuré.
+    current_weight: Option<f32>,
+    spectrum_history: VecDeque<Vec<f32>>,
+    sample_rate: f32,
+    frame_duration: f32,
+    tab: Tab,
+    session_stats: Option<SessionStats>,
+    /// Séance interrompue que `start_recording` doit poursuivre.
+    resumed_session: Option<SessionStats>,
+    autosave: SessionAutosave,
+    session_store: Option<SessionStore>,
+    audio_tap: AudioTap,
+    audio_writer: Option<SessionAudioWriter>,
+    sessions: Vec<SessionSummary>,
+    correlation_explorer: CorrelationExplorer,
+    trend_chart: TrendChart,
+    pitch_histogram: PitchHistogram,
+    device_check_report: Option<DeviceCheckReport>,
+    vowel_chart: VowelChart,
+    waterfall: HarmonicWaterfall,
+    takes: TakeManager,
+    reports: ReportExporter,
+    pitch_in_title: bool,
+    window_title: String,
+    last_title_update: Instant,
+    monitor: Option<Monitor>,
+    monitor_tap: MonitorTap,
+    monitor_config: Arc<Mutex<MonitorConfig>>,
+    gauge_window: GaugeWindow,
+    utterance_tracker: UtteranceTracker,
+    cue_player: CuePlayer,
+    metronome: Metronome,
+    drill_sequencer: DrillSequencer,
+    scripts: ScriptRunner,
+    passage: PassagePractice,
+    reading: ReadingPractice,
+    reference: ReferenceComparison,
+    sustain: SustainedVowel,
+    range_test: RangeExploration,
+    ladder: LadderWarmup,
+    karaoke: KaraokePrompt,
+    spectrum_snapshots: SpectrumSnapshots,
+    good_moments: GoodMoments,
+    setup_wizard: Option<SetupWizard>,
+    /// Bornes d'acceptation à rétablir après la glissade du test d'étendue.
+    range_search_saved: Option<(f32, f32)>,
+    palette: CommandPalette,
+    backups: BackupManager,
+    level_calibrator: LevelCalibrator,
+    pitch_guard: PitchGuard,
+    midi_out: MidiOut,
+    perception: PerceptionGauge,
+    floor_cue: FloorCue,
+    strain_monitor: StrainMonitor,
+    goal_tracker: GoalTracker,
+    threshold_tuner: ThresholdTuner,
+    accept_min_hz: f32,
+    accept_max_hz: f32,
+    min_confidence: f32,
+    settings: Settings,
+    broadcaster: Option<Broadcaster>,
+    last_stats_broadcast: Instant,
+    schemas_dir: Option<std::path::PathBuf>,
+    sessions_export: Option<std::path::PathBuf>,
+    shortcut_help: ShortcutHelp,
+    diagnostics: Diagnostics,
+    log_window: LogWindow,
+    plot_image: PlotImageExport,
+    profiles: ProfilePicker,
+    tray: Tray,
+    /// Fenêtre cachée après réduction; l'icône de notification la rouvre.
+    hidden_to_tray: bool,
+    applied_theme: Option<ThemeMode>,
+    applied_zoom: Option<f32>,
+    target_beep: TargetBeep,
+    /// Graphiques figés (raccourci P); l'analyse et les statistiques continuent.
+    plots_paused: bool,
+    /// Fond de chaque énoncé coloré selon sa part dans la cible.
+    phrase_colors: bool,
+    input_device: Option<String>,
+    pending_switch: Option<PendingDeviceSwitch>,
+    second_stream: SecondStream,
+    peer: PeerLink,
+    silence_watch: SilenceWatch,
+    session_review: SessionReview,
+    session_end: SessionEndSummary,
+    input_channels: SharedInputChannels,
+    input_health: InputHealth,
+    analysis_config: Arc<Mutex<AnalysisConfig>>,
+    reconnect: Option<Reconnect>,
+    last_frame_at: Instant,
+    /// Début de la coupure en cours, comblée dans l'historique au retour du flux.
+    history_gap_since: Option<Instant>,
+    default_input_watch: DefaultInputWatch,
+    history_frames: u64,
+    device_markers: VecDeque<(u64, String)>,
+    /// Repères de la session en cours: trame, indice dans la session, note.
+    moment_markers: VecDeque<(u64, usize, String)>,
+    marker_editor: MarkerEditor,
+    marker_playback: MarkerPlayback,
+    /// Trame où le contour importé a été lancé comme guide sur l'historique.
+    contour_guide: Option<u64>,
 }
 
 impl Default for VoiceFrequencyApp {
     fn default() -> Self {
         Self {
             audio_processor: None,
-            is_recording: false,
-            frequency_history: Default::default(),
-            amplitude_history: Default::default(),
+            mode: AppMode::Idle,
+            history: Default::default(),
             current_frequency: 0.0,
             current_amplitude: 0.0,
+            current_loudness: SILENCE_LUFS,
             frequency_data: Arc::new(Mutex::new(None)),
             error_message: None,
-            min_amplitude_threshold: 0.0200,
+            vad_config: Arc::new(Mutex::new(VadConfig::default())),
+            is_voiced: false,
+            current_flatness: 0.0,
+            current_detector: PitchDetector::default(),
+            current_brightness: 0.0,
+            current_weight: None,
             spectrum_history: Default::default(),
-
+            sample_rate: 48000.0,
+            frame_duration: 1024.0 / 48000.0,
+            tab: Tab::Live,
+            session_stats: None,
+            resumed_session: None,
+            autosave: SessionAutosave::default(),
+            session_store: None,
+            audio_tap: Default::default(),
+            audio_writer: None,
+            sessions: Vec::new(),
+            correlation_explorer: CorrelationExplorer::default(),
+            trend_chart: TrendChart::default(),
+            pitch_histogram: PitchHistogram::default(),
+            device_check_report: None,
+            vowel_chart: VowelChart::default(),
+            waterfall: HarmonicWaterfall::default(),
+            takes: TakeManager::default(),
+            reports: ReportExporter::default(),
+            pitch_in_title: false,
+            window_title: APP_TITLE.to_string(),
+            last_title_update: Instant::now(),
+            monitor: None,
+            monitor_tap: Default::default(),
+            monitor_config: Arc::new(Mutex::new(MonitorConfig::default())),
+            gauge_window: GaugeWindow::default(),
+            utterance_tracker: UtteranceTracker::default(),
+            cue_player: CuePlayer::default(),
+            metronome: Metronome::default(),
+            drill_sequencer: DrillSequencer::default(),
+            scripts: ScriptRunner::default(),
+            passage: PassagePractice::default(),
+            reading: ReadingPractice::default(),
+            reference: ReferenceComparison::default(),
+            sustain: SustainedVowel::default(),
+            range_test: RangeExploration::default(),
+            ladder: LadderWarmup::default(),
+            karaoke: KaraokePrompt::default(),
+            spectrum_snapshots: SpectrumSnapshots::default(),
+            good_moments: GoodMoments::default(),
+            setup_wizard: None,
+            range_search_saved: None,
+            palette: CommandPalette::default(),
+            backups: BackupManager::default(),
+            level_calibrator: LevelCalibrator::default(),
+            pitch_guard: PitchGuard::default(),
+            midi_out: MidiOut::default(),
+            perception: PerceptionGauge::default(),
+            floor_cue: FloorCue::default(),
+            strain_monitor: StrainMonitor::default(),
+            goal_tracker: GoalTracker::default(),
+            threshold_tuner: ThresholdTuner::default(),
+            accept_min_hz: 50.0,
+            accept_max_hz: 450.0,
+            min_confidence: MIN_CONFIDENCE,
+            settings: Settings::default(),
+            broadcaster: None,
+            last_stats_broadcast: Instant::now(),
+            schemas_dir: None,
+            sessions_export: None,
+            shortcut_help: ShortcutHelp::default(),
+            diagnostics: Diagnostics::default(),
+            log_window: LogWindow::default(),
+            plot_image: PlotImageExport::default(),
+            profiles: ProfilePicker::default(),
+            tray: Tray::default(),
+            hidden_to_tray: false,
+            applied_theme: None,
+            applied_zoom: None,
+            target_beep: TargetBeep::default(),
+            plots_paused: false,
+            phrase_colors: true,
+            input_device: None,
+            pending_switch: None,
+            second_stream: SecondStream::default(),
+            peer: PeerLink::default(),
+            silence_watch: SilenceWatch::default(),
+            session_review: SessionReview::default(),
+            session_end: SessionEndSummary::default(),
+            input_channels: Default::default(),
+            input_health: InputHealth::default(),
+            analysis_config: Default::default(),
+            reconnect: None,
+            last_frame_at: Instant::now(),
+            history_gap_since: None,
+            default_input_watch: DefaultInputWatch::default(),
+            history_frames: 0,
+            device_markers: VecDeque::new(),
+            moment_markers: VecDeque::new(),
+            marker_editor: MarkerEditor::default(),
+            marker_playback: MarkerPlayback::default(),
+            contour_guide: None,
         }
     }
 }
 
 impl VoiceFrequencyApp {
     fn new() -> Self {
-        Self {
+        let mut app = Self {
             ..Default::default()
+        };
+        app.profiles.active = profiles::restore_active();
+        app.profiles.refresh();
+
+        match SessionStore::open() {
+            Ok(store) => app.session_store = Some(store),
+            Err(e) => app.error_message = Some(format!("Historique indisponible: {}", e)),
         }
-    }
+        app.reload_sessions();
 
-    fn start_recording(&mut self) {
-        match AudioProcessor::new(self.frequency_data.clone()) {
-            Ok(processor) => {
-                self.audio_processor = Some(processor);
-                self.is_recording = true;
-                self.error_message = None;
-                println!("Enregistrement démarré");
-            }
-            Err(e) => {
-                self.error_message = Some(format!("Erreur audio: {}", e));
-                println!("Erreur lors du démarrage: {}", e);
-            }
+        match Settings::load() {
+            Ok(settings) => app.settings = settings,
+            Err(e) => app.error_message = Some(format!("Lecture des réglages: {}", e)),
         }
+        app.open_setup_wizard_if_new();
+        app.autosave.recover();
+        app.apply_analysis_config();
+        app.apply_thresholds();
+        app.apply_audio_host();
+        app.input_device = app.settings.input_device.clone();
+        app.restart_broadcaster();
+        if app.settings.auto_capture.start_on_launch {
+            app.start_recording();
+        }
+        app
     }
 
-    fn stop_recording(&mut self) {
-        self.audio_processor = None;
-        self.is_recording = false;
-        println!("Enregistrement arrêté");
+    /// Profil sans réglages enregistrés: premier lancement ou profil neuf.
+    fn open_setup_wizard_if_new(&mut self) {
+        self.setup_wizard = (!Settings::exists()).then(|| self.new_setup_wizard());
     }
 
-    fn update_frequency_data(&mut self) -> bool {
-        if let Ok(data_guard) = self.frequency_data.try_lock() {
-            if let Some(data) = data_guard.as_ref() {
-                if data.amplitude < self.min_amplitude_threshold {
-                    return false;
-                }
-
-                let filtered_frequency =
-                    if data.dominant_frequency >= 50.0 && data.dominant_frequency <= 450.0 {
-                        data.dominant_frequency
-                    } else {
-                        0.0
-                    };
-
-                self.current_frequency = filtered_frequency;
-                self.current_amplitude = data.amplitude;
-
-                if filtered_frequency > 0.0 {
-                    self.frequency_history.push_back(filtered_frequency);
-                    self.amplitude_history.push_back(data.amplitude);
-                    self.spectrum_history.push_back(data.spectrum.clone());
-
-                } else {
-                    self.frequency_history.push_back(0.0);
-                    self.amplitude_history.push_back(0.0);
-                    self.spectrum_history.push_back(vec![0.0; 512]); // silence
-
-                }
-
-                if self.frequency_history.len() > 100 {
-                    self.frequency_history.pop_front();
-                    self.amplitude_history.pop_front();
-
-                    self.spectrum_history.pop_front();
-                }
-
-                return true;
-            }
-        }
-        false
-    }
-
-    fn frequency_to_note(&self, freq: f32) -> String {
-        if freq < 50.0 || freq > 450.0 {
-            return "Hors plage".to_string();
-        }
-
-        let notes = [
-            (82.4, "E2"),
-            (87.3, "F2"),
-            (92.5, "F#2"),
-            (98.0, "G2"),
-            (103.8, "G#2"),
-            (110.0, "A2"),
-            (116.5, "A#2"),
-            (123.5, "B2"),
-            (130.8, "C3"),
-            (138.6, "C#3"),
-            (146.8, "D3"),
-            (155.6, "D#3"),
-            (164.8, "E3"),
-            (174.6, "F3"),
-            (185.0, "F#3"),
-            (196.0, "G3"),
-            (207.7, "G#3"),
-            (220.0, "A3"),
-            (233.1, "A#3"),
-            (246.9, "B3"),
-            (261.6, "C4"),
-            (277.2, "C#4"),
-            (293.7, "D4"),
-            (311.1, "D#4"),
-            (329.6, "E4"),
-            (349.2, "F4"),
-            (370.0, "F#4"),
-            (392.0, "G4"),
-            (415.3, "G#4"),
-            (440.0, "A4"),
-        ];
+    fn new_setup_wizard(&self) -> SetupWizard {
+        SetupWizard::new(self.settings.pitch_scale, self.settings.target)
+    }
 
-        let mut closest_note = "?";
-        let mut min_diff = f32::INFINITY;
+    fn apply_setup(&mut self, result: SetupResult) {
+        self.settings.pitch_scale.unit = result.unit;
+        if let Some(threshold) = result.energy_threshold() {
+            self.settings.thresholds.vad.energy_threshold = threshold;
+            self.settings.analysis.denoise.enabled = result.denoise;
+        }
+        self.settings.target = result.target;
+        self.apply_analysis_config();
+        self.apply_thresholds();
+        self.save_settings();
+    }
 
-        for (note_freq, note_name) in &notes {
-            let diff = (freq - note_freq).abs();
-            if diff < min_diff {
-                min_diff = diff;
-                closest_note = note_name;
+    fn show_setup_wizard(&mut self, ctx: &egui::Context) {
+        let Some(mut wizard) = self.setup_wizard.take() else {
+            return;
+        };
+        let is_recording = self.is_recording();
+        let action = wizard.show(ctx, is_recording, self.settings.pitch_scale, |ui| {
+            self.show_input_device_picker(ui);
+            if is_recording {
+                self.input_health.show(ui);
+            }
+        });
+        match action {
+            None => self.setup_wizard = Some(wizard),
+            Some(WizardAction::StartRecording) => {
+                self.start_recording();
+                self.setup_wizard = Some(wizard);
             }
+            Some(WizardAction::Finish(result)) => self.apply_setup(result),
+            // Réglages enregistrés tels quels: l'assistant ne revient pas
+            Some(WizardAction::Skip) => self.save_settings(),
         }
+    }
 
-        format!("{} (~{:.1}Hz)", closest_note, freq)
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save() {
+            self.error_message = Some(format!("Sauvegarde des réglages: {}", e));
+        }
     }
 
-    fn draw_frequency_labels(&self, painter: &egui::Painter, rect: egui::Rect, min_bin: usize, max_bin: usize, freq_per_bin: f32) {
-        let text_color = egui::Color32::WHITE;
-        let font_id = egui::FontId::monospace(10.0);
+    fn apply_analysis_config(&mut self) {
+        self.settings.analysis = self.settings.analysis.sanitized();
+        // Le filtre d'acceptation repart des bornes de recherche
+        self.accept_min_hz = self.settings.analysis.min_frequency_hz;
+        self.accept_max_hz = self.settings.analysis.max_frequency_hz;
+        self.push_analysis_config();
+    }
 
-        let freq_marks = [50.0, 100.0, 200.0, 300.0, 400.0, 500.0];
+    /// Envoie au flux les réglages d'analyse, pas élargi en mode économie.
+    fn push_analysis_config(&self) {
+        if let Ok(mut config) = self.analysis_config.lock() {
+            *config = self.settings.power.apply(self.settings.analysis);
+        }
+    }
 
-        for &freq in &freq_marks {
-            if freq >= 50.0 && freq <= 500.0 {
-                let bin_index = (freq / freq_per_bin) as usize;
-                if bin_index >= min_bin && bin_index < max_bin {
-                    let relative_bin = bin_index - min_bin;
-                    let filtered_bins = max_bin - min_bin;
+    /// Un pilote indisponible (fonction non compilée, serveur JACK arrêté)
+    /// laisse place à celui du système.
+    fn apply_audio_host(&mut self) {
+        if let Err(e) = audio_host::select(self.settings.audio_host.as_deref()) {
+            self.error_message = Some(format!(
+                "Pilote audio: {}, {} utilisé à la place",
+                e,
+                audio_host::default_name()
+            ));
+        }
+    }
 
-                    let y = rect.bottom() - ((relative_bin as f32 / filtered_bins as f32) * rect.height());
+    fn apply_thresholds(&mut self) {
+        if let Ok(mut config) = self.vad_config.lock() {
+            *config = self.settings.thresholds.vad;
+        }
+        self.min_confidence = self.settings.thresholds.min_confidence;
+    }
 
-                    painter.line_segment(
-                        [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
-                        egui::Stroke::new(0.5, egui::Color32::from_rgba_premultiplied(255, 255, 255, 80)),
-                    );
+    /// Enregistre les seuils modifiés depuis l'onglet « Seuils » ou le direct.
+    fn sync_thresholds(&mut self) {
+        let Ok(vad) = self.vad_config.lock().map(|config| *config) else {
+            return;
+        };
+        let thresholds = settings::Thresholds {
+            vad,
+            min_confidence: self.min_confidence,
+        };
+        if thresholds != self.settings.thresholds {
+            self.settings.thresholds = thresholds;
+            self.save_settings();
+        }
+    }
 
-                    painter.text(
-                        egui::pos2(rect.left() + 2.0, y - 6.0),
-                        egui::Align2::LEFT_CENTER,
-                        format!("{}Hz", freq as i32),
-                        font_id.clone(),
-                        text_color,
-                    );
+    /// Le test d'étendue cherche les hauteurs bien au-delà des réglages, le
+    /// temps de la glissade seulement.
+    fn sync_range_search(&mut self) {
+        let running = self.range_test.is_running() && self.is_recording();
+        match (running, self.range_search_saved) {
+            (true, None) => {
+                self.range_search_saved = Some((self.accept_min_hz, self.accept_max_hz));
+                let search = range_test::SEARCH_HZ;
+                self.accept_min_hz = *search.start();
+                self.accept_max_hz = *search.end();
+                if let Ok(mut config) = self.analysis_config.lock() {
+                    config.min_frequency_hz = *search.start();
+                    config.max_frequency_hz = *search.end();
                 }
             }
+            (false, Some((min, max))) => {
+                self.range_search_saved = None;
+                self.accept_min_hz = min;
+                self.accept_max_hz = max;
+                self.push_analysis_config();
+            }
+            _ => {}
         }
     }
-}
 
-impl eframe::App for VoiceFrequencyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.update_frequency_data();
+    fn switch_profile(&mut self, name: String) {
+        if let Err(e) = profiles::activate(&name) {
+            self.error_message = Some(format!("Changement de profil: {}", e));
+            return;
+        }
+        self.profiles.active = name;
+        match SessionStore::open() {
+            Ok(store) => self.session_store = Some(store),
+            Err(e) => {
+                self.session_store = None;
+                self.error_message = Some(format!("Historique indisponible: {}", e));
+            }
+        }
+        self.reload_restored_data();
+        self.open_setup_wizard_if_new();
+        self.autosave.recover();
+        self.goal_tracker = GoalTracker::default();
+        self.reference = ReferenceComparison::default();
+        self.sustain = SustainedVowel::default();
+        self.range_test = RangeExploration::default();
+        self.spectrum_snapshots = SpectrumSnapshots::default();
+        self.good_moments = GoodMoments::default();
+        self.sessions_export = None;
+        if self.settings.input_device != self.input_device {
+            self.switch_input_device(self.settings.input_device.clone());
+        }
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            //ui.heading("🎤 Feminizer voice");
-            //ui.separator();
+    fn show_analysis_settings(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🔬 Paramètres d'analyse");
+        let analysis = &mut self.settings.analysis;
 
-            ui.horizontal(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Fenêtre:");
+            for size in [1024, 2048, 4096] {
                 if ui
-                    .button(if self.is_recording {
-                        "🛑 Arrêter"
-                    } else {
-                        "🎙️ Démarrer"
-                    })
+                    .selectable_value(&mut analysis.window_size, size, format!("{}", size))
                     .clicked()
                 {
-                    if self.is_recording {
-                        self.stop_recording();
-                    } else {
-                        self.start_recording();
-                    }
+                    analysis.hop_size = analysis.hop_size.min(size);
                 }
+            }
 
-                ui.label(if self.is_recording {
-                    "🔴 Enregistrement en cours..."
+            ui.separator();
+            ui.label("Pas:");
+            let window_size = analysis.window_size;
+            for divisor in [1, 2, 4] {
+                let hop = window_size / divisor;
+                let label = if divisor == 1 {
+                    "sans recouvrement".to_string()
                 } else {
-                    "⚪ En attente"
-                });
+                    format!("1/{}", divisor)
+                };
+                ui.selectable_value(&mut analysis.hop_size, hop, label);
+            }
+        });
 
-                ui.separator();
-                ui.label("Seuil minimal:");
-                ui.add(
-                    egui::Slider::new(&mut self.min_amplitude_threshold, 0.001..=0.1)
-                        .logarithmic(true)
-                        .text("Amplitude"),
+        ui.horizontal(|ui| {
+            ui.label("Taux d'analyse:").on_hover_text(
+                "Un périphérique plus rapide est rééchantillonné avant l'analyse: \
+                 même résolution et même coût quel que soit le matériel",
+            );
+            ui.selectable_value(&mut analysis.resample_hz, None, "du périphérique");
+            for hz in AnalysisConfig::RESAMPLE_RATES_HZ {
+                ui.selectable_value(
+                    &mut analysis.resample_hz,
+                    Some(hz),
+                    format!("{:.0} kHz max", hz / 1000.0),
                 );
-            });
+            }
+        });
 
-            if let Some(error) = &self.error_message {
-                ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        ui.horizontal(|ui| {
+            ui.label("Bourrage de zéros:");
+            for factor in [1, 2, 4] {
+                ui.selectable_value(&mut analysis.zero_padding, factor, format!("×{}", factor));
             }
 
             ui.separator();
+            ui.label("Détection:");
+            for detector in PitchDetector::ALL {
+                ui.selectable_value(&mut analysis.detector, detector, detector_label(detector));
+            }
+            ui.label("ℹ").on_hover_text(
+                "Le produit harmonique regroupe les harmoniques de la voix: sur une voix grave \
+                 dont la deuxième harmonique domine, il rapporte la fondamentale et non \
+                 l'octave au-dessus",
+            );
+        });
 
-            ui.horizontal(|ui| {
-                ui.vertical(|ui| {
-                    ui.label("Fréquence dominante:");
-                    if self.current_frequency > 0.0
-                        && self.current_frequency >= 50.0
-                        && self.current_frequency <= 450.0
-                    {
-                        ui.colored_label(
-                            egui::Color32::GREEN,
-                            format!("{:.1} Hz", self.current_frequency),
-                        );
-                        ui.label(format!(
-                            "Note: {}",
-                            self.frequency_to_note(self.current_frequency)
-                        ));
-                    } else {
-                        ui.colored_label(egui::Color32::GRAY, "Aucune fréquence détectée");
+        ui.horizontal(|ui| {
+            ui.label("Pondération:");
+            egui::ComboBox::from_id_salt("window_function")
+                .selected_text(format!("{:?}", analysis.window))
+                .show_ui(ui, |ui| {
+                    for window in WindowFunction::ALL {
+                        ui.selectable_value(&mut analysis.window, window, format!("{:?}", window));
                     }
                 });
+        });
 
-                ui.separator();
+        ui.horizontal(|ui| {
+            let prefilter = &mut analysis.prefilter;
+            ui.checkbox(&mut prefilter.enabled, "Pré-filtre")
+                .on_hover_text("Retire le grondement et le bourdonnement secteur avant la FFT");
+            ui.add_enabled(
+                prefilter.enabled,
+                egui::Slider::new(&mut prefilter.high_pass_hz, 20.0..=120.0).text("Hz passe-haut"),
+            );
+
+            ui.add_enabled_ui(prefilter.enabled, |ui| {
+                ui.label("Secteur:");
+                ui.selectable_value(&mut prefilter.notch_hz, None, "aucun");
+                ui.selectable_value(&mut prefilter.notch_hz, Some(50.0), "50 Hz");
+                ui.selectable_value(&mut prefilter.notch_hz, Some(60.0), "60 Hz");
+            });
+        });
 
-                ui.vertical(|ui| {
-                    ui.label("Amplitude:");
-                    let amplitude_db = if self.current_amplitude > 0.0 {
-                        20.0 * self.current_amplitude.log10()
-                    } else {
-                        -60.0
-                    };
-                    ui.label(format!("{:.1} dB", amplitude_db));
-
-                    let level = ((amplitude_db + 60.0) / 60.0).clamp(0.0, 1.0);
-                    let bar_color = if level > 0.8 {
-                        egui::Color32::RED
-                    } else if level > 0.4 {
-                        egui::Color32::YELLOW
-                    } else {
-                        egui::Color32::GREEN
-                    };
+        ui.horizontal(|ui| {
+            let denoise = &mut analysis.denoise;
+            ui.checkbox(&mut denoise.enabled, "Réduction de bruit").on_hover_text(
+                "Retire le bruit de fond continu (ventilateur, climatisation), appris pendant \
+                 les silences: laissez quelques secondes sans parler après l'application",
+            );
+            ui.add_enabled(
+                denoise.enabled,
+                egui::Slider::new(&mut denoise.strength, DenoiseConfig::STRENGTH_RANGE)
+                    .text("intensité"),
+            )
+            .on_hover_text("Plus forte, elle retire davantage de bruit mais amincit la voix");
+        });
 
-                    ui.add(
-                        egui::ProgressBar::new(level)
-                            .fill(bar_color)
-                            .show_percentage(),
-                    );
-                });
+        ui.horizontal(|ui| {
+            let loudness = &mut analysis.loudness;
+            ui.label("Niveau:").on_hover_text(
+                "Énergie intégrée sur une durée fixe: l'affichage du niveau et le seuil de \
+                 détection de la voix ne dépendent plus de la fenêtre ni du périphérique",
+            );
+            ui.add(
+                egui::Slider::new(&mut loudness.window_ms, LoudnessConfig::WINDOW_RANGE_MS)
+                    .logarithmic(true)
+                    .text("ms d'intégration"),
+            );
+            ui.checkbox(&mut loudness.k_weighting, "Pondération K (LUFS)").on_hover_text(
+                "Pondération de la norme UIT-R BS.1770, proche de l'oreille: les aigus \
+                 comptent davantage. Le seuil d'énergie de la détection peut devoir être \
+                 réajusté",
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Hauteurs recherchées:");
+            ui.add(
+                egui::Slider::new(&mut analysis.min_frequency_hz, AnalysisConfig::FLOOR_RANGE_HZ)
+                    .text("Hz min"),
+            );
+            ui.add(
+                egui::Slider::new(
+                    &mut analysis.max_frequency_hz,
+                    AnalysisConfig::CEILING_RANGE_HZ,
+                )
+                .text("Hz max"),
+            )
+            .on_hover_text("Jusqu'à 800 Hz ou plus pour les sirènes en voix de tête");
+        });
+
+        ui.small(format!(
+            "Résolution: {:.1} Hz par raie, fenêtre de {:.0} ms, une trame toutes les {:.0} ms",
+            self.sample_rate / analysis.fft_size() as f32,
+            1000.0 * analysis.window_size as f32 / self.sample_rate,
+            1000.0 * analysis.hop_size as f32 / self.sample_rate
+        ));
+
+        if ui.button("Appliquer").clicked() {
+            self.apply_analysis_config();
+            self.save_settings();
+        }
+    }
+
+    fn restart_broadcaster(&mut self) {
+        self.broadcaster = None;
+        if !self.settings.broadcast.is_active(&self.settings.network) {
+            return;
+        }
+        match Broadcaster::start(&self.settings.broadcast, &self.settings.network) {
+            Ok(broadcaster) => self.broadcaster = Some(broadcaster),
+            Err(e) => self.error_message = Some(format!("Diffusion réseau: {}", e)),
+        }
+    }
+
+    fn broadcast_frame(&mut self, data: &FrequencyData) {
+        let Some(broadcaster) = &self.broadcaster else {
+            return;
+        };
+
+        let frame = AnalysisFrame::from(data);
+        broadcaster.send(&LiveMessage::Frame {
+            in_target: self.settings.target.contains(frame.frequency),
+            frame,
+        });
+
+        if self.last_stats_broadcast.elapsed() >= STATS_BROADCAST_INTERVAL
+            && let Some(stats) = &self.session_stats
+        {
+            self.last_stats_broadcast = Instant::now();
+            broadcaster.send(&LiveMessage::Stats {
+                voiced_secs: stats.voiced_secs(),
+                median_pitch: stats.median_pitch(),
+                in_range_percent: stats.in_range_percent(),
             });
+        }
+    }
 
-            ui.separator();
+    /// `frequency`: hauteur retenue, 0 hors voix ou si la trame est peu fiable.
+    fn push_script_frame(&mut self, data: &FrequencyData, frequency: f32) {
+        if !self.scripts.is_running() {
+            return;
+        }
+        let frame = AnalysisFrame {
+            frequency,
+            ..AnalysisFrame::from(data)
+        };
+        self.scripts.push_frame(&frame, self.settings.target);
+    }
 
-            if !self.frequency_history.is_empty() {
-                ui.label("📈 Historique des fréquences:");
+    fn show_settings(&mut self, ui: &mut egui::Ui) {
+        if let Some(error) = &self.error_message {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
 
-                let freq_points: PlotPoints = self
-                    .frequency_history
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(i, &freq)| {
-                        if freq >= 50.0 && freq <= 500.0 {
-                            Some([i as f64, freq as f64])
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-
-                let size = ui.available_size_before_wrap();
-
-                Plot::new("frequency_plot")
-                    .view_aspect(2.0)
-                    .width(size.y*2.0)
-                    .height(size.x/4.0)
-                    .y_axis_label("Fréquence (Hz)")
-                    .x_axis_label("Temps (échantillons)")
-                    .include_y(50.0)
-                    .include_y(500.0)
-                    .allow_zoom(false)
-                    .allow_drag(false)
-                    .show(ui, |plot_ui| {
-                        if !freq_points.points().is_empty() {
-                            plot_ui.line(
-                                Line::new("freq_points", freq_points)
-                                    .color(egui::Color32::from_rgb(255, 0, 255))
-                                    .width(2.0),
-                            );
-                        }
+        self.show_analysis_settings(ui);
+        if ui
+            .checkbox(
+                &mut self.settings.keep_session_audio,
+                "💾 Conserver l'audio des sessions (pour les réanalyser)",
+            )
+            .changed()
+        {
+            self.save_settings();
+        }
+        if self.settings.goal.show_settings(ui) {
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("🔈 Niveau sonore");
+        let is_recording = self.is_recording();
+        if self.level_calibrator.show(
+            ui,
+            &mut self.settings.level,
+            is_recording,
+            self.input_device.as_deref(),
+        ) {
+            self.save_settings();
+        }
+        if self.settings.strain.show(ui, &self.settings.level) {
+            self.save_settings();
+        }
+        if self.settings.floor_cue.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
 
-                        plot_ui.hline(
-                            egui_plot::HLine::new("", 180.0)
-                                .color(egui::Color32::RED)
-                                .style(egui_plot::LineStyle::Solid)
-                                .width(1.0),
-                        );
-                        plot_ui.hline(
-                            egui_plot::HLine::new("", 310.0)
-                                .color(egui::Color32::RED)
-                                .style(egui_plot::LineStyle::Solid)
-                                .width(1.0),
-                        );
+        ui.heading("🎨 Apparence");
+        if self.settings.theme.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
 
-                        plot_ui.hline(
-                            egui_plot::HLine::new("", 80.0)
-                                .color(egui::Color32::BLUE)
-                                .style(egui_plot::LineStyle::Solid)
-                                .width(1.0),
-                        );
-                        plot_ui.hline(
-                            egui_plot::HLine::new("", 160.0)
-                                .color(egui::Color32::BLUE)
-                                .style(egui_plot::LineStyle::Solid)
-                                .width(1.0),
-                        );
-                    });
+        ui.heading("🧩 Disposition");
+        if self.settings.layout.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("🔋 Énergie");
+        let hop_secs = self.settings.analysis.hop_size as f32 / self.sample_rate;
+        if self.settings.power.show(ui, hop_secs) {
+            self.push_analysis_config();
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("♿ Accessibilité");
+        if self.settings.accessibility.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("🔌 Pilote audio");
+        self.show_audio_host_settings(ui);
+        ui.separator();
+
+        ui.heading("⏯ Enregistrement automatique");
+        if self.settings.auto_capture.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("🏁 Fin de séance");
+        if self.settings.session_end.show(ui) {
+            self.save_settings();
+        }
+        ui.separator();
+
+        ui.heading("📌 Zone de notification");
+        if self.settings.tray.show(ui) {
+            self.save_settings();
+        }
+        if let Some(error) = &self.tray.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        ui.separator();
+
+        ui.heading("🎹 Sortie MIDI");
+        if self.settings.midi.show(ui) {
+            self.save_settings();
+        }
+        if let Some(error) = &self.midi_out.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        ui.separator();
+
+        ui.heading("⚖ Perception estimée");
+        if self.settings.perception.show(ui) {
+            self.save_settings();
+        }
+        if let Some(error) = &self.perception.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        ui.separator();
+
+        ui.heading("🗄 Sauvegardes");
+        let can_restore = self.mode.kind() == ModeKind::Idle;
+        let (changed, restored) =
+            self.backups.show(ui, &mut self.settings.backup, can_restore);
+        if changed {
+            self.save_settings();
+        }
+        if restored {
+            self.reload_restored_data();
+        }
+        ui.separator();
+
+        ui.heading("🔐 Permissions réseau");
+        ui.small(
+            "Aucune fonction réseau ne démarre sans autorisation ici. \
+             Par défaut, seules les applications de cette machine y ont accès.",
+        );
+        if self.settings.network.show(ui) {
+            self.save_settings();
+            self.restart_broadcaster();
+            self.peer.enforce(&self.settings.network.peer);
+        }
+        ui.separator();
+
+        ui.heading("📡 Diffusion des données en direct");
+        ui.small("Pour un overlay OBS ou un outil externe.");
+
+        let broadcast = &mut self.settings.broadcast;
+        let network = &self.settings.network;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut broadcast.websocket_enabled, "WebSocket (JSON)");
+            ui.label("Port:");
+            ui.add(egui::DragValue::new(&mut broadcast.websocket_port).range(1024..=65535));
+            if broadcast.websocket_enabled && !network.websocket.allowed {
+                ui.small("🔒 non autorisé");
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut broadcast.osc_enabled, "OSC (UDP)");
+            ui.label("Destination:");
+            ui.text_edit_singleline(&mut broadcast.osc_target);
+            if broadcast.osc_enabled && !network.osc.allowed {
+                ui.small("🔒 non autorisé");
             }
+        });
 
-            ui.separator();
-            ui.small("Plages: Graves 80-160 Hz | Aiguës 180-310 Hz");
+        ui.horizontal(|ui| {
+            if ui.button("Appliquer").clicked() {
+                self.save_settings();
+                self.restart_broadcaster();
+            }
 
-            if !self.spectrum_history.is_empty() {
-                let desired_width = ui.available_width();
-                let height = 200.0;
+            match &self.broadcaster {
+                Some(broadcaster) => ui.colored_label(
+                    egui::Color32::GREEN,
+                    format!("● Actif — {} client(s) WebSocket", broadcaster.client_count()),
+                ),
+                None => ui.label("○ Inactif"),
+            };
+        });
 
-                let (rect, _) = ui.allocate_exact_size(
-                    egui::vec2(desired_width, height),
-                    egui::Sense::hover(),
-                );
+        ui.horizontal(|ui| {
+            ui.small(format!("Protocole v{}", broadcast::PROTOCOL_VERSION));
+            if ui.button("📄 Exporter les schémas JSON").clicked() {
+                self.export_schemas();
+            }
+            if let Some(dir) = &self.schemas_dir {
+                ui.small(format!("Écrits dans {}", dir.display()));
+            }
+        });
+        ui.separator();
 
-                let painter = ui.painter_at(rect);
-                let history_len = self.spectrum_history.len();
-                let total_bins = self.spectrum_history[0].len();
+        ui.heading("🤝 Séance partagée");
+        let permission = self.settings.network.peer;
+        let scale = self.settings.pitch_scale;
+        if self.peer.show(ui, &mut self.settings.peer, &permission, scale) {
+            self.save_settings();
+        }
+    }
 
-                let sample_rate = 48000.0;
-                let freq_per_bin = sample_rate / (2.0 * total_bins as f32);
-                let min_bin = (50.0 / freq_per_bin) as usize;
-                let max_bin = (500.0 / freq_per_bin).min(total_bins as f32) as usize;
-                let filtered_bins = max_bin - min_bin;
+    fn export_schemas(&mut self) {
+        match paths::data_subdir("schemas")
+            .and_then(|dir| api_schema::export_schemas(&dir).map(|_| dir))
+        {
+            Ok(dir) => self.schemas_dir = Some(dir),
+            Err(e) => self.error_message = Some(format!("Export des schémas: {}", e)),
+        }
+    }
+
+    fn snapshot_take(&mut self) {
+        let frequencies: Vec<f32> = self
+            .history
+            .iter()
+            .map(|frame| {
+                if frame.confidence >= self.min_confidence { frame.frequency } else { 0.0 }
+            })
+            .collect();
+        let amplitudes: Vec<f32> = self.history.iter().map(|frame| frame.amplitude).collect();
+        self.takes.snapshot(&frequencies, &amplitudes, self.frame_duration);
+    }
+
+    fn export_sessions(&mut self) {
+        self.reload_sessions();
+        match session::export_csv(&self.sessions) {
+            Ok(path) => self.sessions_export = Some(path),
+            Err(e) => self.error_message = Some(format!("Export des sessions: {}", e)),
+        }
+    }
+
+    fn select_tab(&mut self, tab: Tab) {
+        if tab == Tab::Analytics {
+            self.reload_sessions();
+        }
+        // Un onglet ancré est déjà affiché dans son panneau
+        if self.settings.layout.dock_of(tab).is_none() {
+            self.tab = tab;
+        }
+    }
 
-                painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+    fn palette_commands(&self) -> Vec<(String, Action)> {
+        let on_off = |enabled: bool| if enabled { "Désactiver" } else { "Activer" };
+        let mut commands = vec![
+            if self.is_recording() {
+                ("⏹ Arrêter l'enregistrement".to_string(), Action::StopRecording)
+            } else {
+                ("⏺ Démarrer l'enregistrement".to_string(), Action::StartRecording)
+            },
+            ("🎤 Ouvrir: Direct".to_string(), Action::ShowTab(Tab::Live)),
+            ("🗣 Exercice: Voyelles".to_string(), Action::ShowTab(Tab::Vowels)),
+            ("🌈 Ouvrir: Harmoniques".to_string(), Action::ShowTab(Tab::Harmonics)),
+            ("📸 Ouvrir: Instantanés de spectre".to_string(), Action::ShowTab(Tab::Snapshots)),
+            ("🎞 Ouvrir: Comparaison de prises".to_string(), Action::ShowTab(Tab::Takes)),
+            ("🌟 Ouvrir: Bons moments".to_string(), Action::ShowTab(Tab::Moments)),
+            ("🎵 Exercice: Intonation".to_string(), Action::ShowTab(Tab::Prosody)),
+            ("📖 Exercice: Passages répétés".to_string(), Action::ShowTab(Tab::Passage)),
+            ("🗒 Exercice: Lecture guidée".to_string(), Action::ShowTab(Tab::Reading)),
+            ("🆚 Comparer avec la référence (A/B)".to_string(), Action::ShowTab(Tab::Reference)),
+            ("🥁 Exercice: Rythme".to_string(), Action::ShowTab(Tab::Rhythm)),
+            ("🅰 Exercice: Voyelle tenue".to_string(), Action::ShowTab(Tab::Sustain)),
+            ("📏 Exercice: Étendue vocale".to_string(), Action::ShowTab(Tab::Range)),
+            ("📶 Exercice: Échauffement en échelle".to_string(), Action::ShowTab(Tab::Ladder)),
+            ("🎼 Exercice: Texte minuté".to_string(), Action::ShowTab(Tab::Karaoke)),
+            ("📜 Exercice scripté".to_string(), Action::ShowTab(Tab::Scripts)),
+            ("🎚 Ouvrir: Seuils".to_string(), Action::ShowTab(Tab::Tuning)),
+            ("📊 Ouvrir: Analyses".to_string(), Action::ShowTab(Tab::Analytics)),
+            ("⚙ Ouvrir: Réglages".to_string(), Action::ShowTab(Tab::Settings)),
+            (
+                format!("🎧 {} le retour casque", on_off(self.monitor.is_some())),
+                Action::ToggleMonitor,
+            ),
+            (
+                format!("🖥 {} l'affichage externe (mini jauge)", on_off(self.gauge_window.open)),
+                Action::ToggleGauge,
+            ),
+            (
+                format!("🛡 {} le garde-fou de hauteur", on_off(self.pitch_guard.enabled)),
+                Action::TogglePitchGuard,
+            ),
+            (
+                format!("🏷 {} la hauteur dans le titre", on_off(self.pitch_in_title)),
+                Action::TogglePitchInTitle,
+            ),
+            ("📄 Exporter les schémas JSON".to_string(), Action::ExportSchemas),
+            ("🧭 Assistant de premier lancement".to_string(), Action::SetupWizard),
+            ("📤 Exporter les sessions (CSV)".to_string(), Action::ExportSessions),
+            ("🩺 Ouvrir: Diagnostics".to_string(), Action::ShowDiagnostics),
+            ("📃 Ouvrir: Journal".to_string(), Action::ShowLog),
+            (
+                if self.plots_paused {
+                    "▶ Reprendre les graphiques".to_string()
+                } else {
+                    "⏸ Figer les graphiques".to_string()
+                },
+                Action::TogglePlots,
+            ),
+        ];
+        if self.mode.can_enter(ModeKind::Calibrating) {
+            commands.push(("🔧 Tester le périphérique".to_string(), Action::DeviceCheck));
+        }
+        if self.session_stats.is_some() {
+            commands.push(("📍 Poser un repère".to_string(), Action::AddMarker));
+        }
+        commands
+    }
 
-                for (t, spectrum) in self.spectrum_history.iter().enumerate() {
-                    for (f_idx, &amp) in spectrum[min_bin..max_bin].iter().enumerate() {
-                        let norm_amp = amp.sqrt();
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::ShowTab(tab) => self.select_tab(tab),
+            Action::StartRecording => self.start_recording(),
+            Action::StopRecording => self.stop_recording(),
+            Action::DeviceCheck => {
+                self.start_device_check();
+                self.select_tab(Tab::Live);
+            }
+            Action::ToggleMonitor => self.set_monitoring(self.monitor.is_none()),
+            Action::ToggleGauge => self.gauge_window.open = !self.gauge_window.open,
+            Action::TogglePitchGuard => self.pitch_guard.enabled = !self.pitch_guard.enabled,
+            Action::TogglePitchInTitle => self.pitch_in_title = !self.pitch_in_title,
+            Action::ExportSchemas => {
+                self.export_schemas();
+                self.select_tab(Tab::Settings);
+            }
+            Action::SetupWizard => self.setup_wizard = Some(self.new_setup_wizard()),
+            Action::ToggleRecording if self.is_recording() => self.stop_recording(),
+            Action::ToggleRecording => self.start_recording(),
+            Action::TogglePlots => self.plots_paused = !self.plots_paused,
+            Action::ExportSessions => {
+                self.export_sessions();
+                self.select_tab(Tab::Analytics);
+            }
+            Action::ShowDiagnostics => self.diagnostics.open = true,
+            Action::ShowLog => self.log_window.open = true,
+            Action::AddMarker => self.add_marker(),
+            Action::NudgeTarget { shift_hz, widen_hz } => {
+                self.settings.target.nudge(shift_hz, widen_hz);
+                self.save_settings();
+            }
+        }
+    }
 
-                        let hue = (1.0 - norm_amp) * 0.7;
-                        let color = egui::Color32::from(Hsva::new(hue, 1.0, norm_amp, 1.0));
+    fn shortcuts() -> [Shortcut<Action>; 9] {
+        use egui::Key;
+        let nudge = |shift_hz, widen_hz| Action::NudgeTarget { shift_hz, widen_hz };
+        [
+            Shortcut::key(Key::Space, "Démarrer / arrêter", Action::ToggleRecording),
+            Shortcut::key(Key::P, "Figer / reprendre les graphiques", Action::TogglePlots),
+            Shortcut::key(Key::E, "Exporter les sessions (CSV)", Action::ExportSessions),
+            Shortcut::key(Key::M, "Mini jauge externe", Action::ToggleGauge),
+            Shortcut::key(Key::R, "Poser un repère", Action::AddMarker),
+            Shortcut::key(Key::ArrowUp, "Monter la cible de 5 Hz", nudge(5.0, 0.0)),
+            Shortcut::key(Key::ArrowDown, "Descendre la cible de 5 Hz", nudge(-5.0, 0.0)),
+            Shortcut::key(Key::ArrowRight, "Élargir la cible", nudge(0.0, 5.0)),
+            Shortcut::key(Key::ArrowLeft, "Resserrer la cible", nudge(0.0, -5.0)),
+        ]
+    }
 
-                        let x = rect.left() + (t as f32 / history_len as f32) * rect.width();
-                        let y = rect.bottom() - ((f_idx as f32 / filtered_bins as f32) * rect.height());
+    /// Repère le début d'un bon moment dans la session et sur le tracé.
+    fn mark_good_moment(&mut self, clip: &SavedClip) {
+        let Some(stats) = &mut self.session_stats else {
+            return;
+        };
+        let text = format!(
+            "🌟 Bon moment: {:.1} s à {}",
+            clip.duration_secs,
+            self.settings.pitch_scale.format(clip.median_hz)
+        );
+        let index = stats.mark_earlier(clip.started_secs_ago, &text);
+        let frames_ago = (clip.started_secs_ago / self.frame_duration) as u64;
+        let frame = self.history_frames.saturating_sub(frames_ago);
+        self.moment_markers.push_back((frame, index, text));
+    }
 
-                        let cell_width = (rect.width() / history_len as f32).max(1.0);
-                        let cell_height = (rect.height() / filtered_bins as f32).max(1.0);
+    fn add_marker(&mut self) {
+        let Some(stats) = &mut self.session_stats else {
+            return;
+        };
+        let index = stats.mark();
+        self.moment_markers.push_back((self.history_frames, index, String::new()));
+        self.marker_editor.open(index);
+    }
 
-                        let cell = egui::Rect::from_min_size(
-                            egui::pos2(x, y - cell_height),
-                            egui::vec2(cell_width, cell_height),
-                        );
+    /// Note saisie après coup: la session a pu s'arrêter entre-temps.
+    fn annotate_marker(&mut self, index: usize, text: String) {
+        if let Some((.., label)) = self.moment_markers.iter_mut().find(|m| m.1 == index) {
+            label.clone_from(&text);
+        }
+        if let Some(stats) = &mut self.session_stats {
+            stats.annotate(index, &text);
+        } else if let Some(summary) = self.sessions.last_mut()
+            && let Some(marker) = summary.markers.get_mut(index)
+        {
+            marker.text = text;
+            let summary = summary.clone();
+            self.save_session(&summary);
+        }
+    }
 
-                        painter.rect_filled(cell, 0.0, color);
-                    }
-                }
+    fn reload_restored_data(&mut self) {
+        match Settings::load() {
+            Ok(settings) => self.settings = settings,
+            Err(e) => self.error_message = Some(format!("Lecture des réglages: {}", e)),
+        }
+        self.apply_analysis_config();
+        self.apply_thresholds();
+        self.restart_broadcaster();
+        self.reload_sessions();
+    }
 
-                self.draw_frequency_labels(&painter, rect, min_bin, max_bin, freq_per_bin);
+    fn reload_sessions(&mut self) {
+        if let Some(store) = &self.session_store {
+            match store.load_all() {
+                Ok(sessions) => self.sessions = sessions,
+                Err(e) => self.error_message = Some(format!("Lecture de l'historique: {}", e)),
             }
+        }
+    }
+
+    fn save_session(&mut self, summary: &SessionSummary) {
+        if let Some(store) = &self.session_store
+            && let Err(e) = store.save(summary)
+        {
+            self.error_message = Some(format!("Sauvegarde de la session: {}", e));
+        }
+    }
 
+    fn is_recording(&self) -> bool {
+        self.mode.is_capturing()
+    }
 
-        });
+    /// Seul point de changement de mode: une transition non prévue est refusée.
+    fn set_mode(&mut self, mode: AppMode) -> bool {
+        if !self.mode.can_enter(mode.kind()) {
+            warn!("Transition refusée: {:?} → {:?}", self.mode.kind(), mode.kind());
+            return false;
+        }
+        self.mode = mode;
+        true
+    }
 
-        if self.is_recording {
-            ctx.request_repaint();
+    fn exercise_of(tab: Tab) -> Option<Exercise> {
+        match tab {
+            Tab::Vowels => Some(Exercise::Vowels),
+            Tab::Prosody => Some(Exercise::Prosody),
+            Tab::Passage => Some(Exercise::Passage),
+            Tab::Reading => Some(Exercise::Reading),
+            Tab::Reference => Some(Exercise::Reference),
+            Tab::Rhythm => Some(Exercise::Rhythm),
+            Tab::Sustain => Some(Exercise::Sustain),
+            Tab::Range => Some(Exercise::Range),
+            Tab::Ladder => Some(Exercise::Ladder),
+            Tab::Karaoke => Some(Exercise::Karaoke),
+            Tab::Scripts => Some(Exercise::Script),
+            Tab::Live
+            | Tab::Harmonics
+            | Tab::Snapshots
+            | Tab::Takes
+            | Tab::Moments
+            | Tab::Tuning
+            | Tab::Analytics
+            | Tab::Settings => None,
+        }
+    }
+
+    /// L'exercice de l'onglet central, à défaut le premier exercice ancré.
+    fn active_exercise(&self) -> Option<Exercise> {
+        Self::exercise_of(self.tab).or_else(|| {
+            let docked = &self.settings.layout.docked;
+            docked.iter().find_map(|docked| Self::exercise_of(docked.tab))
+        })
+    }
+
+    fn capture_mode(&self) -> AppMode {
+        self.active_exercise().map_or(AppMode::Monitoring, AppMode::Exercise)
+    }
+
+    /// Pendant l'enregistrement, le mode suit l'onglet d'exercice ouvert.
+    fn sync_capture_mode(&mut self) {
+        let current = match self.mode {
+            AppMode::Monitoring => None,
+            AppMode::Exercise(exercise) => Some(exercise),
+            _ => return,
+        };
+        if current != self.active_exercise() {
+            let mode = self.capture_mode();
+            self.set_mode(mode);
+        }
+    }
+
+    fn start_recording(&mut self) {
+        if !self.mode.can_enter(ModeKind::Monitoring) {
+            return;
+        }
+        match AudioProcessor::new(
+            self.input_device.as_deref(),
+            self.frequency_data.clone(),
+            self.vad_config.clone(),
+            self.monitor_tap.clone(),
+            self.audio_tap.clone(),
+            self.input_channels.clone(),
+            self.analysis_config.clone(),
+        ) {
+            Ok(processor) => {
+                self.audio_processor = Some(processor);
+                self.second_stream.start(&self.vad_config, &self.analysis_config);
+                let mode = self.capture_mode();
+                self.set_mode(mode);
+                self.error_message = None;
+                self.last_frame_at = Instant::now();
+                // Une séance reprise garde son fichier audio tel quel
+                let (stats, resumed) = match self.resumed_session.take() {
+                    Some(stats) => (stats, true),
+                    None => (SessionStats::new(), false),
+                };
+                self.moment_markers.clear();
+                self.marker_editor = MarkerEditor::default();
+                if self.settings.keep_session_audio && !resumed {
+                    self.start_session_audio(stats.started_at());
+                }
+                self.session_stats = Some(stats);
+                self.pitch_guard.reset();
+                self.floor_cue.reset();
+                self.strain_monitor.reset();
+                self.target_beep.reset();
+                self.current_weight = None;
+                self.input_health.reset();
+                self.silence_watch.reset();
+                self.perception.reset();
+                self.play_cue(CueCategory::Start);
+                info!("Enregistrement démarré");
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Erreur audio: {}", e));
+                error!("Erreur lors du démarrage: {}", e);
+            }
         }
     }
+
+    fn start_device_check(&mut self) {
+        if !self.mode.can_enter(ModeKind::Calibrating) {
+            return;
+        }
+        match DeviceCheck::start() {
+            Ok(check) => {
+                self.set_mode(AppMode::Calibrating(check));
+                self.device_check_report = None;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Test du périphérique: {}", e));
+            }
+        }
+    }
+
+    fn poll_device_check(&mut self) {
+        if let AppMode::Calibrating(check) = &self.mode
+            && check.is_finished()
+            && let AppMode::Calibrating(check) = std::mem::replace(&mut self.mode, AppMode::Idle)
+        {
+            self.device_check_report = Some(check.report());
+        }
+    }
+
+    fn poll_tray(&mut self, ctx: &egui::Context) {
+        if !self.settings.tray.enabled {
+            self.tray.stop();
+            if self.hidden_to_tray {
+                self.show_window(ctx);
+            }
+            return;
+        }
+        self.tray.start(ctx);
+
+        let tooltip = if !self.is_recording() {
+            APP_TITLE.to_string()
+        } else if self.is_voiced && self.current_frequency > 0.0 {
+            let pitch = self.settings.pitch_scale.format(self.current_frequency);
+            format!("{} — {}", pitch, APP_TITLE)
+        } else {
+            format!("… — {}", APP_TITLE)
+        };
+        self.tray.update(TrayState {
+            recording: self.is_recording(),
+            gauge_open: self.gauge_window.open,
+            tooltip,
+        });
+        while let Some(command) = self.tray.poll() {
+            match command {
+                TrayCommand::ToggleRecording => self.run_action(Action::ToggleRecording),
+                TrayCommand::ToggleGauge => self.run_action(Action::ToggleGauge),
+                TrayCommand::Show => self.show_window(ctx),
+                TrayCommand::Quit => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            }
+        }
+
+        if self.settings.tray.minimize_to_tray
+            && self.tray.is_running()
+            && !self.hidden_to_tray
+            && ctx.input(|i| i.viewport().minimized) == Some(true)
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+            self.hidden_to_tray = true;
+        }
+        if self.hidden_to_tray {
+            // Infobulle à jour et menu réactif malgré la fenêtre cachée
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    fn show_window(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        self.hidden_to_tray = false;
+    }
+
+    fn update_window_title(&mut self, ctx: &egui::Context) {
+        if self.is_recording() && self.last_title_update.elapsed() < TITLE_REFRESH {
+            return;
+        }
+        self.last_title_update = Instant::now();
+
+        let title = if self.pitch_in_title && self.is_recording() {
+            if self.current_frequency > 0.0 && self.is_voiced {
+                let status = if self.current_frequency < self.settings.target.min_hz {
+                    "↓"
+                } else if self.current_frequency > self.settings.target.max_hz {
+                    "↑"
+                } else {
+                    "✓"
+                };
+                format!(
+                    "{} {} — {}",
+                    self.settings.pitch_scale.format(self.current_frequency),
+                    status,
+                    APP_TITLE
+                )
+            } else {
+                format!("… — {}", APP_TITLE)
+            }
+        } else {
+            APP_TITLE.to_string()
+        };
+
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    /// Valeur mesurée, en gros caractères si l'affichage agrandi est activé.
+    fn readout(&self, text: impl Into<String>) -> egui::RichText {
+        let text = egui::RichText::new(text);
+        if self.settings.accessibility.large_readout {
+            text.size(LARGE_READOUT_SIZE)
+        } else {
+            text
+        }
+    }
+
+    /// Résumé du graphique de hauteur pour les lecteurs d'écran.
+    fn pitch_summary(&self) -> String {
+        let scale = self.settings.pitch_scale;
+        let target = self.settings.target;
+        let range = format!("{} – {}", scale.format(target.min_hz), scale.format(target.max_hz));
+        if self.current_frequency <= 0.0 {
+            return format!("Graphique de hauteur, cible {}: aucune voix", range);
+        }
+        let position = if self.current_frequency < target.min_hz {
+            "sous la cible"
+        } else if self.current_frequency > target.max_hz {
+            "au-dessus de la cible"
+        } else {
+            "dans la cible"
+        };
+        format!(
+            "Graphique de hauteur, cible {}: {}, {}",
+            range,
+            scale.format(self.current_frequency),
+            position
+        )
+    }
+
+    fn play_cue(&mut self, category: CueCategory) {
+        if let Err(e) = self.cue_player.play(category) {
+            self.error_message = Some(format!("Signal sonore: {}", e));
+        }
+    }
+
+    fn play_ladder_events(&mut self) {
+        while let Some(event) = self.ladder.next_event() {
+            let result = match event {
+                LadderEvent::Tone(frequency) => self.cue_player.play_note(
+                    CueCategory::Reference,
+                    frequency,
+                    ladder::TONE_SECS,
+                ),
+                LadderEvent::Cue(category) => self.cue_player.play(category),
+            };
+            if let Err(e) = result {
+                self.error_message = Some(format!("Signal sonore: {}", e));
+            }
+        }
+    }
+
+    fn play_floor_cue(&mut self, frequency: f32) {
+        let result = if self.settings.floor_cue.pitch_tone {
+            self.cue_player.play_pitch(CueCategory::BelowFloor, frequency)
+        } else {
+            self.cue_player.play(CueCategory::BelowFloor)
+        };
+        if let Err(e) = result {
+            self.error_message = Some(format!("Signal sonore: {}", e));
+        }
+    }
+
+    fn switch_input_device(&mut self, device: Option<String>) {
+        self.input_device = device;
+        self.pending_switch = None;
+        if !self.is_recording() {
+            return;
+        }
+
+        let frequency_data = Arc::new(Mutex::new(None));
+        let monitor_tap = MonitorTap::default();
+        let audio_tap = AudioTap::default();
+        match AudioProcessor::new(
+            self.input_device.as_deref(),
+            frequency_data.clone(),
+            self.vad_config.clone(),
+            monitor_tap.clone(),
+            audio_tap.clone(),
+            self.input_channels.clone(),
+            self.analysis_config.clone(),
+        ) {
+            Ok(processor) => {
+                self.pending_switch = Some(PendingDeviceSwitch {
+                    processor,
+                    frequency_data,
+                    monitor_tap,
+                    audio_tap,
+                    label: self.input_device_label().to_string(),
+                    requested_at: Instant::now(),
+                });
+            }
+            Err(e) => self.error_message = Some(format!("Changement de périphérique: {}", e)),
+        }
+    }
+
+    fn poll_device_switch(&mut self) {
+        let Some(pending) = &self.pending_switch else {
+            return;
+        };
+
+        let ready = pending
+            .frequency_data
+            .try_lock()
+            .is_ok_and(|data| data.is_some());
+        if !ready {
+            if pending.requested_at.elapsed() >= DEVICE_SWITCH_TIMEOUT {
+                self.pending_switch = None;
+                self.error_message =
+                    Some("Le nouveau périphérique ne fournit aucun signal".to_string());
+            }
+            return;
+        }
+
+        let Some(pending) = self.pending_switch.take() else {
+            return;
+        };
+        // L'ancien flux n'est fermé qu'une fois le nouveau opérationnel
+        self.frequency_data = pending.frequency_data;
+        self.monitor_tap = pending.monitor_tap;
+        self.audio_processor = Some(pending.processor);
+        self.flush_session_audio();
+        self.audio_tap = pending.audio_tap;
+        if self.audio_writer.is_some() {
+            self.audio_tap.open();
+        }
+
+        if self.monitor.is_some() {
+            self.set_monitoring(true);
+        }
+        if let Some(stats) = &mut self.session_stats {
+            stats.mark_device_change(&pending.label);
+        }
+        self.device_markers.push_back((self.history_frames, pending.label.clone()));
+        info!("Périphérique d'entrée changé: {}", pending.label);
+    }
+
+    /// Sans périphérique choisi, suit celui par défaut du système: brancher
+    /// un casque bascule l'enregistrement sans l'interrompre.
+    fn poll_default_input(&mut self) {
+        let follows_default = self.is_recording() && self.input_device.is_none();
+        if !follows_default || self.reconnect.is_some() {
+            self.default_input_watch.reset();
+            return;
+        }
+        if self.pending_switch.is_some() {
+            return;
+        }
+        let Some(name) = self.default_input_watch.poll() else {
+            return;
+        };
+        self.switch_input_device(None);
+        if let Some(pending) = &mut self.pending_switch {
+            pending.label = format!("Par défaut: {}", name);
+        }
+    }
+
+    fn input_device_label(&self) -> &str {
+        self.input_device.as_deref().unwrap_or("Par défaut")
+    }
+
+    fn show_audio_host_settings(&mut self, ui: &mut egui::Ui) {
+        let mut selected = self.settings.audio_host.clone();
+        let default_label = format!("Système ({})", audio_host::default_name());
+        ui.add_enabled_ui(!self.is_recording(), |ui| {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("audio_host")
+                    .selected_text(selected.clone().unwrap_or(default_label.clone()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut selected, None, default_label);
+                        for name in audio_host::available() {
+                            ui.selectable_value(&mut selected, Some(name.to_string()), name);
+                        }
+                    });
+                ui.label("ℹ").on_hover_text(
+                    "JACK (Linux) et ASIO (Windows) demandent les fonctions Cargo « jack » et \
+                     « asio »; un pilote indisponible laisse place à celui du système. Le mode \
+                     exclusif WASAPI n'est pas pris en charge.",
+                );
+            });
+        });
+        if self.is_recording() {
+            ui.weak("Arrêtez l'enregistrement pour changer de pilote.");
+        }
+        if selected != self.settings.audio_host {
+            // Les noms de périphériques changent d'un pilote à l'autre
+            self.settings.audio_host = selected;
+            self.settings.input_device = None;
+            self.input_device = None;
+            self.error_message = None;
+            self.apply_audio_host();
+            self.save_settings();
+        }
+    }
+
+    fn show_input_device_picker(&mut self, ui: &mut egui::Ui) {
+        let mut selected = self.input_device.clone();
+        egui::ComboBox::from_id_salt("input_device")
+            .selected_text(self.input_device_label())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, "Par défaut").on_hover_text(
+                    "Suit le périphérique par défaut du système, même pendant l'enregistrement",
+                );
+                match AudioProcessor::input_device_names() {
+                    Ok(names) => {
+                        for name in names {
+                            ui.selectable_value(&mut selected, Some(name.clone()), name);
+                        }
+                    }
+                    Err(e) => {
+                        ui.label(format!("Périphériques indisponibles: {}", e));
+                    }
+                }
+            });
+
+        if selected != self.input_device {
+            self.settings.input_device = selected.clone();
+            self.save_settings();
+            self.switch_input_device(selected);
+        }
+        if self.pending_switch.is_some() {
+            ui.spinner();
+        }
+    }
+
+    fn show_channel_selector(&mut self, ui: &mut egui::Ui) {
+        let Ok(mut channels) = self.input_channels.lock() else {
+            return;
+        };
+        if !self.is_recording() || channels.levels.len() < 2 {
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Canal analysé:");
+            let mut selected = channels.selected;
+            ui.selectable_value(&mut selected, None, "Moyenne");
+
+            for (channel, &level) in channels.levels.iter().enumerate() {
+                ui.vertical(|ui| {
+                    ui.selectable_value(&mut selected, Some(channel), format!("{}", channel + 1));
+                    let db = 20.0 * level.max(1e-6).log10();
+                    let fill = ((db + 60.0) / 60.0).clamp(0.0, 1.0);
+                    ui.add(
+                        egui::ProgressBar::new(fill)
+                            .desired_width(36.0)
+                            .desired_height(6.0)
+                            .fill(if fill > 0.95 {
+                                egui::Color32::RED
+                            } else {
+                                egui::Color32::GREEN
+                            }),
+                    )
+                    .on_hover_text(format!("{:.0} dBFS", db));
+                });
+            }
+            channels.selected = selected;
+        });
+    }
+
+    fn poll_input_meter(&mut self) {
+        if !self.is_recording() {
+            return;
+        }
+        let meter = match self.input_channels.try_lock() {
+            Ok(mut channels) => std::mem::take(&mut channels.meter),
+            Err(_) => return,
+        };
+        self.input_health.update(meter);
+    }
+
+    fn set_monitoring(&mut self, enabled: bool) {
+        if !enabled {
+            self.monitor = None;
+            return;
+        }
+
+        let Some(processor) = &self.audio_processor else {
+            return;
+        };
+        match Monitor::start(
+            self.monitor_tap.clone(),
+            processor.sample_rate(),
+            self.monitor_config.clone(),
+        ) {
+            Ok(monitor) => self.monitor = Some(monitor),
+            Err(e) => self.error_message = Some(format!("Retour casque: {}", e)),
+        }
+    }
+
+    fn show_monitor_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut monitoring = self.monitor.is_some();
+            if ui
+                .add_enabled(
+                    self.is_recording(),
+                    egui::Checkbox::new(&mut monitoring, "🎧 Retour casque décalé"),
+                )
+                .changed()
+            {
+                self.set_monitoring(monitoring);
+            }
+
+            if let Ok(mut config) = self.monitor_config.lock() {
+                ui.add(
+                    egui::Slider::new(&mut config.semitones, -12.0..=12.0)
+                        .step_by(0.5)
+                        .text("demi-tons"),
+                );
+                if ui
+                    .button("Auto")
+                    .on_hover_text("Décale la médiane récente vers le centre de la cible")
+                    .clicked()
+                {
+                    let mut voiced: Vec<f32> = self
+                        .history
+                        .iter()
+                        .map(|frame| frame.frequency)
+                        .filter(|&f| f > 0.0)
+                        .collect();
+                    if !voiced.is_empty() {
+                        voiced.sort_by(|a, b| a.total_cmp(b));
+                        let median = voiced[voiced.len() / 2];
+                        let target = self.settings.target.center();
+                        config.semitones =
+                            (12.0 * (target / median).log2()).clamp(-12.0, 12.0);
+                    }
+                }
+                ui.add(egui::Slider::new(&mut config.volume, 0.0..=1.0).text("Volume"));
+
+                egui::ComboBox::from_id_salt("listening_context")
+                    .selected_text(config.context.label())
+                    .show_ui(ui, |ui| {
+                        for context in ListeningContext::ALL {
+                            ui.selectable_value(&mut config.context, context, context.label())
+                                .on_hover_text(context.description());
+                        }
+                    })
+                    .response
+                    .on_hover_text("Écouter sa voix telle qu'elle passerait dans cette situation");
+            }
+
+            if self.monitor.is_some() {
+                ui.colored_label(egui::Color32::YELLOW, "⚠ Utilisez un casque (larsen)");
+            }
+        });
+    }
+
+    fn watch_stream(&mut self) {
+        if !self.is_recording() || self.pending_switch.is_some() {
+            return;
+        }
+
+        if let Some(reconnect) = &self.reconnect {
+            if reconnect.is_due() {
+                self.try_reconnect();
+            }
+            return;
+        }
+
+        let reason = match &self.audio_processor {
+            Some(processor) => processor.take_error().or_else(|| {
+                (self.last_frame_at.elapsed() >= STALE_STREAM_TIMEOUT)
+                    .then(|| "plus aucun signal reçu".to_string())
+            }),
+            None => Some("flux absent".to_string()),
+        };
+        let Some(reason) = reason else {
+            return;
+        };
+
+        warn!("Flux audio interrompu: {}", reason);
+        self.audio_processor = None;
+        self.is_voiced = false;
+        self.current_frequency = 0.0;
+        self.utterance_tracker.finish_utterance(self.frame_duration);
+        self.error_message = Some(format!("Flux audio interrompu: {}", reason));
+        self.reconnect = Some(Reconnect::new(reason));
+        self.history_gap_since = Some(self.last_frame_at);
+    }
+
+    fn try_reconnect(&mut self) {
+        // Le périphérique choisi d'abord, puis celui par défaut du système
+        let mut candidates = vec![self.input_device.clone()];
+        if self.input_device.is_some() {
+            candidates.push(None);
+        }
+
+        for device in candidates {
+            let opened = AudioProcessor::new(
+                device.as_deref(),
+                self.frequency_data.clone(),
+                self.vad_config.clone(),
+                self.monitor_tap.clone(),
+                self.audio_tap.clone(),
+                self.input_channels.clone(),
+                self.analysis_config.clone(),
+            );
+            let Ok(processor) = opened else {
+                continue;
+            };
+
+            let label = device.as_deref().unwrap_or("Par défaut").to_string();
+            info!("Flux audio rétabli: {}", label);
+            self.audio_processor = Some(processor);
+            self.reconnect = None;
+            self.error_message = None;
+            self.last_frame_at = Instant::now();
+            if let Some(since) = self.history_gap_since.take() {
+                self.fill_history_gap(since);
+            }
+            if self.monitor.is_some() {
+                self.set_monitoring(true);
+            }
+            if let Some(stats) = &mut self.session_stats {
+                stats.mark_device_change(&label);
+            }
+            self.device_markers.push_back((self.history_frames, label));
+            return;
+        }
+
+        if let Some(reconnect) = &mut self.reconnect {
+            reconnect.schedule_next();
+        }
+    }
+
+    fn stop_recording(&mut self) {
+        self.reconnect = None;
+        self.history_gap_since = None;
+        self.pending_switch = None;
+        self.monitor = None;
+        self.audio_processor = None;
+        self.second_stream.stop();
+        self.midi_out.stop();
+        self.set_mode(AppMode::Idle);
+        self.utterance_tracker.finish_utterance(self.frame_duration);
+        self.finish_session_audio();
+        let session = self.session_stats.as_ref().map(SessionStats::started_at);
+        if let Some(clip) = self.good_moments.finish(&self.settings.good_moments, session) {
+            self.mark_good_moment(&clip);
+        }
+        info!("Enregistrement arrêté");
+
+        let day_secs = self.goal_day_secs();
+        if let Some(stats) = self.session_stats.take() {
+            if stats.voiced_frames() > 0 {
+                let mut summary = stats.finish();
+                if self.settings.goal.enabled {
+                    summary.goal = Some(self.settings.goal.record(day_secs));
+                }
+                self.save_session(&summary);
+                self.announce_session_end(&summary);
+                self.sessions.push(summary);
+            } else {
+                // Session non enregistrée: ni son audio ni ses repères n'ont
+                // plus de raison d'être
+                self.marker_editor = MarkerEditor::default();
+                if let Some(store) = &self.session_store {
+                    let _ = std::fs::remove_file(store.audio_path(stats.started_at()));
+                }
+            }
+        }
+        self.autosave.clear();
+    }
+
+    fn handle_recovery(&mut self, action: RecoveryAction) {
+        match action {
+            RecoveryAction::Save(stats) => {
+                let summary = stats.finish();
+                self.save_session(&summary);
+                let index = self
+                    .sessions
+                    .partition_point(|s| s.started_at <= summary.started_at);
+                self.sessions.insert(index, summary);
+                self.autosave.clear();
+            }
+            RecoveryAction::Resume(stats) => {
+                // Le fichier de la séance interrompue reste en place jusqu'à la
+                // première sauvegarde de la reprise
+                if self.is_recording() {
+                    self.stop_recording();
+                }
+                self.resumed_session = Some(stats);
+                self.start_recording();
+                match self.resumed_session.take() {
+                    Some(stats) => {
+                        let reason = self
+                            .error_message
+                            .take()
+                            .unwrap_or_else(|| "enregistrement impossible".to_string());
+                        self.error_message = Some(format!(
+                            "Reprise de la séance interrompue: {}; elle reste proposée",
+                            reason
+                        ));
+                        self.autosave.offer_again(stats);
+                    }
+                    None => self.autosave.resumed(),
+                }
+            }
+            RecoveryAction::Discard => self.autosave.clear(),
+        }
+    }
+
+    /// Bilan et records de la séance qui vient de se terminer, avant son
+    /// ajout à l'historique.
+    fn announce_session_end(&mut self, summary: &SessionSummary) {
+        let records = session_end::personal_bests(summary, &self.sessions);
+        let end = self.settings.session_end;
+        if end.notify_records
+            && !records.is_empty()
+            && let Err(e) = goal::notify_desktop(
+                &format!("{}: record personnel", APP_TITLE),
+                &records.join("\n"),
+            )
+        {
+            warn!("Notification: {}", e);
+        }
+        if end.show_summary {
+            let previous = self.sessions.last().cloned();
+            self.session_end.open(summary.clone(), previous, records);
+        }
+    }
+
+    /// Temps compté pour l'objectif aujourd'hui, session en cours comprise.
+    fn goal_day_secs(&self) -> f32 {
+        let goal = &self.settings.goal;
+        let current = self
+            .session_stats
+            .as_ref()
+            .map(|stats| goal.counted_secs(stats.voiced_secs(), stats.in_range_secs()))
+            .unwrap_or(0.0);
+        goal.today_secs(&self.sessions) + current
+    }
+
+    fn poll_silence(&mut self) {
+        if self.is_recording() && self.silence_watch.expired(&self.settings.auto_capture) {
+            self.stop_recording();
+        }
+    }
+
+    fn poll_goal(&mut self) {
+        if !self.is_recording() || !self.settings.goal.enabled {
+            return;
+        }
+        if !self.goal_tracker.check(&self.settings.goal, self.goal_day_secs()) {
+            return;
+        }
+
+        self.play_cue(CueCategory::Success);
+        if self.settings.goal.notify
+            && let Err(e) = goal::notify_desktop(
+                APP_TITLE,
+                &format!("Objectif du jour atteint: {:.0} min", self.settings.goal.minutes),
+            )
+        {
+            warn!("Notification: {}", e);
+        }
+    }
+
+    fn start_session_audio(&mut self, started_at: u64) {
+        let (Some(store), Some(processor)) = (&self.session_store, &self.audio_processor) else {
+            return;
+        };
+        match SessionAudioWriter::create(&store.audio_path(started_at), processor.sample_rate()) {
+            Ok(writer) => {
+                self.audio_writer = Some(writer);
+                self.audio_tap.open();
+            }
+            Err(e) => self.error_message = Some(format!("Enregistrement audio: {}", e)),
+        }
+    }
+
+    fn flush_session_audio(&mut self) {
+        let sample_rate = self.audio_processor.as_ref().map(AudioProcessor::sample_rate);
+        if !self.reference.is_capturing()
+            && !self.sustain.is_capturing()
+            && !self.good_moments.is_listening(&self.settings.good_moments)
+            && self.audio_writer.is_none()
+        {
+            self.audio_tap.close();
+            return;
+        }
+
+        // Un fichier WAV n'a qu'une fréquence: on s'arrête si le périphérique en change
+        if let Some(writer) = &self.audio_writer
+            && sample_rate.is_some_and(|rate| rate != writer.sample_rate())
+        {
+            self.audio_tap.close();
+            self.finish_session_audio();
+            self.error_message = Some(
+                "Fréquence d'échantillonnage modifiée: fin de l'enregistrement audio".to_string(),
+            );
+            return;
+        }
+
+        let Some(samples) = self.audio_tap.drain() else {
+            return;
+        };
+        if let Some(rate) = sample_rate {
+            self.reference.push_samples(&samples, rate);
+            self.sustain.push_samples(&samples, rate);
+            if self.good_moments.is_listening(&self.settings.good_moments) {
+                self.good_moments.push_samples(&samples, rate);
+            }
+        }
+        if let Some(writer) = &mut self.audio_writer
+            && let Err(e) = writer.write(&samples)
+        {
+            self.error_message = Some(format!("Enregistrement audio: {}", e));
+            self.finish_session_audio();
+        }
+    }
+
+    fn finish_session_audio(&mut self) {
+        if let Some(samples) = self.audio_tap.close()
+            && let Some(writer) = &mut self.audio_writer
+            && let Err(e) = writer.write(&samples)
+        {
+            self.error_message = Some(format!("Enregistrement audio: {}", e));
+        }
+        if let Some(writer) = self.audio_writer.take()
+            && let Err(e) = writer.finish()
+        {
+            self.error_message = Some(format!("Enregistrement audio: {}", e));
+        }
+    }
+
+    fn reanalysis_params(&self) -> ReanalysisParams {
+        ReanalysisParams {
+            analysis: self.settings.analysis,
+            vad: self.vad_config.lock().map(|config| *config).unwrap_or_default(),
+            accept_min_hz: self.accept_min_hz,
+            accept_max_hz: self.accept_max_hz,
+            min_confidence: self.min_confidence,
+            target_min_hz: self.settings.target.min_hz,
+            target_max_hz: self.settings.target.max_hz,
+        }
+    }
+
+    fn export_report(&mut self, index: usize) {
+        let summary = self.sessions[index].clone();
+        let audio = self
+            .session_store
+            .as_ref()
+            .map(|store| store.audio_path(summary.started_at));
+        let options = ReportOptions {
+            scale: self.settings.pitch_scale,
+            level: self.settings.level.clone(),
+            target: self.settings.target,
+        };
+        self.reports.start(summary, audio, self.reanalysis_params(), options);
+    }
+
+    fn start_reanalysis(&mut self, index: usize) {
+        if !self.mode.can_enter(ModeKind::Reviewing) {
+            return;
+        }
+        let Some(store) = &self.session_store else {
+            return;
+        };
+        let started_at = self.sessions[index].started_at;
+        let path = store.audio_path(started_at);
+        let params = self.reanalysis_params();
+        self.set_mode(AppMode::Reviewing(std::thread::spawn(move || {
+            session_audio::reanalyze(&path, &params).map(|result| (started_at, result))
+        })));
+    }
+
+    fn poll_reanalysis(&mut self) {
+        if !matches!(&self.mode, AppMode::Reviewing(handle) if handle.is_finished()) {
+            return;
+        }
+        let AppMode::Reviewing(handle) = std::mem::replace(&mut self.mode, AppMode::Idle) else {
+            return;
+        };
+
+        match handle.join() {
+            Ok(Ok((started_at, result))) => {
+                if let Some(session) =
+                    self.sessions.iter_mut().find(|session| session.started_at == started_at)
+                {
+                    session.reanalyses.push(result);
+                    let summary = session.clone();
+                    self.save_session(&summary);
+                }
+            }
+            Ok(Err(e)) => self.error_message = Some(format!("Réanalyse: {}", e)),
+            Err(_) => self.error_message = Some("Réanalyse interrompue".to_string()),
+        }
+    }
+
+    /// Relevé du flux pour la fenêtre de diagnostic, seulement quand elle est ouverte.
+    fn stream_info(&self) -> Option<StreamInfo> {
+        if !self.diagnostics.open {
+            return None;
+        }
+        let processor = self.audio_processor.as_ref()?;
+        let load = processor.load().lock().ok()?.clone();
+        Some(StreamInfo {
+            load,
+            device_rate: processor.sample_rate(),
+            analysis_rate: self.sample_rate,
+            window_secs: self.settings.analysis.window_size as f32 / self.sample_rate.max(1.0),
+        })
+    }
+
+    /// Écart entre la lecture d'une trame et la voix qu'elle décrit: tampon
+    /// d'entrée et demi-fenêtre d'analyse.
+    fn capture_delay(&self) -> Duration {
+        let input = self
+            .audio_processor
+            .as_ref()
+            .and_then(|processor| processor.load().lock().ok()?.input_latency)
+            .unwrap_or_default();
+        let window_secs = self.settings.analysis.window_size as f32 / self.sample_rate.max(1.0);
+        input + Duration::from_secs_f32(window_secs / 2.0)
+    }
+
+    fn degradation(&self) -> Degradation {
+        self.audio_processor
+            .as_ref()
+            .and_then(|processor| processor.load().lock().ok().map(|load| load.level()))
+            .unwrap_or_default()
+    }
+
+    fn update_frequency_data(&mut self) -> bool {
+        let data = match self.frequency_data.try_lock() {
+            Ok(mut data_guard) => data_guard.take(),
+            Err(_) => None,
+        };
+        let Some(data) = data else {
+            return false;
+        };
+
+        self.last_frame_at = Instant::now();
+        self.diagnostics.push_frame(data.captured_at);
+        self.sample_rate = data.sample_rate;
+        self.is_voiced = data.is_voiced;
+        self.current_flatness = data.spectral_flatness;
+        self.current_detector = data.detector;
+        self.broadcast_frame(&data);
+        self.metronome.push_frame(data.captured_at, data.amplitude, data.is_voiced);
+        self.threshold_tuner.push(&data);
+        let frame_duration = data.frame_duration;
+        let target = self.settings.target;
+        self.frame_duration = frame_duration;
+        self.level_calibrator.push_frame(data.amplitude, frame_duration);
+        if !self.plots_paused && self.degradation() < Degradation::NoSpectrogram {
+            let accepted = data.is_voiced
+                && (self.accept_min_hz..=self.accept_max_hz).contains(&data.dominant_frequency);
+            let f0 = if accepted { data.dominant_frequency } else { 0.0 };
+            self.waterfall.push(&data, f0, data.confidence >= self.min_confidence);
+        }
+        if self.spectrum_snapshots.is_capturing() {
+            let accepted = data.is_voiced
+                && data.confidence >= self.min_confidence
+                && (self.accept_min_hz..=self.accept_max_hz).contains(&data.dominant_frequency);
+            let f0 = if accepted { data.dominant_frequency } else { 0.0 };
+            self.spectrum_snapshots.push_frame(&data, f0);
+        }
+        if !data.is_voiced {
+            self.floor_cue
+                .push(&self.settings.floor_cue, 0.0, target.min_hz, frame_duration);
+            self.utterance_tracker.push_frame(None, frame_duration);
+            self.passage.push_frame(0.0, data.amplitude, frame_duration);
+            self.range_test.push_frame(0.0, frame_duration);
+            if self.karaoke.is_running() {
+                let delay = self.capture_delay();
+                self.karaoke.push_frame(data.captured_at, delay, 0.0);
+            }
+            self.takes.push_frame(0.0, data.amplitude, frame_duration);
+            self.ladder.push_frame(0.0, frame_duration);
+            self.push_script_frame(&data, 0.0);
+            if let Some(wizard) = &mut self.setup_wizard {
+                wizard.push_frame(data.amplitude, 0.0, frame_duration);
+            }
+            // Repos: le compteur de forçage redescend
+            self.strain_monitor
+                .push(&self.settings.strain, 0.0, data.amplitude, frame_duration);
+            // Le silence compte comme une coupure: il termine le passage
+            let session = self.session_stats.as_ref().map(SessionStats::started_at);
+            if let Some(clip) = self.good_moments.push_frame(
+                &self.settings.good_moments,
+                0.0,
+                false,
+                frame_duration,
+                session,
+            ) {
+                self.mark_good_moment(&clip);
+            }
+            self.reading
+                .push_frame(0.0, frame_duration, target.min_hz, target.max_hz);
+            self.midi_out.update(&self.settings.midi, None);
+            self.drill_sequencer.push_frame(data.captured_at, 0.0, frame_duration);
+            // Le silence reste tracé: c'est lui qui sépare les énoncés
+            if !self.plots_paused {
+                self.push_history(data, 0.0);
+            }
+            return false;
+        }
+
+        let filtered_frequency = if (self.accept_min_hz..=self.accept_max_hz)
+            .contains(&data.dominant_frequency)
+        {
+            data.dominant_frequency
+        } else {
+            0.0
+        };
+
+        // Trame peu fiable: tracée en pâle, ignorée partout ailleurs
+        let reliable = data.confidence >= self.min_confidence;
+        let frequency = if reliable { filtered_frequency } else { 0.0 };
+
+        self.current_frequency = frequency;
+        self.current_amplitude = data.amplitude;
+        self.current_loudness = data.loudness_lufs;
+        self.utterance_tracker.push_frame(Some(frequency), frame_duration);
+        self.passage.push_frame(frequency, data.amplitude, frame_duration);
+        self.range_test.push_frame(frequency, frame_duration);
+        if self.karaoke.is_running() {
+            let delay = self.capture_delay();
+            self.karaoke.push_frame(data.captured_at, delay, frequency);
+        }
+        self.takes.push_frame(frequency, data.amplitude, frame_duration);
+        self.ladder.push_frame(frequency, frame_duration);
+        self.push_script_frame(&data, frequency);
+        if let Some(wizard) = &mut self.setup_wizard {
+            wizard.push_frame(data.amplitude, frequency, frame_duration);
+        }
+        let session = self.session_stats.as_ref().map(SessionStats::started_at);
+        if let Some(clip) = self.good_moments.push_frame(
+            &self.settings.good_moments,
+            frequency,
+            target.contains(frequency),
+            frame_duration,
+            session,
+        ) {
+            self.mark_good_moment(&clip);
+        }
+        self.reading
+            .push_frame(frequency, frame_duration, target.min_hz, target.max_hz);
+        self.midi_out.update(&self.settings.midi, Some(frequency));
+        self.drill_sequencer.push_frame(data.captured_at, frequency, frame_duration);
+        if self.pitch_guard.push(frequency, frame_duration) {
+            self.play_cue(CueCategory::Nudge);
+        }
+        let floor_cue = &self.settings.floor_cue;
+        let floor_hz = target.min_hz;
+        if let Some(low) = self.floor_cue.push(floor_cue, frequency, floor_hz, frame_duration) {
+            self.play_floor_cue(low);
+        }
+        if let Some(duration) = self.strain_monitor.push(
+            &self.settings.strain,
+            frequency,
+            data.amplitude,
+            frame_duration,
+        ) {
+            self.play_cue(CueCategory::Warning);
+            if let Some(stats) = &mut self.session_stats {
+                stats.mark_strain(duration);
+            }
+        }
+        if self.settings.accessibility.target_beep {
+            let in_range = (frequency > 0.0).then(|| target.contains(frequency));
+            if let Some(cue) = self.target_beep.push(in_range, frame_duration) {
+                self.play_cue(cue);
+            }
+        }
+        self.current_brightness = data.spectral_centroid;
+        if let Some(h1_h2) = data.h1_h2_db {
+            let smoothed = self.current_weight.unwrap_or(h1_h2);
+            self.current_weight = Some(smoothed + WEIGHT_SMOOTHING * (h1_h2 - smoothed));
+        }
+
+        if let Some((f1, f2)) = data.formants {
+            self.vowel_chart.push(f1, f2);
+        }
+
+        if frequency > 0.0 {
+            self.silence_watch.heard(data.captured_at);
+            let frame = AnalysisFrame {
+                frequency,
+                ..AnalysisFrame::from(&data)
+            };
+            self.perception.push(&self.settings.perception, &frame, frame_duration);
+        }
+        if frequency > 0.0
+            && let Some(stats) = &mut self.session_stats
+        {
+            let in_range = self.settings.target.contains(frequency);
+            stats.push(frequency, data.amplitude, frame_duration, in_range);
+            stats.push_brightness(data.spectral_centroid);
+            if let Some(h1_h2) = data.h1_h2_db {
+                stats.push_weight(h1_h2);
+            }
+        }
+
+        if self.plots_paused {
+            return true;
+        }
+
+        self.push_history(data, filtered_frequency);
+        true
+    }
+
+    /// Ajoute la trame au tracé; une hauteur nulle y inscrit un silence,
+    /// spectre compris.
+    fn push_history(&mut self, data: FrequencyData, frequency: f32) {
+        // Premier palier de délestage: le spectrogramme reste figé. L'analyse
+        // n'envoie alors plus de spectre, y compris le temps que l'interface
+        // voie le changement de palier.
+        let spectrogram_live =
+            self.degradation() < Degradation::NoSpectrogram && !data.spectrum.is_empty();
+        let frame = AnalysisFrame::from(&data);
+        if frequency > 0.0 {
+            self.history.push_back(AnalysisFrame { frequency, ..frame });
+            if spectrogram_live {
+                self.spectrum_history.push_back(data.spectrum);
+            }
+        } else {
+            self.history.push_back(frame.silent());
+            if spectrogram_live {
+                let mut silence = data.spectrum;
+                silence.fill(0.0);
+                self.spectrum_history.push_back(silence);
+            }
+        }
+
+        self.advance_history();
+    }
+
+    /// Compte la trame qui vient d'être ajoutée et ramène l'historique à
+    /// sa longueur affichée.
+    fn advance_history(&mut self) {
+        self.history_frames += 1;
+        self.second_stream.push_history(100);
+        self.peer.push_history(100);
+        let oldest_frame = self.history_frames.saturating_sub(100);
+        while self.device_markers.front().is_some_and(|&(frame, _)| frame < oldest_frame) {
+            self.device_markers.pop_front();
+        }
+        while self.moment_markers.front().is_some_and(|&(frame, ..)| frame < oldest_frame) {
+            self.moment_markers.pop_front();
+        }
+
+        if self.history.len() > 100 {
+            self.history.pop_front();
+        }
+        if self.spectrum_history.len() > 100
+            && let Some(oldest) = self.spectrum_history.pop_front()
+            && let Some(processor) = &self.audio_processor
+        {
+            processor.recycle_spectrum(oldest);
+        }
+    }
+
+    /// Remplit de silence le temps passé sans flux, pour que le tracé
+    /// reprenne à la bonne distance au lieu de recoller les deux moments.
+    fn fill_history_gap(&mut self, since: Instant) {
+        if self.plots_paused {
+            return;
+        }
+        let missing = (since.elapsed().as_secs_f32() / self.frame_duration) as usize;
+        let Some(&last) = self.history.back() else {
+            return;
+        };
+        let frame_ms = (self.frame_duration * 1000.0) as u64;
+        let spectrogram_live = self.degradation() < Degradation::NoSpectrogram;
+        // Au-delà de la largeur du tracé, seules les dernières trames comptent
+        let skipped = missing.saturating_sub(100) as u64;
+        for i in 1..=missing.min(100) as u64 {
+            self.history.push_back(AnalysisFrame {
+                timestamp_ms: last.timestamp_ms + (skipped + i) * frame_ms,
+                ..AnalysisFrame::default()
+            });
+            if spectrogram_live && let Some(bins) = self.spectrum_history.back().map(Vec::len) {
+                self.spectrum_history.push_back(vec![0.0; bins]);
+            }
+            self.advance_history();
+        }
+    }
+
+    fn save_plot_image(&mut self, figure: &Figure, name: &str) {
+        if let Err(e) = self.plot_image.save(figure, name) {
+            self.error_message = Some(format!("Export de l'image: {}", e));
+        }
+    }
+
+    /// Historique des fréquences tel qu'affiché, pour l'export en image.
+    fn pitch_figure(&self) -> Figure {
+        let search = self.settings.analysis.search_range();
+        let target = self.settings.target;
+        let scale = self.settings.pitch_scale;
+        let frames = self.history.len();
+        let mut reliable = Vec::with_capacity(frames);
+        let mut unreliable = Vec::new();
+        for (i, frame) in self.history.iter().enumerate() {
+            let point = [i as f32, frame.frequency];
+            if !search.contains(&frame.frequency) {
+                reliable.push([i as f32, f32::NAN]);
+            } else if frame.confidence >= self.min_confidence {
+                reliable.push(point);
+            } else {
+                reliable.push([i as f32, f32::NAN]);
+                unreliable.push(point);
+            }
+        }
+        let palette = self.settings.theme.palette();
+        let pitch_color = palette.pitch;
+        let mut items = vec![
+            Item::Line {
+                points: reliable,
+                color: pitch_color,
+                width: 2.0,
+            },
+            Item::Points {
+                points: unreliable,
+                color: pitch_color.gamma_multiply(0.3),
+                radius: 2.0,
+            },
+        ];
+        for (y, color) in [
+            (target.min_hz, palette.target),
+            (target.max_hz, palette.target),
+            (80.0, palette.low_range),
+            (160.0, palette.low_range),
+        ] {
+            items.push(Item::HLine {
+                y,
+                color,
+                dashed: false,
+            });
+        }
+        let first_frame = self.history_frames - frames as u64;
+        for (frame, _) in &self.device_markers {
+            items.push(Item::VLine {
+                x: frame.saturating_sub(first_frame) as f32,
+                color: egui::Color32::LIGHT_BLUE,
+                dashed: true,
+            });
+        }
+        for (frame, ..) in &self.moment_markers {
+            items.push(Item::VLine {
+                x: frame.saturating_sub(first_frame) as f32,
+                color: MARKER_COLOR,
+                dashed: false,
+            });
+        }
+
+        let (low, high) = (*search.start(), *search.end());
+        Figure {
+            title: "Historique des fréquences".to_string(),
+            x_label: "Temps (échantillons)".to_string(),
+            x_range: (0.0, frames.max(1) as f32),
+            y_range: (low, high),
+            x_ticks: (0..=frames).step_by(20).map(|i| (i as f32, i.to_string())).collect(),
+            y_ticks: (0..=5)
+                .map(|i| {
+                    let hz = low + (high - low) * i as f32 / 5.0;
+                    (hz, scale.format(hz))
+                })
+                .collect(),
+            items,
+        }
+    }
+
+    /// Spectrogramme tel qu'affiché, pour l'export en image.
+    fn spectrogram_figure(&self) -> Option<Figure> {
+        let total_bins = self.spectrum_history.front()?.len();
+        let freq_per_bin = self.sample_rate / (2.0 * total_bins as f32);
+        let search = self.settings.analysis.search_range();
+        let min_bin = (search.start() / freq_per_bin) as usize;
+        let max_bin = (search.end() / freq_per_bin).min(total_bins as f32) as usize;
+        let rows = max_bin.checked_sub(min_bin).filter(|&rows| rows > 0)?;
+        let columns = self.spectrum_history.len();
+
+        let mut pixels = vec![egui::Color32::BLACK; columns * rows];
+        for (t, spectrum) in self.spectrum_history.iter().enumerate() {
+            let Some(bins) = spectrum.get(min_bin..max_bin) else {
+                continue;
+            };
+            for (f_idx, &amp) in bins.iter().enumerate() {
+                // Les aigus en haut de l'image
+                pixels[(rows - 1 - f_idx) * columns + t] = spectrogram_color(amp);
+            }
+        }
+
+        let (low, high) = (min_bin as f32 * freq_per_bin, max_bin as f32 * freq_per_bin);
+        Some(Figure {
+            title: "Spectrogramme".to_string(),
+            x_label: "Temps (échantillons)".to_string(),
+            x_range: (0.0, columns as f32),
+            y_range: (low, high),
+            x_ticks: (0..=columns).step_by(20).map(|i| (i as f32, i.to_string())).collect(),
+            y_ticks: SPECTROGRAM_MARKS
+                .into_iter()
+                .filter(|hz| (low..=high).contains(hz))
+                .map(|hz| (hz, format!("{} Hz", hz)))
+                .collect(),
+            items: vec![Item::Heatmap { columns, rows, pixels }],
+        })
+    }
+
+    fn draw_frequency_labels(&self, painter: &egui::Painter, rect: egui::Rect, min_bin: usize, max_bin: usize, freq_per_bin: f32) {
+        let text_color = egui::Color32::WHITE;
+        let font_id = egui::FontId::monospace(10.0);
+
+        for freq in SPECTROGRAM_MARKS {
+            let bin_index = (freq / freq_per_bin) as usize;
+            if bin_index >= min_bin && bin_index < max_bin {
+                let relative_bin = bin_index - min_bin;
+                let filtered_bins = max_bin - min_bin;
+
+                let y = rect.bottom() - ((relative_bin as f32 / filtered_bins as f32) * rect.height());
+
+                painter.line_segment(
+                    [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+                    egui::Stroke::new(0.5, egui::Color32::from_rgba_premultiplied(255, 255, 255, 80)),
+                );
+
+                painter.text(
+                    egui::pos2(rect.left() + 2.0, y - 6.0),
+                    egui::Align2::LEFT_CENTER,
+                    format!("{}Hz", freq as i32),
+                    font_id.clone(),
+                    text_color,
+                );
+            }
+        }
+    }
+
+    fn show_analytics(&mut self, ui: &mut egui::Ui) {
+        if let Some(error) = &self.error_message {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        if self.sessions.is_empty() {
+            ui.label("Aucune session enregistrée pour l'instant.");
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("📤 Exporter les sessions (CSV)").clicked() {
+                self.export_sessions();
+            }
+            if let Some(path) = &self.sessions_export {
+                ui.small(format!("Écrit dans {}", path.display()));
+            }
+        });
+
+        ui.label("📅 Régularité");
+        calendar::show(ui, &self.sessions);
+
+        ui.separator();
+        self.trend_chart.show(
+            ui,
+            &self.sessions,
+            self.settings.target.min_hz,
+            self.settings.target.max_hz,
+            self.settings.pitch_scale,
+        );
+
+        ui.separator();
+        ui.label("🔗 Explorateur de corrélations");
+        self.correlation_explorer.show(ui, &self.sessions);
+
+        ui.separator();
+        ui.label("⭐ Auto-évaluation des sessions");
+
+        let mut rated = None;
+        let mut reanalyze = None;
+        let mut review = None;
+        let mut report = None;
+        let reporting = self.reports.is_running();
+        let can_reanalyze = self.mode.can_enter(ModeKind::Reviewing);
+        let store = &self.session_store;
+        egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+            egui::Grid::new("sessions_grid").striped(true).show(ui, |ui| {
+                ui.label("Session");
+                ui.label("Médiane");
+                ui.label("Dans la cible");
+                ui.label("Note");
+                ui.label("Objectif");
+                ui.label("Forçage");
+                ui.label("Audio");
+                ui.label("Repères");
+                ui.label("Rapport");
+                ui.end_row();
+
+                for (index, session) in self.sessions.iter_mut().enumerate().rev() {
+                    ui.label(format!("#{}", index + 1));
+                    ui.label(self.settings.pitch_scale.format(session.median_pitch));
+                    ui.label(format!("{:.0} %", session.in_range_percent));
+
+                    let mut rating = session.self_rating.unwrap_or(0);
+                    if ui
+                        .add(egui::Slider::new(&mut rating, 0..=10).text("/10"))
+                        .changed()
+                    {
+                        session.self_rating = (rating > 0).then_some(rating);
+                        rated = Some(index);
+                    }
+
+                    match &session.goal {
+                        Some(goal) => {
+                            let text = if goal.reached { "✔" } else { "…" };
+                            ui.label(text).on_hover_text(format!(
+                                "{:.0} / {:.0} min sur la journée",
+                                goal.day_secs / 60.0,
+                                goal.target_secs / 60.0
+                            ));
+                        }
+                        None => {
+                            ui.label("—");
+                        }
+                    }
+
+                    match session.strain_warnings.as_slice() {
+                        [] => {
+                            ui.label("—");
+                        }
+                        warnings => {
+                            let details: Vec<String> = warnings
+                                .iter()
+                                .map(|w| {
+                                    format!("à {:.0} s: {:.0} s", w.at_secs, w.duration_secs)
+                                })
+                                .collect();
+                            ui.label(format!("⚠ {}", warnings.len()))
+                                .on_hover_text(details.join("\n"));
+                        }
+                    }
+
+                    let audio = store
+                        .as_ref()
+                        .map(|store| store.audio_path(session.started_at))
+                        .filter(|path| path.exists());
+                    if audio.is_some() {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .add_enabled(can_reanalyze, egui::Button::new("🔁 Réanalyser"))
+                                .on_hover_text(
+                                    "Avec les paramètres d'analyse et les seuils actuels",
+                                )
+                                .on_disabled_hover_text("Arrêtez d'abord l'enregistrement en cours")
+                                .clicked()
+                            {
+                                reanalyze = Some(index);
+                            }
+                            if ui
+                                .button("🎧 Revoir")
+                                .on_hover_text("Courbe, ralenti et boucle A–B")
+                                .clicked()
+                            {
+                                review = Some(index);
+                            }
+                        });
+                    } else {
+                        ui.label("—");
+                    }
+                    self.marker_playback.menu(ui, session, audio.as_deref());
+                    if ui
+                        .add_enabled(!reporting, egui::Button::new("📄 Exporter"))
+                        .on_hover_text(
+                            "Rapport HTML autonome (mesures, courbes, histogramme) \
+                             à partager ou imprimer en PDF",
+                        )
+                        .clicked()
+                    {
+                        report = Some(index);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        if let Some(index) = rated {
+            let summary = self.sessions[index].clone();
+            self.save_session(&summary);
+        }
+        if let Some(index) = reanalyze {
+            self.start_reanalysis(index);
+        }
+        if let Some(index) = review
+            && let Some(store) = &self.session_store
+        {
+            let session = &self.sessions[index];
+            let audio = store.audio_path(session.started_at);
+            self.session_review.open(session, audio, self.reanalysis_params());
+        }
+        if let Some(index) = report {
+            self.export_report(index);
+        }
+        self.reports.show_status(ui);
+        if let Some(error) = &self.marker_playback.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        if matches!(self.mode, AppMode::Reviewing(_)) {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Réanalyse en cours…");
+            });
+        }
+
+        self.show_reanalyses(ui);
+    }
+
+    fn show_reanalyses(&self, ui: &mut egui::Ui) {
+        if self.sessions.iter().all(|session| session.reanalyses.is_empty()) {
+            return;
+        }
+        let scale = self.settings.pitch_scale;
+
+        ui.separator();
+        ui.label("🔁 Réanalyses (l'analyse d'origine est conservée telle quelle)");
+        egui::Grid::new("reanalyses_grid").striped(true).show(ui, |ui| {
+            ui.label("Session");
+            ui.label("Analyse");
+            ui.label("Médiane");
+            ui.label("Variabilité");
+            ui.label("Dans la cible");
+            ui.label("Voix");
+            ui.end_row();
+
+            for (index, session) in self.sessions.iter().enumerate().rev() {
+                if session.reanalyses.is_empty() {
+                    continue;
+                }
+                ui.label(format!("#{}", index + 1));
+                ui.label("Origine");
+                ui.label(scale.format(session.median_pitch));
+                ui.label(format!("{:.1} dt", session.pitch_variability_st));
+                ui.label(format!("{:.0} %", session.in_range_percent));
+                ui.label(format!("{:.0} s", session.voiced_secs));
+                ui.end_row();
+
+                for reanalysis in &session.reanalyses {
+                    ui.label("");
+                    ui.label(&reanalysis.label);
+                    ui.label(scale.format(reanalysis.median_pitch));
+                    ui.label(format!("{:.1} dt", reanalysis.pitch_variability_st));
+                    ui.label(format!("{:.0} %", reanalysis.in_range_percent));
+                    ui.label(format!("{:.0} s", reanalysis.voiced_secs));
+                    ui.end_row();
+                }
+            }
+        });
+    }
+
+    fn show_device_check(&mut self, ui: &mut egui::Ui) {
+        if let AppMode::Calibrating(check) = &self.mode {
+            ui.horizontal(|ui| {
+                ui.label("🔊 Tonalité de test en cours...");
+                ui.add(egui::ProgressBar::new(check.progress()).desired_width(150.0));
+            });
+        }
+
+        let mut dismissed = false;
+        if let Some(report) = &self.device_check_report {
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Entrée {} Hz | Sortie {} Hz | Bruit {:.0} dB | Tonalité {:.0} dB",
+                        report.input_sample_rate,
+                        report.output_sample_rate,
+                        report.noise_db,
+                        report.tone_db
+                    ));
+                    if let Some(latency) = report.latency_ms {
+                        ui.label(format!("| Latence ≈ {:.0} ms", latency));
+                    }
+                    if let Some(measured) = report.measured_tone_hz {
+                        ui.label(format!("| Tonalité mesurée {:.0} Hz", measured));
+                    }
+                    dismissed = ui.small_button("✖").clicked();
+                });
+
+                if report.issues.is_empty() {
+                    ui.colored_label(egui::Color32::GREEN, "✅ Aucun problème détecté");
+                }
+                for issue in &report.issues {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", issue));
+                }
+            });
+        }
+
+        if dismissed {
+            self.device_check_report = None;
+        }
+    }
+
+    fn show_live(&mut self, ui: &mut egui::Ui) {
+        //ui.heading("🎤 Feminizer voice");
+        //ui.separator();
+
+        ui.horizontal(|ui| {
+            let label = if self.is_recording() {
+                "🛑 Arrêter"
+            } else {
+                "🎙️ Démarrer"
+            };
+            let enabled = self.is_recording() || self.mode.can_enter(ModeKind::Monitoring);
+            if ui.add_enabled(enabled, egui::Button::new(label)).clicked() {
+                if self.is_recording() {
+                    self.stop_recording();
+                } else {
+                    self.start_recording();
+                }
+            }
+
+            match &self.reconnect {
+                Some(reconnect) => {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 170, 60),
+                        format!(
+                            "🔌 Reconnexion (essai {}) dans {:.1} s",
+                            reconnect.attempt + 1,
+                            reconnect.remaining().as_secs_f32()
+                        ),
+                    )
+                    .on_hover_text(&reconnect.reason);
+                }
+                None => {
+                    ui.label(self.mode.status());
+                }
+            }
+            if !self.is_recording()
+                && let Some(silent) = self.silence_watch.stopped_after()
+            {
+                ui.weak(format!(
+                    "⏹ Arrêt automatique après {} min de silence",
+                    silent.as_secs() / 60
+                ));
+            }
+
+            ui.label("Micro:");
+            self.show_input_device_picker(ui);
+            ui.label("Second flux:");
+            if self.second_stream.show_picker(ui) {
+                if self.is_recording() {
+                    self.second_stream.start(&self.vad_config, &self.analysis_config);
+                } else {
+                    self.second_stream.stop();
+                }
+            }
+            if let Some(error) = &self.second_stream.error {
+                ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(error);
+            }
+
+            ui.checkbox(&mut self.pitch_in_title, "Hauteur dans le titre");
+            ui.label("Hauteurs en:");
+            let mut unit = self.settings.pitch_scale.unit;
+            egui::ComboBox::from_id_salt("pitch_unit")
+                .selected_text(unit.label())
+                .show_ui(ui, |ui| {
+                    for option in PitchUnit::ALL {
+                        ui.selectable_value(&mut unit, option, option.label());
+                    }
+                });
+            if unit != self.settings.pitch_scale.unit {
+                self.settings.pitch_scale.unit = unit;
+                self.save_settings();
+            }
+            if unit != PitchUnit::Hertz
+                && ui
+                    .add(
+                        egui::DragValue::new(&mut self.settings.pitch_scale.a4_hz)
+                            .range(pitch_unit::A4_RANGE_HZ)
+                            .speed(0.1)
+                            .prefix("La4 = ")
+                            .suffix(" Hz"),
+                    )
+                    .on_hover_text("Diapason utilisé pour nommer les notes")
+                    .changed()
+            {
+                self.save_settings();
+            }
+            ui.toggle_value(&mut self.gauge_window.open, "🖥 Affichage externe");
+
+            if ui
+                .add_enabled(
+                    self.mode.can_enter(ModeKind::Calibrating),
+                    egui::Button::new("🔧 Tester le périphérique"),
+                )
+                .clicked()
+            {
+                self.start_device_check();
+            }
+
+            ui.separator();
+            if let Ok(mut vad_config) = self.vad_config.lock() {
+                ui.label("Seuil minimal:");
+                ui.add(
+                    egui::Slider::new(&mut vad_config.energy_threshold, 0.001..=0.1)
+                        .logarithmic(true)
+                        .text("Amplitude"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut vad_config.flatness_threshold, 0.05..=1.0)
+                        .text("Planéité max"),
+                );
+            }
+
+            ui.separator();
+            ui.label(if self.is_recording() && self.is_voiced {
+                "🗣 Voix détectée"
+            } else {
+                "🤫 Silence / bruit"
+            });
+            ui.small(format!("Planéité: {:.2}", self.current_flatness));
+            if self.settings.analysis.detector != PitchDetector::SpectralPeak {
+                ui.small(format!("Détecteur: {}", detector_label(self.current_detector)))
+                    .on_hover_text(
+                        "Le pic spectral reprend la main quand le son n'a pas d'harmoniques",
+                    );
+            }
+        });
+
+        if let Some(error) = &self.error_message {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        self.show_device_check(ui);
+        if self.is_recording() {
+            self.input_health.show(ui);
+            ui.horizontal(|ui| {
+                if let Some(processor) = &self.audio_processor
+                    && let Ok(load) = processor.load().lock()
+                {
+                    load.show(ui);
+                }
+                if ui
+                    .small_button("🩺")
+                    .on_hover_text("Diagnostics: trames perdues, latence, charge")
+                    .clicked()
+                {
+                    self.diagnostics.open = true;
+                }
+            });
+        }
+        self.show_channel_selector(ui);
+        self.show_monitor_controls(ui);
+        let warning = self.settings.theme.palette().warning;
+        self.pitch_guard.show(ui, warning);
+        self.strain_monitor.show(ui, warning);
+        if self.is_recording() {
+            self.perception.show(ui, &self.settings.perception);
+        }
+        if self.settings.goal.enabled {
+            let day_secs = self.goal_day_secs();
+            ui.horizontal(|ui| {
+                goal::draw_ring(ui, &self.settings.goal, day_secs);
+                ui.vertical(|ui| {
+                    ui.label("🎯 Objectif du jour");
+                    ui.label(format!(
+                        "{:.1} / {:.0} min{}",
+                        day_secs / 60.0,
+                        self.settings.goal.minutes,
+                        if self.settings.goal.in_range_only {
+                            " dans la cible"
+                        } else {
+                            " de voix"
+                        }
+                    ));
+                });
+            });
+        }
+
+        egui::CollapsingHeader::new("🔔 Signaux sonores").show(ui, |ui| {
+            if let Some(error) = self.cue_player.show_settings(ui) {
+                self.error_message = Some(error);
+            }
+        });
+
+        egui::CollapsingHeader::new("📊 Distribution de la hauteur").show(ui, |ui| {
+            let current = self
+                .session_stats
+                .as_ref()
+                .map(|stats| stats.pitch_histogram())
+                .unwrap_or_default();
+            self.pitch_histogram.show(
+                ui,
+                &current,
+                &self.sessions,
+                self.settings.target.min_hz,
+                self.settings.target.max_hz,
+                self.settings.pitch_scale,
+            );
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Fréquence dominante:");
+                if self.current_frequency > 0.0 {
+                    let scale = self.settings.pitch_scale;
+                    ui.label(
+                        self.readout(scale.format(self.current_frequency))
+                            .color(egui::Color32::GREEN),
+                    );
+                    let others: Vec<String> = PitchUnit::ALL
+                        .iter()
+                        .filter(|&&other| other != scale.unit)
+                        .map(|&other| scale.with_unit(other).format(self.current_frequency))
+                        .collect();
+                    ui.small(others.join(" · "));
+                } else {
+                    ui.label(
+                        self.readout("Aucune fréquence détectée")
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+            });
+
+            ui.separator();
+
+            ui.vertical(|ui| {
+                ui.label("Amplitude:");
+                let amplitude_db = if self.current_amplitude > 0.0 {
+                    20.0 * self.current_amplitude.log10()
+                } else {
+                    -60.0
+                };
+                ui.label(self.readout(self.settings.level.format(amplitude_db)));
+                if self.settings.analysis.loudness.k_weighting {
+                    ui.small(format!("Sonie: {:.1} LUFS", self.current_loudness));
+                }
+
+                let level = ((amplitude_db + 60.0) / 60.0).clamp(0.0, 1.0);
+                let bar_color = if level > 0.8 {
+                    egui::Color32::RED
+                } else if level > 0.4 {
+                    egui::Color32::YELLOW
+                } else {
+                    egui::Color32::GREEN
+                };
+
+                ui.add(
+                    egui::ProgressBar::new(level)
+                        .fill(bar_color)
+                        .show_percentage(),
+                );
+            });
+
+            ui.separator();
+
+            ui.vertical(|ui| {
+                ui.label("Brillance (centroïde):");
+                ui.label(format!("{:.0} Hz", self.current_brightness));
+
+                let brightness = brightness_level(self.current_brightness);
+                ui.add(
+                    egui::ProgressBar::new(brightness)
+                        .fill(brightness_color(brightness))
+                        .text(if brightness > 0.5 { "Clair" } else { "Sombre" }),
+                );
+            });
+
+            ui.separator();
+
+            ui.vertical(|ui| {
+                ui.label("Poids vocal (H1–H2):").on_hover_text(
+                    "Écart entre la fondamentale et la deuxième harmonique: une voix légère \
+                     laisse dominer la fondamentale, une voix lourde ou pressée renforce \
+                     les harmoniques. Repère indicatif, sensible au micro et à la voyelle.",
+                );
+                let Some(h1_h2) = self.current_weight else {
+                    ui.weak("en attente de voix");
+                    return;
+                };
+                ui.label(format!("{:+.1} dB", h1_h2));
+                let weight = weight_level(h1_h2);
+                ui.add(
+                    egui::ProgressBar::new(weight)
+                        .fill(weight_color(weight))
+                        .text(if weight > 0.5 { "Lourd" } else { "Léger" }),
+                );
+            });
+        });
+
+        // Replié par défaut, sauf pour qui lit déjà ses hauteurs en notes
+        egui::CollapsingHeader::new("🎹 Clavier")
+            .default_open(self.settings.pitch_scale.unit == PitchUnit::Note)
+            .show(ui, |ui| {
+                let frequency = if self.is_voiced { self.current_frequency } else { 0.0 };
+                let target = &self.settings.target;
+                piano::draw_keyboard(
+                    ui,
+                    self.settings.pitch_scale,
+                    frequency,
+                    (target.min_hz, target.max_hz),
+                    &self.settings.theme.palette(),
+                );
+            });
+
+        ui.separator();
+
+        if !self.history.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label("📈 Historique des fréquences:");
+                let label = if self.plots_paused { "▶ Reprendre" } else { "⏸ Figer" };
+                if ui.button(label).on_hover_text("Raccourci: P").clicked() {
+                    self.plots_paused = !self.plots_paused;
+                }
+                ui.checkbox(&mut self.phrase_colors, "Colorer les énoncés").on_hover_text(
+                    "Vert, jaune ou rouge selon la part de chaque énoncé dans la plage cible",
+                );
+                match (self.reference.contour(), self.contour_guide) {
+                    (Some(_), Some(_)) => {
+                        if ui.button("⏹ Arrêter le contour importé").clicked() {
+                            self.contour_guide = None;
+                        }
+                    }
+                    (Some(contour), None) => {
+                        if ui
+                            .button(format!("🎯 Suivre « {} »", contour.name))
+                            .on_hover_text("Fait défiler le contour importé à partir de maintenant")
+                            .clicked()
+                        {
+                            self.contour_guide = Some(self.history_frames);
+                        }
+                    }
+                    (None, _) => self.contour_guide = None,
+                }
+            });
+
+            let search = self.settings.analysis.search_range();
+            let target = self.settings.target;
+            let palette = self.settings.theme.palette();
+            let (reliable, unreliable): (Vec<_>, Vec<_>) = self
+                .history
+                .iter()
+                .enumerate()
+                .filter(|&(_, frame)| search.contains(&frame.frequency))
+                .partition(|&(_, frame)| frame.confidence >= self.min_confidence);
+            let to_points = |frames: Vec<(usize, &AnalysisFrame)>| -> PlotPoints {
+                frames
+                    .into_iter()
+                    .map(|(i, frame)| [i as f64, frame.frequency as f64])
+                    .collect()
+            };
+            let freq_points = to_points(reliable);
+            let unreliable_points = to_points(unreliable);
+            let phrases = if self.phrase_colors {
+                let frame_duration = self.frame_duration;
+                let frames = self.history.iter().map(|frame| {
+                    let freq = Some(frame.frequency)
+                        .filter(|f| search.contains(f))
+                        .unwrap_or(0.0);
+                    (freq, frame.confidence >= self.min_confidence)
+                });
+                segment_phrases(frames, frame_duration, &target)
+            } else {
+                Vec::new()
+            };
+
+            let size = ui.available_size_before_wrap();
+            let first_frame = self.history_frames - self.history.len() as u64;
+            let visible = 0.0..self.history.len() as f64 + CONTOUR_LOOKAHEAD_FRAMES;
+            let guide = self.contour_guide.zip(self.reference.contour());
+            let guide_runs: Vec<Vec<[f64; 2]>> = match guide {
+                Some((start, contour)) => {
+                    let origin = start as f64 - first_frame as f64;
+                    let frame_secs = self.frame_duration.max(1e-3) as f64;
+                    contour
+                        .runs(0.0)
+                        .into_iter()
+                        .map(|run| {
+                            run.into_iter()
+                                .map(|[secs, hz]| [origin + secs / frame_secs, hz])
+                                .filter(|[x, _]| visible.contains(x))
+                                .collect::<Vec<_>>()
+                        })
+                        .filter(|run| !run.is_empty())
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            // Le second flux et le pair sont alignés sur les trames les plus
+            // récentes du micro
+            let aligned_runs = |history: &VecDeque<f32>| {
+                let offset = self.history.len().saturating_sub(history.len());
+                let mut runs: Vec<Vec<[f64; 2]>> = Vec::new();
+                let mut previous_voiced = false;
+                for (i, &freq) in history.iter().enumerate() {
+                    let voiced = search.contains(&freq);
+                    if voiced {
+                        if !previous_voiced {
+                            runs.push(Vec::new());
+                        }
+                        if let Some(run) = runs.last_mut() {
+                            run.push([(offset + i) as f64, freq as f64]);
+                        }
+                    }
+                    previous_voiced = voiced;
+                }
+                runs
+            };
+            let second_runs = aligned_runs(self.second_stream.history());
+            let second_label = self.second_stream.label();
+            let peer_runs = aligned_runs(self.peer.history());
+            let peer_label = self.peer.label();
+
+            let plot = Plot::new("frequency_plot")
+                .view_aspect(2.0)
+                .width(size.y*2.0)
+                .height(size.x/4.0)
+                .x_axis_label("Temps (échantillons)");
+            let response = self.settings.pitch_scale.y_axis(plot)
+                .include_y(*search.start())
+                .include_y(*search.end())
+                .allow_zoom(false)
+                .allow_drag(false)
+                .show(ui, |plot_ui| {
+                    let (low, high) = (*search.start() as f64, *search.end() as f64);
+                    for phrase in &phrases {
+                        let color = match phrase.grade() {
+                            PhraseGrade::Good => palette.in_range,
+                            PhraseGrade::Fair => egui::Color32::YELLOW,
+                            PhraseGrade::Poor => palette.warning,
+                        };
+                        let (start, end) =
+                            (phrase.frames.start as f64 - 0.5, phrase.frames.end as f64 - 0.5);
+                        let name =
+                            format!("Énoncé: {:.0} % dans la cible", 100.0 * phrase.in_range);
+                        plot_ui.polygon(
+                            egui_plot::Polygon::new(
+                                name,
+                                PlotPoints::from(vec![
+                                    [start, low],
+                                    [end, low],
+                                    [end, high],
+                                    [start, high],
+                                ]),
+                            )
+                            .fill_color(color.gamma_multiply(0.12))
+                            .stroke(egui::Stroke::NONE),
+                        );
+                        for x in [start, end] {
+                            plot_ui.vline(
+                                egui_plot::VLine::new("", x)
+                                    .color(egui::Color32::from_gray(110))
+                                    .style(egui_plot::LineStyle::dotted_dense())
+                                    .width(1.0),
+                            );
+                        }
+                    }
+                    if !freq_points.points().is_empty() {
+                        plot_ui.line(
+                            Line::new("freq_points", freq_points)
+                                .color(palette.pitch)
+                                .width(2.0),
+                        );
+                    }
+                    for run in second_runs {
+                        plot_ui.line(
+                            Line::new(second_label.as_str(), PlotPoints::from(run))
+                                .color(egui::Color32::from_rgb(255, 170, 0))
+                                .width(1.5),
+                        );
+                    }
+                    for run in peer_runs {
+                        plot_ui.line(
+                            Line::new(peer_label.as_str(), PlotPoints::from(run))
+                                .color(egui::Color32::from_rgb(120, 220, 120))
+                                .width(1.5),
+                        );
+                    }
+                    for run in guide_runs {
+                        plot_ui.line(
+                            Line::new("Contour importé", PlotPoints::from(run))
+                                .color(egui::Color32::from_rgb(0, 200, 255))
+                                .style(egui_plot::LineStyle::dashed_dense())
+                                .width(2.0),
+                        );
+                    }
+                    if !unreliable_points.points().is_empty() {
+                        plot_ui.points(
+                            egui_plot::Points::new("Peu fiable", unreliable_points)
+                                .color(palette.pitch.gamma_multiply(0.3))
+                                .radius(2.0),
+                        );
+                    }
+
+                    plot_ui.hline(
+                        egui_plot::HLine::new("", target.min_hz)
+                            .color(palette.target)
+                            .style(egui_plot::LineStyle::Solid)
+                            .width(1.0),
+                    );
+                    plot_ui.hline(
+                        egui_plot::HLine::new("", target.max_hz)
+                            .color(palette.target)
+                            .style(egui_plot::LineStyle::Solid)
+                            .width(1.0),
+                    );
+
+                    plot_ui.hline(
+                        egui_plot::HLine::new("", 80.0)
+                            .color(palette.low_range)
+                            .style(egui_plot::LineStyle::Solid)
+                            .width(1.0),
+                    );
+                    plot_ui.hline(
+                        egui_plot::HLine::new("", 160.0)
+                            .color(palette.low_range)
+                            .style(egui_plot::LineStyle::Solid)
+                            .width(1.0),
+                    );
+
+                    for (frame, label) in &self.device_markers {
+                        let x = frame.saturating_sub(first_frame) as f64;
+                        plot_ui.vline(
+                            egui_plot::VLine::new(format!("🎙 {}", label), x)
+                                .color(egui::Color32::LIGHT_BLUE)
+                                .style(egui_plot::LineStyle::dashed_dense())
+                                .width(1.5),
+                        );
+                    }
+                    for (frame, _, text) in &self.moment_markers {
+                        let x = frame.saturating_sub(first_frame) as f64;
+                        plot_ui.vline(
+                            egui_plot::VLine::new(format!("📍 {}", text), x)
+                                .color(MARKER_COLOR)
+                                .width(1.5),
+                        );
+                    }
+                })
+                .response;
+            accessibility::describe(&response, egui::WidgetType::Image, &self.pitch_summary());
+            let mut export = false;
+            response.context_menu(|ui| export = self.plot_image.menu(ui));
+            if export {
+                let figure = self.pitch_figure();
+                self.save_plot_image(&figure, "hauteur");
+            }
+        }
+
+        if !self.history.is_empty() {
+            ui.label("✨ Historique de brillance:");
+
+            let brightness_points: PlotPoints = self
+                .history
+                .iter()
+                .enumerate()
+                .filter(|&(_, frame)| frame.brightness > 0.0)
+                .map(|(i, frame)| [i as f64, frame.brightness as f64])
+                .collect();
+
+            Plot::new("brightness_plot")
+                .height(100.0)
+                .y_axis_label("Centroïde (Hz)")
+                .include_y(BRIGHTNESS_DARK_HZ)
+                .include_y(BRIGHTNESS_BRIGHT_HZ)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .show(ui, |plot_ui| {
+                    plot_ui.line(
+                        Line::new("brightness_points", brightness_points)
+                            .color(egui::Color32::GOLD)
+                            .width(2.0),
+                    );
+                });
+        }
+
+        if self.history.iter().any(|frame| frame.resonance_hz.is_some()) {
+            ui.label("🔔 Pic de résonance (R1):").on_hover_text(
+                "Pic le plus fort entre 500 et 3500 Hz: plus il monte, plus l'espace \
+                 de résonance sonne petit et clair",
+            );
+
+            let mut steps: Vec<Vec<[f64; 2]>> = vec![Vec::new(); RESONANCE_COLOR_STEPS];
+            for (i, frame) in self.history.iter().enumerate() {
+                if let Some(hz) = frame.resonance_hz {
+                    let step = resonance_level(hz) * (RESONANCE_COLOR_STEPS - 1) as f32;
+                    steps[step.round() as usize].push([i as f64, hz as f64]);
+                }
+            }
+
+            Plot::new("resonance_plot")
+                .height(100.0)
+                .y_axis_label("R1 (Hz)")
+                .include_y(500.0)
+                .include_y(3500.0)
+                .allow_zoom(false)
+                .allow_drag(false)
+                .show(ui, |plot_ui| {
+                    for (step, points) in steps.into_iter().enumerate() {
+                        let level = step as f32 / (RESONANCE_COLOR_STEPS - 1) as f32;
+                        plot_ui.points(
+                            egui_plot::Points::new("", PlotPoints::from(points))
+                                .color(brightness_color(level))
+                                .radius(2.5),
+                        );
+                    }
+                    let marks = [(RESONANCE_DARK_HZ, "Sombre"), (RESONANCE_BRIGHT_HZ, "Clair")];
+                    for (hz, name) in marks {
+                        plot_ui.hline(
+                            egui_plot::HLine::new(name, hz)
+                                .color(egui::Color32::from_gray(110))
+                                .style(egui_plot::LineStyle::dotted_dense())
+                                .width(1.0),
+                        );
+                    }
+                });
+        }
+
+        if !self.history.is_empty() {
+            ui.label("🔊 Historique du niveau:");
+
+            let level = &self.settings.level;
+            let level_points: PlotPoints = self
+                .history
+                .iter()
+                .enumerate()
+                .filter(|&(_, frame)| frame.amplitude > 0.0)
+                .map(|(i, frame)| [i as f64, level.level(to_dbfs(frame.amplitude)) as f64])
+                .collect();
+            let strain = &self.settings.strain;
+            let warning = self.settings.theme.palette().warning;
+
+            Plot::new("level_plot")
+                .height(100.0)
+                .y_axis_label(level.suffix())
+                .include_y(level.level(-60.0))
+                .include_y(level.level(0.0))
+                .allow_zoom(false)
+                .allow_drag(false)
+                .show(ui, |plot_ui| {
+                    plot_ui.line(
+                        Line::new("level_points", level_points)
+                            .color(egui::Color32::LIGHT_RED)
+                            .width(2.0),
+                    );
+                    if strain.enabled {
+                        plot_ui.hline(
+                            egui_plot::HLine::new(
+                                "Seuil de forçage",
+                                level.level(strain.min_level_dbfs),
+                            )
+                            .color(warning)
+                            .style(egui_plot::LineStyle::dashed_dense()),
+                        );
+                    }
+                });
+        }
+
+        ui.separator();
+        ui.small(format!(
+            "Plages: Graves 80-160 Hz | Cible {:.0}-{:.0} Hz",
+            self.settings.target.min_hz, self.settings.target.max_hz
+        ));
+        self.plot_image.show_status(ui);
+
+        if !self.spectrum_history.is_empty() {
+            let desired_width = ui.available_width();
+            let height = 200.0;
+
+            let (rect, response) = ui.allocate_exact_size(
+                egui::vec2(desired_width, height),
+                egui::Sense::click(),
+            );
+
+            let painter = ui.painter_at(rect);
+            let history_len = self.spectrum_history.len();
+            let total_bins = self.spectrum_history[0].len();
+
+            let sample_rate = self.sample_rate;
+            let freq_per_bin = sample_rate / (2.0 * total_bins as f32);
+            let search = self.settings.analysis.search_range();
+            let min_bin = (search.start() / freq_per_bin) as usize;
+            let max_bin = (search.end() / freq_per_bin).min(total_bins as f32) as usize;
+            let filtered_bins = max_bin - min_bin;
+
+            painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+
+            for (t, spectrum) in self.spectrum_history.iter().enumerate() {
+                for (f_idx, &amp) in spectrum[min_bin..max_bin].iter().enumerate() {
+                    let color = spectrogram_color(amp);
+
+                    let x = rect.left() + (t as f32 / history_len as f32) * rect.width();
+                    let y = rect.bottom() - ((f_idx as f32 / filtered_bins as f32) * rect.height());
+
+                    let cell_width = (rect.width() / history_len as f32).max(1.0);
+                    let cell_height = (rect.height() / filtered_bins as f32).max(1.0);
+
+                    let cell = egui::Rect::from_min_size(
+                        egui::pos2(x, y - cell_height),
+                        egui::vec2(cell_width, cell_height),
+                    );
+
+                    painter.rect_filled(cell, 0.0, color);
+                }
+            }
+
+            self.draw_frequency_labels(&painter, rect, min_bin, max_bin, freq_per_bin);
+            accessibility::describe(&response, egui::WidgetType::Image, "Spectrogramme");
+
+            let mut export = false;
+            response.context_menu(|ui| export = self.plot_image.menu(ui));
+            if export && let Some(figure) = self.spectrogram_figure() {
+                self.save_plot_image(&figure, "spectrogramme");
+            }
+        }
+    }
+
+    fn show_tab(&mut self, ui: &mut egui::Ui, tab: Tab) {
+        match tab {
+            Tab::Live => self.show_live(ui),
+            Tab::Vowels => self.vowel_chart.show(ui),
+            Tab::Harmonics => self.waterfall.show(ui),
+            Tab::Snapshots => {
+                self.spectrum_snapshots
+                    .show(ui, self.is_recording(), self.settings.pitch_scale)
+            }
+            Tab::Takes => {
+                let is_recording = self.is_recording();
+                let snapshot = self.takes.show(
+                    ui,
+                    is_recording,
+                    self.settings.target,
+                    self.settings.pitch_scale,
+                    &self.settings.level,
+                );
+                if snapshot {
+                    self.snapshot_take();
+                }
+            }
+            Tab::Moments => {
+                let changed = self.good_moments.show(
+                    ui,
+                    &mut self.settings.good_moments,
+                    self.settings.pitch_scale,
+                );
+                if changed {
+                    self.save_settings();
+                }
+            }
+            Tab::Prosody => self.utterance_tracker.show(ui),
+            Tab::Passage => {
+                self.passage.show(
+                    ui,
+                    self.is_recording(),
+                    self.settings.target.min_hz,
+                    self.settings.target.max_hz,
+                    self.settings.pitch_scale,
+                )
+            }
+            Tab::Reading => {
+                self.reading
+                    .show(ui, self.is_recording(), self.settings.pitch_scale)
+            }
+            Tab::Reference => {
+                let params = self.reanalysis_params();
+                self.reference
+                    .show(ui, self.is_recording(), &params, self.settings.pitch_scale)
+            }
+            Tab::Rhythm => {
+                self.metronome.show(ui, self.is_recording());
+                ui.separator();
+                self.drill_sequencer.show(ui, self.is_recording());
+            }
+            Tab::Sustain => {
+                let changed = self.sustain.show(
+                    ui,
+                    self.is_recording(),
+                    &mut self.settings.sustain,
+                    self.settings.analysis.search_range(),
+                    self.settings.pitch_scale,
+                );
+                if changed {
+                    self.save_settings();
+                }
+            }
+            Tab::Range => {
+                let target = &self.settings.target;
+                self.range_test.show(
+                    ui,
+                    self.is_recording(),
+                    (target.min_hz, target.max_hz),
+                    self.settings.pitch_scale,
+                );
+            }
+            Tab::Ladder => {
+                let changed = self.ladder.show(
+                    ui,
+                    self.is_recording(),
+                    &mut self.settings.ladder,
+                    self.settings.target,
+                    self.settings.pitch_scale,
+                );
+                if changed {
+                    self.save_settings();
+                }
+            }
+            Tab::Karaoke => {
+                let target = &self.settings.target;
+                self.karaoke.show(
+                    ui,
+                    self.is_recording(),
+                    (target.min_hz, target.max_hz),
+                    self.settings.pitch_scale,
+                );
+            }
+            Tab::Scripts => self.scripts.show(ui, self.is_recording(), self.settings.pitch_scale),
+            Tab::Tuning => {
+                if let Ok(mut config) = self.vad_config.lock() {
+                    self.threshold_tuner.show(
+                        ui,
+                        &mut config,
+                        &mut self.accept_min_hz,
+                        &mut self.accept_max_hz,
+                        &mut self.min_confidence,
+                        self.settings.analysis.search_range(),
+                    );
+                }
+            }
+            Tab::Analytics => self.show_analytics(ui),
+            Tab::Settings => self.show_settings(ui),
+        }
+    }
+
+    /// Panneaux des onglets ancrés, à dessiner avant le panneau central.
+    fn show_docks(&mut self, ctx: &egui::Context) {
+        for dock in Dock::ALL {
+            let tabs = self.settings.layout.tabs_in(dock);
+            if tabs.is_empty() {
+                continue;
+            }
+            let id = egui::Id::new(("dock", dock));
+            let contents = |ui: &mut egui::Ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for &tab in &tabs {
+                        egui::CollapsingHeader::new(tab.label())
+                            .id_salt(("docked_tab", tab))
+                            .default_open(true)
+                            .show(ui, |ui| self.show_tab(ui, tab));
+                    }
+                });
+            };
+            match dock {
+                Dock::Left => {
+                    egui::SidePanel::left(id).resizable(true).show(ctx, contents);
+                }
+                Dock::Right => {
+                    egui::SidePanel::right(id).resizable(true).show(ctx, contents);
+                }
+                Dock::Bottom => {
+                    egui::TopBottomPanel::bottom(id).resizable(true).show(ctx, contents);
+                }
+            }
+        }
+    }
+}
+
+impl eframe::App for VoiceFrequencyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_default_input();
+        self.poll_device_switch();
+        self.update_frequency_data();
+        self.second_stream
+            .poll(self.accept_min_hz..=self.accept_max_hz, self.min_confidence);
+        self.peer.poll(self.accept_min_hz..=self.accept_max_hz, self.min_confidence);
+        self.watch_stream();
+        self.poll_input_meter();
+        self.poll_goal();
+        self.poll_silence();
+        self.flush_session_audio();
+        if let Some(stats) = &self.session_stats {
+            self.autosave.poll(stats);
+        }
+        self.poll_reanalysis();
+        self.reports.poll();
+        self.backups.poll(&self.settings.backup);
+        self.poll_device_check();
+        self.sync_capture_mode();
+        self.sync_range_search();
+        self.sync_thresholds();
+        self.poll_tray(ctx);
+        if self.applied_theme != Some(self.settings.theme.mode) {
+            self.settings.theme.mode.apply(ctx);
+            self.applied_theme = Some(self.settings.theme.mode);
+        }
+        if self.settings.accessibility.sync_zoom(ctx, &mut self.applied_zoom) {
+            self.save_settings();
+        }
+        self.update_window_title(ctx);
+        if self.metronome.tick() {
+            self.play_cue(CueCategory::Metronome);
+        }
+        if let Some(cue) = self.drill_sequencer.tick() {
+            self.play_cue(cue);
+        }
+        self.play_ladder_events();
+        let commands = self.palette_commands();
+        if let Some(action) = self.palette.show(ctx, &commands) {
+            self.run_action(action);
+        }
+        let shortcuts = Self::shortcuts();
+        self.shortcut_help.show(ctx, &shortcuts);
+        let stream = self.stream_info();
+        self.diagnostics.show(ctx, stream.as_ref());
+        self.log_window.show(ctx);
+        if let Some((index, text)) = self.marker_editor.show(ctx) {
+            self.annotate_marker(index, text);
+        }
+        let target = (self.settings.target.min_hz, self.settings.target.max_hz);
+        self.session_review.show(ctx, self.settings.pitch_scale, target);
+        if let Some(started_at) =
+            self.session_end.show(ctx, self.settings.pitch_scale, &self.reports)
+            && let Some(index) = self.sessions.iter().position(|s| s.started_at == started_at)
+        {
+            self.export_report(index);
+        }
+        self.show_setup_wizard(ctx);
+        if let Some(action) = self.autosave.show(ctx) {
+            self.handle_recovery(action);
+        }
+        if let Some(action) = shortcuts::pressed(ctx, &shortcuts) {
+            self.run_action(action);
+        }
+
+        if self.settings.layout.dock_of(self.tab).is_some() {
+            self.tab = self.settings.layout.central_tabs().next().unwrap_or(Tab::Settings);
+        }
+        self.show_docks(ctx);
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let tabs: Vec<Tab> = self.settings.layout.central_tabs().collect();
+                for tab in tabs {
+                    if ui.selectable_label(self.tab == tab, tab.label()).clicked() {
+                        self.select_tab(tab);
+                    }
+                }
+                ui.weak("Ctrl+K: actions").on_hover_text("Palette de commandes");
+                if ui
+                    .add(
+                        egui::Label::new(egui::RichText::new("F1: raccourcis").weak())
+                            .sense(egui::Sense::click()),
+                    )
+                    .clicked()
+                {
+                    self.shortcut_help.open = true;
+                }
+                let unseen = self.log_window.unseen();
+                let journal = if unseen > 0 {
+                    egui::RichText::new(format!("📃 Journal ({})", unseen))
+                        .color(egui::Color32::RED)
+                } else {
+                    egui::RichText::new("📃 Journal").weak()
+                };
+                if ui
+                    .add(egui::Label::new(journal).sense(egui::Sense::click()))
+                    .on_hover_text("Avertissements, erreurs et événements du flux audio")
+                    .clicked()
+                {
+                    self.log_window.open = true;
+                }
+                ui.separator();
+                let idle = self.mode.kind() == ModeKind::Idle;
+                if let Some(name) = self.profiles.show(ui, idle) {
+                    self.switch_profile(name);
+                }
+
+                let mut services = self
+                    .broadcaster
+                    .as_ref()
+                    .map(Broadcaster::describe)
+                    .unwrap_or_default();
+                services.extend(self.peer.describe());
+                if !services.is_empty() {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let indicator = ui
+                            .add(
+                                egui::Label::new(
+                                    egui::RichText::new("📡 Réseau actif")
+                                        .color(egui::Color32::from_rgb(255, 165, 0)),
+                                )
+                                .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text(services.join("\n"));
+                        if indicator.clicked() {
+                            self.tab = Tab::Settings;
+                        }
+                    });
+                }
+            });
+            ui.separator();
+
+            self.show_tab(ui, self.tab);
+        });
+
+        let reading = GaugeReading {
+            frequency: self.current_frequency,
+            is_voiced: self.is_recording() && self.is_voiced,
+            target_min: self.settings.target.min_hz,
+            target_max: self.settings.target.max_hz,
+            scale_min: self.settings.analysis.min_frequency_hz,
+            scale_max: self.settings.analysis.max_frequency_hz,
+            scale: self.settings.pitch_scale,
+            palette: self.settings.theme.palette(),
+        };
+        self.gauge_window.show(ctx, &reading);
+
+        let animating = self.metronome.is_running()
+            || self.drill_sequencer.is_running()
+            || self.ladder.is_running()
+            || self.reference.is_playing();
+        if animating || (self.mode.kind() != ModeKind::Idle && !self.settings.power.low_power) {
+            ctx.request_repaint();
+        } else if self.mode.kind() != ModeKind::Idle {
+            // Rien de neuf à afficher avant la prochaine trame
+            ctx.request_repaint_after(Duration::from_secs_f32(self.frame_duration));
+        }
+    }
+}
+
+fn detector_label(detector: PitchDetector) -> &'static str {
+    match detector {
+        PitchDetector::SpectralPeak => "pic spectral",
+        PitchDetector::HarmonicProduct => "produit harmonique (HPS)",
+    }
+}
+
+fn brightness_level(centroid: f32) -> f32 {
+    ((centroid - BRIGHTNESS_DARK_HZ) / (BRIGHTNESS_BRIGHT_HZ - BRIGHTNESS_DARK_HZ)).clamp(0.0, 1.0)
+}
+
+fn resonance_level(resonance_hz: f32) -> f32 {
+    ((resonance_hz - RESONANCE_DARK_HZ) / (RESONANCE_BRIGHT_HZ - RESONANCE_DARK_HZ)).clamp(0.0, 1.0)
+}
+
+/// 0 pour une voix légère, 1 pour une voix lourde.
+fn weight_level(h1_h2_db: f32) -> f32 {
+    ((WEIGHT_LIGHT_DB - h1_h2_db) / (WEIGHT_LIGHT_DB - WEIGHT_HEAVY_DB)).clamp(0.0, 1.0)
+}
+
+fn weight_color(level: f32) -> egui::Color32 {
+    let light = egui::Color32::from_rgb(150, 220, 255);
+    let heavy = egui::Color32::from_rgb(170, 90, 50);
+    light.lerp_to_gamma(heavy, level)
+}
+
+fn spectrogram_color(amplitude: f32) -> egui::Color32 {
+    let norm_amp = amplitude.sqrt();
+    let hue = (1.0 - norm_amp) * 0.7;
+    egui::Color32::from(Hsva::new(hue, 1.0, norm_amp, 1.0))
+}
+
+fn brightness_color(level: f32) -> egui::Color32 {
+    let dark = egui::Color32::from_rgb(90, 60, 160);
+    let bright = egui::Color32::from_rgb(255, 220, 90);
+    dark.lerp_to_gamma(bright, level)
 }
@@ -0,0 +1,136 @@
+use eframe::egui;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::playback::ClipPlayer;
+use crate::session::SessionSummary;
+use crate::wav;
+
+/// Écoute lancée un peu avant le repère, pour entendre ce qui y mène.
+const PREROLL_SECS: f32 = 2.0;
+
+/// Note du repère qui vient d'être posé.
+struct Editing {
+    index: usize,
+    text: String,
+    focused: bool,
+}
+
+/// Petite fenêtre pour annoter le dernier repère; la laisser vide garde un
+/// repère sans texte.
+#[derive(Default)]
+pub struct MarkerEditor {
+    editing: Option<Editing>,
+}
+
+impl MarkerEditor {
+    pub fn open(&mut self, index: usize) {
+        self.editing = Some(Editing {
+            index,
+            text: String::new(),
+            focused: false,
+        });
+    }
+
+    /// Indice du repère et sa note, une fois validée.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(usize, String)> {
+        let editing = self.editing.as_mut()?;
+        let mut open = true;
+        let mut done = false;
+        egui::Window::new("📍 Repère posé")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut editing.text).hint_text("Note facultative"),
+                );
+                if !editing.focused {
+                    response.request_focus();
+                    editing.focused = true;
+                }
+                let entered =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                done = entered || ui.button("✔ Valider").clicked();
+            });
+        if !open {
+            self.editing = None;
+            return None;
+        }
+        if !done {
+            return None;
+        }
+        self.editing
+            .take()
+            .map(|editing| (editing.index, editing.text.trim().to_string()))
+    }
+}
+
+/// Réécoute de l'audio conservé d'une session à partir de ses repères.
+#[derive(Default)]
+pub struct MarkerPlayback {
+    player: ClipPlayer,
+    /// Audio de la dernière session écoutée, gardé pour passer d'un repère à
+    /// l'autre sans relire le fichier.
+    loaded: Option<(u64, Arc<Vec<f32>>, f32)>,
+    pub error: Option<String>,
+}
+
+impl MarkerPlayback {
+    pub fn is_playing(&self) -> bool {
+        self.player.position_secs().is_some()
+    }
+
+    fn play(&mut self, session: &SessionSummary, audio: &Path, at_secs: f32) {
+        if self.loaded.as_ref().is_none_or(|(started_at, ..)| *started_at != session.started_at) {
+            match wav::read_mono(audio) {
+                Ok((samples, rate)) => {
+                    self.loaded = Some((session.started_at, Arc::new(samples), rate));
+                }
+                Err(e) => {
+                    self.error = Some(format!("Lecture de l'audio: {}", e));
+                    return;
+                }
+            }
+        }
+        if let Some((_, samples, rate)) = &self.loaded {
+            let start = (at_secs - PREROLL_SECS).max(0.0);
+            match self.player.play(samples.clone(), *rate, start) {
+                Ok(()) => self.error = None,
+                Err(e) => self.error = Some(format!("Lecture: {}", e)),
+            }
+        }
+    }
+
+    /// Liste des repères d'une session; un clic réécoute à partir du repère si
+    /// l'audio a été conservé.
+    pub fn menu(&mut self, ui: &mut egui::Ui, session: &SessionSummary, audio: Option<&Path>) {
+        if session.markers.is_empty() {
+            ui.label("—");
+            return;
+        }
+        ui.menu_button(format!("📍 {}", session.markers.len()), |ui| {
+            if audio.is_none() {
+                ui.weak("Audio non conservé: écoute impossible");
+            }
+            for marker in &session.markers {
+                let secs = marker.at_secs.max(0.0) as u32;
+                let label = match marker.text.as_str() {
+                    "" => format!("{}:{:02}", secs / 60, secs % 60),
+                    text => format!("{}:{:02} — {}", secs / 60, secs % 60, text),
+                };
+                let button = ui.add_enabled(audio.is_some(), egui::Button::new(label));
+                if button.on_hover_text("Écouter à partir de ce repère").clicked()
+                    && let Some(audio) = audio
+                {
+                    self.play(session, audio, marker.at_secs);
+                    ui.close();
+                }
+            }
+            if self.is_playing() && ui.button("⏹ Arrêter l'écoute").clicked() {
+                self.player.stop();
+                ui.close();
+            }
+        });
+    }
+}
@@ -0,0 +1,345 @@
+use eframe::egui;
+use egui_plot::{Bar, BarChart, Plot};
+use std::time::{Duration, Instant};
+
+const COUNT_IN_BEATS: u64 = 4;
+const ONSET_RISE: f32 = 2.0;
+const ONSET_MIN_AMPLITUDE: f32 = 0.02;
+const ONSET_REFRACTORY_SECS: f32 = 0.12;
+
+pub struct BeatResult {
+    pub offset_ms: Option<f32>,
+}
+
+pub struct DrillReport {
+    pub beats: Vec<BeatResult>,
+    pub extra_onsets: usize,
+    pub tolerance_ms: f32,
+}
+
+impl DrillReport {
+    pub fn hits(&self) -> usize {
+        self.beats
+            .iter()
+            .filter(|beat| beat.offset_ms.is_some_and(|o| o.abs() <= self.tolerance_ms))
+            .count()
+    }
+
+    pub fn mean_offset_ms(&self) -> Option<f32> {
+        let offsets: Vec<f32> = self.beats.iter().filter_map(|beat| beat.offset_ms).collect();
+        if offsets.is_empty() {
+            None
+        } else {
+            Some(offsets.iter().sum::<f32>() / offsets.len() as f32)
+        }
+    }
+}
+
+struct PacingDrill {
+    first_beat: u64,
+    beat_count: u64,
+    onsets: Vec<Instant>,
+}
+
+pub struct Metronome {
+    bpm: f32,
+    beats_per_bar: u64,
+    tolerance_ms: f32,
+    drill_beats: u64,
+    started: Option<Instant>,
+    last_beat: Option<u64>,
+    drill: Option<PacingDrill>,
+    report: Option<DrillReport>,
+    last_onset: Option<Instant>,
+    valley: f32,
+}
+
+impl Default for Metronome {
+    fn default() -> Self {
+        Self {
+            bpm: 90.0,
+            beats_per_bar: 4,
+            tolerance_ms: 120.0,
+            drill_beats: 16,
+            started: None,
+            last_beat: None,
+            drill: None,
+            report: None,
+            last_onset: None,
+            valley: 0.0,
+        }
+    }
+}
+
+impl Metronome {
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f32(60.0 / self.bpm)
+    }
+
+    fn beat_time(&self, started: Instant, beat: u64) -> Instant {
+        started + self.interval().mul_f64(beat as f64)
+    }
+
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+        self.last_beat = None;
+    }
+
+    fn stop(&mut self) {
+        self.started = None;
+        self.drill = None;
+    }
+
+    fn start_drill(&mut self) {
+        self.start();
+        self.report = None;
+        self.drill = Some(PacingDrill {
+            first_beat: COUNT_IN_BEATS,
+            beat_count: self.drill_beats,
+            onsets: Vec::new(),
+        });
+    }
+
+    /// Avance l'horloge; renvoie vrai quand un nouveau temps vient de tomber.
+    pub fn tick(&mut self) -> bool {
+        let Some(started) = self.started else {
+            return false;
+        };
+
+        let beat = (started.elapsed().as_secs_f32() / self.interval().as_secs_f32()) as u64;
+        if let Some(drill) = &self.drill
+            && beat >= drill.first_beat + drill.beat_count
+        {
+            self.finish_drill(started);
+            self.stop();
+            return false;
+        }
+
+        if self.last_beat == Some(beat) {
+            return false;
+        }
+        self.last_beat = Some(beat);
+        true
+    }
+
+    /// Détecte les attaques de syllabes sur la chronologie d'analyse.
+    pub fn push_frame(&mut self, captured_at: Instant, amplitude: f32, is_voiced: bool) {
+        if self.drill.is_none() {
+            return;
+        }
+
+        let refractory = self.last_onset.is_none_or(|last| {
+            captured_at.duration_since(last).as_secs_f32() >= ONSET_REFRACTORY_SECS
+        });
+        let rising = amplitude >= ONSET_MIN_AMPLITUDE && amplitude >= self.valley * ONSET_RISE;
+
+        if is_voiced && rising && refractory {
+            self.last_onset = Some(captured_at);
+            self.valley = amplitude;
+            if let Some(drill) = &mut self.drill {
+                drill.onsets.push(captured_at);
+            }
+        } else {
+            self.valley = self.valley.min(amplitude);
+        }
+    }
+
+    fn finish_drill(&mut self, started: Instant) {
+        let Some(drill) = self.drill.take() else {
+            return;
+        };
+
+        let half_beat = self.interval().as_secs_f32() * 500.0;
+        let mut used = vec![false; drill.onsets.len()];
+        let mut beats = Vec::with_capacity(drill.beat_count as usize);
+
+        for beat in drill.first_beat..drill.first_beat + drill.beat_count {
+            let beat_time = self.beat_time(started, beat);
+            let nearest = drill
+                .onsets
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| !used[i])
+                .map(|(i, &onset)| (i, signed_ms(onset, beat_time)))
+                .filter(|&(_, offset)| offset.abs() <= half_beat)
+                .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()));
+
+            if let Some((i, _)) = nearest {
+                used[i] = true;
+            }
+            beats.push(BeatResult {
+                offset_ms: nearest.map(|(_, offset)| offset),
+            });
+        }
+
+        let first = self.beat_time(started, drill.first_beat) - self.interval() / 2;
+        let extra_onsets = drill
+            .onsets
+            .iter()
+            .zip(&used)
+            .filter(|&(&onset, &used)| !used && onset >= first)
+            .count();
+
+        self.report = Some(DrillReport {
+            beats,
+            extra_onsets,
+            tolerance_ms: self.tolerance_ms,
+        });
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, is_recording: bool) {
+        ui.heading("🥁 Métronome et exercices de rythme");
+
+        ui.horizontal(|ui| {
+            ui.add_enabled(
+                !self.is_running(),
+                egui::Slider::new(&mut self.bpm, 40.0..=200.0).text("BPM"),
+            );
+            ui.add_enabled(
+                !self.is_running(),
+                egui::Slider::new(&mut self.beats_per_bar, 2..=7).text("temps/mesure"),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("⏹ Arrêter").clicked() {
+                    self.stop();
+                }
+            } else if ui.button("▶ Métronome seul").clicked() {
+                self.start();
+            }
+        });
+
+        self.draw_beat(ui);
+        ui.separator();
+
+        ui.label("Exercice: prononcez une syllabe (ou un mot) sur chaque temps.");
+        ui.horizontal(|ui| {
+            ui.add(egui::Slider::new(&mut self.drill_beats, 4..=64).text("temps"));
+            ui.add(egui::Slider::new(&mut self.tolerance_ms, 40.0..=250.0).text("tolérance (ms)"));
+        });
+        ui.horizontal(|ui| {
+            let can_start = is_recording && !self.is_running();
+            if ui
+                .add_enabled(can_start, egui::Button::new("🎯 Lancer l'exercice"))
+                .clicked()
+            {
+                self.start_drill();
+            }
+            if !is_recording {
+                ui.small("Démarrez l'enregistrement pour lancer l'exercice.");
+            }
+        });
+
+        if let Some(drill) = &self.drill
+            && let Some(beat) = self.last_beat
+        {
+            if beat < drill.first_beat {
+                ui.label(format!("Décompte: {}", drill.first_beat - beat));
+            } else {
+                ui.label(format!(
+                    "Temps {} / {} — {} attaque(s) détectée(s)",
+                    beat - drill.first_beat + 1,
+                    drill.beat_count,
+                    drill.onsets.len()
+                ));
+            }
+        }
+
+        if let Some(report) = &self.report {
+            show_report(ui, report);
+        }
+    }
+
+    fn draw_beat(&self, ui: &mut egui::Ui) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 50.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+
+        let (beat, phase) = match self.started {
+            Some(started) => {
+                let beats = started.elapsed().as_secs_f32() / self.interval().as_secs_f32();
+                (beats as u64, beats.fract())
+            }
+            None => (0, 1.0),
+        };
+        let flash = (1.0 - phase * 3.0).max(0.0);
+
+        for i in 0..self.beats_per_bar {
+            let center = egui::pos2(rect.left() + 25.0 + i as f32 * 45.0, rect.center().y);
+            let current = self.started.is_some() && beat % self.beats_per_bar == i;
+            let base = if i == 0 {
+                egui::Color32::from_rgb(255, 0, 255)
+            } else {
+                egui::Color32::from_rgb(120, 180, 255)
+            };
+            let color = if current {
+                egui::Color32::from_gray(40).lerp_to_gamma(base, 0.3 + 0.7 * flash)
+            } else {
+                egui::Color32::from_gray(40)
+            };
+            let radius = if current { 14.0 + 6.0 * flash } else { 14.0 };
+            painter.circle_filled(center, radius, color);
+        }
+    }
+}
+
+fn signed_ms(onset: Instant, beat: Instant) -> f32 {
+    if onset >= beat {
+        onset.duration_since(beat).as_secs_f32() * 1000.0
+    } else {
+        -(beat.duration_since(onset).as_secs_f32() * 1000.0)
+    }
+}
+
+fn show_report(ui: &mut egui::Ui, report: &DrillReport) {
+    ui.separator();
+    ui.label(format!(
+        "✔ {} / {} temps dans la tolérance, {} attaque(s) en trop",
+        report.hits(),
+        report.beats.len(),
+        report.extra_onsets
+    ));
+    if let Some(mean) = report.mean_offset_ms() {
+        let tendency = if mean < -20.0 {
+            "en avance"
+        } else if mean > 20.0 {
+            "en retard"
+        } else {
+            "bien calé"
+        };
+        ui.label(format!("Décalage moyen: {:+.0} ms ({})", mean, tendency));
+    }
+
+    let bars: Vec<Bar> = report
+        .beats
+        .iter()
+        .enumerate()
+        .map(|(i, beat)| match beat.offset_ms {
+            Some(offset) => {
+                let color = if offset.abs() <= report.tolerance_ms {
+                    egui::Color32::GREEN
+                } else {
+                    egui::Color32::YELLOW
+                };
+                Bar::new(i as f64 + 1.0, offset as f64).fill(color)
+            }
+            None => Bar::new(i as f64 + 1.0, 0.0).fill(egui::Color32::RED).width(0.2),
+        })
+        .collect();
+
+    Plot::new("pacing_offsets")
+        .height(160.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .y_axis_label("Décalage (ms)")
+        .x_axis_label("Temps")
+        .show(ui, |plot_ui| {
+            plot_ui.bar_chart(BarChart::new("Décalage", bars));
+        });
+}
@@ -0,0 +1,214 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// La sortie MIDI demande la fonction Cargo `midi`.
+pub const AVAILABLE: bool = cfg!(feature = "midi");
+
+/// Nom du client et du port virtuel vus par les autres logiciels.
+#[cfg_attr(not(feature = "midi"), allow(dead_code))]
+const PORT_NAME: &str = "Feminizer-voice";
+#[cfg_attr(not(feature = "midi"), allow(dead_code))]
+const VELOCITY: u8 = 100;
+/// Valeur de pitch-bend au repos, sur 14 bits.
+#[cfg_attr(not(feature = "midi"), allow(dead_code))]
+const BEND_CENTER: u16 = 8192;
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MidiSettings {
+    pub enabled: bool,
+    /// Canal MIDI, de 1 à 16.
+    pub channel: u8,
+    /// Amplitude du pitch-bend réglée sur le synthé, en demi-tons.
+    pub bend_range: u8,
+    /// Port existant auquel se connecter; vide pour créer un port virtuel.
+    pub port: String,
+}
+
+impl Default for MidiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: 1,
+            bend_range: 2,
+            port: String::new(),
+        }
+    }
+}
+
+impl MidiSettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if !AVAILABLE {
+            ui.weak("Cette version a été compilée sans sortie MIDI (fonction « midi »).");
+            return false;
+        }
+        let mut changed = ui
+            .checkbox(&mut self.enabled, "Envoyer la hauteur détectée en MIDI")
+            .on_hover_text("Une note par demi-ton, affinée par le pitch-bend")
+            .changed();
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(egui::Slider::new(&mut self.channel, 1..=16).text("canal"))
+                    .changed();
+                changed |= ui
+                    .add(egui::Slider::new(&mut self.bend_range, 1..=24).text("dt de pitch-bend"))
+                    .on_hover_text("Doit correspondre à la plage réglée sur le synthé")
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Port:");
+                changed |= ui
+                    .text_edit_singleline(&mut self.port)
+                    .on_hover_text(
+                        "Vide: port virtuel « Feminizer-voice » (Linux, macOS). Sinon, une \
+                         partie du nom d'un port existant, par exemple loopMIDI sous Windows",
+                    )
+                    .lost_focus();
+            });
+        });
+        changed
+    }
+}
+
+/// Note la plus proche de `frequency` et pitch-bend vers la hauteur exacte.
+#[cfg_attr(not(feature = "midi"), allow(dead_code))]
+fn note_and_bend(frequency: f32, bend_range: u8) -> (u8, u16) {
+    let position = 69.0 + 12.0 * (frequency / 440.0).log2();
+    let note = position.round().clamp(0.0, 127.0);
+    let offset = (position - note) / bend_range.max(1) as f32;
+    let bend = (BEND_CENTER as f32 + offset * BEND_CENTER as f32).clamp(0.0, 16383.0);
+    (note as u8, bend as u16)
+}
+
+/// Suit la hauteur détectée trame après trame et l'envoie en MIDI: une note
+/// tenue tant qu'elle ne change pas, le pitch-bend pour le reste.
+#[derive(Default)]
+pub struct MidiOut {
+    #[cfg(feature = "midi")]
+    connection: Option<midir::MidiOutputConnection>,
+    /// Port demandé à la dernière ouverture, même si elle a échoué.
+    #[cfg(feature = "midi")]
+    port: Option<String>,
+    #[cfg(feature = "midi")]
+    channel: u8,
+    #[cfg(feature = "midi")]
+    note: Option<u8>,
+    #[cfg(feature = "midi")]
+    bend: u16,
+    pub error: Option<String>,
+}
+
+#[cfg(not(feature = "midi"))]
+impl MidiOut {
+    pub fn update(&mut self, _settings: &MidiSettings, _frequency: Option<f32>) {}
+
+    pub fn stop(&mut self) {}
+}
+
+#[cfg(feature = "midi")]
+impl MidiOut {
+    /// `frequency` à `None` pendant les silences: la note en cours est relâchée.
+    pub fn update(&mut self, settings: &MidiSettings, frequency: Option<f32>) {
+        if !settings.enabled {
+            self.stop();
+            return;
+        }
+        let port = settings.port.trim();
+        if self.port.as_deref() != Some(port) {
+            self.stop();
+            self.port = Some(port.to_string());
+            match imp::connect(port) {
+                Ok(connection) => self.connection = Some(connection),
+                Err(e) => self.error = Some(format!("Sortie MIDI: {}", e)),
+            }
+        }
+
+        let channel = settings.channel.clamp(1, 16) - 1;
+        if channel != self.channel {
+            self.release();
+            self.channel = channel;
+        }
+        let Some(frequency) = frequency.filter(|&f| f > 0.0) else {
+            self.release();
+            return;
+        };
+        let (note, bend) = note_and_bend(frequency, settings.bend_range);
+        if bend != self.bend || self.note != Some(note) {
+            // Le pitch-bend d'abord: la nouvelle note démarre à la bonne hauteur
+            let [low, high] = [(bend & 0x7f) as u8, (bend >> 7) as u8];
+            self.send(&[0xe0 | self.channel, low, high]);
+            self.bend = bend;
+        }
+        if self.note != Some(note) {
+            self.release();
+            self.send(&[0x90 | self.channel, note, VELOCITY]);
+            self.note = Some(note);
+        }
+    }
+
+    /// Relâche la note en cours et ferme le port.
+    pub fn stop(&mut self) {
+        self.release();
+        self.connection = None;
+        self.port = None;
+        self.error = None;
+    }
+
+    fn release(&mut self) {
+        if let Some(note) = self.note.take() {
+            self.send(&[0x80 | self.channel, note, 0]);
+        }
+    }
+
+    fn send(&mut self, message: &[u8]) {
+        let Some(connection) = &mut self.connection else {
+            return;
+        };
+        if let Err(e) = connection.send(message) {
+            self.error = Some(format!("Sortie MIDI: {}", e));
+            self.connection = None;
+        }
+    }
+}
+
+#[cfg(feature = "midi")]
+mod imp {
+    use anyhow::{Result, anyhow};
+    use midir::{MidiOutput, MidiOutputConnection};
+
+    use super::PORT_NAME;
+
+    pub fn connect(port: &str) -> Result<MidiOutputConnection> {
+        let output = MidiOutput::new(PORT_NAME)?;
+        if port.is_empty() {
+            return virtual_port(output);
+        }
+        let found = output.ports().into_iter().find(|candidate| {
+            output
+                .port_name(candidate)
+                .is_ok_and(|name| name.to_lowercase().contains(&port.to_lowercase()))
+        });
+        let Some(found) = found else {
+            anyhow::bail!("aucun port ne correspond à « {} »", port);
+        };
+        output
+            .connect(&found, PORT_NAME)
+            .map_err(|e| anyhow!("{}", e))
+    }
+
+    #[cfg(unix)]
+    fn virtual_port(output: MidiOutput) -> Result<MidiOutputConnection> {
+        use midir::os::unix::VirtualOutput;
+        output
+            .create_virtual(PORT_NAME)
+            .map_err(|e| anyhow!("{}", e))
+    }
+
+    #[cfg(not(unix))]
+    fn virtual_port(_output: MidiOutput) -> Result<MidiOutputConnection> {
+        anyhow::bail!("pas de port virtuel sur ce système: indiquez un port existant")
+    }
+}
@@ -0,0 +1,100 @@
+use std::thread::JoinHandle;
+
+use crate::device_check::DeviceCheck;
+use crate::session::Reanalysis;
+
+pub type ReanalysisHandle = JoinHandle<anyhow::Result<(u64, Reanalysis)>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exercise {
+    Vowels,
+    Prosody,
+    Passage,
+    Reading,
+    Reference,
+    Rhythm,
+    Sustain,
+    Range,
+    Ladder,
+    Karaoke,
+    Script,
+}
+
+impl Exercise {
+    pub fn label(self) -> &'static str {
+        match self {
+            Exercise::Vowels => "Voyelles",
+            Exercise::Prosody => "Intonation",
+            Exercise::Passage => "Passages répétés",
+            Exercise::Reading => "Lecture guidée",
+            Exercise::Reference => "Comparaison A/B",
+            Exercise::Rhythm => "Rythme",
+            Exercise::Sustain => "Voyelle tenue",
+            Exercise::Range => "Étendue vocale",
+            Exercise::Ladder => "Échauffement en échelle",
+            Exercise::Karaoke => "Texte minuté",
+            Exercise::Script => "Exercice scripté",
+        }
+    }
+}
+
+/// Genre d'un mode, sans son état: ce qui sert à valider une transition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeKind {
+    Idle,
+    Monitoring,
+    Exercise,
+    Reviewing,
+    Calibrating,
+}
+
+/// Mode de l'application. Les modes s'excluent: pas d'analyse en direct
+/// pendant un test du périphérique ou une réanalyse, par exemple.
+pub enum AppMode {
+    Idle,
+    /// Analyse en direct du micro.
+    Monitoring,
+    /// Analyse en direct, un exercice guidé ouvert.
+    Exercise(Exercise),
+    /// Réanalyse d'un enregistrement conservé.
+    Reviewing(ReanalysisHandle),
+    /// Tonalité de test en cours.
+    Calibrating(DeviceCheck),
+}
+
+impl AppMode {
+    pub fn kind(&self) -> ModeKind {
+        match self {
+            AppMode::Idle => ModeKind::Idle,
+            AppMode::Monitoring => ModeKind::Monitoring,
+            AppMode::Exercise(_) => ModeKind::Exercise,
+            AppMode::Reviewing(_) => ModeKind::Reviewing,
+            AppMode::Calibrating(_) => ModeKind::Calibrating,
+        }
+    }
+
+    /// Vrai quand le micro est ouvert et analysé.
+    pub fn is_capturing(&self) -> bool {
+        matches!(self, AppMode::Monitoring | AppMode::Exercise(_))
+    }
+
+    pub fn can_enter(&self, next: ModeKind) -> bool {
+        let live = |kind| matches!(kind, ModeKind::Monitoring | ModeKind::Exercise);
+        match self.kind() {
+            ModeKind::Idle => true,
+            // Passer d'un exercice à l'autre ne coupe pas le micro
+            ModeKind::Monitoring | ModeKind::Exercise => live(next) || next == ModeKind::Idle,
+            ModeKind::Reviewing | ModeKind::Calibrating => next == ModeKind::Idle,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        match self {
+            AppMode::Idle => "⚪ En attente".to_string(),
+            AppMode::Monitoring => "🔴 Enregistrement en cours...".to_string(),
+            AppMode::Exercise(exercise) => format!("🔴 Exercice: {}", exercise.label()),
+            AppMode::Reviewing(_) => "🔁 Réanalyse en cours…".to_string(),
+            AppMode::Calibrating(_) => "🔊 Test du périphérique…".to_string(),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, Stream, StreamConfig};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+use crate::audio_host;
+use crate::listening::{ListeningContext, ListeningFilter};
+
+const MAX_BUFFERED_SECS: f32 = 0.08;
+const SHIFTER_WINDOW_SECS: f32 = 0.04;
+
+#[derive(Clone, Copy, Debug)]
+pub struct MonitorConfig {
+    pub semitones: f32,
+    pub volume: f32,
+    pub context: ListeningContext,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            semitones: 4.0,
+            volume: 0.8,
+            context: ListeningContext::Direct,
+        }
+    }
+}
+
+pub type MonitorTap = Arc<Mutex<VecDeque<f32>>>;
+
+pub fn push_to_tap(tap: &MonitorTap, samples: &[f32], sample_rate: f32) {
+    if let Ok(mut buffer) = tap.try_lock() {
+        buffer.extend(samples.iter().copied());
+        let max_len = (sample_rate * MAX_BUFFERED_SECS) as usize;
+        let excess = buffer.len().saturating_sub(max_len);
+        buffer.drain(..excess);
+    }
+}
+
+pub struct Monitor {
+    _stream: Stream,
+}
+
+impl Monitor {
+    pub fn start(
+        tap: MonitorTap,
+        input_sample_rate: f32,
+        config: Arc<Mutex<MonitorConfig>>,
+    ) -> Result<Self> {
+        let host = audio_host::host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique de sortie audio trouvé"))?;
+
+        let output_config = device.default_output_config()?;
+        let stream_config: StreamConfig = output_config.clone().into();
+
+        let stream = match output_config.sample_format() {
+            cpal::SampleFormat::F32 => Self::build_stream::<f32>(
+                &device,
+                &stream_config,
+                tap,
+                input_sample_rate,
+                config,
+            )?,
+            cpal::SampleFormat::I16 => Self::build_stream::<i16>(
+                &device,
+                &stream_config,
+                tap,
+                input_sample_rate,
+                config,
+            )?,
+            cpal::SampleFormat::U16 => Self::build_stream::<u16>(
+                &device,
+                &stream_config,
+                tap,
+                input_sample_rate,
+                config,
+            )?,
+            format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
+        };
+
+        stream.play()?;
+
+        Ok(Monitor { _stream: stream })
+    }
+
+    fn build_stream<T>(
+        device: &Device,
+        config: &StreamConfig,
+        tap: MonitorTap,
+        input_sample_rate: f32,
+        monitor_config: Arc<Mutex<MonitorConfig>>,
+    ) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
+    {
+        let channels = config.channels as usize;
+        let output_rate = config.sample_rate.0 as f32;
+        let step = input_sample_rate / output_rate;
+
+        let max_pending = (input_sample_rate * MAX_BUFFERED_SECS) as usize;
+        let mut pending: VecDeque<f32> = VecDeque::with_capacity(max_pending * 2);
+        let mut shifter = PitchShifter::new((SHIFTER_WINDOW_SECS * output_rate) as usize);
+        let mut settings = MonitorConfig::default();
+        let mut listening = ListeningFilter::new(settings.context, output_rate);
+        let mut position = 0.0_f32;
+        let mut previous = 0.0_f32;
+        let mut current = 0.0_f32;
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                if let Ok(config) = monitor_config.try_lock() {
+                    settings = *config;
+                }
+                let ratio = 2.0_f32.powf(settings.semitones / 12.0);
+                if listening.context() != settings.context {
+                    listening = ListeningFilter::new(settings.context, output_rate);
+                }
+
+                if let Ok(mut buffer) = tap.try_lock() {
+                    pending.extend(buffer.drain(..));
+                }
+                let excess = pending.len().saturating_sub(max_pending);
+                pending.drain(..excess);
+
+                for frame in data.chunks_mut(channels) {
+                    // Rééchantillonnage linéaire de l'entrée vers la fréquence de sortie
+                    position += step;
+                    while position >= 1.0 {
+                        position -= 1.0;
+                        previous = current;
+                        current = pending.pop_front().unwrap_or(0.0);
+                    }
+                    let sample = previous + (current - previous) * position;
+
+                    let shifted = shifter.process(sample, ratio);
+                    let value = (listening.process(shifted) * settings.volume).clamp(-1.0, 1.0);
+                    for out in frame.iter_mut() {
+                        *out = T::from_sample(value);
+                    }
+                }
+            },
+            |err| error!("Erreur du flux du retour casque: {}", err),
+            None,
+        )?;
+
+        Ok(stream)
+    }
+}
+
+// Décalage de hauteur par ligne à retard modulée: deux lectures décalées d'une
+// demi-fenêtre et fondues en sinus pour masquer les sauts de la ligne à retard.
+struct PitchShifter {
+    buffer: Vec<f32>,
+    write_pos: usize,
+    window: f32,
+    phase: f32,
+}
+
+impl PitchShifter {
+    fn new(window: usize) -> Self {
+        let window = window.max(64);
+        Self {
+            buffer: vec![0.0; window * 2 + 4],
+            write_pos: 0,
+            window: window as f32,
+            phase: 0.0,
+        }
+    }
+
+    fn read(&self, delay: f32) -> f32 {
+        let len = self.buffer.len() as f32;
+        let position = (self.write_pos as f32 - delay).rem_euclid(len);
+        let index = position.floor() as usize;
+        let frac = position - index as f32;
+        let a = self.buffer[index % self.buffer.len()];
+        let b = self.buffer[(index + 1) % self.buffer.len()];
+        a + (b - a) * frac
+    }
+
+    fn process(&mut self, input: f32, ratio: f32) -> f32 {
+        self.buffer[self.write_pos] = input;
+
+        self.phase = (self.phase + (1.0 - ratio) / self.window).rem_euclid(1.0);
+        let second = (self.phase + 0.5).rem_euclid(1.0);
+
+        let output = self.read(self.phase * self.window + 1.0)
+            * (std::f32::consts::PI * self.phase).sin()
+            + self.read(second * self.window + 1.0) * (std::f32::consts::PI * second).sin();
+
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+        output
+    }
+}
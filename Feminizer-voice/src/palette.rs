@@ -0,0 +1,116 @@
+use eframe::egui;
+
+const MAX_RESULTS: usize = 12;
+
+/// Score d'une correspondance approximative: toutes les lettres de la requête
+/// doivent apparaître dans l'ordre. Les lettres consécutives et les débuts de
+/// mot comptent davantage, les trous pénalisent. `None` sans correspondance.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        let found = (position..candidate.len()).find(|&i| candidate[i] == wanted)?;
+        let word_start = found == 0 || !candidate[found - 1].is_alphanumeric();
+        score += match previous {
+            Some(p) if p + 1 == found => 8,
+            _ if word_start => 6,
+            Some(p) => 1 - (found - p).min(5) as i32,
+            None => 1 - found.min(5) as i32,
+        };
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+/// Palette de commandes (Ctrl+K): recherche approximative parmi toutes les
+/// actions de l'application.
+#[derive(Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// Renvoie l'action choisie, le cas échéant.
+    pub fn show<A: Copy>(&mut self, ctx: &egui::Context, commands: &[(String, A)]) -> Option<A> {
+        let shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::K);
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+            self.open = !self.open;
+            self.query.clear();
+            self.selected = 0;
+        }
+        if !self.open {
+            return None;
+        }
+
+        let mut matches: Vec<(i32, usize)> = commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (label, _))| {
+                fuzzy_score(&self.query, label).map(|score| (score, index))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        matches.truncate(MAX_RESULTS);
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down && self.selected + 1 < matches.len() {
+            self.selected += 1;
+        }
+
+        let mut chosen = enter.then(|| matches.get(self.selected)).flatten().map(|m| m.1);
+        egui::Window::new("Palette de commandes")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size([420.0, 0.0])
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .show(ctx, |ui| {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Rechercher une action…")
+                        .desired_width(f32::INFINITY),
+                );
+                input.request_focus();
+                if input.changed() {
+                    self.selected = 0;
+                }
+                ui.separator();
+
+                if matches.is_empty() {
+                    ui.weak("Aucune action ne correspond.");
+                }
+                for (row, &(_, index)) in matches.iter().enumerate() {
+                    let label = &commands[index].0;
+                    if ui.selectable_label(row == self.selected, label).clicked() {
+                        chosen = Some(index);
+                    }
+                }
+                ui.small("↑↓ pour choisir, Entrée pour lancer, Échap pour fermer");
+            });
+
+        if escape || chosen.is_some() {
+            self.open = false;
+        }
+        chosen.map(|index| commands[index].1)
+    }
+}
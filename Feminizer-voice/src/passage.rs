@@ -0,0 +1,405 @@
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints, Polygon};
+use feminizer_voice_core::dtw_path;
+
+use crate::pitch_unit::PitchScale;
+
+/// Les prises sont ramenées à des pas de 50 ms avant l'alignement: le DTW
+/// reste quadratique, mais sur quelques milliers de points au plus.
+const BIN_SECS: f32 = 0.05;
+const SILENCE_DB: f32 = -60.0;
+const SENTENCE_GAP_SECS: f32 = 0.4;
+const MIN_SENTENCE_SECS: f32 = 0.5;
+/// Part des prises sous la cible à partir de laquelle une phrase est signalée.
+const PROBLEM_SHARE: f32 = 0.6;
+
+#[derive(Clone, Copy, Default)]
+struct Bin {
+    /// Hauteur médiane du pas, 0 sans voix.
+    pitch: f32,
+    /// Enveloppe en dB relative au niveau médian de la prise.
+    level_db: f32,
+}
+
+#[derive(Default)]
+struct TakeRecorder {
+    pitches: Vec<f32>,
+    amplitude_sum: f32,
+    frames: usize,
+    elapsed: f32,
+    bins: Vec<Bin>,
+}
+
+impl TakeRecorder {
+    fn push(&mut self, pitch: f32, amplitude: f32, frame_duration: f32) {
+        if pitch > 0.0 {
+            self.pitches.push(pitch);
+        }
+        self.amplitude_sum += amplitude;
+        self.frames += 1;
+        self.elapsed += frame_duration;
+
+        while self.elapsed >= BIN_SECS {
+            self.elapsed -= BIN_SECS;
+            self.close_bin();
+        }
+    }
+
+    fn close_bin(&mut self) {
+        let pitch = if self.pitches.is_empty() {
+            0.0
+        } else {
+            self.pitches.sort_by(|a, b| a.total_cmp(b));
+            self.pitches[self.pitches.len() / 2]
+        };
+        let amplitude = self.amplitude_sum / self.frames.max(1) as f32;
+        self.bins.push(Bin {
+            pitch,
+            level_db: (20.0 * amplitude.max(1e-6).log10()).max(SILENCE_DB),
+        });
+        self.pitches.clear();
+        self.amplitude_sum = 0.0;
+        self.frames = 0;
+    }
+
+    fn finish(mut self) -> Vec<Bin> {
+        // Enveloppe relative: deux prises à des gains différents restent comparables
+        let mut levels: Vec<f32> = self
+            .bins
+            .iter()
+            .filter(|bin| bin.pitch > 0.0)
+            .map(|bin| bin.level_db)
+            .collect();
+        if !levels.is_empty() {
+            levels.sort_by(|a, b| a.total_cmp(b));
+            let median = levels[levels.len() / 2];
+            for bin in &mut self.bins {
+                bin.level_db -= median;
+            }
+        }
+        self.bins
+    }
+}
+
+struct Sentence {
+    start: usize,
+    end: usize,
+    /// Nombre de prises dont la médiane de la phrase est sous la cible.
+    below: usize,
+}
+
+struct Average {
+    mean: Vec<f32>,
+    low: Vec<f32>,
+    high: Vec<f32>,
+    takes: Vec<Vec<f32>>,
+    sentences: Vec<Sentence>,
+}
+
+fn semitones(frequency: f32) -> f32 {
+    12.0 * (frequency / 440.0).log2()
+}
+
+fn frequency(semitones: f32) -> f32 {
+    440.0 * 2.0_f32.powf(semitones / 12.0)
+}
+
+fn median(values: &mut [f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    Some(values[values.len() / 2])
+}
+
+/// Aligne chaque prise sur la première puis moyenne les hauteurs, en
+/// demi-tons, pas par pas de la prise de référence.
+fn average(takes: &[Vec<Bin>], target_min: f32) -> Average {
+    let reference = &takes[0];
+    let envelope = |take: &[Bin]| take.iter().map(|bin| bin.level_db).collect::<Vec<f32>>();
+    let reference_envelope = envelope(reference);
+
+    let warped: Vec<Vec<f32>> = takes
+        .iter()
+        .map(|take| {
+            let mut sums = vec![(0.0_f32, 0_usize); reference.len()];
+            for (i, j) in dtw_path(&reference_envelope, &envelope(take)) {
+                if take[j].pitch > 0.0 {
+                    sums[i].0 += semitones(take[j].pitch);
+                    sums[i].1 += 1;
+                }
+            }
+            sums.iter()
+                .map(|&(sum, count)| if count > 0 { frequency(sum / count as f32) } else { 0.0 })
+                .collect()
+        })
+        .collect();
+
+    let mut mean = Vec::with_capacity(reference.len());
+    let mut low = Vec::with_capacity(reference.len());
+    let mut high = Vec::with_capacity(reference.len());
+    for i in 0..reference.len() {
+        let values: Vec<f32> = warped
+            .iter()
+            .filter(|take| take[i] > 0.0)
+            .map(|take| semitones(take[i]))
+            .collect();
+        // Au moins la moitié des prises doivent être voisées pour moyenner
+        if values.len() * 2 < takes.len() || values.is_empty() {
+            mean.push(0.0);
+            low.push(0.0);
+            high.push(0.0);
+            continue;
+        }
+        let m = values.iter().sum::<f32>() / values.len() as f32;
+        let sd = (values.iter().map(|v| (v - m).powi(2)).sum::<f32>() / values.len() as f32).sqrt();
+        mean.push(frequency(m));
+        low.push(frequency(m - sd));
+        high.push(frequency(m + sd));
+    }
+
+    let sentences = sentences(reference)
+        .into_iter()
+        .map(|(start, end)| Sentence {
+            start,
+            end,
+            below: warped
+                .iter()
+                .filter(|take| {
+                    let mut voiced: Vec<f32> =
+                        take[start..end].iter().copied().filter(|&f| f > 0.0).collect();
+                    median(&mut voiced).is_some_and(|m| m < target_min)
+                })
+                .count(),
+        })
+        .collect();
+
+    Average {
+        mean,
+        low,
+        high,
+        takes: warped,
+        sentences,
+    }
+}
+
+/// Phrases de la prise de référence: passages voisés séparés par des pauses.
+fn sentences(reference: &[Bin]) -> Vec<(usize, usize)> {
+    let gap = (SENTENCE_GAP_SECS / BIN_SECS) as usize;
+    let min_len = (MIN_SENTENCE_SECS / BIN_SECS) as usize;
+    let mut sentences = Vec::new();
+    let mut start = None;
+    let mut silent = 0;
+
+    for (i, bin) in reference.iter().enumerate() {
+        if bin.pitch > 0.0 {
+            start.get_or_insert(i);
+            silent = 0;
+        } else {
+            silent += 1;
+            if silent >= gap
+                && let Some(s) = start.take()
+            {
+                let end = i + 1 - silent;
+                if end - s >= min_len {
+                    sentences.push((s, end));
+                }
+            }
+        }
+    }
+    if let Some(s) = start {
+        let end = reference.len() - silent;
+        if end - s >= min_len {
+            sentences.push((s, end));
+        }
+    }
+    sentences
+}
+
+/// Lectures répétées d'un même passage, alignées et moyennées.
+#[derive(Default)]
+pub struct PassagePractice {
+    recorder: Option<TakeRecorder>,
+    takes: Vec<Vec<Bin>>,
+    average: Option<Average>,
+}
+
+impl PassagePractice {
+    pub fn is_recording_take(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// `pitch` vaut 0 pour une trame sans voix ou hors plage.
+    pub fn push_frame(&mut self, pitch: f32, amplitude: f32, frame_duration: f32) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.push(pitch, amplitude, frame_duration);
+        }
+    }
+
+    fn finish_take(&mut self, target_min: f32) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+        let bins = recorder.finish();
+        if bins.iter().any(|bin| bin.pitch > 0.0) {
+            self.takes.push(bins);
+            self.average = (self.takes.len() >= 2).then(|| average(&self.takes, target_min));
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        target_min: f32,
+        target_max: f32,
+        scale: PitchScale,
+    ) {
+        ui.heading("📖 Lecture répétée d'un passage");
+        ui.label(
+            "Lisez le même passage plusieurs fois, une prise à la fois. Les prises sont \
+             alignées sur la première pour faire ressortir les phrases qui décrochent à chaque fois.",
+        );
+
+        ui.horizontal(|ui| {
+            if self.is_recording_take() {
+                if ui.button("⏹ Terminer la prise").clicked() {
+                    self.finish_take(target_min);
+                }
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("⏺ Nouvelle prise"))
+                .clicked()
+            {
+                self.recorder = Some(TakeRecorder::default());
+            }
+            if !is_recording && !self.is_recording_take() {
+                ui.small("Démarrez l'enregistrement pour ajouter une prise.");
+            }
+
+            ui.label(format!("{} prise(s)", self.takes.len()));
+            if ui
+                .add_enabled(!self.takes.is_empty(), egui::Button::new("🗑 Tout effacer"))
+                .clicked()
+            {
+                self.takes.clear();
+                self.average = None;
+            }
+        });
+
+        let Some(average) = &self.average else {
+            if self.takes.len() == 1 {
+                ui.small("Encore une prise au moins pour calculer la moyenne.");
+            }
+            return;
+        };
+
+        let time = |i: usize| i as f64 * BIN_SECS as f64;
+        let problem = |sentence: &Sentence| {
+            sentence.below as f32 >= PROBLEM_SHARE * average.takes.len() as f32
+        };
+
+        let plot = Plot::new("passage_average")
+            .height(300.0)
+            .legend(Legend::default())
+            .x_axis_label("Temps de la première prise (s)");
+        scale.y_axis(plot)
+            .show(ui, |plot_ui| {
+                for sentence in average.sentences.iter().filter(|s| problem(s)) {
+                    let (x0, x1) = (time(sentence.start), time(sentence.end));
+                    plot_ui.polygon(
+                        Polygon::new(
+                            "Phrase souvent sous la cible",
+                            PlotPoints::from(vec![
+                                [x0, target_min as f64 * 0.6],
+                                [x1, target_min as f64 * 0.6],
+                                [x1, target_max as f64 * 1.2],
+                                [x0, target_max as f64 * 1.2],
+                            ]),
+                        )
+                        .fill_color(egui::Color32::from_rgba_unmultiplied(255, 60, 60, 30))
+                        .stroke(egui::Stroke::NONE),
+                    );
+                }
+
+                plot_ui.hline(HLine::new("Cible min", target_min).color(egui::Color32::GREEN));
+                plot_ui.hline(HLine::new("Cible max", target_max).color(egui::Color32::GREEN));
+
+                for take in &average.takes {
+                    for run in voiced_runs(take) {
+                        let points: PlotPoints =
+                            run.map(|i| [time(i), take[i] as f64]).collect();
+                        plot_ui.line(
+                            Line::new("Prises", points).color(egui::Color32::from_gray(90)),
+                        );
+                    }
+                }
+
+                let band_color = egui::Color32::from_rgba_unmultiplied(255, 0, 255, 50);
+                for run in voiced_runs(&average.mean) {
+                    let run: Vec<usize> = run.collect();
+                    // Un trapèze par pas: un seul polygone concave serait mal rempli
+                    for pair in run.windows(2) {
+                        let (a, b) = (pair[0], pair[1]);
+                        plot_ui.polygon(
+                            Polygon::new(
+                                "Écart type entre prises",
+                                PlotPoints::from(vec![
+                                    [time(a), average.low[a] as f64],
+                                    [time(b), average.low[b] as f64],
+                                    [time(b), average.high[b] as f64],
+                                    [time(a), average.high[a] as f64],
+                                ]),
+                            )
+                            .fill_color(band_color)
+                            .stroke(egui::Stroke::NONE),
+                        );
+                    }
+                    let points: PlotPoints =
+                        run.iter().map(|&i| [time(i), average.mean[i] as f64]).collect();
+                    plot_ui.line(
+                        Line::new("Moyenne", points)
+                            .color(egui::Color32::from_rgb(255, 0, 255))
+                            .width(2.0),
+                    );
+                }
+            });
+
+        egui::Grid::new("passage_sentences").striped(true).show(ui, |ui| {
+            ui.label("Phrase");
+            ui.label("Début");
+            ui.label("Fin");
+            ui.label("Sous la cible");
+            ui.end_row();
+            for (index, sentence) in average.sentences.iter().enumerate() {
+                ui.label(format!("{}", index + 1));
+                ui.label(format!("{:.1} s", time(sentence.start)));
+                ui.label(format!("{:.1} s", time(sentence.end)));
+                let text = format!("{} / {} prises", sentence.below, average.takes.len());
+                if problem(sentence) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 90, 90), format!("⚠ {}", text));
+                } else {
+                    ui.label(text);
+                }
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// Plages d'indices consécutifs où la courbe est définie.
+fn voiced_runs(values: &[f32]) -> impl Iterator<Item = std::ops::Range<usize>> + '_ {
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < values.len() && values[i] <= 0.0 {
+            i += 1;
+        }
+        if i >= values.len() {
+            return None;
+        }
+        let start = i;
+        while i < values.len() && values[i] > 0.0 {
+            i += 1;
+        }
+        Some(start..i)
+    })
+}
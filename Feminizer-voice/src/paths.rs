@@ -0,0 +1,44 @@
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Profil actif; `None` pour le profil principal, rangé à la racine.
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn data_dir() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .ok_or_else(|| anyhow::anyhow!("Dossier de données introuvable"))?
+        .join("feminizer-voice");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn data_subdir(name: &str) -> Result<PathBuf> {
+    let dir = data_dir()?.join(name);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn set_profile(name: Option<&str>) {
+    if let Ok(mut profile) = PROFILE.write() {
+        *profile = name.map(str::to_string);
+    }
+}
+
+/// Dossier des données propres au profil actif (réglages, historique…).
+pub fn profile_dir() -> Result<PathBuf> {
+    let profile = PROFILE.read().ok().and_then(|profile| profile.clone());
+    let dir = match profile {
+        Some(name) => data_dir()?.join("profiles").join(name),
+        None => data_dir()?,
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn profile_subdir(name: &str) -> Result<PathBuf> {
+    let dir = profile_dir()?.join(name);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
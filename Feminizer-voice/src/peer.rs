@@ -0,0 +1,297 @@
+use anyhow::Result;
+use eframe::egui;
+use feminizer_voice_core::AnalysisFrame;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tungstenite::Message;
+
+use crate::broadcast::{LiveMessage, PROTOCOL_VERSION};
+use crate::permissions::Permission;
+use crate::pitch_unit::PitchScale;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Attente maximale d'un message avant de vérifier la demande d'arrêt.
+const READ_POLL: Duration = Duration::from_millis(200);
+/// Sans trame plus récente, le pair est considéré silencieux.
+const STALE_FRAME: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PeerSettings {
+    /// Adresse du serveur WebSocket du pair, `hôte:port`.
+    pub address: String,
+}
+
+impl Default for PeerSettings {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:9001".to_string(),
+        }
+    }
+}
+
+/// Ce que le fil de lecture transmet à l'interface.
+#[derive(Default)]
+struct Incoming {
+    connected: bool,
+    app_version: Option<String>,
+    frame: Option<(AnalysisFrame, Instant)>,
+    error: Option<String>,
+}
+
+/// Séance partagée: l'application se connecte au serveur WebSocket d'une
+/// autre (la personne suivie) et affiche sa hauteur à côté de la sienne.
+/// Seules les trames d'analyse circulent, jamais le son.
+#[derive(Default)]
+pub struct PeerLink {
+    address: Option<SocketAddr>,
+    running: Arc<AtomicBool>,
+    incoming: Arc<Mutex<Incoming>>,
+    /// Dernière hauteur fiable du pair, 0 hors voix.
+    current: f32,
+    last_frame_at: Option<Instant>,
+    /// Une valeur par trame de l'historique du micro, comme le second flux.
+    history: VecDeque<f32>,
+    pub error: Option<String>,
+}
+
+impl PeerLink {
+    pub fn history(&self) -> &VecDeque<f32> {
+        &self.history
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.incoming.lock().is_ok_and(|incoming| incoming.connected)
+    }
+
+    pub fn label(&self) -> String {
+        self.address
+            .map_or("Pair".to_string(), |address| format!("Pair {}", address))
+    }
+
+    /// Une ligne pour l'indicateur global du réseau.
+    pub fn describe(&self) -> Option<String> {
+        let address = self.address?;
+        Some(format!("Séance partagée ← ws://{}", address))
+    }
+
+    pub fn connect(&mut self, settings: &PeerSettings, permission: &Permission) -> Result<()> {
+        self.disconnect();
+        if !permission.allowed {
+            anyhow::bail!("connexion à un pair non autorisée");
+        }
+        let address = settings
+            .address
+            .trim()
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("adresse introuvable: {}", settings.address))?;
+        if !permission.scope.permits(&address) {
+            anyhow::bail!("pair {} hors de cette machine, non autorisé", address);
+        }
+
+        let running = Arc::new(AtomicBool::new(true));
+        let incoming: Arc<Mutex<Incoming>> = Default::default();
+        let thread_running = running.clone();
+        let thread_incoming = incoming.clone();
+        thread::spawn(move || {
+            let result = read_loop(address, &thread_incoming, &thread_running);
+            if let Ok(mut incoming) = thread_incoming.lock() {
+                incoming.connected = false;
+                if let Err(e) = result {
+                    incoming.error = Some(e.to_string());
+                }
+            }
+        });
+        self.address = Some(address);
+        self.running = running;
+        self.incoming = incoming;
+        self.error = None;
+        Ok(())
+    }
+
+    pub fn disconnect(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        self.address = None;
+        self.current = 0.0;
+        self.last_frame_at = None;
+        self.history.clear();
+    }
+
+    /// Coupe la connexion si l'autorisation ne la couvre plus.
+    pub fn enforce(&mut self, permission: &Permission) {
+        if self
+            .address
+            .is_some_and(|address| !permission.allowed || !permission.scope.permits(&address))
+        {
+            self.disconnect();
+        }
+    }
+
+    /// Relève la dernière trame reçue; comme pour le second flux, seule compte
+    /// la hauteur au moment de chaque trame du micro.
+    pub fn poll(&mut self, accepted: RangeInclusive<f32>, min_confidence: f32) {
+        if self.address.is_none() {
+            return;
+        }
+        let received = match self.incoming.lock() {
+            Ok(mut incoming) => {
+                if let Some(error) = incoming.error.take() {
+                    self.error = Some(format!("Séance partagée: {}", error));
+                }
+                incoming.frame.take()
+            }
+            Err(_) => None,
+        };
+        if let Some((frame, at)) = received {
+            let reliable = frame.is_voiced
+                && frame.confidence >= min_confidence
+                && accepted.contains(&frame.frequency);
+            self.current = if reliable { frame.frequency } else { 0.0 };
+            self.last_frame_at = Some(at);
+        } else if self.last_frame_at.is_none_or(|at| at.elapsed() > STALE_FRAME) {
+            self.current = 0.0;
+        }
+    }
+
+    /// À appeler à chaque trame ajoutée à l'historique du micro.
+    pub fn push_history(&mut self, max_len: usize) {
+        if self.address.is_none() {
+            return;
+        }
+        self.history.push_back(self.current);
+        while self.history.len() > max_len {
+            self.history.pop_front();
+        }
+    }
+
+    /// Renvoie `true` si l'adresse a changé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        settings: &mut PeerSettings,
+        permission: &Permission,
+        scale: PitchScale,
+    ) -> bool {
+        ui.small(
+            "La personne suivie active la diffusion WebSocket, autorisée sur le réseau \
+             local; le coach saisit ici son adresse et voit sa courbe de hauteur à côté \
+             de la sienne pendant l'enregistrement. Le son n'est jamais transmis.",
+        );
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Adresse du pair:");
+            changed |= ui
+                .add_enabled(
+                    self.address.is_none(),
+                    egui::TextEdit::singleline(&mut settings.address).desired_width(160.0),
+                )
+                .changed();
+            if self.address.is_some() {
+                if ui.button("Se déconnecter").clicked() {
+                    self.disconnect();
+                }
+            } else if ui
+                .add_enabled(permission.allowed, egui::Button::new("🤝 Se connecter"))
+                .on_disabled_hover_text("Autorisez d'abord la connexion à un pair")
+                .clicked()
+                && let Err(e) = self.connect(settings, permission)
+            {
+                self.error = Some(format!("Séance partagée: {}", e));
+            }
+        });
+
+        if self.address.is_some() {
+            let version = self
+                .incoming
+                .lock()
+                .ok()
+                .and_then(|incoming| incoming.app_version.clone());
+            match (self.is_connected(), version) {
+                (true, version) => {
+                    let pitch = if self.current > 0.0 {
+                        scale.format(self.current)
+                    } else {
+                        "silence".to_string()
+                    };
+                    ui.colored_label(
+                        egui::Color32::GREEN,
+                        format!(
+                            "● Connecté (version {}) — {}",
+                            version.as_deref().unwrap_or("?"),
+                            pitch
+                        ),
+                    );
+                }
+                (false, _) if self.error.is_none() => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Connexion…");
+                    });
+                }
+                (false, _) => {}
+            }
+        }
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+        changed
+    }
+}
+
+impl Drop for PeerLink {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+fn read_loop(
+    address: SocketAddr,
+    incoming: &Mutex<Incoming>,
+    running: &AtomicBool,
+) -> Result<()> {
+    let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+    let url = format!("ws://{}/?protocol={}", address, PROTOCOL_VERSION);
+    let (mut ws, _) =
+        tungstenite::client(url.as_str(), stream).map_err(|e| anyhow::anyhow!("{}", e))?;
+    ws.get_mut().set_read_timeout(Some(READ_POLL))?;
+    if let Ok(mut incoming) = incoming.lock() {
+        incoming.connected = true;
+    }
+
+    while running.load(Ordering::Relaxed) {
+        let text = match ws.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => anyhow::bail!("connexion fermée par le pair"),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let Ok(message) = serde_json::from_str::<LiveMessage>(text.as_str()) else {
+            continue;
+        };
+        let Ok(mut incoming) = incoming.lock() else {
+            break;
+        };
+        match message {
+            LiveMessage::Hello { app_version, .. } => incoming.app_version = Some(app_version),
+            LiveMessage::Frame { frame, .. } => incoming.frame = Some((frame, Instant::now())),
+            LiveMessage::Stats { .. } => {}
+        }
+    }
+    let _ = ws.close(None);
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use eframe::egui;
+use feminizer_voice_core::AnalysisFrame;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// L'estimation demande la fonction Cargo `perception` (onnxruntime).
+pub const AVAILABLE: bool = cfg!(feature = "perception");
+
+/// Mesures passées au modèle, dans cet ordre: F0, F1, F2 (Hz), H1–H2 (dB)
+/// et centroïde spectral (Hz), chacune en médiane sur les trames voisées.
+pub const FEATURE_COUNT: usize = 5;
+/// Voix prise en compte par une estimation: quelques syllabes.
+const WINDOW_SECS: f32 = 3.0;
+const MIN_VOICED_SECS: f32 = 1.0;
+const REFRESH_SECS: f32 = 0.5;
+
+const CAVEAT: &str = "Estimation d'un modèle statistique entraîné sur d'autres voix. Elle dépend \
+                      du micro, de la langue et de ce que vous dites, et chaque personne entend \
+                      une voix à sa façon. Un indice parmi d'autres, jamais un verdict sur vous.";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PerceptionSettings {
+    pub enabled: bool,
+    /// Modèle ONNX local: entrée `[1, 5]` en f32, sortie la probabilité
+    /// qu'une voix soit perçue comme féminine, entre 0 et 1.
+    pub model: String,
+}
+
+impl PerceptionSettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if !AVAILABLE {
+            ui.weak("Cette version a été compilée sans estimation (fonction « perception »).");
+            return false;
+        }
+        ui.small(CAVEAT);
+        let mut changed = ui
+            .checkbox(&mut self.enabled, "Afficher la perception estimée pendant l'enregistrement")
+            .on_hover_text("Tout est calculé sur cette machine, rien n'est envoyé")
+            .changed();
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Modèle:");
+                changed |= ui
+                    .text_edit_singleline(&mut self.model)
+                    .on_hover_text(
+                        "Chemin d'un fichier .onnx. Entrée [1, 5] en f32: F0, F1, F2 (Hz), \
+                         H1–H2 (dB), centroïde (Hz). Sortie: probabilité entre 0 et 1",
+                    )
+                    .lost_focus();
+            });
+        });
+        changed
+    }
+}
+
+/// Médianes des mesures des trames voisées, dans l'ordre de
+/// [`FEATURE_COUNT`]. `None` si une mesure manque sur toutes les trames.
+pub fn features<'a>(
+    frames: impl IntoIterator<Item = &'a AnalysisFrame>,
+) -> Option<[f32; FEATURE_COUNT]> {
+    let mut columns: [Vec<f32>; FEATURE_COUNT] = Default::default();
+    for frame in frames.into_iter().filter(|frame| frame.frequency > 0.0) {
+        columns[0].push(frame.frequency);
+        if let Some((f1, f2)) = frame.formants {
+            columns[1].push(f1);
+            columns[2].push(f2);
+        }
+        columns[3].extend(frame.h1_h2_db);
+        columns[4].push(frame.brightness);
+    }
+    let mut features = [0.0; FEATURE_COUNT];
+    for (feature, column) in features.iter_mut().zip(&mut columns) {
+        if column.is_empty() {
+            return None;
+        }
+        column.sort_by(f32::total_cmp);
+        *feature = column[column.len() / 2];
+    }
+    Some(features)
+}
+
+/// Jauge de perception: garde les dernières secondes de voix et demande
+/// une nouvelle estimation au modèle deux fois par seconde.
+#[derive(Default)]
+pub struct PerceptionGauge {
+    voiced: VecDeque<AnalysisFrame>,
+    since_estimate: f32,
+    /// Probabilité d'être perçue comme féminine, entre 0 et 1.
+    estimate: Option<f32>,
+    #[cfg(feature = "perception")]
+    model: Option<imp::Model>,
+    /// Modèle demandé au dernier chargement, même s'il a échoué.
+    #[cfg(feature = "perception")]
+    model_path: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PerceptionGauge {
+    /// À appeler au démarrage de l'enregistrement.
+    pub fn reset(&mut self) {
+        self.voiced.clear();
+        self.since_estimate = 0.0;
+        self.estimate = None;
+    }
+
+    /// Trame voisée et fiable, `frequency` déjà filtrée.
+    pub fn push(
+        &mut self,
+        settings: &PerceptionSettings,
+        frame: &AnalysisFrame,
+        frame_duration: f32,
+    ) {
+        if !AVAILABLE || !settings.enabled {
+            self.reset();
+            return;
+        }
+        self.voiced.push_back(*frame);
+        let capacity = (WINDOW_SECS / frame_duration).ceil() as usize;
+        while self.voiced.len() > capacity {
+            self.voiced.pop_front();
+        }
+
+        self.since_estimate += frame_duration;
+        let voiced_secs = self.voiced.len() as f32 * frame_duration;
+        if self.since_estimate < REFRESH_SECS || voiced_secs < MIN_VOICED_SECS {
+            return;
+        }
+        self.since_estimate = 0.0;
+        if let Some(features) = features(&self.voiced) {
+            self.estimate = self.run(settings, &features).or(self.estimate);
+        }
+    }
+
+    #[cfg(not(feature = "perception"))]
+    fn run(&mut self, _settings: &PerceptionSettings, _features: &[f32]) -> Option<f32> {
+        None
+    }
+
+    #[cfg(feature = "perception")]
+    fn run(&mut self, settings: &PerceptionSettings, features: &[f32]) -> Option<f32> {
+        let path = settings.model.trim();
+        if self.model_path.as_deref() != Some(path) {
+            self.model_path = Some(path.to_string());
+            self.model = None;
+            match imp::Model::load(path) {
+                Ok(model) => {
+                    self.model = Some(model);
+                    self.error = None;
+                }
+                Err(e) => self.error = Some(format!("Modèle de perception: {}", e)),
+            }
+        }
+        let model = self.model.as_mut()?;
+        match model.estimate(features) {
+            Ok(probability) => Some(probability.clamp(0.0, 1.0)),
+            Err(e) => {
+                self.error = Some(format!("Modèle de perception: {}", e));
+                self.model = None;
+                None
+            }
+        }
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui, settings: &PerceptionSettings) {
+        if !AVAILABLE || !settings.enabled {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("⚖ Perception estimée:").on_hover_text(CAVEAT);
+            match self.estimate {
+                Some(probability) => {
+                    ui.add(
+                        egui::ProgressBar::new(probability)
+                            .desired_width(160.0)
+                            .text(format!("féminine à {:.0} %", probability * 100.0)),
+                    )
+                    .on_hover_text(CAVEAT);
+                }
+                None => {
+                    ui.weak(format!("après {:.0} s de voix", MIN_VOICED_SECS));
+                }
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(egui::Color32::RED, "⚠").on_hover_text(error);
+            }
+        });
+    }
+}
+
+#[cfg(feature = "perception")]
+mod imp {
+    use anyhow::{Result, anyhow};
+    use ort::session::Session;
+    use ort::value::TensorRef;
+
+    use super::FEATURE_COUNT;
+
+    pub struct Model {
+        session: Session,
+    }
+
+    impl Model {
+        pub fn load(path: &str) -> Result<Self> {
+            if path.is_empty() {
+                anyhow::bail!("aucun fichier choisi");
+            }
+            let session = Session::builder()?.commit_from_file(path)?;
+            Ok(Self { session })
+        }
+
+        pub fn estimate(&mut self, features: &[f32]) -> Result<f32> {
+            let input = TensorRef::from_array_view(([1, FEATURE_COUNT], features))?;
+            let outputs = self.session.run(ort::inputs![input])?;
+            let (_, values) = outputs[0].try_extract_tensor::<f32>()?;
+            values
+                .first()
+                .copied()
+                .ok_or_else(|| anyhow!("sortie vide"))
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// Jusqu'où une fonction réseau peut écouter ou émettre.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkScope {
+    #[default]
+    Localhost,
+    Network,
+}
+
+impl NetworkScope {
+    pub fn bind_ip(self) -> IpAddr {
+        match self {
+            NetworkScope::Localhost => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            NetworkScope::Network => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        }
+    }
+
+    pub fn permits(self, address: &SocketAddr) -> bool {
+        self == NetworkScope::Network || address.ip().is_loopback()
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            NetworkScope::Localhost => "Cette machine",
+            NetworkScope::Network => "Réseau local",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Permission {
+    pub allowed: bool,
+    pub scope: NetworkScope,
+}
+
+/// Autorisations de toutes les fonctions réseau, refusées par défaut. Une
+/// fonction activée dans ses propres réglages ne démarre que si elle est
+/// autorisée ici.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NetworkPermissions {
+    pub websocket: Permission,
+    pub osc: Permission,
+    /// Connexion sortante au serveur WebSocket d'un pair (séance partagée).
+    pub peer: Permission,
+}
+
+impl NetworkPermissions {
+    /// Affiche le panneau; renvoie vrai si une autorisation a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        egui::Grid::new("network_permissions").striped(true).show(ui, |ui| {
+            ui.label("Fonction");
+            ui.label("Autorisée");
+            ui.label("Portée");
+            ui.end_row();
+
+            for (label, permission) in [
+                ("Serveur WebSocket", &mut self.websocket),
+                ("Envoi OSC", &mut self.osc),
+                ("Connexion à un pair", &mut self.peer),
+            ] {
+                ui.label(label);
+                changed |= ui.checkbox(&mut permission.allowed, "").changed();
+                ui.add_enabled_ui(permission.allowed, |ui| {
+                    egui::ComboBox::from_id_salt(label)
+                        .selected_text(permission.scope.label())
+                        .show_ui(ui, |ui| {
+                            for scope in [NetworkScope::Localhost, NetworkScope::Network] {
+                                changed |= ui
+                                    .selectable_value(&mut permission.scope, scope, scope.label())
+                                    .changed();
+                            }
+                        });
+                });
+                ui.end_row();
+            }
+        });
+
+        let widened = [self.websocket, self.osc]
+            .iter()
+            .any(|p| p.allowed && p.scope == NetworkScope::Network);
+        if widened {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "⚠ « Réseau local »: d'autres appareils peuvent accéder à vos données vocales.",
+            );
+        }
+        changed
+    }
+}
@@ -0,0 +1,123 @@
+use eframe::egui;
+
+use crate::pitch_unit::PitchScale;
+use crate::theme::Palette;
+
+/// Étendue du clavier, en demi-tons au-dessus de C0: de C2 (65 Hz) à C6
+/// (1047 Hz), assez pour toutes les voix parlées et chantées courantes.
+const LOWEST: i32 = 24;
+const HIGHEST: i32 = 72;
+const HEIGHT: f32 = 64.0;
+/// Rang de chaque touche blanche dans l'octave, `None` pour les noires.
+const WHITE_RANK: [Option<i32>; 12] = [
+    Some(0),
+    None,
+    Some(1),
+    None,
+    Some(2),
+    Some(3),
+    None,
+    Some(4),
+    None,
+    Some(5),
+    None,
+    Some(6),
+];
+
+/// Bord gauche d'une touche blanche, ou milieu d'une touche noire, en
+/// largeurs de touche blanche depuis le début du clavier.
+fn position(semitone: i32) -> f32 {
+    let white = |semitone: i32| {
+        let rank = WHITE_RANK[semitone.rem_euclid(12) as usize].unwrap_or_default();
+        semitone.div_euclid(12) * 7 + rank
+    };
+    let offset = match WHITE_RANK[semitone.rem_euclid(12) as usize] {
+        Some(_) => white(semitone),
+        // Une noire est à cheval entre la blanche d'en dessous et la suivante
+        None => white(semitone - 1) + 1,
+    };
+    (offset - white(LOWEST)) as f32
+}
+
+fn is_black(semitone: i32) -> bool {
+    WHITE_RANK[semitone.rem_euclid(12) as usize].is_none()
+}
+
+/// Clavier horizontal: la touche de la note la plus proche de `frequency`
+/// s'allume et les touches de la plage cible sont teintées.
+pub fn draw_keyboard(
+    ui: &mut egui::Ui,
+    scale: PitchScale,
+    frequency: f32,
+    (target_min, target_max): (f32, f32),
+    palette: &Palette,
+) {
+    let width = ui.available_width();
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(width, HEIGHT), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let key_width = width / (position(HIGHEST) + 1.0);
+
+    let target = scale.semitones_above_c0(target_min).round() as i32
+        ..=scale.semitones_above_c0(target_max).round() as i32;
+    let current = (frequency > 0.0).then(|| scale.semitones_above_c0(frequency).round() as i32);
+    let in_target = (target_min..=target_max).contains(&frequency);
+    let fill = |semitone: i32, base: egui::Color32| {
+        if current == Some(semitone) {
+            if in_target { palette.in_range } else { palette.pitch }
+        } else if target.contains(&semitone) {
+            base.lerp_to_gamma(palette.target, 0.35)
+        } else {
+            base
+        }
+    };
+
+    let outline = egui::Stroke::new(1.0, egui::Color32::from_gray(60));
+    for semitone in (LOWEST..=HIGHEST).filter(|&s| !is_black(s)) {
+        let left = rect.left() + position(semitone) * key_width;
+        let key = egui::Rect::from_min_size(
+            egui::pos2(left, rect.top()),
+            egui::vec2(key_width, HEIGHT),
+        );
+        painter.rect(
+            key,
+            2.0,
+            fill(semitone, egui::Color32::WHITE),
+            outline,
+            egui::StrokeKind::Inside,
+        );
+        if semitone.rem_euclid(12) == 0 {
+            painter.text(
+                key.center_bottom() - egui::vec2(0.0, 2.0),
+                egui::Align2::CENTER_BOTTOM,
+                format!("C{}", semitone / 12),
+                egui::FontId::proportional(10.0),
+                egui::Color32::from_gray(90),
+            );
+        }
+    }
+    for semitone in (LOWEST..=HIGHEST).filter(|&s| is_black(s)) {
+        let center = rect.left() + position(semitone) * key_width;
+        let key = egui::Rect::from_center_size(
+            egui::pos2(center, rect.top() + HEIGHT * 0.3),
+            egui::vec2(key_width * 0.6, HEIGHT * 0.6),
+        );
+        painter.rect(
+            key,
+            1.0,
+            fill(semitone, egui::Color32::from_gray(25)),
+            outline,
+            egui::StrokeKind::Inside,
+        );
+    }
+
+    let text = match current {
+        Some(semitone) if (LOWEST..=HIGHEST).contains(&semitone) => {
+            let (note, cents) = scale.note_and_cents(frequency);
+            format!("Note jouée: {} {:+}¢", note, cents)
+        }
+        Some(_) => "Hauteur hors du clavier".to_string(),
+        None => "Aucune voix".to_string(),
+    };
+    response.on_hover_text(text);
+}
@@ -0,0 +1,167 @@
+use egui_plot::{GridInput, GridMark, Plot};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+/// Demi-tons de C0 à A4.
+const A4_ABOVE_C0: f32 = 57.0;
+/// En dessous, les axes en notes ou en demi-tons n'ont plus de sens.
+const AXIS_FLOOR_HZ: f64 = 20.0;
+pub const A4_RANGE_HZ: std::ops::RangeInclusive<f32> = 415.0..=466.0;
+
+/// Unité d'affichage des hauteurs: la littérature en orthophonie raisonne en
+/// demi-tons, les musiciens en notes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PitchUnit {
+    #[default]
+    Hertz,
+    Note,
+    Semitones,
+}
+
+impl PitchUnit {
+    pub const ALL: [PitchUnit; 3] = [PitchUnit::Hertz, PitchUnit::Note, PitchUnit::Semitones];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PitchUnit::Hertz => "Hz",
+            PitchUnit::Note => "Note + cents",
+            PitchUnit::Semitones => "Demi-tons au-dessus de C0",
+        }
+    }
+
+    fn axis_label(self) -> &'static str {
+        match self {
+            PitchUnit::Hertz => "Fréquence (Hz)",
+            PitchUnit::Note => "Note",
+            PitchUnit::Semitones => "Demi-tons au-dessus de C0",
+        }
+    }
+}
+
+/// Unité d'affichage et diapason: de quoi nommer n'importe quelle fréquence.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PitchScale {
+    pub unit: PitchUnit,
+    /// Fréquence du la 4 (diapason).
+    pub a4_hz: f32,
+}
+
+impl Default for PitchScale {
+    fn default() -> Self {
+        Self {
+            unit: PitchUnit::Hertz,
+            a4_hz: 440.0,
+        }
+    }
+}
+
+impl PitchScale {
+    fn c0_hz(self) -> f32 {
+        self.a4_hz.clamp(*A4_RANGE_HZ.start(), *A4_RANGE_HZ.end())
+            * 2.0_f32.powf(-A4_ABOVE_C0 / 12.0)
+    }
+
+    pub fn semitones_above_c0(self, frequency: f32) -> f32 {
+        12.0 * (frequency / self.c0_hz()).log2()
+    }
+
+    fn frequency_of_semitone(self, semitone: f64) -> f64 {
+        self.c0_hz() as f64 * 2.0_f64.powf(semitone / 12.0)
+    }
+
+    /// Note la plus proche et écart en cents (-50..=50).
+    pub fn note_and_cents(self, frequency: f32) -> (String, i32) {
+        let semitones = self.semitones_above_c0(frequency);
+        let nearest = semitones.round();
+        let index = nearest as i32;
+        let name = format!(
+            "{}{}",
+            NOTE_NAMES[index.rem_euclid(12) as usize],
+            index.div_euclid(12)
+        );
+        (name, ((semitones - nearest) * 100.0).round() as i32)
+    }
+
+    pub fn with_unit(self, unit: PitchUnit) -> Self {
+        Self { unit, ..self }
+    }
+
+    pub fn format(self, frequency: f32) -> String {
+        if frequency <= 0.0 {
+            return "—".to_string();
+        }
+        match self.unit {
+            PitchUnit::Hertz => format!("{:.0} Hz", frequency),
+            PitchUnit::Note => {
+                let (name, cents) = self.note_and_cents(frequency);
+                format!("{} {:+}¢", name, cents)
+            }
+            PitchUnit::Semitones => format!("{:.1} ST", self.semitones_above_c0(frequency)),
+        }
+    }
+
+    /// Graduations de l'axe des hauteurs d'un graphique tracé en Hz.
+    pub fn y_axis<'a>(self, plot: Plot<'a>) -> Plot<'a> {
+        let plot = plot.y_axis_label(self.unit.axis_label());
+        match self.unit {
+            PitchUnit::Hertz => plot,
+            _ => plot
+                .y_grid_spacer(move |input| self.semitone_marks(input))
+                .y_axis_formatter(move |mark, _| self.tick(mark.value)),
+        }
+    }
+
+    /// Comme `y_axis`, pour un graphique dont les hauteurs sont en abscisse.
+    pub fn x_axis<'a>(self, plot: Plot<'a>) -> Plot<'a> {
+        let plot = plot.x_axis_label(self.unit.axis_label());
+        match self.unit {
+            PitchUnit::Hertz => plot,
+            _ => plot
+                .x_grid_spacer(move |input| self.semitone_marks(input))
+                .x_axis_formatter(move |mark, _| self.tick(mark.value)),
+        }
+    }
+
+    fn tick(self, frequency: f64) -> String {
+        if frequency < AXIS_FLOOR_HZ {
+            return String::new();
+        }
+        match self.unit {
+            PitchUnit::Hertz => format!("{:.0}", frequency),
+            PitchUnit::Note => self.note_and_cents(frequency as f32).0,
+            PitchUnit::Semitones => format!("{:.0}", self.semitones_above_c0(frequency as f32)),
+        }
+    }
+
+    /// Une graduation par demi-ton; les do (octaves) comptent comme des pas de
+    /// 12 demi-tons pour rester visibles quand l'axe est resserré.
+    fn semitone_marks(self, input: GridInput) -> Vec<GridMark> {
+        let (low, high) = input.bounds;
+        let low = low.max(AXIS_FLOOR_HZ);
+        if high <= low {
+            return Vec::new();
+        }
+        let first = self.semitones_above_c0(low as f32).ceil() as i32;
+        let last = self.semitones_above_c0(high as f32).floor() as i32;
+
+        (first..=last)
+            .map(|semitone| {
+                let span = if semitone.rem_euclid(12) == 0 {
+                    12.0
+                } else if semitone.rem_euclid(3) == 0 {
+                    3.0
+                } else {
+                    1.0
+                };
+                let value = self.frequency_of_semitone(semitone as f64);
+                GridMark {
+                    value,
+                    step_size: self.frequency_of_semitone(semitone as f64 + span) - value,
+                }
+            })
+            .collect()
+    }
+}
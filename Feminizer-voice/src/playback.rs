@@ -0,0 +1,240 @@
+use anyhow::Result;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, Stream, StreamConfig};
+use std::sync::{Arc, Mutex};
+use tracing::error;
+
+use crate::audio_host;
+
+/// Durée d'un grain de la lecture ralentie: assez long pour contenir
+/// plusieurs périodes d'une voix grave, assez court pour ne pas s'entendre.
+const GRAIN_SECS: f32 = 0.04;
+
+struct Playing {
+    samples: Arc<Vec<f32>>,
+    /// Début du grain en cours, en échantillons de la source.
+    position: f64,
+    /// Début du grain précédent, qui s'efface pendant que le suivant monte.
+    previous: f64,
+    /// Échantillons de sortie déjà joués dans le demi-grain en cours.
+    offset: usize,
+    /// Demi-grain, en échantillons de sortie.
+    hop: usize,
+    step: f64,
+    /// Vitesse de lecture; la hauteur ne change pas.
+    speed: f64,
+    /// Région rejouée en boucle, en échantillons de la source.
+    loop_region: Option<(f64, f64)>,
+}
+
+impl Playing {
+    fn read_position(&self) -> f64 {
+        self.position + self.offset as f64 * self.step
+    }
+
+    /// Le grain suivant démarre à `position`; le précédent continue là où la
+    /// lecture en était, pour un fondu sans clic.
+    fn jump(&mut self, position: f64) {
+        self.previous = self.read_position() - self.hop as f64 * self.step;
+        self.position = position;
+        self.offset = 0;
+    }
+}
+
+/// Échantillon de la source à une position fractionnaire, par interpolation
+/// linéaire.
+fn sample_at(samples: &[f32], position: f64) -> f32 {
+    if position < 0.0 {
+        return 0.0;
+    }
+    let index = position as usize;
+    let fraction = (position - index as f64) as f32;
+    match (samples.get(index), samples.get(index + 1)) {
+        (Some(&a), Some(&b)) => a + (b - a) * fraction,
+        (Some(&a), None) => a,
+        _ => 0.0,
+    }
+}
+
+/// Lecture d'un enregistrement sur la sortie par défaut, un seul à la fois.
+///
+/// La lecture se fait par grains de [`GRAIN_SECS`] qui se chevauchent de
+/// moitié: chaque grain garde la vitesse d'origine, seul l'écart entre deux
+/// grains suit la vitesse choisie, si bien qu'un ralenti garde sa hauteur.
+pub struct ClipPlayer {
+    stream: Option<Stream>,
+    output_rate: f32,
+    source_rate: f32,
+    playing: Arc<Mutex<Option<Playing>>>,
+}
+
+impl Default for ClipPlayer {
+    fn default() -> Self {
+        Self {
+            stream: None,
+            output_rate: 44100.0,
+            source_rate: 44100.0,
+            playing: Default::default(),
+        }
+    }
+}
+
+impl ClipPlayer {
+    /// Remplace la lecture en cours, à partir de `start_secs`.
+    pub fn play(
+        &mut self,
+        samples: Arc<Vec<f32>>,
+        sample_rate: f32,
+        start_secs: f32,
+    ) -> Result<()> {
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+        self.source_rate = sample_rate;
+        if let Ok(mut playing) = self.playing.lock() {
+            let position = (start_secs.max(0.0) * sample_rate) as f64;
+            let (speed, loop_region) = playing
+                .as_ref()
+                .map_or((1.0, None), |playing| (playing.speed, playing.loop_region));
+            *playing = Some(Playing {
+                position,
+                previous: position,
+                offset: 0,
+                hop: ((GRAIN_SECS / 2.0 * self.output_rate) as usize).max(1),
+                step: (sample_rate / self.output_rate) as f64,
+                speed,
+                loop_region,
+                samples,
+            });
+        }
+        Ok(())
+    }
+
+    /// Change la vitesse de la lecture en cours, sans changer sa hauteur.
+    pub fn set_speed(&mut self, speed: f32) {
+        if let Ok(mut playing) = self.playing.lock()
+            && let Some(playing) = playing.as_mut()
+        {
+            playing.speed = speed.clamp(0.25, 2.0) as f64;
+        }
+    }
+
+    /// Rejoue `start..end` (s) en boucle, ou lit jusqu'au bout avec `None`.
+    pub fn set_loop(&mut self, region: Option<(f32, f32)>) {
+        let rate = self.source_rate as f64;
+        if let Ok(mut playing) = self.playing.lock()
+            && let Some(playing) = playing.as_mut()
+        {
+            playing.loop_region = region
+                .filter(|(start, end)| end > start)
+                .map(|(start, end)| (start as f64 * rate, end as f64 * rate));
+        }
+    }
+
+    /// Reprend la lecture en cours à `secs`.
+    pub fn seek(&mut self, secs: f32) {
+        let position = (secs.max(0.0) * self.source_rate) as f64;
+        if let Ok(mut playing) = self.playing.lock()
+            && let Some(playing) = playing.as_mut()
+        {
+            playing.jump(position);
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if let Ok(mut playing) = self.playing.lock() {
+            *playing = None;
+        }
+    }
+
+    /// Position de lecture (s), `None` à l'arrêt.
+    pub fn position_secs(&self) -> Option<f32> {
+        let playing = self.playing.lock().ok()?;
+        playing
+            .as_ref()
+            .map(|playing| (playing.read_position() / self.source_rate as f64) as f32)
+    }
+
+    fn open_stream(&mut self) -> Result<()> {
+        let host = audio_host::host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("Aucun périphérique de sortie audio trouvé"))?;
+        let config = device.default_output_config()?;
+        let stream_config: StreamConfig = config.clone().into();
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => {
+                Self::build_stream::<f32>(&device, &stream_config, self.playing.clone())?
+            }
+            cpal::SampleFormat::I16 => {
+                Self::build_stream::<i16>(&device, &stream_config, self.playing.clone())?
+            }
+            cpal::SampleFormat::U16 => {
+                Self::build_stream::<u16>(&device, &stream_config, self.playing.clone())?
+            }
+            format => return Err(anyhow::anyhow!("Format audio non supporté: {:?}", format)),
+        };
+        stream.play()?;
+
+        self.output_rate = stream_config.sample_rate.0 as f32;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn build_stream<T>(
+        device: &Device,
+        config: &StreamConfig,
+        playing: Arc<Mutex<Option<Playing>>>,
+    ) -> Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
+    {
+        let channels = config.channels as usize;
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                let Ok(mut guard) = playing.try_lock() else {
+                    data.fill(T::EQUILIBRIUM);
+                    return;
+                };
+                let Some(clip) = guard.as_mut() else {
+                    data.fill(T::EQUILIBRIUM);
+                    return;
+                };
+
+                for frame in data.chunks_mut(channels) {
+                    // Fenêtres de Hann à demi recouvrement: leur somme vaut 1
+                    let (hop, offset) = (clip.hop as f64, clip.offset as f64);
+                    let rising = (std::f64::consts::FRAC_PI_2 * offset / hop).sin().powi(2);
+                    let current = sample_at(&clip.samples, clip.read_position());
+                    let fading =
+                        sample_at(&clip.samples, clip.previous + (offset + hop) * clip.step);
+                    let sample = (rising * current as f64 + (1.0 - rising) * fading as f64) as f32;
+                    frame.fill(T::from_sample(sample.clamp(-1.0, 1.0)));
+
+                    clip.offset += 1;
+                    if clip.offset == clip.hop {
+                        clip.offset = 0;
+                        clip.previous = clip.position;
+                        clip.position += hop * clip.step * clip.speed;
+                        if let Some((start, end)) = clip.loop_region
+                            && clip.position >= end
+                        {
+                            clip.position = start;
+                        }
+                    }
+                }
+
+                if clip.previous as usize >= clip.samples.len() {
+                    *guard = None;
+                }
+            },
+            |err| error!("Erreur du flux de lecture: {}", err),
+            None,
+        )?;
+
+        Ok(stream)
+    }
+}
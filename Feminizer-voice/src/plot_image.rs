@@ -0,0 +1,657 @@
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont, point};
+use anyhow::{Context, Result};
+use eframe::egui::{self, Color32};
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tiny_skia::{
+    FillRule, FilterQuality, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Stroke, StrokeDash,
+    Transform,
+};
+
+use crate::dates::DateTime;
+use crate::paths;
+
+/// Largeur de référence: marges, textes et traits grandissent avec l'image.
+const BASE_WIDTH: f32 = 800.0;
+const SIZE_PRESETS: [(u32, u32); 3] = [(800, 400), (1600, 800), (3200, 1600)];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Élément tracé, en unités des données.
+pub enum Item {
+    /// Les points d'ordonnée NaN coupent la courbe.
+    Line {
+        points: Vec<[f32; 2]>,
+        color: Color32,
+        width: f32,
+    },
+    Points {
+        points: Vec<[f32; 2]>,
+        color: Color32,
+        radius: f32,
+    },
+    HLine {
+        y: f32,
+        color: Color32,
+        dashed: bool,
+    },
+    VLine {
+        x: f32,
+        color: Color32,
+        dashed: bool,
+    },
+    /// Image couvrant toute la zone de tracé, première ligne en haut.
+    Heatmap {
+        columns: usize,
+        rows: usize,
+        pixels: Vec<Color32>,
+    },
+}
+
+/// Graphique reconstruit à partir des données plutôt que capturé à l'écran,
+/// pour obtenir n'importe quelle résolution.
+pub struct Figure {
+    pub title: String,
+    pub x_label: String,
+    pub x_range: (f32, f32),
+    pub y_range: (f32, f32),
+    /// Position dans les unités des données, et libellé.
+    pub x_ticks: Vec<(f32, String)>,
+    pub y_ticks: Vec<(f32, String)>,
+    pub items: Vec<Item>,
+}
+
+#[derive(Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Surface de dessin en pixels, commune au SVG et au PNG.
+trait Canvas {
+    fn fill_rect(&mut self, rect: egui::Rect, color: Color32);
+    fn stroke_rect(&mut self, rect: egui::Rect, color: Color32, width: f32);
+    fn polyline(&mut self, points: &[egui::Pos2], color: Color32, width: f32, dashed: bool);
+    fn circle(&mut self, center: egui::Pos2, radius: f32, color: Color32);
+    fn text(&mut self, pos: egui::Pos2, text: &str, size: f32, anchor: Anchor, color: Color32);
+    fn image(&mut self, rect: egui::Rect, columns: usize, rows: usize, pixels: &[Color32]);
+}
+
+impl Figure {
+    fn draw(&self, canvas: &mut dyn Canvas, width: f32, height: f32) {
+        let s = (width / BASE_WIDTH).clamp(0.5, 6.0);
+        let font = 12.0 * s;
+        let plot = egui::Rect::from_min_max(
+            egui::pos2(80.0 * s, 30.0 * s),
+            egui::pos2(width - 16.0 * s, height - 42.0 * s),
+        );
+        let (x_min, x_max) = self.x_range;
+        let (y_min, y_max) = self.y_range;
+        let x_of =
+            |x: f32| plot.left() + (x - x_min) / (x_max - x_min).max(f32::EPSILON) * plot.width();
+        let y_of = |y: f32| {
+            let y = y.clamp(y_min, y_max);
+            plot.bottom() - (y - y_min) / (y_max - y_min).max(f32::EPSILON) * plot.height()
+        };
+        let dark = Color32::from_gray(40);
+        let grid = Color32::from_gray(220);
+
+        canvas.fill_rect(
+            egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width, height)),
+            Color32::WHITE,
+        );
+        canvas.text(
+            egui::pos2(plot.left(), 20.0 * s),
+            &self.title,
+            font * 1.2,
+            Anchor::Start,
+            dark,
+        );
+
+        // Le fond (spectrogramme) passe sous la grille
+        for item in &self.items {
+            if let Item::Heatmap {
+                columns,
+                rows,
+                pixels,
+            } = item
+            {
+                canvas.image(plot, *columns, *rows, pixels);
+            }
+        }
+        for (value, label) in &self.y_ticks {
+            let y = y_of(*value);
+            canvas.polyline(
+                &[egui::pos2(plot.left(), y), egui::pos2(plot.right(), y)],
+                grid,
+                s,
+                false,
+            );
+            canvas.text(
+                egui::pos2(plot.left() - 6.0 * s, y + font / 3.0),
+                label,
+                font,
+                Anchor::End,
+                dark,
+            );
+        }
+        for (value, label) in &self.x_ticks {
+            let x = x_of(*value);
+            canvas.polyline(
+                &[
+                    egui::pos2(x, plot.bottom()),
+                    egui::pos2(x, plot.bottom() + 5.0 * s),
+                ],
+                dark,
+                s,
+                false,
+            );
+            canvas.text(
+                egui::pos2(x, plot.bottom() + 7.0 * s + font),
+                label,
+                font,
+                Anchor::Middle,
+                dark,
+            );
+        }
+
+        for item in &self.items {
+            match item {
+                Item::Line {
+                    points,
+                    color,
+                    width,
+                } => {
+                    for segment in points.split(|p| p[1].is_nan()) {
+                        let pixels: Vec<egui::Pos2> = segment
+                            .iter()
+                            .map(|p| egui::pos2(x_of(p[0]), y_of(p[1])))
+                            .collect();
+                        if pixels.len() >= 2 {
+                            canvas.polyline(&pixels, *color, width * s, false);
+                        }
+                    }
+                }
+                Item::Points {
+                    points,
+                    color,
+                    radius,
+                } => {
+                    for p in points.iter().filter(|p| p[1] >= y_min && p[1] <= y_max) {
+                        canvas.circle(egui::pos2(x_of(p[0]), y_of(p[1])), radius * s, *color);
+                    }
+                }
+                Item::HLine { y, color, dashed } if (y_min..=y_max).contains(y) => {
+                    let y = y_of(*y);
+                    canvas.polyline(
+                        &[egui::pos2(plot.left(), y), egui::pos2(plot.right(), y)],
+                        *color,
+                        1.5 * s,
+                        *dashed,
+                    );
+                }
+                Item::VLine { x, color, dashed } if (x_min..=x_max).contains(x) => {
+                    let x = x_of(*x);
+                    canvas.polyline(
+                        &[egui::pos2(x, plot.top()), egui::pos2(x, plot.bottom())],
+                        *color,
+                        1.5 * s,
+                        *dashed,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        canvas.stroke_rect(plot, Color32::from_gray(110), s);
+        canvas.text(
+            egui::pos2(plot.center().x, height - 8.0 * s),
+            &self.x_label,
+            font,
+            Anchor::Middle,
+            Color32::from_gray(90),
+        );
+    }
+
+    pub fn to_svg(&self, width: u32, height: u32) -> Result<String> {
+        let mut canvas = SvgCanvas {
+            svg: format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+                 viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\">\n"
+            ),
+            error: None,
+        };
+        self.draw(&mut canvas, width as f32, height as f32);
+        if let Some(error) = canvas.error {
+            return Err(error);
+        }
+        canvas.svg.push_str("</svg>\n");
+        Ok(canvas.svg)
+    }
+
+    pub fn to_png(&self, width: u32, height: u32) -> Result<Vec<u8>> {
+        let mut canvas = PngCanvas {
+            pixmap: Pixmap::new(width, height).context("Taille d'image invalide")?,
+            font: default_font()?,
+        };
+        self.draw(&mut canvas, width as f32, height as f32);
+        Ok(canvas.pixmap.encode_png()?)
+    }
+}
+
+fn hex(color: Color32) -> String {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn opacity(color: Color32) -> f32 {
+    color.a() as f32 / 255.0
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Image en pixels pleins, une cellule par point de la matrice.
+fn heatmap_pixmap(columns: usize, rows: usize, pixels: &[Color32]) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(columns as u32, rows as u32)?;
+    for (dst, color) in pixmap.data_mut().chunks_exact_mut(4).zip(pixels) {
+        dst.copy_from_slice(&color.to_array());
+    }
+    Some(pixmap)
+}
+
+struct SvgCanvas {
+    svg: String,
+    error: Option<anyhow::Error>,
+}
+
+impl Canvas for SvgCanvas {
+    fn fill_rect(&mut self, rect: egui::Rect, color: Color32) {
+        let _ = writeln!(
+            self.svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>",
+            rect.left(),
+            rect.top(),
+            rect.width(),
+            rect.height(),
+            hex(color)
+        );
+    }
+
+    fn stroke_rect(&mut self, rect: egui::Rect, color: Color32, width: f32) {
+        let _ = writeln!(
+            self.svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"none\" \
+             stroke=\"{}\" stroke-width=\"{width:.1}\"/>",
+            rect.left(),
+            rect.top(),
+            rect.width(),
+            rect.height(),
+            hex(color)
+        );
+    }
+
+    fn polyline(&mut self, points: &[egui::Pos2], color: Color32, width: f32, dashed: bool) {
+        let points: Vec<String> = points
+            .iter()
+            .map(|p| format!("{:.1},{:.1}", p.x, p.y))
+            .collect();
+        let dash = if dashed {
+            format!(
+                " stroke-dasharray=\"{:.1} {:.1}\"",
+                4.0 * width,
+                3.0 * width
+            )
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            self.svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{:.2}\" \
+             stroke-width=\"{width:.1}\" stroke-linejoin=\"round\"{dash}/>",
+            points.join(" "),
+            hex(color),
+            opacity(color)
+        );
+    }
+
+    fn circle(&mut self, center: egui::Pos2, radius: f32, color: Color32) {
+        let _ = writeln!(
+            self.svg,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{radius:.1}\" fill=\"{}\" \
+             fill-opacity=\"{:.2}\"/>",
+            center.x,
+            center.y,
+            hex(color),
+            opacity(color)
+        );
+    }
+
+    fn text(&mut self, pos: egui::Pos2, text: &str, size: f32, anchor: Anchor, color: Color32) {
+        let anchor = match anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        let _ = writeln!(
+            self.svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{size:.1}\" text-anchor=\"{anchor}\" \
+             fill=\"{}\">{}</text>",
+            pos.x,
+            pos.y,
+            hex(color),
+            escape(text)
+        );
+    }
+
+    fn image(&mut self, rect: egui::Rect, columns: usize, rows: usize, pixels: &[Color32]) {
+        let png = heatmap_pixmap(columns, rows, pixels)
+            .context("Image vide")
+            .and_then(|pixmap| Ok(pixmap.encode_png()?));
+        match png {
+            Ok(png) => {
+                let _ = writeln!(
+                    self.svg,
+                    "<image x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" \
+                     preserveAspectRatio=\"none\" style=\"image-rendering:pixelated\" \
+                     href=\"data:image/png;base64,{}\"/>",
+                    rect.left(),
+                    rect.top(),
+                    rect.width(),
+                    rect.height(),
+                    base64(&png)
+                );
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+/// Police proportionnelle d'egui, pour des images semblables à l'écran.
+fn default_font() -> Result<FontArc> {
+    let fonts = egui::FontDefinitions::default();
+    let data = fonts
+        .families
+        .get(&egui::FontFamily::Proportional)
+        .and_then(|names| names.first())
+        .and_then(|name| fonts.font_data.get(name))
+        .context("Police introuvable")?;
+    Ok(FontArc::try_from_vec(data.font.to_vec())?)
+}
+
+struct PngCanvas {
+    pixmap: Pixmap,
+    font: FontArc,
+}
+
+fn paint(color: Color32) -> Paint<'static> {
+    let mut paint = Paint::default();
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+impl PngCanvas {
+    /// Mélange un pixel de texte (couverture 0..1) dans l'image prémultipliée.
+    fn blend(&mut self, x: i32, y: i32, color: Color32, coverage: f32) {
+        let (width, height) = (self.pixmap.width() as i32, self.pixmap.height() as i32);
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return;
+        }
+        let alpha = coverage.clamp(0.0, 1.0) * opacity(color);
+        let source = color.to_srgba_unmultiplied();
+        let index = (y * width + x) as usize * 4;
+        let pixel = &mut self.pixmap.data_mut()[index..index + 4];
+        for (channel, dst) in pixel.iter_mut().enumerate() {
+            let src = if channel == 3 {
+                255.0
+            } else {
+                source[channel] as f32
+            };
+            *dst = (src * alpha + *dst as f32 * (1.0 - alpha)).round() as u8;
+        }
+    }
+}
+
+impl Canvas for PngCanvas {
+    fn fill_rect(&mut self, rect: egui::Rect, color: Color32) {
+        if let Some(rect) = Rect::from_ltrb(rect.left(), rect.top(), rect.right(), rect.bottom()) {
+            self.pixmap
+                .fill_rect(rect, &paint(color), Transform::identity(), None);
+        }
+    }
+
+    fn stroke_rect(&mut self, rect: egui::Rect, color: Color32, width: f32) {
+        self.polyline(
+            &[
+                rect.left_top(),
+                rect.right_top(),
+                rect.right_bottom(),
+                rect.left_bottom(),
+                rect.left_top(),
+            ],
+            color,
+            width,
+            false,
+        );
+    }
+
+    fn polyline(&mut self, points: &[egui::Pos2], color: Color32, width: f32, dashed: bool) {
+        let mut builder = PathBuilder::new();
+        for (i, p) in points.iter().enumerate() {
+            if i == 0 {
+                builder.move_to(p.x, p.y);
+            } else {
+                builder.line_to(p.x, p.y);
+            }
+        }
+        let Some(path) = builder.finish() else {
+            return;
+        };
+        let stroke = Stroke {
+            width,
+            line_join: tiny_skia::LineJoin::Round,
+            dash: dashed
+                .then(|| StrokeDash::new(vec![4.0 * width, 3.0 * width], 0.0))
+                .flatten(),
+            ..Stroke::default()
+        };
+        self.pixmap
+            .stroke_path(&path, &paint(color), &stroke, Transform::identity(), None);
+    }
+
+    fn circle(&mut self, center: egui::Pos2, radius: f32, color: Color32) {
+        if let Some(path) = PathBuilder::from_circle(center.x, center.y, radius) {
+            self.pixmap.fill_path(
+                &path,
+                &paint(color),
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn text(&mut self, pos: egui::Pos2, text: &str, size: f32, anchor: Anchor, color: Color32) {
+        let font = self.font.clone();
+        let scaled = font.as_scaled(PxScale::from(size));
+        let mut caret = 0.0;
+        let mut previous = None;
+        let mut glyphs = Vec::new();
+        for c in text.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += scaled.kern(previous, id);
+            }
+            glyphs.push((id, caret));
+            caret += scaled.h_advance(id);
+            previous = Some(id);
+        }
+        let left = match anchor {
+            Anchor::Start => pos.x,
+            Anchor::Middle => pos.x - caret / 2.0,
+            Anchor::End => pos.x - caret,
+        };
+        for (id, offset) in glyphs {
+            let glyph = id.with_scale_and_position(size, point(left + offset, pos.y));
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                outlined.draw(|x, y, coverage| {
+                    self.blend(
+                        bounds.min.x as i32 + x as i32,
+                        bounds.min.y as i32 + y as i32,
+                        color,
+                        coverage,
+                    );
+                });
+            }
+        }
+    }
+
+    fn image(&mut self, rect: egui::Rect, columns: usize, rows: usize, pixels: &[Color32]) {
+        let Some(image) = heatmap_pixmap(columns, rows, pixels) else {
+            return;
+        };
+        let transform = Transform::from_row(
+            rect.width() / columns as f32,
+            0.0,
+            0.0,
+            rect.height() / rows as f32,
+            rect.left(),
+            rect.top(),
+        );
+        let paint = PixmapPaint {
+            quality: FilterQuality::Nearest,
+            ..PixmapPaint::default()
+        };
+        self.pixmap
+            .draw_pixmap(0, 0, image.as_ref(), &paint, transform, None);
+    }
+}
+
+/// Réglages d'export d'image et dernier fichier écrit.
+pub struct PlotImageExport {
+    format: ImageFormat,
+    width: u32,
+    height: u32,
+    last_export: Option<PathBuf>,
+}
+
+impl Default for PlotImageExport {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            width: 1600,
+            height: 800,
+            last_export: None,
+        }
+    }
+}
+
+impl PlotImageExport {
+    /// Contenu du menu contextuel (clic droit) d'un graphique. Renvoie `true`
+    /// quand l'image doit être enregistrée.
+    pub fn menu(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label("🖼 Exporter l'image");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.format, ImageFormat::Png, "PNG");
+            ui.selectable_value(&mut self.format, ImageFormat::Svg, "SVG");
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.width)
+                    .range(200..=8000)
+                    .suffix(" px"),
+            );
+            ui.label("×");
+            ui.add(
+                egui::DragValue::new(&mut self.height)
+                    .range(150..=8000)
+                    .suffix(" px"),
+            );
+        });
+        ui.horizontal(|ui| {
+            for (width, height) in SIZE_PRESETS {
+                if ui.small_button(format!("{}×{}", width, height)).clicked() {
+                    (self.width, self.height) = (width, height);
+                }
+            }
+        });
+        let save = ui.button("💾 Enregistrer").clicked();
+        if save {
+            ui.close();
+        }
+        save
+    }
+
+    /// Écrit la figure dans le dossier « images » des données.
+    pub fn save(&mut self, figure: &Figure, name: &str) -> Result<PathBuf> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let date = DateTime::from_unix(now);
+        let file = format!(
+            "{}-{:04}-{:02}-{:02}-{:02}{:02}{:02}.{}",
+            name,
+            date.year,
+            date.month,
+            date.day,
+            date.hour,
+            date.minute,
+            date.second,
+            self.format.extension()
+        );
+        let path = paths::profile_subdir("images")?.join(file);
+        match self.format {
+            ImageFormat::Png => fs::write(&path, figure.to_png(self.width, self.height)?)?,
+            ImageFormat::Svg => fs::write(&path, figure.to_svg(self.width, self.height)?)?,
+        }
+        self.last_export = Some(path.clone());
+        Ok(path)
+    }
+
+    pub fn show_status(&self, ui: &mut egui::Ui) {
+        match &self.last_export {
+            Some(path) => ui.small(format!("🖼 Image écrite dans {}", path.display())),
+            None => ui.weak("Clic droit sur un graphique pour l'exporter en image"),
+        };
+    }
+}
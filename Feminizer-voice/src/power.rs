@@ -0,0 +1,80 @@
+use eframe::egui;
+use feminizer_voice_core::AnalysisConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Économie d'énergie: l'interface n'est redessinée qu'au rythme des
+/// trames d'analyse, et les trames peuvent être espacées.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PowerSettings {
+    pub low_power: bool,
+    /// Pas d'analyse multiplié par ce facteur en mode économie.
+    pub hop_factor: usize,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            low_power: false,
+            hop_factor: 2,
+        }
+    }
+}
+
+impl PowerSettings {
+    const HOP_FACTORS: [usize; 3] = [1, 2, 4];
+
+    /// Réglages d'analyse effectivement envoyés au flux.
+    pub fn apply(&self, config: AnalysisConfig) -> AnalysisConfig {
+        if !self.low_power {
+            return config;
+        }
+        AnalysisConfig {
+            hop_size: config.hop_size * self.hop_factor.max(1),
+            ..config
+        }
+        .sanitized()
+    }
+
+    /// `hop_secs`: durée d'une trame hors économie. Renvoie `true` si un
+    /// réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui, hop_secs: f32) -> bool {
+        let mut changed = ui
+            .checkbox(&mut self.low_power, "Mode économie d'énergie")
+            .on_hover_text("Pour les portables sur batterie")
+            .changed();
+        ui.add_enabled_ui(self.low_power, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Trames d'analyse:");
+                for factor in Self::HOP_FACTORS {
+                    let label = match factor {
+                        1 => "inchangées".to_string(),
+                        _ => format!("1 sur {}", factor),
+                    };
+                    changed |= ui.selectable_value(&mut self.hop_factor, factor, label).changed();
+                }
+            });
+        });
+        let frame_ms = 1000.0 * hop_secs;
+        let text = match (self.low_power, self.hop_factor) {
+            (false, _) => {
+                "L'affichage est redessiné à chaque image de l'écran pendant l'enregistrement."
+                    .to_string()
+            }
+            (true, 1) => format!(
+                "L'affichage n'est redessiné qu'à l'arrivée d'une trame, toutes les {:.0} ms.",
+                frame_ms
+            ),
+            (true, factor) => format!(
+                "L'affichage n'est redessiné qu'à l'arrivée d'une trame, toutes les {:.0} ms au \
+                 lieu de {:.0} ms: moins de calcul, mais une courbe plus saccadée et des \
+                 attaques brèves parfois manquées.",
+                frame_ms * factor as f32,
+                frame_ms
+            ),
+        };
+        ui.small(text);
+        changed
+    }
+}
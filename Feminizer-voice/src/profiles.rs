@@ -0,0 +1,178 @@
+use anyhow::{Result, bail};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{accessibility, paths};
+
+/// Profil d'origine, dont les données restent à la racine du dossier.
+const MAIN_PROFILE: &str = "Principal";
+
+/// Profil actif, retrouvé au démarrage.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfileIndex {
+    active: Option<String>,
+}
+
+fn index_path() -> Result<PathBuf> {
+    Ok(paths::data_dir()?.join("profiles.json"))
+}
+
+fn profiles_dir() -> Result<PathBuf> {
+    Ok(paths::data_dir()?.join("profiles"))
+}
+
+/// Profils existants, le principal en premier.
+pub fn names() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let dir = profiles_dir()?;
+    if dir.exists() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+    }
+    names.sort();
+    names.insert(0, MAIN_PROFILE.to_string());
+    Ok(names)
+}
+
+/// Le nom sert de nom de dossier: pas de séparateurs ni de noms spéciaux.
+fn validate(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Nom de profil vide");
+    }
+    if name.starts_with('.')
+        || name
+            .chars()
+            .any(|c| "/\\:*?\"<>|".contains(c) || c.is_control())
+    {
+        bail!("Caractère interdit dans le nom de profil");
+    }
+    Ok(name)
+}
+
+pub fn create(name: &str) -> Result<String> {
+    let name = validate(name)?;
+    let dir = profiles_dir()?.join(name);
+    if name == MAIN_PROFILE || dir.exists() {
+        bail!("Le profil « {} » existe déjà", name);
+    }
+    fs::create_dir_all(dir)?;
+    Ok(name.to_string())
+}
+
+/// Rend le profil actif pour toute l'application et s'en souvient.
+pub fn activate(name: &str) -> Result<()> {
+    let active = (name != MAIN_PROFILE).then_some(name);
+    if let Some(name) = active
+        && !profiles_dir()?.join(validate(name)?).is_dir()
+    {
+        bail!("Profil « {} » introuvable", name);
+    }
+    paths::set_profile(active);
+    let index = ProfileIndex {
+        active: active.map(str::to_string),
+    };
+    fs::write(index_path()?, serde_json::to_string_pretty(&index)?)?;
+    Ok(())
+}
+
+/// Réactive le profil de la dernière session; le principal s'il a disparu.
+pub fn restore_active() -> String {
+    let index: ProfileIndex = index_path()
+        .and_then(|path| Ok(fs::read_to_string(path)?))
+        .and_then(|json| Ok(serde_json::from_str(&json)?))
+        .unwrap_or_default();
+    match index.active {
+        Some(name) if activate(&name).is_ok() => name,
+        _ => {
+            paths::set_profile(None);
+            MAIN_PROFILE.to_string()
+        }
+    }
+}
+
+/// Liste déroulante des profils, avec création d'un nouveau profil.
+pub struct ProfilePicker {
+    pub active: String,
+    names: Vec<String>,
+    new_name: String,
+    error: Option<String>,
+}
+
+impl Default for ProfilePicker {
+    fn default() -> Self {
+        Self {
+            active: MAIN_PROFILE.to_string(),
+            names: vec![MAIN_PROFILE.to_string()],
+            new_name: String::new(),
+            error: None,
+        }
+    }
+}
+
+impl ProfilePicker {
+    pub fn refresh(&mut self) {
+        match names() {
+            Ok(names) => self.names = names,
+            Err(e) => self.error = Some(format!("Lecture des profils: {}", e)),
+        }
+    }
+
+    /// Renvoie le profil à activer quand l'utilisateur en choisit ou en crée un.
+    pub fn show(&mut self, ui: &mut egui::Ui, enabled: bool) -> Option<String> {
+        let mut selected = None;
+        ui.add_enabled_ui(enabled, |ui| {
+            egui::ComboBox::from_id_salt("profile_picker")
+                .selected_text(format!("👤 {}", self.active))
+                .show_ui(ui, |ui| {
+                    for name in &self.names {
+                        if ui.selectable_label(*name == self.active, name).clicked()
+                            && *name != self.active
+                        {
+                            selected = Some(name.clone());
+                        }
+                    }
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        let input = ui.add(
+                            egui::TextEdit::singleline(&mut self.new_name)
+                                .hint_text("Nouveau profil")
+                                .desired_width(120.0),
+                        );
+                        let submitted =
+                            input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        let add = ui.button("➕").on_hover_text("Créer le profil");
+                        accessibility::describe(&add, egui::WidgetType::Button, "Créer le profil");
+                        if add.clicked() || submitted {
+                            match create(&self.new_name) {
+                                Ok(name) => {
+                                    self.new_name.clear();
+                                    self.error = None;
+                                    self.refresh();
+                                    selected = Some(name);
+                                }
+                                Err(e) => self.error = Some(e.to_string()),
+                            }
+                        }
+                    });
+                    if let Some(error) = &self.error {
+                        ui.colored_label(egui::Color32::RED, error);
+                    }
+                })
+                .response
+                .on_hover_text(
+                    "Chaque profil a sa plage cible, ses seuils, son périphérique, \
+                     son historique et ses objectifs",
+                )
+                .on_disabled_hover_text("Arrêtez l'enregistrement pour changer de profil");
+        });
+        selected
+    }
+}
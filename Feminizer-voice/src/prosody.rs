@@ -0,0 +1,322 @@
+use eframe::egui;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use crate::settings::TargetRange;
+
+const END_OF_UTTERANCE_SECS: f32 = 0.3;
+const MIN_UTTERANCE_SECS: f32 = 0.25;
+const MAX_UTTERANCES: usize = 20;
+const UPSPEAK_SEMITONES: f32 = 2.0;
+/// Part dans la cible au-delà de laquelle un énoncé est réussi, ou passable.
+const PHRASE_GOOD: f32 = 0.7;
+const PHRASE_FAIR: f32 = 0.4;
+
+pub struct Utterance {
+    pub contour: Vec<f32>,
+    pub duration: f32,
+    pub start_pitch: f32,
+    pub end_pitch: f32,
+    pub range_st: f32,
+    pub slope_st_per_s: f32,
+    pub upspeak: bool,
+}
+
+impl Utterance {
+    fn from_contour(contour: Vec<f32>, frame_duration: f32) -> Option<Self> {
+        let voiced: Vec<(usize, f32)> = contour
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, f)| f > 0.0)
+            .collect();
+        if voiced.len() as f32 * frame_duration < MIN_UTTERANCE_SECS {
+            return None;
+        }
+
+        let edge = (voiced.len() / 5).clamp(1, 3);
+        let start_pitch = median(voiced[..edge].iter().map(|&(_, f)| f).collect());
+        let end_pitch = median(voiced[voiced.len() - edge..].iter().map(|&(_, f)| f).collect());
+
+        let reference = median(voiced.iter().map(|&(_, f)| f).collect());
+        let semitones: Vec<(f32, f32)> = voiced
+            .iter()
+            .map(|&(i, f)| (i as f32 * frame_duration, 12.0 * (f / reference).log2()))
+            .collect();
+
+        let low = semitones.iter().map(|&(_, s)| s).fold(f32::INFINITY, f32::min);
+        let high = semitones.iter().map(|&(_, s)| s).fold(f32::NEG_INFINITY, f32::max);
+
+        // Montée finale: dernier cinquième de l'énoncé comparé à sa médiane
+        let tail_start = voiced.len() * 4 / 5;
+        let tail = median(voiced[tail_start..].iter().map(|&(_, f)| f).collect());
+        let body = median(voiced[..tail_start.max(1)].iter().map(|&(_, f)| f).collect());
+        let upspeak = 12.0 * (end_pitch.max(tail) / body).log2() >= UPSPEAK_SEMITONES;
+
+        Some(Self {
+            duration: contour.len() as f32 * frame_duration,
+            contour,
+            start_pitch,
+            end_pitch,
+            range_st: high - low,
+            slope_st_per_s: linear_slope(&semitones),
+            upspeak,
+        })
+    }
+}
+
+fn median(mut values: Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    values[values.len() / 2]
+}
+
+fn linear_slope(points: &[(f32, f32)]) -> f32 {
+    let n = points.len() as f32;
+    if n < 2.0 {
+        return 0.0;
+    }
+    let mean_x = points.iter().map(|p| p.0).sum::<f32>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f32>() / n;
+    let covariance: f32 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f32 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if variance > 0.0 { covariance / variance } else { 0.0 }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhraseGrade {
+    Good,
+    Fair,
+    Poor,
+}
+
+/// Énoncé repéré dans l'historique affiché.
+pub struct Phrase {
+    pub frames: Range<usize>,
+    /// Part des trames fiables dans la cible, de 0 à 1.
+    pub in_range: f32,
+}
+
+impl Phrase {
+    pub fn grade(&self) -> PhraseGrade {
+        if self.in_range >= PHRASE_GOOD {
+            PhraseGrade::Good
+        } else if self.in_range >= PHRASE_FAIR {
+            PhraseGrade::Fair
+        } else {
+            PhraseGrade::Poor
+        }
+    }
+}
+
+/// Découpe un historique de trames `(hauteur, fiable)` en énoncés, avec le
+/// même silence de fin que le suivi d'intonation. Une hauteur nulle marque
+/// le silence; les trames peu fiables prolongent l'énoncé sans compter.
+pub fn segment_phrases(
+    frames: impl IntoIterator<Item = (f32, bool)>,
+    frame_duration: f32,
+    target: &TargetRange,
+) -> Vec<Phrase> {
+    let frame_duration = frame_duration.max(1e-3);
+    let gap = (END_OF_UTTERANCE_SECS / frame_duration).ceil() as usize;
+    let min_frames = (MIN_UTTERANCE_SECS / frame_duration).ceil() as usize;
+
+    let mut phrases = Vec::new();
+    let mut start = None;
+    let (mut end, mut reliable, mut inside) = (0, 0, 0);
+    let mut close = |start: usize, end: usize, reliable: usize, inside: usize| {
+        if end - start >= min_frames && reliable > 0 {
+            phrases.push(Phrase {
+                frames: start..end,
+                in_range: inside as f32 / reliable as f32,
+            });
+        }
+    };
+    for (i, (frequency, is_reliable)) in frames.into_iter().enumerate() {
+        if frequency <= 0.0 {
+            if let Some(first) = start
+                && i - end >= gap
+            {
+                close(first, end, reliable, inside);
+                start = None;
+            }
+            continue;
+        }
+        if start.is_none() {
+            start = Some(i);
+            (reliable, inside) = (0, 0);
+        }
+        end = i + 1;
+        if is_reliable {
+            reliable += 1;
+            inside += usize::from(target.contains(frequency));
+        }
+    }
+    if let Some(first) = start {
+        close(first, end, reliable, inside);
+    }
+    phrases
+}
+
+#[derive(Default)]
+pub struct UtteranceTracker {
+    current: Vec<f32>,
+    silence_secs: f32,
+    recent: VecDeque<Utterance>,
+}
+
+impl UtteranceTracker {
+    pub fn push_frame(&mut self, frequency: Option<f32>, frame_duration: f32) {
+        match frequency {
+            Some(freq) => {
+                self.silence_secs = 0.0;
+                self.current.push(freq);
+            }
+            None => {
+                if self.current.is_empty() {
+                    return;
+                }
+                self.silence_secs += frame_duration;
+                if self.silence_secs >= END_OF_UTTERANCE_SECS {
+                    self.finish_utterance(frame_duration);
+                }
+            }
+        }
+    }
+
+    pub fn finish_utterance(&mut self, frame_duration: f32) {
+        let contour = std::mem::take(&mut self.current);
+        self.silence_secs = 0.0;
+
+        if let Some(utterance) = Utterance::from_contour(contour, frame_duration) {
+            self.recent.push_back(utterance);
+            if self.recent.len() > MAX_UTTERANCES {
+                self.recent.pop_front();
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("🎵 Intonation des derniers énoncés");
+            if ui.small_button("Effacer").clicked() {
+                self.recent.clear();
+            }
+        });
+
+        if self.recent.is_empty() {
+            ui.label("Parlez quelques phrases pour voir leur contour.");
+            return;
+        }
+
+        let monotone = self.recent.iter().filter(|u| u.range_st < 3.0).count();
+        ui.small(format!(
+            "{} énoncé(s) monotone(s) (< 3 demi-tons) sur {}",
+            monotone,
+            self.recent.len()
+        ));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("utterances_grid").striped(true).show(ui, |ui| {
+                ui.label("Contour");
+                ui.label("Durée");
+                ui.label("Début → fin");
+                ui.label("Étendue");
+                ui.label("Pente");
+                ui.label("");
+                ui.end_row();
+
+                for utterance in self.recent.iter().rev() {
+                    sparkline(ui, &utterance.contour);
+                    ui.label(format!("{:.1} s", utterance.duration));
+                    ui.label(format!(
+                        "{:.0} → {:.0} Hz",
+                        utterance.start_pitch, utterance.end_pitch
+                    ));
+                    let range_color = if utterance.range_st < 3.0 {
+                        egui::Color32::YELLOW
+                    } else {
+                        egui::Color32::GREEN
+                    };
+                    ui.colored_label(range_color, format!("{:.1} dt", utterance.range_st));
+                    ui.label(format!("{:+.1} dt/s", utterance.slope_st_per_s));
+                    if utterance.upspeak {
+                        ui.colored_label(egui::Color32::LIGHT_BLUE, "↗ montée finale");
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
+
+fn sparkline(ui: &mut egui::Ui, contour: &[f32]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(160.0, 28.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+    let voiced: Vec<f32> = contour.iter().copied().filter(|&f| f > 0.0).collect();
+    if voiced.is_empty() {
+        return;
+    }
+    let low = voiced.iter().copied().fold(f32::INFINITY, f32::min).log2();
+    let high = voiced.iter().copied().fold(f32::NEG_INFINITY, f32::max).log2();
+    let span = (high - low).max(0.1);
+
+    let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 0, 255));
+    let mut segment: Vec<egui::Pos2> = Vec::new();
+    for (i, &freq) in contour.iter().enumerate() {
+        if freq > 0.0 {
+            let x = rect.left() + i as f32 / contour.len().max(2) as f32 * rect.width();
+            let y = rect.bottom() - 2.0 - (freq.log2() - low) / span * (rect.height() - 4.0);
+            segment.push(egui::pos2(x, y));
+        } else if segment.len() > 1 {
+            painter.add(egui::Shape::line(std::mem::take(&mut segment), stroke));
+        } else {
+            segment.clear();
+        }
+    }
+    if segment.len() > 1 {
+        painter.add(egui::Shape::line(segment, stroke));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.01;
+
+    fn frames(frequency: f32, secs: f32) -> impl Iterator<Item = (f32, bool)> {
+        std::iter::repeat_n((frequency, true), (secs / FRAME).round() as usize)
+    }
+
+    #[test]
+    fn a_silence_separates_two_phrases() {
+        let target = TargetRange { min_hz: 165.0, max_hz: 255.0 };
+        let history = frames(200.0, 0.5)
+            .chain(frames(0.0, 0.5))
+            .chain(frames(120.0, 0.5));
+        let phrases = segment_phrases(history, FRAME, &target);
+        assert_eq!(phrases.len(), 2);
+        assert_eq!(phrases[0].frames, 0..50);
+        assert_eq!(phrases[1].frames, 100..150);
+        assert_eq!(phrases[0].grade(), PhraseGrade::Good);
+        assert_eq!(phrases[1].grade(), PhraseGrade::Poor);
+    }
+
+    #[test]
+    fn a_short_pause_stays_in_the_phrase() {
+        let target = TargetRange::default();
+        let history = frames(200.0, 0.5)
+            .chain(frames(0.0, 0.1))
+            .chain(frames(200.0, 0.5));
+        let phrases = segment_phrases(history, FRAME, &target);
+        assert_eq!(phrases.len(), 1);
+        assert_eq!(phrases[0].frames, 0..110);
+    }
+}
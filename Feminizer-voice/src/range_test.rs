@@ -0,0 +1,423 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dates::DateTime;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+
+/// Hauteurs recherchées pendant le test, bien au-delà du plafond habituel
+/// de 450 Hz: le haut d'une glissade monte souvent en voix de tête.
+pub const SEARCH_HZ: RangeInclusive<f32> = 50.0..=1100.0;
+const MAX_GLIDE_SECS: f32 = 25.0;
+/// Silence qui termine la glissade, une fois assez de voix entendue.
+const END_SILENCE_SECS: f32 = 1.5;
+const MIN_VOICED_SECS: f32 = 2.0;
+/// Une hauteur ne compte que tenue sur ces quelques trames à moins d'un
+/// demi-ton près: un saut d'octave isolé aux extrémités est écarté.
+const STABLE_FRAMES: usize = 5;
+const STABLE_SEMITONES: f32 = 1.0;
+/// Centiles retenus: les extrêmes physiologiques à 2 % des bords, la zone
+/// confortable sur les 80 % du milieu.
+const EXTREME_PERCENTILE: f32 = 0.02;
+const COMFORT_PERCENTILE: f32 = 0.1;
+const SHOWN_RESULTS: usize = 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RangeResult {
+    /// Horodatage Unix du test.
+    at: u64,
+    lowest_hz: f32,
+    highest_hz: f32,
+    comfortable_low_hz: f32,
+    comfortable_high_hz: f32,
+    /// Plage cible au moment du test.
+    target_min_hz: f32,
+    target_max_hz: f32,
+}
+
+impl RangeResult {
+    /// Étendue en octaves, absente si elle est nulle (une seule note tenue)
+    /// ou si une extrémité n'est pas une hauteur valide.
+    fn octaves(&self) -> Option<f32> {
+        let octaves = (self.highest_hz / self.lowest_hz).log2();
+        (octaves.is_finite() && octaves > 0.0).then_some(octaves)
+    }
+
+    fn semitones(&self) -> f32 {
+        12.0 * self.octaves().unwrap_or(0.0)
+    }
+
+    /// Position d'une hauteur dans l'étendue, de 0 (le plus grave) à 1;
+    /// absente quand l'étendue est vide.
+    fn position(&self, frequency: f32) -> Option<f32> {
+        let position = (frequency / self.lowest_hz).log2() / self.octaves()?;
+        position.is_finite().then_some(position)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct RangeHistory {
+    results: Vec<RangeResult>,
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(paths::profile_dir()?.join("range_tests.json"))
+}
+
+fn load_history() -> Result<Vec<RangeResult>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let history: RangeHistory = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(history.results)
+}
+
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    let index = (fraction * (sorted.len() - 1) as f32).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+/// Glissade en cours: hauteurs stables et fin de voix.
+#[derive(Default)]
+struct Glide {
+    recent: VecDeque<f32>,
+    stable: Vec<f32>,
+    elapsed: f32,
+    voiced_secs: f32,
+    silence_secs: f32,
+}
+
+impl Glide {
+    /// Renvoie `true` quand la glissade est terminée.
+    fn push(&mut self, frequency: f32, frame_duration: f32) -> bool {
+        self.elapsed += frame_duration;
+        if frequency <= 0.0 {
+            self.recent.clear();
+            self.silence_secs += frame_duration;
+        } else {
+            self.silence_secs = 0.0;
+            self.voiced_secs += frame_duration;
+            self.recent.push_back(frequency);
+            if self.recent.len() > STABLE_FRAMES {
+                self.recent.pop_front();
+            }
+            if self.recent.len() == STABLE_FRAMES {
+                let (low, high) = self
+                    .recent
+                    .iter()
+                    .fold((f32::MAX, 0.0_f32), |(low, high), &f| (low.min(f), high.max(f)));
+                if 12.0 * (high / low).log2() <= STABLE_SEMITONES {
+                    self.stable.push(frequency);
+                }
+            }
+        }
+        self.elapsed >= MAX_GLIDE_SECS
+            || (self.voiced_secs >= MIN_VOICED_SECS && self.silence_secs >= END_SILENCE_SECS)
+    }
+
+    fn result(mut self, (target_min_hz, target_max_hz): (f32, f32)) -> Option<RangeResult> {
+        if self.voiced_secs < MIN_VOICED_SECS || self.stable.len() < 2 * STABLE_FRAMES {
+            return None;
+        }
+        self.stable.sort_by(f32::total_cmp);
+        let at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Some(RangeResult {
+            at,
+            lowest_hz: percentile(&self.stable, EXTREME_PERCENTILE),
+            highest_hz: percentile(&self.stable, 1.0 - EXTREME_PERCENTILE),
+            comfortable_low_hz: percentile(&self.stable, COMFORT_PERCENTILE),
+            comfortable_high_hz: percentile(&self.stable, 1.0 - COMFORT_PERCENTILE),
+            target_min_hz,
+            target_max_hz,
+        })
+    }
+}
+
+/// Test d'étendue vocale: une glissade du plus grave au plus aigu, puis
+/// l'étendue, la zone confortable et la place de la cible, gardées par
+/// profil pour suivre leur évolution.
+#[derive(Default)]
+pub struct RangeExploration {
+    history: Vec<RangeResult>,
+    loaded: bool,
+    glide: Option<Glide>,
+    target: (f32, f32),
+    error: Option<String>,
+}
+
+impl RangeExploration {
+    /// Vrai pendant la glissade: la recherche de hauteur doit alors couvrir
+    /// [`SEARCH_HZ`].
+    pub fn is_running(&self) -> bool {
+        self.glide.is_some()
+    }
+
+    /// Hauteur fiable de la trame, 0 hors voix.
+    pub fn push_frame(&mut self, frequency: f32, frame_duration: f32) {
+        let Some(glide) = &mut self.glide else {
+            return;
+        };
+        if glide.push(frequency, frame_duration) {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(glide) = self.glide.take() else {
+            return;
+        };
+        let Some(result) = glide.result(self.target) else {
+            self.error = Some(
+                "Pas assez de voix stable: glissez lentement, sans vous arrêter, du grave à \
+                 l'aigu"
+                    .to_string(),
+            );
+            return;
+        };
+        self.history.push(result);
+        if let Err(e) = self.save_history() {
+            self.error = Some(format!("Enregistrement de l'historique: {}", e));
+        }
+    }
+
+    fn save_history(&self) -> Result<()> {
+        let history = RangeHistory {
+            results: self.history.clone(),
+        };
+        fs::write(history_path()?, serde_json::to_string_pretty(&history)?)?;
+        Ok(())
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        target: (f32, f32),
+        scale: PitchScale,
+    ) {
+        if !self.loaded {
+            self.loaded = true;
+            match load_history() {
+                Ok(history) => self.history = history,
+                Err(e) => self.error = Some(format!("Historique illisible: {}", e)),
+            }
+        }
+        if self.glide.is_some() && !is_recording {
+            self.glide = None;
+            self.error = Some("Enregistrement arrêté: test annulé".to_string());
+        }
+
+        ui.heading("📏 Étendue vocale");
+        ui.label(
+            "Partez de la note la plus grave que vous tenez sans forcer, puis glissez lentement \
+             jusqu'à la plus aiguë, sur un « ou » ou une sirène bouche fermée. Le test s'arrête \
+             après un court silence. Les hauteurs sont recherchées jusqu'à 1100 Hz pendant le \
+             test.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.horizontal(|ui| {
+            if let Some(glide) = &self.glide {
+                let progress = glide.elapsed / MAX_GLIDE_SECS;
+                let text = match glide.stable.iter().copied().reduce(f32::max) {
+                    Some(highest) => format!("Glissez vers l'aigu… {}", scale.format(highest)),
+                    None => "Commencez par votre note la plus grave…".to_string(),
+                };
+                ui.add(egui::ProgressBar::new(progress).text(text).desired_width(280.0));
+                if ui.button("✔ Terminé").clicked() {
+                    self.finish();
+                } else if ui.button("Annuler").clicked() {
+                    self.glide = None;
+                }
+                ui.ctx().request_repaint();
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("⏺ Commencer la glissade"))
+                .on_disabled_hover_text("Démarrez l'enregistrement pour faire le test")
+                .clicked()
+            {
+                self.error = None;
+                self.target = target;
+                self.glide = Some(Glide::default());
+            }
+        });
+        ui.separator();
+
+        match self.history.last() {
+            Some(last) => show_result(ui, last, scale),
+            None => {
+                ui.weak("Aucun test pour ce profil.");
+            }
+        }
+        if self.history.len() >= 2 {
+            self.show_history(ui, scale);
+        }
+    }
+
+    fn show_history(&self, ui: &mut egui::Ui, scale: PitchScale) {
+        ui.separator();
+        ui.label("📈 Évolution");
+        let points = |value: fn(&RangeResult) -> f32| -> PlotPoints {
+            self.history
+                .iter()
+                .enumerate()
+                .map(|(i, result)| [i as f64 + 1.0, value(result) as f64])
+                .collect()
+        };
+        let plot = Plot::new("range_history")
+            .height(180.0)
+            .legend(Legend::default())
+            .x_axis_label("Test");
+        scale.y_axis(plot).show(ui, |plot_ui| {
+            let extreme = egui::Color32::from_rgb(255, 0, 255);
+            let comfortable = egui::Color32::LIGHT_BLUE;
+            plot_ui.line(Line::new("Plus aiguë", points(|r| r.highest_hz)).color(extreme));
+            plot_ui.line(Line::new("Plus grave", points(|r| r.lowest_hz)).color(extreme));
+            plot_ui.line(
+                Line::new("Confort haut", points(|r| r.comfortable_high_hz)).color(comfortable),
+            );
+            plot_ui.line(
+                Line::new("Confort bas", points(|r| r.comfortable_low_hz)).color(comfortable),
+            );
+            if let Some(last) = self.history.last() {
+                let (min, max) = (last.target_min_hz, last.target_max_hz);
+                for (name, hz) in [("Cible min", min), ("Cible max", max)] {
+                    plot_ui.hline(
+                        HLine::new(name, hz)
+                            .color(egui::Color32::GREEN)
+                            .style(egui_plot::LineStyle::dashed_dense()),
+                    );
+                }
+            }
+        });
+
+        egui::Grid::new("range_history_table")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Date", "Étendue", "Demi-tons", "Zone confortable"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for result in self.history.iter().rev().take(SHOWN_RESULTS) {
+                    ui.label(DateTime::from_unix(result.at).to_string());
+                    ui.label(format!(
+                        "{} – {}",
+                        scale.format(result.lowest_hz),
+                        scale.format(result.highest_hz)
+                    ));
+                    ui.label(format!("{:.0}", result.semitones()));
+                    ui.label(format!(
+                        "{} – {}",
+                        scale.format(result.comfortable_low_hz),
+                        scale.format(result.comfortable_high_hz)
+                    ));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+fn show_result(ui: &mut egui::Ui, result: &RangeResult, scale: PitchScale) {
+    egui::Grid::new("range_last").striped(true).show(ui, |ui| {
+        ui.strong("Dernier test");
+        ui.label(DateTime::from_unix(result.at).to_string());
+        ui.end_row();
+
+        ui.label("Étendue physiologique");
+        ui.label(format!(
+            "{} – {} ({:.0} demi-tons)",
+            scale.format(result.lowest_hz),
+            scale.format(result.highest_hz),
+            result.semitones()
+        ));
+        ui.end_row();
+
+        ui.label("Zone confortable").on_hover_text(
+            "Les 80 % du milieu de la glissade: une approximation, à confirmer à l'oreille",
+        );
+        ui.label(format!(
+            "{} – {}",
+            scale.format(result.comfortable_low_hz),
+            scale.format(result.comfortable_high_hz)
+        ));
+        ui.end_row();
+
+        ui.label("Cible");
+        let target = format!(
+            "{} – {}",
+            scale.format(result.target_min_hz),
+            scale.format(result.target_max_hz)
+        );
+        match (result.position(result.target_min_hz), result.position(result.target_max_hz)) {
+            (Some(low), Some(high)) => ui.label(format!(
+                "{target}, de {:.0} % à {:.0} % de l'étendue",
+                100.0 * low,
+                100.0 * high
+            )),
+            _ => ui.label(target),
+        };
+        ui.end_row();
+    });
+
+    let (low, high) = (result.comfortable_low_hz, result.comfortable_high_hz);
+    if result.target_max_hz > result.highest_hz {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 170, 60),
+            "⚠ Le haut de la cible dépasse l'aigu atteint: visez plus bas ou travaillez \
+             l'étendue d'abord",
+        );
+    } else if result.target_min_hz >= low && result.target_max_hz <= high {
+        ui.colored_label(egui::Color32::GREEN, "La cible tient dans votre zone confortable");
+    } else {
+        ui.label("La cible déborde de la zone confortable: restez-y sans forcer.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(lowest_hz: f32, highest_hz: f32) -> RangeResult {
+        RangeResult {
+            at: 0,
+            lowest_hz,
+            highest_hz,
+            comfortable_low_hz: lowest_hz,
+            comfortable_high_hz: highest_hz,
+            target_min_hz: 165.0,
+            target_max_hz: 255.0,
+        }
+    }
+
+    #[test]
+    fn positions_run_from_the_lowest_to_the_highest_note() {
+        let range = result(110.0, 440.0);
+        assert_eq!(range.semitones(), 24.0);
+        assert_eq!(range.position(110.0), Some(0.0));
+        assert_eq!(range.position(220.0), Some(0.5));
+        assert_eq!(range.position(440.0), Some(1.0));
+        // Une cible hors de l'étendue reste située, au-delà de 0 ou de 1
+        assert_eq!(range.position(880.0), Some(1.5));
+    }
+
+    #[test]
+    fn an_empty_range_has_no_positions() {
+        for range in [result(200.0, 200.0), result(250.0, 200.0), result(0.0, 200.0)] {
+            assert_eq!(range.position(200.0), None);
+            assert_eq!(range.semitones(), 0.0);
+        }
+    }
+}
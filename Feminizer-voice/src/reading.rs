@@ -0,0 +1,356 @@
+use eframe::egui;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+
+/// Silence après lequel une phrase lue est considérée comme terminée.
+const END_SILENCE_SECS: f32 = 1.0;
+const MIN_VOICED_SECS: f32 = 0.3;
+
+const BUNDLED: &[(&str, &str)] = &[
+    (
+        "Phrases courtes",
+        "Bonjour, comment allez-vous aujourd'hui ? \
+         Je voudrais un café et un croissant, s'il vous plaît. \
+         Il fait beau, allons nous promener au bord de l'eau. \
+         Pouvez-vous me rappeler demain matin ? \
+         Merci beaucoup, c'est très gentil de votre part. \
+         Le train de midi part du quai numéro trois. \
+         J'ai oublié mes clés sur la table de la cuisine. \
+         Quelle belle surprise de vous voir ici !",
+    ),
+    (
+        "La bise et le soleil",
+        "La bise et le soleil se disputaient, chacun assurant qu'il était le plus fort. \
+         Quand ils virent un voyageur qui s'avançait, enveloppé dans son manteau, \
+         ils convinrent que celui qui arriverait le premier à le lui faire ôter \
+         serait regardé comme le plus fort. \
+         Alors la bise se mit à souffler de toute sa force, \
+         mais plus elle soufflait, plus le voyageur serrait son manteau autour de lui. \
+         Finalement, elle renonça à le lui faire ôter. \
+         Alors le soleil commença à briller et, au bout d'un moment, \
+         le voyageur, réchauffé, ôta son manteau. \
+         Ainsi, la bise dut reconnaître que le soleil était le plus fort des deux.",
+    ),
+];
+
+struct PromptSet {
+    name: String,
+    sentences: Vec<String>,
+}
+
+/// Découpe un texte en phrases: ponctuation finale ou retour à la ligne.
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            push_sentence(&mut sentences, &mut current);
+            continue;
+        }
+        current.push(c);
+        let ends = matches!(c, '.' | '!' | '?' | '…')
+            && chars.peek().is_none_or(|next| next.is_whitespace());
+        if ends {
+            push_sentence(&mut sentences, &mut current);
+        }
+    }
+    push_sentence(&mut sentences, &mut current);
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, current: &mut String) {
+    let sentence = current.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+    current.clear();
+}
+
+fn prompts_dir() -> anyhow::Result<PathBuf> {
+    paths::data_subdir("prompts")
+}
+
+fn load_prompt_sets() -> (Vec<PromptSet>, Option<String>) {
+    let mut sets: Vec<PromptSet> = BUNDLED
+        .iter()
+        .map(|(name, text)| PromptSet {
+            name: name.to_string(),
+            sentences: split_sentences(text),
+        })
+        .collect();
+
+    let imported = prompts_dir().and_then(|dir| {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        for path in files {
+            let sentences = split_sentences(&fs::read_to_string(&path)?);
+            if !sentences.is_empty() {
+                let name = path.file_stem().map_or_else(String::new, |s| {
+                    format!("📄 {}", s.to_string_lossy())
+                });
+                sets.push(PromptSet { name, sentences });
+            }
+        }
+        Ok(())
+    });
+
+    let error = imported.err().map(|e| format!("Textes importés: {}", e));
+    (sets, error)
+}
+
+#[derive(Default)]
+struct SentenceRecorder {
+    pitch_sum: f32,
+    voiced_secs: f32,
+    in_range_secs: f32,
+    silence_secs: f32,
+}
+
+#[derive(Clone, Copy)]
+struct SentenceResult {
+    mean_pitch: f32,
+    in_range_percent: f32,
+    voiced_secs: f32,
+}
+
+enum ReadingState {
+    Idle,
+    /// En attente de la voix, puis lecture jusqu'au silence final.
+    Listening(SentenceRecorder),
+}
+
+/// Lecture guidée phrase par phrase, avec un bilan après chaque phrase.
+pub struct ReadingPractice {
+    sets: Vec<PromptSet>,
+    selected: usize,
+    current: usize,
+    state: ReadingState,
+    results: Vec<Option<SentenceResult>>,
+    error: Option<String>,
+}
+
+impl Default for ReadingPractice {
+    fn default() -> Self {
+        let mut practice = Self {
+            sets: Vec::new(),
+            selected: 0,
+            current: 0,
+            state: ReadingState::Idle,
+            results: Vec::new(),
+            error: None,
+        };
+        practice.reload();
+        practice
+    }
+}
+
+impl ReadingPractice {
+    fn reload(&mut self) {
+        let name = self.sets.get(self.selected).map(|set| set.name.clone());
+        (self.sets, self.error) = load_prompt_sets();
+        let selected = name.and_then(|name| self.sets.iter().position(|set| set.name == name));
+        self.select(selected.unwrap_or(0));
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.current = 0;
+        self.state = ReadingState::Idle;
+        self.results = vec![None; self.sets[index].sentences.len()];
+    }
+
+    /// `pitch` vaut 0 pour une trame sans voix ou hors plage.
+    pub fn push_frame(
+        &mut self,
+        pitch: f32,
+        frame_duration: f32,
+        target_min: f32,
+        target_max: f32,
+    ) {
+        let ReadingState::Listening(recorder) = &mut self.state else {
+            return;
+        };
+
+        if pitch > 0.0 {
+            recorder.pitch_sum += pitch * frame_duration;
+            recorder.voiced_secs += frame_duration;
+            if (target_min..=target_max).contains(&pitch) {
+                recorder.in_range_secs += frame_duration;
+            }
+            recorder.silence_secs = 0.0;
+        } else if recorder.voiced_secs > 0.0 {
+            recorder.silence_secs += frame_duration;
+        }
+
+        if recorder.silence_secs >= END_SILENCE_SECS {
+            if recorder.voiced_secs >= MIN_VOICED_SECS {
+                self.results[self.current] = Some(SentenceResult {
+                    mean_pitch: recorder.pitch_sum / recorder.voiced_secs,
+                    in_range_percent: 100.0 * recorder.in_range_secs / recorder.voiced_secs,
+                    voiced_secs: recorder.voiced_secs,
+                });
+                self.state = ReadingState::Idle;
+            } else {
+                // Bruit bref: on continue d'attendre la phrase
+                *recorder = SentenceRecorder::default();
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, is_recording: bool, scale: PitchScale) {
+        ui.heading("🗒 Lecture guidée");
+        ui.label(
+            "Lisez chaque phrase à voix haute. La phrase se termine après une seconde de \
+             silence; son bilan s'affiche avant de passer à la suivante.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Texte:");
+            let mut selected = self.selected;
+            egui::ComboBox::from_id_salt("reading_set")
+                .selected_text(&self.sets[self.selected].name)
+                .show_ui(ui, |ui| {
+                    for (index, set) in self.sets.iter().enumerate() {
+                        ui.selectable_value(&mut selected, index, &set.name);
+                    }
+                });
+            if selected != self.selected {
+                self.select(selected);
+            }
+            if ui.button("🔄 Recharger").clicked() {
+                self.reload();
+            }
+        });
+        if let Ok(dir) = prompts_dir() {
+            ui.small(format!(
+                "Ajoutez vos propres textes (.txt) dans {}",
+                dir.display()
+            ));
+        }
+        ui.separator();
+
+        let count = self.sets[self.selected].sentences.len();
+        ui.label(format!("Phrase {} / {}", self.current + 1, count));
+        ui.label(
+            egui::RichText::new(&self.sets[self.selected].sentences[self.current])
+                .size(24.0)
+                .strong(),
+        );
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| match &self.state {
+            ReadingState::Listening(recorder) => {
+                if recorder.voiced_secs > 0.0 {
+                    ui.colored_label(egui::Color32::from_rgb(255, 0, 255), "● Lecture…");
+                } else {
+                    ui.label("⏳ En attente de votre voix…");
+                }
+                if ui.button("✖ Annuler").clicked() {
+                    self.state = ReadingState::Idle;
+                }
+            }
+            ReadingState::Idle => {
+                let label = if self.results[self.current].is_some() {
+                    "🔁 Relire"
+                } else {
+                    "⏺ Lire la phrase"
+                };
+                if ui.add_enabled(is_recording, egui::Button::new(label)).clicked() {
+                    self.state = ReadingState::Listening(SentenceRecorder::default());
+                }
+                if ui
+                    .add_enabled(self.current + 1 < count, egui::Button::new("Suivante ➡"))
+                    .clicked()
+                {
+                    self.current += 1;
+                }
+                if !is_recording {
+                    ui.small("Démarrez l'enregistrement pour lire.");
+                }
+            }
+        });
+
+        if let Some(result) = self.results[self.current]
+            && matches!(self.state, ReadingState::Idle)
+        {
+            ui.label(format!(
+                "Hauteur moyenne {} — {:.0}% dans la cible ({:.1} s voisées)",
+                scale.format(result.mean_pitch),
+                result.in_range_percent,
+                result.voiced_secs
+            ));
+        }
+        ui.separator();
+
+        self.show_results(ui, scale);
+    }
+
+    fn show_results(&mut self, ui: &mut egui::Ui, scale: PitchScale) {
+        let read: Vec<SentenceResult> = self.results.iter().flatten().copied().collect();
+        if read.is_empty() {
+            return;
+        }
+        let total_secs: f32 = read.iter().map(|r| r.voiced_secs).sum();
+        let weighted = |f: fn(&SentenceResult) -> f32| {
+            read.iter().map(|r| f(r) * r.voiced_secs).sum::<f32>() / total_secs
+        };
+        ui.label(format!(
+            "Bilan: {} phrase(s) lue(s), {} en moyenne, {:.0}% dans la cible",
+            read.len(),
+            scale.format(weighted(|r| r.mean_pitch)),
+            weighted(|r| r.in_range_percent),
+        ));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("reading_results").striped(true).show(ui, |ui| {
+                ui.label("#");
+                ui.label("Phrase");
+                ui.label("Hauteur moyenne");
+                ui.label("Dans la cible");
+                ui.end_row();
+
+                let sentences = &self.sets[self.selected].sentences;
+                for (index, result) in self.results.iter().enumerate() {
+                    let Some(result) = result else {
+                        continue;
+                    };
+                    if ui.link(format!("{}", index + 1)).clicked() {
+                        self.current = index;
+                        self.state = ReadingState::Idle;
+                    }
+                    let sentence = &sentences[index];
+                    let short: String = sentence.chars().take(50).collect();
+                    if short.len() < sentence.len() {
+                        ui.label(format!("{}…", short));
+                    } else {
+                        ui.label(short);
+                    }
+                    ui.label(scale.format(result.mean_pitch));
+                    let color = if result.in_range_percent >= 70.0 {
+                        egui::Color32::GREEN
+                    } else if result.in_range_percent >= 40.0 {
+                        egui::Color32::YELLOW
+                    } else {
+                        egui::Color32::from_rgb(255, 90, 90)
+                    };
+                    ui.colored_label(color, format!("{:.0}%", result.in_range_percent));
+                    ui.end_row();
+                }
+            });
+        });
+    }
+}
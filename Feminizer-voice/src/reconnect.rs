@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+const FIRST_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// Tentatives de réouverture du flux d'entrée avec attente exponentielle.
+pub struct Reconnect {
+    pub reason: String,
+    pub attempt: u32,
+    next_try: Instant,
+}
+
+impl Reconnect {
+    pub fn new(reason: String) -> Self {
+        Self {
+            reason,
+            attempt: 0,
+            next_try: Instant::now() + FIRST_DELAY,
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.next_try
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.next_try.saturating_duration_since(Instant::now())
+    }
+
+    pub fn schedule_next(&mut self) {
+        self.attempt += 1;
+        let delay = FIRST_DELAY
+            .saturating_mul(1 << self.attempt.min(5))
+            .min(MAX_DELAY);
+        self.next_try = Instant::now() + delay;
+    }
+}
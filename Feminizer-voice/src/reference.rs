@@ -0,0 +1,474 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, LineStyle, Plot, PlotPoints, VLine};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::accessibility;
+use crate::contour_import::ImportedContour;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+use crate::playback::ClipPlayer;
+use crate::session::{self, PitchStats};
+use crate::session_audio::{self, ReanalysisParams, SessionAudioWriter};
+use crate::wav;
+
+const MIN_CLIP_SECS: f32 = 1.0;
+const CONTOUR_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 200, 255);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Reference,
+    Take,
+}
+
+impl Slot {
+    fn label(self) -> &'static str {
+        match self {
+            Slot::Reference => "A — Référence",
+            Slot::Take => "B — Nouvelle prise",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Slot::Reference => egui::Color32::from_rgb(255, 200, 0),
+            Slot::Take => egui::Color32::from_rgb(255, 0, 255),
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Slot::Reference => Slot::Take,
+            Slot::Take => Slot::Reference,
+        }
+    }
+}
+
+struct Clip {
+    samples: Arc<Vec<f32>>,
+    sample_rate: f32,
+    pitch_track: Vec<f32>,
+    frame_secs: f32,
+    stats: PitchStats,
+    voiced_secs: f32,
+    in_range_percent: f32,
+}
+
+impl Clip {
+    fn analyze(samples: Vec<f32>, sample_rate: f32, params: &ReanalysisParams) -> Self {
+        let (pitch_track, frame_secs) = session_audio::pitch_track(&samples, sample_rate, params);
+        let voiced: Vec<f32> = pitch_track.iter().copied().filter(|&f| f > 0.0).collect();
+        let in_range = voiced
+            .iter()
+            .filter(|f| (params.target_min_hz..=params.target_max_hz).contains(f))
+            .count();
+        Self {
+            samples: Arc::new(samples),
+            sample_rate,
+            frame_secs,
+            stats: session::pitch_stats(&voiced),
+            voiced_secs: voiced.len() as f32 * frame_secs,
+            in_range_percent: if voiced.is_empty() {
+                0.0
+            } else {
+                100.0 * in_range as f32 / voiced.len() as f32
+            },
+            pitch_track,
+        }
+    }
+
+    fn duration_secs(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate
+    }
+}
+
+/// Libellé, mesure et unité d'une ligne du tableau comparatif.
+type StatRow = (&'static str, fn(&Clip) -> f32, &'static str);
+
+fn reference_path() -> Result<PathBuf> {
+    Ok(paths::profile_subdir("reference")?.join("reference.wav"))
+}
+
+fn write_wav(path: &Path, samples: &[f32], sample_rate: f32) -> Result<()> {
+    let mut writer = SessionAudioWriter::create(path, sample_rate)?;
+    writer.write(samples)?;
+    writer.finish()
+}
+
+/// Comparaison avant/après: un enregistrement de référence conservé d'une
+/// session à l'autre, face à une nouvelle prise.
+#[derive(Default)]
+pub struct ReferenceComparison {
+    reference: Option<Clip>,
+    take: Option<Clip>,
+    reference_loaded: bool,
+    capturing: Option<Slot>,
+    captured: Vec<f32>,
+    captured_rate: f32,
+    import_path: String,
+    /// Contour envoyé par un tiers (Praat, CSV), superposé aux deux prises.
+    contour: Option<ImportedContour>,
+    contour_path: String,
+    /// Décalage appliqué au contour importé pour l'aligner sur les prises.
+    contour_offset: f32,
+    player: ClipPlayer,
+    playing: Option<Slot>,
+    error: Option<String>,
+}
+
+impl ReferenceComparison {
+    /// Vrai tant qu'une prise est en cours: l'audio brut doit lui parvenir.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing.is_some()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.player.position_secs().is_some()
+    }
+
+    pub fn push_samples(&mut self, samples: &[f32], sample_rate: f32) {
+        if self.capturing.is_none() {
+            return;
+        }
+        if !self.captured.is_empty() && sample_rate != self.captured_rate {
+            self.capturing = None;
+            self.captured.clear();
+            self.error = Some("Fréquence d'échantillonnage modifiée: prise annulée".to_string());
+            return;
+        }
+        self.captured_rate = sample_rate;
+        self.captured.extend_from_slice(samples);
+    }
+
+    fn clip(&self, slot: Slot) -> Option<&Clip> {
+        match slot {
+            Slot::Reference => self.reference.as_ref(),
+            Slot::Take => self.take.as_ref(),
+        }
+    }
+
+    fn set_clip(&mut self, slot: Slot, clip: Clip) {
+        if self.playing == Some(slot) {
+            self.player.stop();
+            self.playing = None;
+        }
+        if slot == Slot::Reference {
+            let saved = reference_path()
+                .and_then(|path| write_wav(&path, &clip.samples, clip.sample_rate));
+            if let Err(e) = saved {
+                self.error = Some(format!("Enregistrement de la référence: {}", e));
+            }
+        }
+        match slot {
+            Slot::Reference => self.reference = Some(clip),
+            Slot::Take => self.take = Some(clip),
+        }
+    }
+
+    fn finish_capture(&mut self, params: &ReanalysisParams) {
+        let Some(slot) = self.capturing.take() else {
+            return;
+        };
+        let samples = std::mem::take(&mut self.captured);
+        if (samples.len() as f32) < MIN_CLIP_SECS * self.captured_rate {
+            self.error = Some("Prise trop courte".to_string());
+            return;
+        }
+        self.set_clip(slot, Clip::analyze(samples, self.captured_rate, params));
+    }
+
+    fn import(&mut self, slot: Slot, params: &ReanalysisParams) {
+        match wav::read_mono(Path::new(self.import_path.trim())) {
+            Ok((samples, sample_rate)) => {
+                self.error = None;
+                self.set_clip(slot, Clip::analyze(samples, sample_rate, params));
+            }
+            Err(e) => self.error = Some(format!("Import: {}", e)),
+        }
+    }
+
+    pub fn contour(&self) -> Option<&ImportedContour> {
+        self.contour.as_ref()
+    }
+
+    fn import_contour(&mut self) {
+        match ImportedContour::load(Path::new(self.contour_path.trim())) {
+            Ok(contour) => {
+                self.error = None;
+                self.contour_offset = 0.0;
+                self.contour = Some(contour);
+            }
+            Err(e) => self.error = Some(format!("Import du contour: {}", e)),
+        }
+    }
+
+    fn play(&mut self, slot: Slot, start_secs: f32) {
+        let Some(clip) = self.clip(slot) else {
+            return;
+        };
+        let (samples, sample_rate) = (clip.samples.clone(), clip.sample_rate);
+        match self.player.play(samples, sample_rate, start_secs) {
+            Ok(()) => self.playing = Some(slot),
+            Err(e) => self.error = Some(format!("Lecture: {}", e)),
+        }
+    }
+
+    /// Bascule de A vers B (ou l'inverse) au même instant de la lecture.
+    fn switch_playback(&mut self) {
+        let (Some(slot), Some(position)) = (self.playing, self.player.position_secs()) else {
+            return;
+        };
+        let other = slot.other();
+        if let Some(clip) = self.clip(other) {
+            let start = position.min(clip.duration_secs());
+            self.play(other, start);
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        params: &ReanalysisParams,
+        scale: PitchScale,
+    ) {
+        if !self.reference_loaded {
+            self.reference_loaded = true;
+            if let Ok(path) = reference_path()
+                && path.exists()
+            {
+                match wav::read_mono(&path) {
+                    Ok((samples, rate)) => {
+                        self.reference = Some(Clip::analyze(samples, rate, params))
+                    }
+                    Err(e) => self.error = Some(format!("Référence illisible: {}", e)),
+                }
+            }
+        }
+        if self.capturing.is_some() && !is_recording {
+            self.finish_capture(params);
+        }
+        let position = self.player.position_secs();
+        if position.is_none() {
+            self.playing = None;
+        }
+
+        ui.heading("🆚 Comparaison avec une référence");
+        ui.label(
+            "Enregistrez ou importez une référence, puis une nouvelle prise du même texte. \
+             La référence est conservée pour mesurer vos progrès au fil des mois.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        for slot in [Slot::Reference, Slot::Take] {
+            ui.horizontal(|ui| {
+                ui.colored_label(slot.color(), slot.label());
+                if self.capturing == Some(slot) {
+                    let secs = self.captured.len() as f32 / self.captured_rate.max(1.0);
+                    if ui.button(format!("⏹ Terminer ({:.0} s)", secs)).clicked() {
+                        self.finish_capture(params);
+                    }
+                } else if ui
+                    .add_enabled(
+                        is_recording && self.capturing.is_none(),
+                        egui::Button::new("⏺ Enregistrer"),
+                    )
+                    .clicked()
+                {
+                    self.error = None;
+                    self.captured.clear();
+                    self.capturing = Some(slot);
+                }
+
+                if ui.button("📂 Importer le WAV").clicked() {
+                    self.import(slot, params);
+                }
+                if let Some(clip) = self.clip(slot) {
+                    ui.label(format!("{:.1} s", clip.duration_secs()));
+                    if self.playing == Some(slot) {
+                        let stop = ui.button("⏹");
+                        accessibility::describe(&stop, egui::WidgetType::Button, "Arrêter");
+                        if stop.clicked() {
+                            self.player.stop();
+                            self.playing = None;
+                        }
+                    } else {
+                        let play = ui.button("▶");
+                        accessibility::describe(&play, egui::WidgetType::Button, "Écouter");
+                        if play.clicked() {
+                            self.play(slot, 0.0);
+                        }
+                    }
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Fichier WAV à importer:");
+            ui.text_edit_singleline(&mut self.import_path);
+        });
+        if !is_recording {
+            ui.small("Démarrez l'enregistrement pour capturer une prise.");
+        }
+
+        ui.horizontal(|ui| {
+            ui.colored_label(CONTOUR_COLOR, "C — Contour importé");
+            ui.text_edit_singleline(&mut self.contour_path)
+                .on_hover_text("PitchTier de Praat, liste de hauteurs ou CSV temps, hauteur");
+            if ui.button("📂 Importer (Praat/CSV)").clicked() {
+                self.import_contour();
+            }
+        });
+        let mut remove = false;
+        if let Some(contour) = &self.contour {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{}: {:.1} s, médiane {}",
+                    contour.name,
+                    contour.duration_secs(),
+                    scale.format(contour.median_hz())
+                ));
+                ui.add(
+                    egui::DragValue::new(&mut self.contour_offset)
+                        .speed(0.01)
+                        .suffix(" s")
+                        .prefix("Décalage: "),
+                )
+                .on_hover_text("Aligne le contour importé sur les prises");
+                remove = ui.button("✖").on_hover_text("Retirer le contour").clicked();
+            });
+        }
+        if remove {
+            self.contour = None;
+        }
+
+        let can_switch = self.playing.is_some() && self.reference.is_some() && self.take.is_some();
+        if ui
+            .add_enabled(can_switch, egui::Button::new("⇄ Basculer A/B"))
+            .on_hover_text("Passe à l'autre enregistrement au même instant")
+            .clicked()
+        {
+            self.switch_playback();
+        }
+        ui.separator();
+
+        self.show_stats(ui, scale);
+        self.show_contours(ui, position, params, scale);
+    }
+
+    fn show_stats(&self, ui: &mut egui::Ui, scale: PitchScale) {
+        if self.reference.is_none() && self.take.is_none() {
+            return;
+        }
+        let rows: [StatRow; 6] = [
+            ("Médiane", |c| c.stats.median, "Hz"),
+            ("Moyenne", |c| c.stats.mean, "Hz"),
+            ("Q1", |c| c.stats.q1, "Hz"),
+            ("Q3", |c| c.stats.q3, "Hz"),
+            ("Variabilité", |c| c.stats.variability_st, "dt"),
+            ("Dans la cible", |c| c.in_range_percent, "%"),
+        ];
+
+        egui::Grid::new("reference_stats").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.colored_label(Slot::Reference.color(), "A");
+            ui.colored_label(Slot::Take.color(), "B");
+            ui.label("B − A");
+            ui.end_row();
+
+            for (label, value, unit) in rows {
+                ui.label(label);
+                let a = self.reference.as_ref().map(value);
+                let b = self.take.as_ref().map(value);
+                for v in [a, b] {
+                    ui.label(v.map_or("—".to_string(), |v| match unit {
+                        "Hz" => scale.format(v),
+                        _ => format!("{:.1} {}", v, unit),
+                    }));
+                }
+                match (a, b) {
+                    // Écart des hauteurs en demi-tons: comparable d'une tessiture à l'autre
+                    (Some(a), Some(b)) if unit == "Hz" && a > 0.0 && b > 0.0 => {
+                        ui.label(format!("{:+.1} dt", 12.0 * (b / a).log2()))
+                    }
+                    (Some(a), Some(b)) => ui.label(format!("{:+.1} {}", b - a, unit)),
+                    _ => ui.label("—"),
+                };
+                ui.end_row();
+            }
+
+            ui.label("Durée voisée");
+            for clip in [&self.reference, &self.take] {
+                ui.label(clip.as_ref().map_or("—".to_string(), |c| {
+                    format!("{:.1} / {:.1} s", c.voiced_secs, c.duration_secs())
+                }));
+            }
+            ui.end_row();
+        });
+    }
+
+    fn show_contours(
+        &self,
+        ui: &mut egui::Ui,
+        position: Option<f32>,
+        params: &ReanalysisParams,
+        scale: PitchScale,
+    ) {
+        let plot = Plot::new("reference_contours")
+            .height(260.0)
+            .legend(Legend::default())
+            .x_axis_label("Temps (s)");
+        scale
+            .y_axis(plot)
+            .show(ui, |plot_ui| {
+                plot_ui.hline(
+                    HLine::new("Cible min", params.target_min_hz).color(egui::Color32::GREEN),
+                );
+                plot_ui.hline(
+                    HLine::new("Cible max", params.target_max_hz).color(egui::Color32::GREEN),
+                );
+
+                for slot in [Slot::Reference, Slot::Take] {
+                    let Some(clip) = self.clip(slot) else {
+                        continue;
+                    };
+                    // Une ligne par passage voisé, sans relier les silences
+                    let mut run: Vec<[f64; 2]> = Vec::new();
+                    let track = clip.pitch_track.iter().chain(std::iter::once(&0.0));
+                    for (i, &frequency) in track.enumerate() {
+                        if frequency > 0.0 {
+                            run.push([(i as f32 * clip.frame_secs) as f64, frequency as f64]);
+                        } else if !run.is_empty() {
+                            plot_ui.line(
+                                Line::new(slot.label(), PlotPoints::from(std::mem::take(&mut run)))
+                                    .color(slot.color())
+                                    .width(2.0),
+                            );
+                        }
+                    }
+                }
+
+                if let Some(contour) = &self.contour {
+                    let name = format!("C — {}", contour.name);
+                    for run in contour.runs(self.contour_offset) {
+                        plot_ui.line(
+                            Line::new(name.as_str(), PlotPoints::from(run))
+                                .color(CONTOUR_COLOR)
+                                .style(LineStyle::dashed_dense())
+                                .width(2.0),
+                        );
+                    }
+                }
+
+                if let (Some(slot), Some(position)) = (self.playing, position) {
+                    plot_ui.vline(
+                        VLine::new("Lecture", position)
+                            .color(slot.color())
+                            .width(1.5),
+                    );
+                }
+            });
+    }
+}
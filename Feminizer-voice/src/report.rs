@@ -0,0 +1,626 @@
+use anyhow::Result;
+use eframe::egui;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::thread::JoinHandle;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::calibration::{LevelCalibration, to_dbfs};
+use crate::dates::DateTime;
+use crate::histogram;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+use crate::session::SessionSummary;
+use crate::session_audio::{self, ReanalysisParams};
+use crate::settings::TargetRange;
+use crate::wav;
+
+const WIDTH: f32 = 760.0;
+const HEIGHT: f32 = 220.0;
+const MARGIN_LEFT: f32 = 70.0;
+const MARGIN_RIGHT: f32 = 12.0;
+const MARGIN_TOP: f32 = 10.0;
+const MARGIN_BOTTOM: f32 = 36.0;
+/// Colonnes au plus par courbe: assez pour l'œil, fichier léger.
+const MAX_COLUMNS: usize = 700;
+const PITCH_COLOR: &str = "#c000c0";
+const LEVEL_COLOR: &str = "#d04030";
+const TARGET_COLOR: &str = "rgba(40, 170, 80, 0.15)";
+
+/// Présentation des mesures, comme dans l'application.
+#[derive(Clone)]
+pub struct ReportOptions {
+    pub scale: PitchScale,
+    pub level: LevelCalibration,
+    pub target: TargetRange,
+}
+
+/// Hauteur (0 hors voix) et niveau RMS par trame.
+struct Contour {
+    frames: Vec<(f32, f32)>,
+    frame_secs: f32,
+    /// Faux quand seule une réanalyse (hauteur sans niveau) est disponible.
+    has_levels: bool,
+}
+
+impl Contour {
+    /// Regroupe les trames en colonnes: hauteur médiane des trames voisées
+    /// (0 si la colonne est surtout silencieuse), niveau RMS.
+    fn columns(&self) -> (Vec<(f32, f32, f32)>, f32) {
+        let per_column = self.frames.len().div_ceil(MAX_COLUMNS).max(1);
+        let column_secs = per_column as f32 * self.frame_secs;
+        let columns = self
+            .frames
+            .chunks(per_column)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut voiced: Vec<f32> = chunk.iter().map(|f| f.0).filter(|&f| f > 0.0).collect();
+                voiced.sort_by(|a, b| a.total_cmp(b));
+                let pitch = if voiced.len() * 2 >= chunk.len() {
+                    voiced[voiced.len() / 2]
+                } else {
+                    0.0
+                };
+                let rms =
+                    (chunk.iter().map(|f| f.1 * f.1).sum::<f32>() / chunk.len() as f32).sqrt();
+                (i as f32 * column_secs, pitch, rms)
+            })
+            .collect();
+        (columns, column_secs)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn format_duration(secs: f32) -> String {
+    let secs = secs.max(0.0).round() as u32;
+    if secs >= 3600 {
+        format!("{} h {:02} min", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{} min {:02} s", secs / 60, secs % 60)
+    }
+}
+
+/// Repères de graphique: position dans les unités des données, et libellé.
+type Ticks = Vec<(f32, String)>;
+
+/// Repère orthonormé d'un graphique SVG.
+struct Axes {
+    x_min: f32,
+    x_max: f32,
+    y_min: f32,
+    y_max: f32,
+}
+
+impl Axes {
+    fn x(&self, x: f32) -> f32 {
+        let span = (self.x_max - self.x_min).max(f32::EPSILON);
+        MARGIN_LEFT + (x - self.x_min) / span * (WIDTH - MARGIN_LEFT - MARGIN_RIGHT)
+    }
+
+    fn y(&self, y: f32) -> f32 {
+        let span = (self.y_max - self.y_min).max(f32::EPSILON);
+        let y = y.clamp(self.y_min, self.y_max);
+        HEIGHT - MARGIN_BOTTOM - (y - self.y_min) / span * (HEIGHT - MARGIN_TOP - MARGIN_BOTTOM)
+    }
+
+    /// Cadre, grille et libellés, autour du contenu déjà tracé.
+    fn svg(&self, body: &str, x_ticks: &Ticks, y_ticks: &Ticks, x_label: &str) -> String {
+        let mut svg = format!(
+            "<svg viewBox=\"0 0 {WIDTH} {HEIGHT}\" xmlns=\"http://www.w3.org/2000/svg\" \
+             font-family=\"sans-serif\" font-size=\"11\">\n"
+        );
+        let (left, right) = (MARGIN_LEFT, WIDTH - MARGIN_RIGHT);
+        let (top, bottom) = (MARGIN_TOP, HEIGHT - MARGIN_BOTTOM);
+        for (value, label) in y_ticks {
+            let y = self.y(*value);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{left}\" y1=\"{y:.1}\" x2=\"{right}\" y2=\"{y:.1}\" stroke=\"#ddd\"/>\
+                 <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                left - 4.0,
+                y + 4.0,
+                escape(label)
+            );
+        }
+        for (value, label) in x_ticks {
+            let x = self.x(*value);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{x:.1}\" y1=\"{bottom}\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"#999\"/>\
+                 <text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+                bottom + 4.0,
+                bottom + 16.0,
+                escape(label)
+            );
+        }
+        svg.push_str(body);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{left}\" y=\"{top}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"none\" \
+             stroke=\"#666\"/>\n<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" \
+             fill=\"#555\">{}</text>\n</svg>",
+            right - left,
+            bottom - top,
+            (left + right) / 2.0,
+            HEIGHT - 4.0,
+            escape(x_label)
+        );
+        svg
+    }
+
+    /// Polylignes des portions continues (les valeurs `None` coupent la courbe).
+    fn polylines(&self, points: impl Iterator<Item = (f32, Option<f32>)>, color: &str) -> String {
+        let mut svg = String::new();
+        let mut current = String::new();
+        let flush = |current: &mut String, svg: &mut String| {
+            // Un point seul ne trace rien
+            if current.matches(' ').count() >= 2 {
+                let _ = writeln!(
+                    svg,
+                    "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                    current.trim(),
+                    color
+                );
+            }
+            current.clear();
+        };
+        for (x, y) in points {
+            match y {
+                Some(y) => {
+                    let _ = write!(current, "{:.1},{:.1} ", self.x(x), self.y(y));
+                }
+                None => flush(&mut current, &mut svg),
+            }
+        }
+        flush(&mut current, &mut svg);
+        svg
+    }
+}
+
+/// Repères de temps lisibles (« 2:30 »), au plus une dizaine.
+fn time_ticks(duration: f32) -> Ticks {
+    let step = [
+        1.0, 2.0, 5.0, 10.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 900.0, 1800.0,
+    ]
+    .into_iter()
+    .find(|step| duration / step <= 10.0)
+    .unwrap_or(3600.0);
+    (0..=(duration / step) as usize)
+        .map(|i| {
+            let secs = i as f32 * step;
+            (
+                secs,
+                format!("{}:{:02}", secs as u32 / 60, secs as u32 % 60),
+            )
+        })
+        .collect()
+}
+
+fn value_ticks(min: f32, max: f32, count: usize, label: impl Fn(f32) -> String) -> Ticks {
+    (0..=count)
+        .map(|i| {
+            let value = min + (max - min) * i as f32 / count as f32;
+            (value, label(value))
+        })
+        .collect()
+}
+
+fn pitch_chart(contour: &Contour, options: &ReportOptions) -> Option<String> {
+    let (columns, column_secs) = contour.columns();
+    let mut voiced: Vec<f32> = columns.iter().map(|c| c.1).filter(|&f| f > 0.0).collect();
+    if voiced.is_empty() {
+        return None;
+    }
+    voiced.sort_by(|a, b| a.total_cmp(b));
+    let low = voiced[voiced.len() / 50].min(options.target.min_hz);
+    let high = voiced[voiced.len() * 49 / 50].max(options.target.max_hz);
+    let duration = columns.len() as f32 * column_secs;
+    let axes = Axes {
+        x_min: 0.0,
+        x_max: duration,
+        y_min: (low * 0.9).floor(),
+        y_max: (high * 1.1).ceil(),
+    };
+
+    let band_top = axes.y(options.target.max_hz);
+    let mut body = format!(
+        "<rect x=\"{:.1}\" y=\"{band_top:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>\n",
+        axes.x(0.0),
+        axes.x(duration) - axes.x(0.0),
+        axes.y(options.target.min_hz) - band_top,
+        TARGET_COLOR
+    );
+    body += &axes.polylines(
+        columns
+            .iter()
+            .map(|&(t, pitch, _)| (t, (pitch > 0.0).then_some(pitch))),
+        PITCH_COLOR,
+    );
+    let scale = options.scale;
+    Some(axes.svg(
+        &body,
+        &time_ticks(duration),
+        &value_ticks(axes.y_min, axes.y_max, 5, |hz| scale.format(hz)),
+        "Temps (min:s) — bande verte: plage cible",
+    ))
+}
+
+fn level_chart(contour: &Contour, options: &ReportOptions) -> Option<String> {
+    if !contour.has_levels {
+        return None;
+    }
+    let (columns, column_secs) = contour.columns();
+    let duration = columns.len() as f32 * column_secs;
+    let level = &options.level;
+    let axes = Axes {
+        x_min: 0.0,
+        x_max: duration,
+        y_min: level.level(-60.0),
+        y_max: level.level(0.0),
+    };
+    let body = axes.polylines(
+        columns
+            .iter()
+            .map(|&(t, _, rms)| (t, (rms > 0.0).then(|| level.level(to_dbfs(rms))))),
+        LEVEL_COLOR,
+    );
+    let suffix = level.suffix();
+    Some(axes.svg(
+        &body,
+        &time_ticks(duration),
+        &value_ticks(axes.y_min, axes.y_max, 4, |db| {
+            format!("{:.0} {}", db, suffix)
+        }),
+        "Temps (min:s)",
+    ))
+}
+
+fn histogram_chart(percentages: &[f32], options: &ReportOptions) -> Option<String> {
+    let first = percentages.iter().position(|&p| p > 0.0)?;
+    let last = percentages.iter().rposition(|&p| p > 0.0)?;
+    let (first, last) = (first.saturating_sub(2), (last + 3).min(percentages.len()));
+    let peak = percentages[first..last]
+        .iter()
+        .copied()
+        .fold(0.0_f32, f32::max);
+    let axes = Axes {
+        x_min: first as f32,
+        x_max: last as f32,
+        y_min: 0.0,
+        y_max: (peak * 1.1).max(1.0),
+    };
+
+    let mut body = String::new();
+    for (bin, &percent) in percentages.iter().enumerate().take(last).skip(first) {
+        if percent <= 0.0 {
+            continue;
+        }
+        let center = ((histogram::bin_edge(bin) * histogram::bin_edge(bin + 1)).sqrt()) as f32;
+        let fill = if options.target.contains(center) {
+            "#3a9a5a"
+        } else {
+            "#8a8aa0"
+        };
+        let (x0, x1) = (axes.x(bin as f32), axes.x(bin as f32 + 1.0));
+        let top = axes.y(percent);
+        let _ = writeln!(
+            body,
+            "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{fill}\">\
+             <title>{} : {:.1} %</title></rect>",
+            x0 + 0.5,
+            (x1 - x0 - 1.0).max(0.5),
+            axes.y(0.0) - top,
+            escape(&options.scale.format(center)),
+            percent
+        );
+    }
+    // Un repère par octave environ, aligné sur les bords de classes
+    let step = ((last - first) / 6).max(1);
+    let x_ticks = (first..=last)
+        .step_by(step)
+        .map(|bin| {
+            (
+                bin as f32,
+                options.scale.format(histogram::bin_edge(bin) as f32),
+            )
+        })
+        .collect();
+    Some(axes.svg(
+        &body,
+        &x_ticks,
+        &value_ticks(0.0, axes.y_max, 4, |p| format!("{:.0} %", p)),
+        "Hauteur — en vert: dans la plage cible",
+    ))
+}
+
+/// Page HTML autonome (styles et graphiques intégrés), imprimable en PDF
+/// depuis le navigateur.
+fn render(summary: &SessionSummary, contour: Option<&Contour>, options: &ReportOptions) -> String {
+    let scale = options.scale;
+    let date = DateTime::from_unix(summary.started_at);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Séance du {date}</title>\n<style>\n\
+         body {{ font-family: sans-serif; max-width: 800px; margin: 2em auto; color: #222; }}\n\
+         h1 {{ font-size: 1.5em; }} h2 {{ font-size: 1.15em; margin-top: 1.6em; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         td, th {{ padding: 3px 14px 3px 0; text-align: left; }}\n\
+         th {{ color: #555; font-weight: normal; }}\n\
+         svg {{ width: 100%; height: auto; }}\n\
+         .note {{ color: #777; font-size: 0.85em; }}\n\
+         @media print {{ body {{ margin: 0; }} h2 {{ break-after: avoid; }} \
+         svg {{ break-inside: avoid; }} }}\n\
+         </style>\n</head>\n<body>\n<h1>Séance de pratique vocale du {date}</h1>\n"
+    );
+
+    let mut rows: Vec<(&str, String)> = vec![
+        ("Durée", format_duration(summary.duration_secs)),
+        ("Temps voisé", format_duration(summary.voiced_secs)),
+    ];
+    if let Some(rating) = summary.self_rating {
+        rows.push(("Auto-évaluation", format!("{} / 10", rating)));
+    }
+    if let Some(goal) = &summary.goal {
+        rows.push((
+            "Objectif du jour",
+            format!(
+                "{:.0} / {:.0} min{}",
+                goal.day_secs / 60.0,
+                goal.target_secs / 60.0,
+                if goal.reached { " (atteint)" } else { "" }
+            ),
+        ));
+    }
+    for change in &summary.device_changes {
+        rows.push((
+            "Changement de micro",
+            format!("à {} : {}", format_duration(change.at_secs), change.device),
+        ));
+    }
+    for marker in &summary.markers {
+        let at = format_duration(marker.at_secs);
+        rows.push((
+            "Repère",
+            match marker.text.as_str() {
+                "" => format!("à {}", at),
+                text => format!("à {} : {}", at, text),
+            },
+        ));
+    }
+    html.push_str("<h2>Séance</h2>\n<table>\n");
+    for (label, value) in &rows {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            label,
+            escape(value)
+        );
+    }
+    html.push_str("</table>\n");
+
+    let stats: Vec<(&str, String)> = vec![
+        ("Hauteur médiane", scale.format(summary.median_pitch)),
+        ("Hauteur moyenne", scale.format(summary.mean_pitch)),
+        (
+            "Moitié centrale (Q1 – Q3)",
+            format!(
+                "{} – {}",
+                scale.format(summary.pitch_q1),
+                scale.format(summary.pitch_q3)
+            ),
+        ),
+        (
+            "Étendue",
+            format!(
+                "{} – {}",
+                scale.format(summary.min_pitch),
+                scale.format(summary.max_pitch)
+            ),
+        ),
+        (
+            "Variabilité",
+            format!("{:.1} demi-tons", summary.pitch_variability_st),
+        ),
+        (
+            "Dans la cible",
+            format!(
+                "{:.0} % (cible {} – {})",
+                summary.in_range_percent,
+                scale.format(options.target.min_hz),
+                scale.format(options.target.max_hz)
+            ),
+        ),
+        (
+            "Niveau moyen",
+            options.level.format(summary.mean_amplitude_db),
+        ),
+        (
+            "Brillance moyenne",
+            format!("{:.0} Hz", summary.mean_brightness),
+        ),
+        (
+            "Poids vocal (H1–H2)",
+            summary
+                .mean_h1_h2_db
+                .map(|db| format!("{:+.1} dB", db))
+                .unwrap_or_else(|| "non mesuré".to_string()),
+        ),
+        (
+            "Alertes de forçage",
+            summary.strain_warnings.len().to_string(),
+        ),
+    ];
+    html.push_str("<h2>Mesures</h2>\n<table>\n");
+    for (label, value) in &stats {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            label,
+            escape(value)
+        );
+    }
+    html.push_str("</table>\n");
+
+    if let Some(contour) = contour {
+        if let Some(svg) = pitch_chart(contour, options) {
+            let _ = write!(html, "<h2>Hauteur au fil de la séance</h2>\n{}", svg);
+        }
+        if let Some(svg) = level_chart(contour, options) {
+            let _ = write!(html, "<h2>Niveau sonore</h2>\n{}", svg);
+        }
+    } else {
+        html.push_str(
+            "<p class=\"note\">Courbes indisponibles: l'audio de cette séance n'a pas été \
+             conservé.</p>\n",
+        );
+    }
+    if let Some(svg) = histogram_chart(&summary.pitch_histogram, options) {
+        let _ = write!(
+            html,
+            "<h2>Répartition du temps voisé par hauteur</h2>\n{}",
+            svg
+        );
+    }
+
+    if !summary.reanalyses.is_empty() {
+        html.push_str(
+            "<h2>Réanalyses</h2>\n<table>\n<tr><th>Paramètres</th><th>Médiane</th>\
+             <th>Variabilité</th><th>Dans la cible</th></tr>\n",
+        );
+        for reanalysis in &summary.reanalyses {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{:.1} dt</td><td>{:.0} %</td></tr>",
+                escape(&reanalysis.label),
+                escape(&scale.format(reanalysis.median_pitch)),
+                reanalysis.pitch_variability_st,
+                reanalysis.in_range_percent
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let _ = write!(
+        html,
+        "<p class=\"note\">Rapport généré par Feminizer voice le {}. Mesures acoustiques \
+         indicatives, à interpréter avec un·e professionnel·le.</p>\n</body>\n</html>\n",
+        DateTime::from_unix(now)
+    );
+    html
+}
+
+/// Courbes de la séance: audio conservé de préférence, sinon la dernière
+/// réanalyse (hauteur seule).
+fn contour(
+    summary: &SessionSummary,
+    audio: Option<PathBuf>,
+    params: &ReanalysisParams,
+) -> Result<Option<Contour>> {
+    if let Some(path) = audio.filter(|path| path.exists()) {
+        let (samples, sample_rate) = wav::read_mono(&path)?;
+        let (frames, frame_secs) = session_audio::analysis_track(&samples, sample_rate, params);
+        return Ok(Some(Contour {
+            frames,
+            frame_secs,
+            has_levels: true,
+        }));
+    }
+    Ok(summary.reanalyses.last().map(|reanalysis| Contour {
+        frames: reanalysis.pitch_track.iter().map(|&f| (f, 0.0)).collect(),
+        frame_secs: reanalysis.frame_secs,
+        has_levels: false,
+    }))
+}
+
+fn export(
+    summary: &SessionSummary,
+    audio: Option<PathBuf>,
+    params: &ReanalysisParams,
+    options: &ReportOptions,
+) -> Result<PathBuf> {
+    let contour = contour(summary, audio, params)?;
+    let html = render(summary, contour.as_ref(), options);
+    let date = DateTime::from_unix(summary.started_at);
+    let name = format!(
+        "seance-{:04}-{:02}-{:02}-{:02}{:02}.html",
+        date.year, date.month, date.day, date.hour, date.minute
+    );
+    let path = paths::profile_subdir("reports")?.join(name);
+    fs::write(&path, html)?;
+    Ok(path)
+}
+
+/// Génération en arrière-plan: réanalyser l'audio d'une longue séance prend
+/// quelques secondes.
+#[derive(Default)]
+pub struct ReportExporter {
+    job: Option<JoinHandle<Result<PathBuf>>>,
+    last: Option<Result<PathBuf, String>>,
+}
+
+impl ReportExporter {
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+
+    pub fn start(
+        &mut self,
+        summary: SessionSummary,
+        audio: Option<PathBuf>,
+        params: ReanalysisParams,
+        options: ReportOptions,
+    ) {
+        if self.is_running() {
+            return;
+        }
+        self.last = None;
+        self.job = Some(std::thread::spawn(move || {
+            export(&summary, audio, &params, &options)
+        }));
+    }
+
+    pub fn poll(&mut self) {
+        if self.job.as_ref().is_some_and(JoinHandle::is_finished)
+            && let Some(job) = self.job.take()
+        {
+            self.last = Some(match job.join() {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(_) => Err("génération interrompue".to_string()),
+            });
+        }
+    }
+
+    pub fn show_status(&self, ui: &mut egui::Ui) {
+        if self.is_running() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Génération du rapport...");
+            });
+        }
+        match &self.last {
+            Some(Ok(path)) => {
+                ui.small(format!(
+                    "📄 Rapport écrit dans {} (imprimable en PDF depuis le navigateur)",
+                    path.display()
+                ));
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, format!("❌ Rapport: {}", e));
+            }
+            None => {}
+        }
+    }
+}
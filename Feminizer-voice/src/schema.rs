@@ -0,0 +1,153 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+const VERSION_KEY: &str = "schema_version";
+
+pub type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// Format de fichier versionné. `migrations[n]` fait passer un document de la
+/// version `n` à `n + 1`; la version courante est donc `migrations.len()`.
+pub struct Schema {
+    pub name: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+/// Document tel qu'écrit sur disque: le contenu et sa version de format.
+#[derive(Serialize, JsonSchema)]
+pub struct Versioned<'a, T> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub value: &'a T,
+}
+
+pub struct Loaded<T> {
+    pub value: T,
+    /// Version d'origine si le document a dû être migré.
+    pub migrated_from: Option<u32>,
+}
+
+impl Schema {
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&Versioned {
+            schema_version: self.version(),
+            value,
+        })?)
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, json: &str) -> Result<Loaded<T>> {
+        let Value::Object(mut map) = serde_json::from_str(json)? else {
+            anyhow::bail!("{}: objet JSON attendu", self.name);
+        };
+
+        // Les fichiers antérieurs au versionnement n'ont pas de champ de version
+        let from_version = map
+            .get(VERSION_KEY)
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as u32;
+        if from_version > self.version() {
+            anyhow::bail!(
+                "{} en version {}, créé par une version plus récente de l'application (max {})",
+                self.name,
+                from_version,
+                self.version()
+            );
+        }
+
+        for (version, migration) in self.migrations.iter().enumerate().skip(from_version as usize) {
+            migration(&mut map).map_err(|e| {
+                anyhow::anyhow!("{}: migration v{} → v{}: {}", self.name, version, version + 1, e)
+            })?;
+        }
+        map.remove(VERSION_KEY);
+
+        Ok(Loaded {
+            value: serde_json::from_value(Value::Object(map))?,
+            migrated_from: (from_version < self.version()).then_some(from_version),
+        })
+    }
+}
+
+/// Ajoute un champ avec sa valeur par défaut s'il est absent.
+pub fn default_field(map: &mut Map<String, Value>, key: &str, value: Value) {
+    map.entry(key).or_insert(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    /// Chaque étape note son numéro dans `applied`.
+    fn record_step(map: &mut Map<String, Value>, step: u64) -> Result<()> {
+        let applied = map.entry("applied").or_insert_with(|| Value::Array(Vec::new()));
+        applied.as_array_mut().unwrap().push(step.into());
+        Ok(())
+    }
+
+    const TEST_SCHEMA: Schema = Schema {
+        name: "Test",
+        migrations: &[
+            |map| record_step(map, 0),
+            |map| record_step(map, 1),
+            |map| record_step(map, 2),
+        ],
+    };
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    #[serde(default)]
+    struct Document {
+        applied: Vec<u64>,
+        name: String,
+    }
+
+    #[test]
+    fn each_step_from_the_stored_version_runs_once() {
+        let loaded = TEST_SCHEMA
+            .decode::<Document>(r#"{ "schema_version": 1, "name": "a" }"#)
+            .unwrap();
+        assert_eq!(loaded.value.applied, [1, 2]);
+        assert_eq!(loaded.value.name, "a");
+        assert_eq!(loaded.migrated_from, Some(1));
+
+        // Sans champ de version: document d'avant le versionnement
+        let loaded = TEST_SCHEMA.decode::<Document>(r#"{ "name": "a" }"#).unwrap();
+        assert_eq!(loaded.value.applied, [0, 1, 2]);
+        assert_eq!(loaded.migrated_from, Some(0));
+    }
+
+    #[test]
+    fn current_documents_are_not_migrated() {
+        let json = TEST_SCHEMA.encode(&Document::default()).unwrap();
+        assert!(json.contains(r#""schema_version": 3"#), "{}", json);
+        let loaded = TEST_SCHEMA.decode::<Document>(&json).unwrap();
+        assert!(loaded.value.applied.is_empty());
+        assert_eq!(loaded.migrated_from, None);
+    }
+
+    #[test]
+    fn newer_documents_are_rejected() {
+        let error = TEST_SCHEMA
+            .decode::<Document>(r#"{ "schema_version": 4 }"#)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("plus récente"), "{}", error);
+    }
+
+    #[test]
+    fn a_failed_step_is_named() {
+        const FAILING: Schema = Schema {
+            name: "Test",
+            migrations: &[|_| Ok(()), |_| anyhow::bail!("champ manquant")],
+        };
+        let error = FAILING.decode::<Document>("{}").err().unwrap();
+        assert!(error.to_string().contains("v1 → v2"), "{}", error);
+        assert!(FAILING.decode::<Document>("[]").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+use anyhow::Result;
+use eframe::egui;
+use feminizer_voice_core::AnalysisFrame;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, CallFnOptions, Dynamic, Engine, EvalAltResult, Scope};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+use crate::settings::TargetRange;
+
+/// Opérations permises à chaque appel d'un script: une boucle sans fin est
+/// interrompue au lieu de figer l'interface.
+const MAX_OPERATIONS: u64 = 200_000;
+const MAX_LOG_LINES: usize = 50;
+
+/// Résultat d'une fonction de l'API: une erreur arrête le script.
+type ApiResult<T> = Result<T, Box<EvalAltResult>>;
+
+const BUNDLED: &[(&str, &str)] = &[(
+    "Tenir la cible",
+    r#"// Tenir la cible: chaque seconde de voix dans la cible rapporte un point.
+// L'exercice se termine à 30 points.
+
+fn on_start() {
+    this.held = 0.0;
+    this.last = 0.0;
+    message("Tenez une voyelle dans la cible");
+}
+
+fn on_frame(frame) {
+    let dt = frame.time - this.last;
+    this.last = frame.time;
+    if frame.in_target {
+        this.held += dt;
+        set_score(this.held.floor());
+        message("Parfait, continuez");
+    } else if frame.frequency > 0.0 {
+        if frame.frequency < frame.target_min_hz {
+            message("Un peu plus haut");
+        } else {
+            message("Un peu plus bas");
+        }
+    }
+    if this.held >= 30.0 {
+        set_score(30);
+        message("Bravo: 30 secondes dans la cible !");
+        finish();
+    }
+}
+"#,
+)];
+
+/// Fonctions et champs offerts aux scripts, affichés dans l'onglet.
+const API: &[(&str, &str)] = &[
+    ("fn on_frame(frame)", "Obligatoire: appelée à chaque trame analysée."),
+    ("fn on_start(), fn on_stop()", "Facultatives: appelées au lancement et à l'arrêt."),
+    ("this", "État du script, gardé d'un appel à l'autre (this.points = 0)."),
+    ("frame.time", "Secondes écoulées depuis le lancement."),
+    ("frame.frequency", "Hauteur en Hz, 0 hors voix ou si la mesure est peu fiable."),
+    ("frame.confidence", "Fiabilité de la hauteur, entre 0 et 1."),
+    ("frame.amplitude, frame.is_voiced", "Niveau RMS, voix détectée."),
+    ("frame.brightness", "Centroïde spectral en Hz."),
+    ("frame.formants", "[F1, F2] en Hz, () si inconnus."),
+    ("frame.h1_h2_db, frame.resonance_hz", "Poids vocal et résonance, () si inconnus."),
+    ("frame.in_target", "Hauteur dans la cible de l'exercice."),
+    ("frame.target_min_hz, frame.target_max_hz", "Cible de l'exercice."),
+    ("message(texte)", "Affiche une consigne."),
+    ("set_score(n), add_score(n)", "Score affiché."),
+    ("set_target(min_hz, max_hz)", "Cible propre à l'exercice."),
+    ("clear_target()", "Revient à la cible des réglages."),
+    ("finish()", "Termine l'exercice."),
+    ("semitones(de_hz, vers_hz)", "Écart en demi-tons."),
+    ("print(valeur)", "Écrit dans le journal du script."),
+];
+
+fn scripts_dir() -> Result<PathBuf> {
+    paths::data_subdir("scripts")
+}
+
+struct Script {
+    name: String,
+    source: String,
+}
+
+impl Script {
+    /// Premières lignes de commentaire du fichier.
+    fn description(&self) -> String {
+        self.source
+            .lines()
+            .map_while(|line| line.trim().strip_prefix("//"))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn load_scripts() -> (Vec<Script>, Option<String>) {
+    let mut scripts: Vec<Script> = BUNDLED
+        .iter()
+        .map(|(name, source)| Script {
+            name: name.to_string(),
+            source: source.to_string(),
+        })
+        .collect();
+
+    let imported = scripts_dir().and_then(|dir| {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "rhai") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        for path in files {
+            let name = path.file_stem().map_or_else(String::new, |s| {
+                format!("📄 {}", s.to_string_lossy())
+            });
+            let source = fs::read_to_string(&path)?;
+            scripts.push(Script { name, source });
+        }
+        Ok(())
+    });
+
+    let error = imported.err().map(|e| format!("Scripts: {}", e));
+    (scripts, error)
+}
+
+/// Ce que le script affiche, modifié par les fonctions qu'il appelle.
+#[derive(Default)]
+struct Output {
+    message: String,
+    score: Option<f64>,
+    target: Option<(f32, f32)>,
+    finished: bool,
+    log: VecDeque<String>,
+}
+
+impl Output {
+    fn log(&mut self, line: String) {
+        if self.log.len() == MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line);
+    }
+}
+
+fn number(value: &Dynamic) -> ApiResult<f64> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|n| n as f64))
+        .map_err(|kind| format!("nombre attendu, {} reçu", kind).into())
+}
+
+/// Moteur sans accès aux fichiers ni au réseau, limité en calcul et en
+/// mémoire, avec les fonctions de l'API branchées sur `output`.
+fn sandboxed_engine(output: &Rc<RefCell<Output>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(10_000);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(1_000);
+
+    let out = Rc::clone(output);
+    engine.on_print(move |text| out.borrow_mut().log(text.to_string()));
+    let out = Rc::clone(output);
+    engine.on_debug(move |text, _, position| {
+        out.borrow_mut().log(format!("{} {}", position, text));
+    });
+    let out = Rc::clone(output);
+    engine.register_fn("message", move |text: &str| {
+        out.borrow_mut().message = text.to_string();
+    });
+    let out = Rc::clone(output);
+    engine.register_fn("set_score", move |value: Dynamic| -> ApiResult<()> {
+        out.borrow_mut().score = Some(number(&value)?);
+        Ok(())
+    });
+    let out = Rc::clone(output);
+    engine.register_fn("add_score", move |value: Dynamic| -> ApiResult<()> {
+        let delta = number(&value)?;
+        let mut out = out.borrow_mut();
+        out.score = Some(out.score.unwrap_or(0.0) + delta);
+        Ok(())
+    });
+    let out = Rc::clone(output);
+    engine.register_fn("set_target", move |min: Dynamic, max: Dynamic| -> ApiResult<()> {
+        let (min, max) = (number(&min)? as f32, number(&max)? as f32);
+        if !(min > 0.0 && max > min) {
+            return Err(format!("cible invalide: {} – {} Hz", min, max).into());
+        }
+        out.borrow_mut().target = Some((min, max));
+        Ok(())
+    });
+    let out = Rc::clone(output);
+    engine.register_fn("clear_target", move || out.borrow_mut().target = None);
+    let out = Rc::clone(output);
+    engine.register_fn("finish", move || out.borrow_mut().finished = true);
+    engine.register_fn("semitones", |from: Dynamic, to: Dynamic| -> ApiResult<f64> {
+        Ok(12.0 * (number(&to)? / number(&from)?).log2())
+    });
+    engine
+}
+
+/// Script lancé: son moteur, son code compilé et son état (`this`).
+struct Running {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Dynamic,
+    started: Instant,
+}
+
+impl Running {
+    fn has_fn(&self, name: &str, params: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == params)
+    }
+
+    fn call(&mut self, name: &str, args: impl rhai::FuncArgs) -> Result<(), String> {
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, name, args)
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", name, e))
+    }
+}
+
+/// Exercices écrits en Rhai, lus dans le dossier des scripts: ils suivent
+/// les trames analysées et pilotent consigne, score et cible sans
+/// recompiler l'application.
+pub struct ScriptRunner {
+    scripts: Vec<Script>,
+    selected: usize,
+    running: Option<Running>,
+    output: Rc<RefCell<Output>>,
+    error: Option<String>,
+}
+
+impl Default for ScriptRunner {
+    fn default() -> Self {
+        let (scripts, error) = load_scripts();
+        Self {
+            scripts,
+            selected: 0,
+            running: None,
+            output: Rc::default(),
+            error,
+        }
+    }
+}
+
+impl ScriptRunner {
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    fn start(&mut self) {
+        self.error = None;
+        self.output = Rc::default();
+        let engine = sandboxed_engine(&self.output);
+        let script = &self.scripts[self.selected];
+        let ast = match engine.compile(&script.source) {
+            Ok(ast) => ast,
+            Err(e) => {
+                self.error = Some(format!("Script invalide: {}", e));
+                return;
+            }
+        };
+        let mut scope = Scope::new();
+        if let Err(e) = engine.run_ast_with_scope(&mut scope, &ast) {
+            self.error = Some(format!("Script: {}", e));
+            return;
+        }
+        let mut running = Running {
+            engine,
+            ast,
+            scope,
+            state: Dynamic::from_map(rhai::Map::new()),
+            started: Instant::now(),
+        };
+        if !running.has_fn("on_frame", 1) {
+            self.error = Some("Le script doit définir fn on_frame(frame)".to_string());
+            return;
+        }
+        if running.has_fn("on_start", 0)
+            && let Err(e) = running.call("on_start", ())
+        {
+            self.error = Some(e);
+            return;
+        }
+        self.running = Some(running);
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut running) = self.running.take()
+            && running.has_fn("on_stop", 0)
+            && let Err(e) = running.call("on_stop", ())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    /// `frame.frequency`: hauteur retenue par l'application, 0 si la trame
+    /// est peu fiable. `target`: cible des réglages.
+    pub fn push_frame(&mut self, frame: &AnalysisFrame, target: TargetRange) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+        let (min_hz, max_hz) = self
+            .output
+            .borrow()
+            .target
+            .unwrap_or((target.min_hz, target.max_hz));
+        let mut map = match rhai::serde::to_dynamic(frame).map(Dynamic::try_cast::<rhai::Map>) {
+            Ok(Some(map)) => map,
+            _ => return,
+        };
+        map.insert("time".into(), Dynamic::from_float(running.started.elapsed().as_secs_f64()));
+        map.insert("in_target".into(), (min_hz..=max_hz).contains(&frame.frequency).into());
+        map.insert("target_min_hz".into(), Dynamic::from_float(min_hz as f64));
+        map.insert("target_max_hz".into(), Dynamic::from_float(max_hz as f64));
+
+        if let Err(e) = running.call("on_frame", (Dynamic::from_map(map),)) {
+            self.error = Some(e);
+            self.running = None;
+        } else if self.output.borrow().finished {
+            self.stop();
+        }
+    }
+
+    fn reload(&mut self) {
+        let name = self.scripts.get(self.selected).map(|script| script.name.clone());
+        (self.scripts, self.error) = load_scripts();
+        self.selected = name
+            .and_then(|name| self.scripts.iter().position(|script| script.name == name))
+            .unwrap_or(0);
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, is_recording: bool, scale: PitchScale) {
+        if self.running.is_some() && !is_recording {
+            self.stop();
+        }
+
+        ui.heading("📜 Exercices scriptés");
+        ui.label(
+            "Des exercices écrits en Rhai, à partager sans recompiler l'application: le \
+             script suit chaque trame analysée et affiche consignes, score et cible.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.add_enabled_ui(self.running.is_none(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Script:");
+                egui::ComboBox::from_id_salt("script_file")
+                    .selected_text(&self.scripts[self.selected].name)
+                    .show_ui(ui, |ui| {
+                        for (index, script) in self.scripts.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected, index, &script.name);
+                        }
+                    });
+                if ui.button("🔄 Recharger").clicked() {
+                    self.reload();
+                }
+            });
+        });
+        let description = self.scripts[self.selected].description();
+        if !description.is_empty() {
+            ui.weak(description);
+        }
+        if let Ok(dir) = scripts_dir() {
+            ui.small(format!("Ajoutez vos scripts (.rhai) dans {}", dir.display()));
+        }
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            if self.running.is_some() {
+                if ui.button("⏹ Arrêter").clicked() {
+                    self.stop();
+                }
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("▶ Lancer le script"))
+                .on_disabled_hover_text("Démarrez l'enregistrement pour lancer le script")
+                .clicked()
+            {
+                self.start();
+            }
+        });
+        self.show_output(ui, scale);
+        if self.running.is_some() {
+            ui.ctx().request_repaint();
+        }
+
+        ui.separator();
+        egui::CollapsingHeader::new("📖 API des scripts").show(ui, |ui| {
+            ui.small(
+                "Un script ne peut ni lire ni écrire de fichiers, ni accéder au réseau; \
+                 chaque appel est limité en nombre d'opérations.",
+            );
+            egui::Grid::new("script_api").num_columns(2).striped(true).show(ui, |ui| {
+                for (signature, description) in API {
+                    ui.monospace(*signature);
+                    ui.label(*description);
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn show_output(&self, ui: &mut egui::Ui, scale: PitchScale) {
+        let output = self.output.borrow();
+        if !output.message.is_empty() {
+            ui.label(
+                egui::RichText::new(&output.message)
+                    .size(24.0)
+                    .strong()
+                    .color(egui::Color32::from_rgb(255, 0, 255)),
+            );
+        }
+        if let Some(score) = output.score {
+            ui.label(egui::RichText::new(format!("Score: {}", score)).size(18.0));
+        }
+        if let Some((min, max)) = output.target {
+            ui.label(format!("Cible de l'exercice: {} – {}", scale.format(min), scale.format(max)));
+        }
+        if output.finished {
+            ui.label("✔ Exercice terminé");
+        }
+        if !output.log.is_empty() {
+            egui::CollapsingHeader::new("Journal du script").show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in &output.log {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use eframe::egui;
+use feminizer_voice_core::{AnalysisConfig, FrequencyData, VadConfig};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
+
+use crate::audio_processor::{AudioProcessor, SecondaryInput};
+
+/// Second flux analysé pendant l'enregistrement (une voix à imiter, le son
+/// d'un jeu ou d'une vidéo), dont la hauteur s'affiche sous celle du micro.
+#[derive(Default)]
+pub struct SecondStream {
+    source: Option<SecondaryInput>,
+    processor: Option<AudioProcessor>,
+    frequency_data: Arc<Mutex<Option<FrequencyData>>>,
+    /// Dernière hauteur fiable du flux, 0 hors voix.
+    current: f32,
+    /// Une valeur par trame de l'historique du micro: les deux courbes
+    /// partagent ainsi leur axe du temps.
+    history: VecDeque<f32>,
+    pub error: Option<String>,
+}
+
+impl SecondStream {
+    pub fn history(&self) -> &VecDeque<f32> {
+        &self.history
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.processor.is_some()
+    }
+
+    pub fn label(&self) -> String {
+        self.source
+            .as_ref()
+            .map_or("Second flux".to_string(), SecondaryInput::label)
+    }
+
+    pub fn start(
+        &mut self,
+        vad_config: &Arc<Mutex<VadConfig>>,
+        analysis: &Arc<Mutex<AnalysisConfig>>,
+    ) {
+        self.stop();
+        let Some(source) = &self.source else {
+            return;
+        };
+        match AudioProcessor::secondary(
+            source,
+            self.frequency_data.clone(),
+            vad_config.clone(),
+            analysis.clone(),
+        ) {
+            Ok(processor) => {
+                self.processor = Some(processor);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Second flux: {}", e)),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.processor = None;
+        self.current = 0.0;
+        self.history.clear();
+    }
+
+    /// Relève la dernière trame du flux; les trames intermédiaires sont
+    /// perdues, seule compte la hauteur au moment de chaque trame du micro.
+    pub fn poll(&mut self, accepted: RangeInclusive<f32>, min_confidence: f32) {
+        let Some(processor) = &self.processor else {
+            return;
+        };
+        if let Some(error) = processor.take_error() {
+            self.error = Some(format!("Second flux: {}", error));
+            self.stop();
+            return;
+        }
+        let data = self.frequency_data.try_lock().ok().and_then(|mut data| data.take());
+        let Some(data) = data else {
+            return;
+        };
+        let reliable = data.is_voiced
+            && data.confidence >= min_confidence
+            && accepted.contains(&data.dominant_frequency);
+        self.current = if reliable { data.dominant_frequency } else { 0.0 };
+        processor.recycle_spectrum(data.spectrum);
+    }
+
+    /// À appeler à chaque trame ajoutée à l'historique du micro.
+    pub fn push_history(&mut self, max_len: usize) {
+        if !self.is_running() {
+            return;
+        }
+        self.history.push_back(self.current);
+        while self.history.len() > max_len {
+            self.history.pop_front();
+        }
+    }
+
+    /// Choix de la source; renvoie `true` si elle a changé.
+    pub fn show_picker(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut selected = self.source.clone();
+        egui::ComboBox::from_id_salt("second_stream")
+            .selected_text(selected.as_ref().map_or("Aucun".to_string(), SecondaryInput::label))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, "Aucun");
+                match AudioProcessor::secondary_sources() {
+                    Ok(sources) => {
+                        for source in sources {
+                            let label = source.label();
+                            ui.selectable_value(&mut selected, Some(source), label);
+                        }
+                    }
+                    Err(e) => {
+                        ui.label(format!("Périphériques indisponibles: {}", e));
+                    }
+                }
+            })
+            .response
+            .on_hover_text(
+                "Une autre entrée ou ce que joue l'ordinateur, pour comparer sa hauteur \
+                 à la vôtre en direct",
+            );
+        if selected == self.source {
+            return false;
+        }
+        self.source = selected;
+        true
+    }
+}
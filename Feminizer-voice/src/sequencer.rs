@@ -0,0 +1,286 @@
+use eframe::egui;
+use egui_plot::{Bar, BarChart, HLine, Plot};
+use std::time::Instant;
+
+use crate::cues::CueCategory;
+
+/// Préparation avant la première phonation.
+const COUNT_IN_SECS: f32 = 3.0;
+/// Part de la phonation au-dessus du seuil pour réussir une répétition.
+const PASS_RATIO: f32 = 0.8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    CountIn,
+    Phonation(usize),
+    Rest(usize),
+    Done,
+}
+
+/// Temps mesurés pendant la phonation d'une répétition.
+#[derive(Clone, Copy, Default)]
+struct RepTally {
+    voiced_secs: f32,
+    /// Temps voisé au-dessus du seuil.
+    compliant_secs: f32,
+}
+
+/// Déroulé figé au lancement: les réglages peuvent bouger pendant la série.
+#[derive(Clone, Copy)]
+struct Plan {
+    phonation_secs: f32,
+    rest_secs: f32,
+    min_hz: f32,
+    reps: usize,
+}
+
+impl Plan {
+    fn phase_at(&self, elapsed_secs: f32) -> Phase {
+        let elapsed = elapsed_secs - COUNT_IN_SECS;
+        if elapsed < 0.0 {
+            return Phase::CountIn;
+        }
+        let cycle = self.phonation_secs + self.rest_secs;
+        let rep = (elapsed / cycle) as usize;
+        let resting = elapsed - rep as f32 * cycle >= self.phonation_secs;
+        // Pas de repos après la dernière répétition
+        if rep >= self.reps || (resting && rep + 1 == self.reps) {
+            Phase::Done
+        } else if resting {
+            Phase::Rest(rep)
+        } else {
+            Phase::Phonation(rep)
+        }
+    }
+
+    /// Secondes restantes dans la phase en cours.
+    fn remaining_secs(&self, elapsed_secs: f32) -> f32 {
+        let elapsed = elapsed_secs - COUNT_IN_SECS;
+        if elapsed < 0.0 {
+            return -elapsed;
+        }
+        let cycle = self.phonation_secs + self.rest_secs;
+        let within = elapsed.rem_euclid(cycle);
+        if within < self.phonation_secs {
+            self.phonation_secs - within
+        } else {
+            cycle - within
+        }
+    }
+
+    fn compliance(&self, tally: &RepTally) -> f32 {
+        (tally.compliant_secs / self.phonation_secs).min(1.0)
+    }
+}
+
+struct Run {
+    plan: Plan,
+    started: Instant,
+    phase: Phase,
+    tallies: Vec<RepTally>,
+}
+
+/// Série chronométrée: phonation au-dessus d'un seuil, repos, répétée. Chaque
+/// changement de phase joue un signal et chaque répétition est notée.
+pub struct DrillSequencer {
+    phonation_secs: f32,
+    rest_secs: f32,
+    min_hz: f32,
+    reps: usize,
+    run: Option<Run>,
+    /// Dernière série terminée.
+    results: Option<(Plan, Vec<RepTally>)>,
+}
+
+impl Default for DrillSequencer {
+    fn default() -> Self {
+        Self {
+            phonation_secs: 5.0,
+            rest_secs: 5.0,
+            min_hz: 180.0,
+            reps: 10,
+            run: None,
+            results: None,
+        }
+    }
+}
+
+impl DrillSequencer {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    fn start(&mut self) {
+        let plan = Plan {
+            phonation_secs: self.phonation_secs,
+            rest_secs: self.rest_secs,
+            min_hz: self.min_hz,
+            reps: self.reps,
+        };
+        self.results = None;
+        self.run = Some(Run {
+            plan,
+            started: Instant::now(),
+            phase: Phase::CountIn,
+            tallies: vec![RepTally::default(); plan.reps],
+        });
+    }
+
+    fn stop(&mut self) {
+        if let Some(run) = self.run.take() {
+            self.results = Some((run.plan, run.tallies));
+        }
+    }
+
+    /// Avance le déroulé; renvoie le signal à jouer au changement de phase.
+    pub fn tick(&mut self) -> Option<CueCategory> {
+        let run = self.run.as_mut()?;
+        let phase = run.plan.phase_at(run.started.elapsed().as_secs_f32());
+        if phase == run.phase {
+            return None;
+        }
+        let previous = std::mem::replace(&mut run.phase, phase);
+        let cue = match (previous, phase) {
+            (Phase::Phonation(rep), _) => {
+                let passed = run.plan.compliance(&run.tallies[rep]) >= PASS_RATIO;
+                Some(if passed { CueCategory::Success } else { CueCategory::Warning })
+            }
+            (_, Phase::Phonation(_)) => Some(CueCategory::Start),
+            _ => None,
+        };
+        if phase == Phase::Done {
+            self.stop();
+        }
+        cue
+    }
+
+    /// Compte chaque trame analysée dans la répétition où elle a été captée.
+    pub fn push_frame(&mut self, captured_at: Instant, frequency: f32, frame_duration: f32) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let elapsed = captured_at.saturating_duration_since(run.started).as_secs_f32();
+        let Phase::Phonation(rep) = run.plan.phase_at(elapsed) else {
+            return;
+        };
+        let tally = &mut run.tallies[rep];
+        if frequency > 0.0 {
+            tally.voiced_secs += frame_duration;
+            if frequency >= run.plan.min_hz {
+                tally.compliant_secs += frame_duration;
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, is_recording: bool) {
+        ui.label("Série chronométrée: tenez la voix au-dessus du seuil, puis reposez-vous.");
+        ui.add_enabled_ui(!self.is_running(), |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.phonation_secs, 1.0..=30.0)
+                        .text("s de phonation"),
+                );
+                ui.add(egui::Slider::new(&mut self.rest_secs, 1.0..=30.0).text("s de repos"));
+            });
+            ui.horizontal(|ui| {
+                ui.add(egui::Slider::new(&mut self.min_hz, 80.0..=400.0).text("Hz minimum"));
+                ui.add(egui::Slider::new(&mut self.reps, 1..=30).text("répétitions"));
+            });
+        });
+
+        ui.horizontal(|ui| {
+            if self.is_running() {
+                if ui.button("⏹ Arrêter la série").clicked() {
+                    self.stop();
+                }
+            } else if ui
+                .add_enabled(is_recording, egui::Button::new("⏱ Lancer la série"))
+                .clicked()
+            {
+                self.start();
+            }
+            if !is_recording {
+                ui.small("Démarrez l'enregistrement pour lancer la série.");
+            }
+        });
+
+        if let Some(run) = &self.run {
+            let elapsed = run.started.elapsed().as_secs_f32();
+            let remaining = run.plan.remaining_secs(elapsed);
+            let (text, color) = match run.phase {
+                Phase::CountIn => ("Préparez-vous".to_string(), egui::Color32::GRAY),
+                Phase::Phonation(rep) => (
+                    format!("🗣 Phonation {} / {}", rep + 1, run.plan.reps),
+                    egui::Color32::from_rgb(255, 0, 255),
+                ),
+                Phase::Rest(rep) => {
+                    let compliance = run.plan.compliance(&run.tallies[rep]);
+                    (
+                        format!("😌 Repos — répétition {} à {:.0} %", rep + 1, 100.0 * compliance),
+                        egui::Color32::from_rgb(120, 180, 255),
+                    )
+                }
+                Phase::Done => (String::new(), egui::Color32::GRAY),
+            };
+            ui.horizontal(|ui| {
+                ui.colored_label(color, egui::RichText::new(text).size(20.0));
+                ui.label(format!("{:.0} s", remaining.ceil()));
+            });
+            if let Phase::Phonation(rep) = run.phase {
+                let tally = &run.tallies[rep];
+                let text =
+                    format!("{:.1} s au-dessus de {:.0} Hz", tally.compliant_secs, run.plan.min_hz);
+                ui.add(
+                    egui::ProgressBar::new(run.plan.compliance(tally) / PASS_RATIO).text(text),
+                );
+            }
+        }
+
+        if let Some((plan, tallies)) = &self.results {
+            show_results(ui, plan, tallies);
+        }
+    }
+}
+
+fn show_results(ui: &mut egui::Ui, plan: &Plan, tallies: &[RepTally]) {
+    let compliance: Vec<f32> = tallies.iter().map(|tally| plan.compliance(tally)).collect();
+    let passed = compliance.iter().filter(|&&c| c >= PASS_RATIO).count();
+    ui.label(format!(
+        "✔ {} / {} répétition(s) réussie(s) ({:.0} s à {:.0} Hz ou plus, {:.0} % requis)",
+        passed,
+        tallies.len(),
+        plan.phonation_secs,
+        plan.min_hz,
+        100.0 * PASS_RATIO
+    ));
+
+    let bars: Vec<Bar> = compliance
+        .iter()
+        .zip(tallies)
+        .enumerate()
+        .map(|(i, (&c, tally))| {
+            let color = if c >= PASS_RATIO {
+                egui::Color32::GREEN
+            } else {
+                egui::Color32::YELLOW
+            };
+            let voiced = 100.0 * tally.voiced_secs / plan.phonation_secs;
+            Bar::new(i as f64 + 1.0, 100.0 * c as f64)
+                .fill(color)
+                .name(format!("{:.0} % voisé", voiced.min(100.0)))
+        })
+        .collect();
+
+    Plot::new("drill_compliance")
+        .height(160.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .include_y(0.0)
+        .include_y(100.0)
+        .y_axis_label("Au-dessus du seuil (%)")
+        .x_axis_label("Répétition")
+        .show(ui, |plot_ui| {
+            plot_ui.hline(HLine::new("Réussite", 100.0 * PASS_RATIO).color(egui::Color32::GRAY));
+            plot_ui.bar_chart(BarChart::new("Conformité", bars));
+        });
+}
@@ -0,0 +1,582 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+use feminizer_voice_core::{AnalysisConfig, VadConfig};
+
+use crate::goal::GoalRecord;
+use crate::histogram;
+use crate::paths;
+use crate::schema::{self, Schema};
+
+pub const SESSION_SCHEMA: Schema = Schema {
+    name: "Session",
+    migrations: &[
+        migrate_session_v0,
+        migrate_session_v1,
+        migrate_session_v2,
+        migrate_session_v3,
+        migrate_session_v4,
+        migrate_session_v5,
+        migrate_session_v6,
+        migrate_session_v7,
+        migrate_session_v8,
+    ],
+};
+
+// v0 → v1: fichiers écrits avant le versionnement, champs ajoutés au fil de l'eau
+fn migrate_session_v0(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "mean_brightness", 0.0.into());
+    schema::default_field(map, "self_rating", serde_json::Value::Null);
+    schema::default_field(map, "device_changes", serde_json::json!([]));
+    Ok(())
+}
+
+// v1 → v2: réanalyses conservées à côté de l'analyse d'origine
+fn migrate_session_v1(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "reanalyses", serde_json::json!([]));
+    Ok(())
+}
+
+// v2 → v3: quartiles de hauteur. Pour les anciennes sessions, estimés à partir
+// de la médiane et de l'écart type en demi-tons (±0,674 σ pour une loi normale)
+fn migrate_session_v2(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let field = |key: &str| map.get(key).and_then(serde_json::Value::as_f64).unwrap_or(0.0);
+    let median = field("median_pitch");
+    let half_iqr_st = 0.674 * field("pitch_variability_st");
+    let q1 = median * 2.0_f64.powf(-half_iqr_st / 12.0);
+    let q3 = median * 2.0_f64.powf(half_iqr_st / 12.0);
+    schema::default_field(map, "pitch_q1", q1.into());
+    schema::default_field(map, "pitch_q3", q3.into());
+    Ok(())
+}
+
+// v3 → v4: état de l'objectif quotidien en fin de session
+fn migrate_session_v3(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "goal", serde_json::Value::Null);
+    Ok(())
+}
+
+// v4 → v5: distribution de la hauteur, inconnue pour les sessions antérieures
+fn migrate_session_v4(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "pitch_histogram", serde_json::json!([]));
+    Ok(())
+}
+
+// v5 → v6: alertes de forçage
+fn migrate_session_v5(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "strain_warnings", serde_json::json!([]));
+    Ok(())
+}
+
+// v6 → v7: poids vocal, non mesuré avant
+fn migrate_session_v6(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "mean_h1_h2_db", serde_json::Value::Null);
+    Ok(())
+}
+
+// v7 → v8: repères posés pendant l'enregistrement
+fn migrate_session_v7(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "markers", serde_json::json!([]));
+    Ok(())
+}
+
+// v8 → v9: plus longue série dans la cible, inconnue avant (comptée à 0)
+fn migrate_session_v8(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    schema::default_field(map, "best_streak_secs", 0.0.into());
+    Ok(())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionSummary {
+    pub started_at: u64,
+    pub duration_secs: f32,
+    pub voiced_secs: f32,
+    pub mean_pitch: f32,
+    pub median_pitch: f32,
+    pub pitch_q1: f32,
+    pub pitch_q3: f32,
+    pub pitch_variability_st: f32,
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    pub in_range_percent: f32,
+    /// Plus long temps de voix passé dans la cible sans en sortir (les
+    /// silences n'interrompent pas la série).
+    pub best_streak_secs: f32,
+    pub mean_amplitude_db: f32,
+    pub mean_brightness: f32,
+    /// Poids vocal moyen (H1–H2, dB): plus il est haut, plus la voix est légère.
+    pub mean_h1_h2_db: Option<f32>,
+    pub self_rating: Option<u8>,
+    pub device_changes: Vec<DeviceChange>,
+    pub reanalyses: Vec<Reanalysis>,
+    pub goal: Option<GoalRecord>,
+    /// Pourcentage du temps voisé par classe de hauteur (voir `histogram`).
+    pub pitch_histogram: Vec<f32>,
+    pub strain_warnings: Vec<StrainWarning>,
+    pub markers: Vec<SessionMarker>,
+}
+
+/// Moment marqué d'un raccourci pendant l'enregistrement, avec une note
+/// facultative.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionMarker {
+    pub at_secs: f32,
+    pub text: String,
+}
+
+/// Alerte de forçage (voix forte et haute) donnée pendant la session.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StrainWarning {
+    pub at_secs: f32,
+    /// Temps cumulé en zone de forçage au moment de l'alerte.
+    pub duration_secs: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeviceChange {
+    pub at_secs: f32,
+    pub device: String,
+}
+
+/// Analyse alternative de l'audio d'une session, avec d'autres paramètres.
+/// Les mesures d'origine de la session ne sont jamais remplacées.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Reanalysis {
+    pub created_at: u64,
+    pub label: String,
+    pub analysis: AnalysisConfig,
+    pub vad: VadConfig,
+    /// Durée d'une trame de `pitch_track` (s).
+    pub frame_secs: f32,
+    /// Fréquence retenue par trame, 0 hors voix ou hors plage.
+    pub pitch_track: Vec<f32>,
+    pub voiced_secs: f32,
+    pub mean_pitch: f32,
+    pub median_pitch: f32,
+    pub pitch_variability_st: f32,
+    pub in_range_percent: f32,
+}
+
+pub struct PitchStats {
+    pub mean: f32,
+    pub median: f32,
+    pub q1: f32,
+    pub q3: f32,
+    /// Écart type en demi-tons autour de la médiane.
+    pub variability_st: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+pub fn pitch_stats(frequencies: &[f32]) -> PitchStats {
+    let count = frequencies.len();
+    if count == 0 {
+        return PitchStats {
+            mean: 0.0,
+            median: 0.0,
+            q1: 0.0,
+            q3: 0.0,
+            variability_st: 0.0,
+            min: 0.0,
+            max: 0.0,
+        };
+    }
+
+    let mut sorted = frequencies.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let mean = sorted.iter().sum::<f32>() / count as f32;
+    let median = sorted[count / 2];
+
+    let semitones: Vec<f32> = sorted.iter().map(|f| 12.0 * (f / median).log2()).collect();
+    let mean_st = semitones.iter().sum::<f32>() / count as f32;
+    let variance = semitones.iter().map(|s| (s - mean_st).powi(2)).sum::<f32>() / count as f32;
+
+    PitchStats {
+        mean,
+        median,
+        q1: sorted[count / 4],
+        q3: sorted[count * 3 / 4],
+        variability_st: variance.sqrt(),
+        min: sorted[0],
+        max: sorted[count - 1],
+    }
+}
+
+/// Séance en cours. Sérialisable pour la sauvegarde automatique, qui garde
+/// aussi le temps écoulé: `start` n'a de sens que dans ce processus.
+#[derive(Serialize, Deserialize)]
+pub struct SessionStats {
+    started_at: u64,
+    #[serde(skip, default = "Instant::now")]
+    start: Instant,
+    voiced_secs: f32,
+    in_range_secs: f32,
+    histogram_secs: Vec<f32>,
+    frequencies: Vec<f32>,
+    amplitude_sum: f32,
+    brightness_sum: f32,
+    h1_h2_sum: f32,
+    h1_h2_frames: usize,
+    in_range_frames: usize,
+    streak_secs: f32,
+    best_streak_secs: f32,
+    device_changes: Vec<DeviceChange>,
+    strain_warnings: Vec<StrainWarning>,
+    markers: Vec<SessionMarker>,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            started_at,
+            start: Instant::now(),
+            voiced_secs: 0.0,
+            in_range_secs: 0.0,
+            histogram_secs: vec![0.0; histogram::bin_count()],
+            frequencies: Vec::new(),
+            amplitude_sum: 0.0,
+            brightness_sum: 0.0,
+            h1_h2_sum: 0.0,
+            h1_h2_frames: 0,
+            in_range_frames: 0,
+            streak_secs: 0.0,
+            best_streak_secs: 0.0,
+            device_changes: Vec::new(),
+            strain_warnings: Vec::new(),
+            markers: Vec::new(),
+        }
+    }
+
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+
+    pub fn elapsed_secs(&self) -> f32 {
+        self.start.elapsed().as_secs_f32()
+    }
+
+    /// Reprend une séance sauvegardée après `elapsed_secs` d'enregistrement:
+    /// le temps passé hors de l'application n'est pas compté.
+    pub fn resume(mut self, elapsed_secs: f32) -> Self {
+        let elapsed = Duration::from_secs_f32(elapsed_secs.max(0.0));
+        self.start = Instant::now().checked_sub(elapsed).unwrap_or_else(Instant::now);
+        self.streak_secs = 0.0;
+        self
+    }
+
+    pub fn mark_device_change(&mut self, device: &str) {
+        self.device_changes.push(DeviceChange {
+            at_secs: self.start.elapsed().as_secs_f32(),
+            device: device.to_string(),
+        });
+    }
+
+    pub fn mark_strain(&mut self, duration_secs: f32) {
+        self.strain_warnings.push(StrainWarning {
+            at_secs: self.start.elapsed().as_secs_f32(),
+            duration_secs,
+        });
+    }
+
+    /// Pose un repère maintenant; renvoie son indice pour l'annoter ensuite.
+    pub fn mark(&mut self) -> usize {
+        self.markers.push(SessionMarker {
+            at_secs: self.start.elapsed().as_secs_f32(),
+            text: String::new(),
+        });
+        self.markers.len() - 1
+    }
+
+    /// Repère annoté posé `secs_ago` secondes plus tôt; renvoie son indice.
+    pub fn mark_earlier(&mut self, secs_ago: f32, text: &str) -> usize {
+        self.markers.push(SessionMarker {
+            at_secs: (self.start.elapsed().as_secs_f32() - secs_ago).max(0.0),
+            text: text.to_string(),
+        });
+        self.markers.len() - 1
+    }
+
+    pub fn annotate(&mut self, index: usize, text: &str) {
+        if let Some(marker) = self.markers.get_mut(index) {
+            marker.text = text.to_string();
+        }
+    }
+
+    pub fn push(&mut self, frequency: f32, amplitude: f32, frame_duration: f32, in_range: bool) {
+        self.voiced_secs += frame_duration;
+        self.frequencies.push(frequency);
+        if let Some(bin) = histogram::bin_of(frequency) {
+            self.histogram_secs[bin] += frame_duration;
+        }
+        self.amplitude_sum += amplitude;
+        if in_range {
+            self.in_range_frames += 1;
+            self.in_range_secs += frame_duration;
+            self.streak_secs += frame_duration;
+            self.best_streak_secs = self.best_streak_secs.max(self.streak_secs);
+        } else {
+            self.streak_secs = 0.0;
+        }
+    }
+
+    pub fn push_brightness(&mut self, centroid: f32) {
+        self.brightness_sum += centroid;
+    }
+
+    pub fn push_weight(&mut self, h1_h2_db: f32) {
+        self.h1_h2_sum += h1_h2_db;
+        self.h1_h2_frames += 1;
+    }
+
+    pub fn voiced_frames(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    pub fn voiced_secs(&self) -> f32 {
+        self.voiced_secs
+    }
+
+    pub fn in_range_secs(&self) -> f32 {
+        self.in_range_secs
+    }
+
+    pub fn pitch_histogram(&self) -> Vec<f32> {
+        histogram::normalized(&self.histogram_secs)
+    }
+
+    pub fn median_pitch(&self) -> f32 {
+        if self.frequencies.is_empty() {
+            return 0.0;
+        }
+        let mut values = self.frequencies.clone();
+        let middle = values.len() / 2;
+        *values.select_nth_unstable_by(middle, |a, b| a.total_cmp(b)).1
+    }
+
+    pub fn in_range_percent(&self) -> f32 {
+        if self.frequencies.is_empty() {
+            return 0.0;
+        }
+        100.0 * self.in_range_frames as f32 / self.frequencies.len() as f32
+    }
+
+    pub fn finish(self) -> SessionSummary {
+        let count = self.frequencies.len();
+        let duration_secs = self.start.elapsed().as_secs_f32();
+
+        if count == 0 {
+            return SessionSummary {
+                started_at: self.started_at,
+                duration_secs,
+                voiced_secs: 0.0,
+                mean_pitch: 0.0,
+                median_pitch: 0.0,
+                pitch_q1: 0.0,
+                pitch_q3: 0.0,
+                pitch_variability_st: 0.0,
+                min_pitch: 0.0,
+                max_pitch: 0.0,
+                in_range_percent: 0.0,
+                best_streak_secs: 0.0,
+                mean_amplitude_db: -60.0,
+                mean_brightness: 0.0,
+                mean_h1_h2_db: None,
+                self_rating: None,
+                device_changes: self.device_changes,
+                reanalyses: Vec::new(),
+                goal: None,
+                pitch_histogram: Vec::new(),
+                strain_warnings: self.strain_warnings,
+                markers: self.markers,
+            };
+        }
+
+        let pitch = pitch_stats(&self.frequencies);
+        let pitch_histogram = self.pitch_histogram();
+        let mean_amplitude = self.amplitude_sum / count as f32;
+
+        SessionSummary {
+            started_at: self.started_at,
+            duration_secs,
+            voiced_secs: self.voiced_secs,
+            mean_pitch: pitch.mean,
+            median_pitch: pitch.median,
+            pitch_q1: pitch.q1,
+            pitch_q3: pitch.q3,
+            pitch_variability_st: pitch.variability_st,
+            min_pitch: pitch.min,
+            max_pitch: pitch.max,
+            in_range_percent: 100.0 * self.in_range_frames as f32 / count as f32,
+            best_streak_secs: self.best_streak_secs,
+            mean_amplitude_db: if mean_amplitude > 0.0 {
+                20.0 * mean_amplitude.log10()
+            } else {
+                -60.0
+            },
+            mean_brightness: self.brightness_sum / count as f32,
+            mean_h1_h2_db: (self.h1_h2_frames > 0)
+                .then(|| self.h1_h2_sum / self.h1_h2_frames as f32),
+            self_rating: None,
+            device_changes: self.device_changes,
+            reanalyses: Vec::new(),
+            goal: None,
+            pitch_histogram,
+            strain_warnings: self.strain_warnings,
+            markers: self.markers,
+        }
+    }
+}
+
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+/// Une ligne par session, pour un tableur.
+pub fn export_csv(sessions: &[SessionSummary]) -> Result<PathBuf> {
+    let mut csv = String::from(
+        "started_at,duration_secs,voiced_secs,median_pitch_hz,pitch_q1_hz,pitch_q3_hz,\
+         variability_st,in_range_percent,mean_amplitude_db,mean_brightness_hz,self_rating,\
+         strain_warnings,mean_h1_h2_db,best_streak_secs\n",
+    );
+    for s in sessions {
+        csv.push_str(&format!(
+            "{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{:.1},{:.1},{:.0},{},{},{},{:.1}\n",
+            s.started_at,
+            s.duration_secs,
+            s.voiced_secs,
+            s.median_pitch,
+            s.pitch_q1,
+            s.pitch_q3,
+            s.pitch_variability_st,
+            s.in_range_percent,
+            s.mean_amplitude_db,
+            s.mean_brightness,
+            s.self_rating.map(|r| r.to_string()).unwrap_or_default(),
+            s.strain_warnings.len(),
+            s.mean_h1_h2_db.map(|db| format!("{:.1}", db)).unwrap_or_default(),
+            s.best_streak_secs,
+        ));
+    }
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = paths::profile_subdir("exports")?.join(format!("sessions-{}.csv", now));
+    fs::write(&path, csv)?;
+    Ok(path)
+}
+
+impl SessionStore {
+    pub fn open() -> Result<Self> {
+        Ok(Self {
+            dir: paths::profile_subdir("sessions")?,
+        })
+    }
+
+    fn path_for(&self, summary: &SessionSummary) -> PathBuf {
+        self.dir.join(format!("{}.json", summary.started_at))
+    }
+
+    /// Enregistrement audio de la session, s'il a été conservé.
+    pub fn audio_path(&self, started_at: u64) -> PathBuf {
+        self.dir.join(format!("{}.wav", started_at))
+    }
+
+    pub fn save(&self, summary: &SessionSummary) -> Result<()> {
+        let json = SESSION_SCHEMA.encode(summary)?;
+        fs::write(self.path_for(summary), json)?;
+        Ok(())
+    }
+
+    pub fn load_all(&self) -> Result<Vec<SessionSummary>> {
+        let mut sessions = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match self.load_file(&path) {
+                    Ok(summary) => sessions.push(summary),
+                    Err(e) => warn!("Session illisible {}: {}", path.display(), e),
+                }
+            }
+        }
+
+        sessions.sort_by_key(|s| s.started_at);
+        Ok(sessions)
+    }
+
+    fn load_file(&self, path: &Path) -> Result<SessionSummary> {
+        let json = fs::read_to_string(path)?;
+        let loaded = SESSION_SCHEMA.decode::<SessionSummary>(&json)?;
+
+        // Réécrit au format courant en gardant l'original à côté
+        if let Some(version) = loaded.migrated_from {
+            fs::write(path.with_extension(format!("v{}.bak", version)), &json)?;
+            self.save(&loaded.value)?;
+        }
+        Ok(loaded.value)
+    }
+}
+
+/// Session sans mesures commencée à `started_at`, à compléter par les tests.
+#[cfg(test)]
+pub fn test_summary(started_at: u64) -> SessionSummary {
+    SessionSummary {
+        started_at,
+        ..SessionStats::new().finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Session écrite avant le versionnement des fichiers.
+    const V0_SESSION: &str = r#"{
+        "started_at": 1700000000,
+        "duration_secs": 600.0,
+        "voiced_secs": 240.0,
+        "mean_pitch": 190.0,
+        "median_pitch": 185.0,
+        "pitch_variability_st": 2.0,
+        "min_pitch": 150.0,
+        "max_pitch": 260.0,
+        "in_range_percent": 62.5,
+        "mean_amplitude_db": -24.0
+    }"#;
+
+    #[test]
+    fn a_v0_session_is_migrated_to_the_current_format() {
+        let loaded = SESSION_SCHEMA.decode::<SessionSummary>(V0_SESSION).unwrap();
+        assert_eq!(loaded.migrated_from, Some(0));
+        let session = loaded.value;
+        assert_eq!(session.started_at, 1_700_000_000);
+        assert_eq!(session.median_pitch, 185.0);
+        assert!(session.pitch_q1 < 185.0 && session.pitch_q3 > 185.0);
+        assert!(session.reanalyses.is_empty() && session.markers.is_empty());
+        assert_eq!(session.mean_h1_h2_db, None);
+        assert_eq!(session.best_streak_secs, 0.0);
+
+        // Réécrite, elle est relue sans migration
+        let json = SESSION_SCHEMA.encode(&session).unwrap();
+        let loaded = SESSION_SCHEMA.decode::<SessionSummary>(&json).unwrap();
+        assert_eq!(loaded.migrated_from, None);
+        assert_eq!(loaded.value.pitch_q1, session.pitch_q1);
+    }
+
+    #[test]
+    fn migrations_keep_existing_fields() {
+        let mut map: serde_json::Map<_, _> = serde_json::from_str(V0_SESSION).unwrap();
+        schema::default_field(&mut map, "pitch_q1", 170.0.into());
+        migrate_session_v2(&mut map).unwrap();
+        assert_eq!(map["pitch_q1"], 170.0);
+    }
+}
@@ -0,0 +1,243 @@
+use anyhow::Result;
+use feminizer_voice_core::{AnalysisConfig, FrequencyProcessor, Resampler, VadConfig};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::session::{self, Reanalysis};
+use crate::wav;
+
+/// Échantillons mono analysés, à vider régulièrement dans le fichier de la
+/// session.
+#[derive(Default)]
+pub struct SharedAudioTap {
+    /// `None` quand l'audio n'est pas conservé.
+    buffer: Mutex<Option<Vec<f32>>>,
+    enabled: AtomicBool,
+    /// Échantillons que le callback n'a pas pu déposer pendant que
+    /// l'interface vidait le tampon.
+    dropped: AtomicUsize,
+}
+
+pub type AudioTap = Arc<SharedAudioTap>;
+
+impl SharedAudioTap {
+    /// Commence à garder l'audio, avec un tampon vide.
+    pub fn open(&self) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            *buffer = Some(Vec::new());
+        }
+        self.dropped.store(0, Ordering::Relaxed);
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Prend ce que le callback a déposé, et commence à garder l'audio si ce
+    /// n'était pas le cas. `None` si le callback tient le verrou.
+    pub fn drain(&self) -> Option<Vec<f32>> {
+        // Relevées avant de prendre le verrou: ces pertes précèdent tout ce
+        // que le tampon contient
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        let Ok(mut buffer) = self.buffer.try_lock() else {
+            self.dropped.fetch_add(dropped, Ordering::Relaxed);
+            return None;
+        };
+        // Le callback garde un tampon de même capacité: il n'a pas à réallouer
+        let buffer = buffer.get_or_insert_default();
+        let capacity = buffer.capacity();
+        let samples = std::mem::replace(buffer, Vec::with_capacity(capacity));
+        self.enabled.store(true, Ordering::Relaxed);
+        Some(with_silence(samples, dropped))
+    }
+
+    /// Cesse de garder l'audio et rend ce qui restait à écrire.
+    pub fn close(&self) -> Option<Vec<f32>> {
+        if !self.enabled.swap(false, Ordering::Relaxed) {
+            return None;
+        }
+        let dropped = self.dropped.swap(0, Ordering::Relaxed);
+        let samples = self.buffer.lock().ok()?.take()?;
+        Some(with_silence(samples, dropped))
+    }
+}
+
+/// Les échantillons perdus sont remplacés par du silence: l'audio garde la
+/// durée de la capture, et les repères de la session restent alignés.
+fn with_silence(mut samples: Vec<f32>, dropped: usize) -> Vec<f32> {
+    if dropped > 0 {
+        samples.splice(0..0, std::iter::repeat_n(0.0, dropped));
+    }
+    samples
+}
+
+pub fn push_to_audio_tap(tap: &AudioTap, samples: &[f32]) {
+    match tap.buffer.try_lock() {
+        Ok(mut buffer) => {
+            if let Some(buffer) = buffer.as_mut() {
+                buffer.extend_from_slice(samples);
+            }
+        }
+        Err(TryLockError::WouldBlock) if tap.enabled.load(Ordering::Relaxed) => {
+            tap.dropped.fetch_add(samples.len(), Ordering::Relaxed);
+        }
+        Err(_) => {}
+    }
+}
+
+pub struct SessionAudioWriter {
+    writer: hound::WavWriter<BufWriter<File>>,
+    sample_rate: f32,
+}
+
+impl SessionAudioWriter {
+    pub fn create(path: &Path, sample_rate: f32) -> Result<Self> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: sample_rate as u32,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        Ok(Self {
+            writer: hound::WavWriter::create(path, spec)?,
+            sample_rate,
+        })
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    pub fn write(&mut self, samples: &[f32]) -> Result<()> {
+        for &sample in samples {
+            self.writer
+                .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<()> {
+        self.writer.finalize()?;
+        Ok(())
+    }
+}
+
+/// Paramètres d'une réanalyse: découpage, détection de voix et plages.
+pub struct ReanalysisParams {
+    pub analysis: AnalysisConfig,
+    pub vad: VadConfig,
+    pub accept_min_hz: f32,
+    pub accept_max_hz: f32,
+    /// Confiance minimale d'une trame pour compter.
+    pub min_confidence: f32,
+    pub target_min_hz: f32,
+    pub target_max_hz: f32,
+}
+
+/// Fréquence retenue (0 hors voix ou hors plage) et niveau RMS par trame,
+/// et durée d'une trame (s).
+pub fn analysis_track(
+    samples: &[f32],
+    sample_rate: f32,
+    params: &ReanalysisParams,
+) -> (Vec<(f32, f32)>, f32) {
+    let analysis = params.analysis.sanitized();
+    let mut resampler = Resampler::new(sample_rate, analysis.resample_hz);
+    let mut resampled = Vec::new();
+    resampler.process(samples, &mut resampled);
+    let sample_rate = resampler.output_rate();
+    let mut processor = FrequencyProcessor::new(sample_rate, analysis, params.vad);
+
+    // Un bloc par pas d'analyse: chaque trame produite est conservée
+    let mut track = Vec::with_capacity(resampled.len() / analysis.hop_size + 1);
+    for block in resampled.chunks(analysis.hop_size) {
+        if let Some(data) = processor.process_samples(block) {
+            let accepted = data.is_voiced
+                && data.confidence >= params.min_confidence
+                && (params.accept_min_hz..=params.accept_max_hz).contains(&data.dominant_frequency);
+            let frequency = if accepted { data.dominant_frequency } else { 0.0 };
+            track.push((frequency, data.amplitude));
+        }
+    }
+    (track, analysis.hop_size as f32 / sample_rate)
+}
+
+/// Fréquence retenue par trame (0 hors voix ou hors plage) et durée d'une
+/// trame (s).
+pub fn pitch_track(
+    samples: &[f32],
+    sample_rate: f32,
+    params: &ReanalysisParams,
+) -> (Vec<f32>, f32) {
+    let (track, frame_secs) = analysis_track(samples, sample_rate, params);
+    (track.into_iter().map(|(frequency, _)| frequency).collect(), frame_secs)
+}
+
+/// Repasse l'audio d'une session dans l'analyseur avec d'autres paramètres.
+pub fn reanalyze(path: &Path, params: &ReanalysisParams) -> Result<Reanalysis> {
+    let (samples, sample_rate) = wav::read_mono(path)?;
+    let analysis = params.analysis.sanitized();
+    let (pitch_track, frame_secs) = self::pitch_track(&samples, sample_rate, params);
+    let voiced: Vec<f32> = pitch_track.iter().copied().filter(|&f| f > 0.0).collect();
+    let in_range = voiced
+        .iter()
+        .filter(|&f| (params.target_min_hz..=params.target_max_hz).contains(f))
+        .count();
+    let stats = session::pitch_stats(&voiced);
+
+    Ok(Reanalysis {
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        label: format!(
+            "{:?} {} / pas {} / ×{}",
+            analysis.window, analysis.window_size, analysis.hop_size, analysis.zero_padding
+        ),
+        analysis,
+        vad: params.vad,
+        frame_secs,
+        voiced_secs: voiced.len() as f32 * frame_secs,
+        mean_pitch: stats.mean,
+        median_pitch: stats.median,
+        pitch_variability_st: stats.variability_st,
+        in_range_percent: if voiced.is_empty() {
+            0.0
+        } else {
+            100.0 * in_range as f32 / voiced.len() as f32
+        },
+        pitch_track,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_lost_while_draining_become_silence() {
+        let tap = AudioTap::default();
+        tap.open();
+        push_to_audio_tap(&tap, &[0.5; 3]);
+        assert_eq!(tap.drain().unwrap(), [0.5; 3]);
+
+        // L'interface tient le verrou: le bloc du callback est perdu
+        let held = tap.buffer.lock().unwrap();
+        push_to_audio_tap(&tap, &[0.5; 4]);
+        drop(held);
+        push_to_audio_tap(&tap, &[0.25; 2]);
+        assert_eq!(tap.drain().unwrap(), [0.0, 0.0, 0.0, 0.0, 0.25, 0.25]);
+        assert!(tap.close().unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_closed_tap_counts_nothing() {
+        let tap = AudioTap::default();
+        let held = tap.buffer.lock().unwrap();
+        push_to_audio_tap(&tap, &[0.5; 4]);
+        drop(held);
+        assert!(tap.close().is_none());
+        assert!(tap.drain().unwrap().is_empty());
+    }
+}
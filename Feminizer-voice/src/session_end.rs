@@ -0,0 +1,185 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::dates::DateTime;
+use crate::pitch_unit::PitchScale;
+use crate::report::{self, ReportExporter};
+use crate::session::SessionSummary;
+
+/// En dessous, un pourcentage dans la cible ne vaut pas record: quelques
+/// secondes de voix suffiraient à l'atteindre.
+const MIN_RECORD_VOICED_SECS: f32 = 60.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SessionEndSettings {
+    /// Bilan affiché à l'arrêt de l'enregistrement.
+    pub show_summary: bool,
+    /// Notification du bureau quand un record personnel est battu.
+    pub notify_records: bool,
+}
+
+impl Default for SessionEndSettings {
+    fn default() -> Self {
+        Self {
+            show_summary: true,
+            notify_records: false,
+        }
+    }
+}
+
+impl SessionEndSettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = ui
+            .checkbox(&mut self.show_summary, "Afficher le bilan à l'arrêt de l'enregistrement")
+            .changed();
+        changed |= ui
+            .checkbox(&mut self.notify_records, "Notification en cas de record personnel")
+            .on_hover_text("Dans la cible, plus longue série, temps de voix")
+            .changed();
+        changed
+    }
+}
+
+/// Records personnels battus par `session` face à toutes les précédentes.
+/// La toute première séance n'en bat aucun.
+pub fn personal_bests(session: &SessionSummary, previous: &[SessionSummary]) -> Vec<String> {
+    let mut records = Vec::new();
+    if previous.is_empty() {
+        return records;
+    }
+    let best = |value: fn(&SessionSummary) -> f32| {
+        previous.iter().map(value).fold(0.0, f32::max)
+    };
+
+    let in_range = previous
+        .iter()
+        .filter(|s| s.voiced_secs >= MIN_RECORD_VOICED_SECS)
+        .map(|s| s.in_range_percent)
+        .fold(0.0, f32::max);
+    if session.voiced_secs >= MIN_RECORD_VOICED_SECS && session.in_range_percent > in_range {
+        records.push(format!("Dans la cible: {:.0} % du temps", session.in_range_percent));
+    }
+    if session.best_streak_secs > best(|s| s.best_streak_secs) {
+        records.push(format!(
+            "Plus longue série dans la cible: {}",
+            report::format_duration(session.best_streak_secs)
+        ));
+    }
+    if session.voiced_secs > best(|s| s.voiced_secs) {
+        records.push(format!(
+            "Temps de voix: {}",
+            report::format_duration(session.voiced_secs)
+        ));
+    }
+    records
+}
+
+/// Ligne du tableau comparatif: libellé et valeur lue dans une séance.
+type ComparisonRow = (&'static str, fn(&SessionSummary, PitchScale) -> String);
+
+struct Shown {
+    summary: SessionSummary,
+    previous: Option<SessionSummary>,
+    records: Vec<String>,
+}
+
+/// Bilan de fin de séance, comparé à la séance précédente.
+#[derive(Default)]
+pub struct SessionEndSummary {
+    shown: Option<Shown>,
+}
+
+impl SessionEndSummary {
+    pub fn open(
+        &mut self,
+        summary: SessionSummary,
+        previous: Option<SessionSummary>,
+        records: Vec<String>,
+    ) {
+        self.shown = Some(Shown {
+            summary,
+            previous,
+            records,
+        });
+    }
+
+    /// Renvoie le début de la séance dont le rapport est demandé.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        scale: PitchScale,
+        reports: &ReportExporter,
+    ) -> Option<u64> {
+        let shown = self.shown.as_ref()?;
+        let mut open = true;
+        let mut close = false;
+        let mut export = None;
+        egui::Window::new("🏁 Fin de séance")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let session = &shown.summary;
+                ui.label(format!("Séance du {}", DateTime::from_unix(session.started_at)));
+                if !shown.records.is_empty() {
+                    ui.add_space(4.0);
+                    ui.strong("🏆 Record personnel !");
+                    for record in &shown.records {
+                        ui.label(format!("• {}", record));
+                    }
+                }
+                ui.add_space(4.0);
+                Self::show_comparison(ui, session, shown.previous.as_ref(), scale);
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let button = egui::Button::new("📄 Exporter le rapport");
+                    if ui.add_enabled(!reports.is_running(), button).clicked() {
+                        export = Some(session.started_at);
+                    }
+                    if ui.button("Fermer").clicked() {
+                        close = true;
+                    }
+                });
+                reports.show_status(ui);
+            });
+        if !open || close {
+            self.shown = None;
+        }
+        export
+    }
+
+    fn show_comparison(
+        ui: &mut egui::Ui,
+        session: &SessionSummary,
+        previous: Option<&SessionSummary>,
+        scale: PitchScale,
+    ) {
+        let rows: [ComparisonRow; 5] = [
+            ("Durée", |s, _| report::format_duration(s.duration_secs)),
+            ("Temps de voix", |s, _| report::format_duration(s.voiced_secs)),
+            ("Hauteur moyenne", |s, scale| scale.format(s.mean_pitch)),
+            ("Dans la cible", |s, _| format!("{:.0} %", s.in_range_percent)),
+            ("Meilleure série", |s, _| report::format_duration(s.best_streak_secs)),
+        ];
+        egui::Grid::new("session_end").num_columns(3).striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.strong("Cette séance");
+            ui.strong("Précédente");
+            ui.end_row();
+            for (label, value) in rows {
+                ui.label(label);
+                ui.label(value(session, scale));
+                match previous {
+                    Some(previous) => ui.weak(value(previous, scale)),
+                    None => ui.weak("—"),
+                };
+                ui.end_row();
+            }
+        });
+    }
+}
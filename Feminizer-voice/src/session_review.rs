@@ -0,0 +1,322 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{HLine, Line, Plot, PlotPoints, VLine};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::dates::DateTime;
+use crate::pitch_unit::PitchScale;
+use crate::playback::ClipPlayer;
+use crate::session::{SessionMarker, SessionSummary};
+use crate::session_audio::{self, ReanalysisParams};
+use crate::wav;
+
+const SPEEDS: [f32; 4] = [0.5, 0.75, 1.0, 1.25];
+const PLAYHEAD_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 0);
+const LOOP_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 180, 255);
+
+/// Audio d'une session et hauteur retrouvée trame par trame.
+struct Loaded {
+    samples: Arc<Vec<f32>>,
+    sample_rate: f32,
+    pitch_track: Vec<f32>,
+    frame_secs: f32,
+}
+
+impl Loaded {
+    fn load(path: PathBuf, params: ReanalysisParams) -> Result<Self> {
+        let (samples, sample_rate) = wav::read_mono(&path)?;
+        let (pitch_track, frame_secs) =
+            session_audio::pitch_track(&samples, sample_rate, &params);
+        Ok(Self {
+            samples: Arc::new(samples),
+            sample_rate,
+            pitch_track,
+            frame_secs,
+        })
+    }
+
+    fn duration_secs(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate
+    }
+
+    /// Une ligne par passage voisé, sans relier les silences.
+    fn runs(&self) -> Vec<Vec<[f64; 2]>> {
+        let mut runs: Vec<Vec<[f64; 2]>> = Vec::new();
+        let mut previous_voiced = false;
+        for (i, &frequency) in self.pitch_track.iter().enumerate() {
+            let voiced = frequency > 0.0;
+            if voiced && !previous_voiced {
+                runs.push(Vec::new());
+            }
+            if voiced && let Some(run) = runs.last_mut() {
+                run.push([(i as f32 * self.frame_secs) as f64, frequency as f64]);
+            }
+            previous_voiced = voiced;
+        }
+        runs
+    }
+}
+
+/// Réécoute d'une session enregistrée: courbe de hauteur, lecture ralentie
+/// sans changer la hauteur, boucle A–B et clic sur la courbe pour s'y placer.
+pub struct SessionReview {
+    open: bool,
+    title: String,
+    markers: Vec<SessionMarker>,
+    loading: Option<JoinHandle<Result<Loaded>>>,
+    loaded: Option<Loaded>,
+    player: ClipPlayer,
+    speed: f32,
+    loop_start: Option<f32>,
+    loop_end: Option<f32>,
+    looping: bool,
+    /// Position où reprendre la lecture, déplacée par un clic sur la courbe.
+    cursor_secs: f32,
+    error: Option<String>,
+}
+
+impl Default for SessionReview {
+    fn default() -> Self {
+        Self {
+            open: false,
+            title: String::new(),
+            markers: Vec::new(),
+            loading: None,
+            loaded: None,
+            player: ClipPlayer::default(),
+            speed: 1.0,
+            loop_start: None,
+            loop_end: None,
+            looping: false,
+            cursor_secs: 0.0,
+            error: None,
+        }
+    }
+}
+
+impl SessionReview {
+    pub fn open(&mut self, session: &SessionSummary, audio: PathBuf, params: ReanalysisParams) {
+        self.player.stop();
+        *self = Self {
+            open: true,
+            title: format!("🎧 Session du {}", DateTime::from_unix(session.started_at)),
+            markers: session.markers.clone(),
+            loading: Some(std::thread::spawn(move || Loaded::load(audio, params))),
+            speed: self.speed,
+            ..Self::default()
+        };
+    }
+
+    fn poll_loading(&mut self) {
+        if !self.loading.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        let Some(handle) = self.loading.take() else {
+            return;
+        };
+        match handle.join() {
+            Ok(Ok(loaded)) => self.loaded = Some(loaded),
+            Ok(Err(e)) => self.error = Some(format!("Lecture de l'audio: {}", e)),
+            Err(_) => self.error = Some("Lecture de l'audio interrompue".to_string()),
+        }
+    }
+
+    fn loop_region(&self) -> Option<(f32, f32)> {
+        let (a, b) = (self.loop_start?, self.loop_end?);
+        Some((a.min(b), a.max(b)))
+    }
+
+    fn apply_loop(&mut self) {
+        let region = self.loop_region().filter(|_| self.looping);
+        self.player.set_loop(region);
+    }
+
+    fn play(&mut self) {
+        let Some(loaded) = &self.loaded else {
+            return;
+        };
+        if self.cursor_secs >= loaded.duration_secs() {
+            self.cursor_secs = 0.0;
+        }
+        let start = match self.loop_region().filter(|_| self.looping) {
+            Some((a, b)) if !(a..b).contains(&self.cursor_secs) => a,
+            _ => self.cursor_secs,
+        };
+        match self.player.play(loaded.samples.clone(), loaded.sample_rate, start) {
+            Ok(()) => {
+                self.player.set_speed(self.speed);
+                self.apply_loop();
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Lecture: {}", e)),
+        }
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, scale: PitchScale, target: (f32, f32)) {
+        if !self.open {
+            return;
+        }
+        self.poll_loading();
+        let position = self.player.position_secs();
+        if let Some(position) = position {
+            self.cursor_secs = position;
+            ctx.request_repaint();
+        }
+
+        let mut open = self.open;
+        egui::Window::new(self.title.clone())
+            .id(egui::Id::new("session_review"))
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+                }
+                if self.loading.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Analyse de l'audio…");
+                    });
+                    return;
+                }
+                if self.loaded.is_some() {
+                    self.show_controls(ui, position.is_some());
+                    self.show_plot(ui, position, scale, target);
+                }
+            });
+        if !open {
+            self.player.stop();
+            self.open = false;
+        }
+    }
+
+    fn show_controls(&mut self, ui: &mut egui::Ui, playing: bool) {
+        let duration = self.loaded.as_ref().map_or(0.0, Loaded::duration_secs);
+        ui.horizontal(|ui| {
+            if playing {
+                if ui.button("⏸ Pause").clicked() {
+                    self.player.stop();
+                }
+            } else if ui.button("▶ Lire").clicked() {
+                self.play();
+            }
+            ui.label(format!("{} / {}", clock(self.cursor_secs), clock(duration)));
+
+            ui.separator();
+            ui.label("Vitesse:");
+            for speed in SPEEDS {
+                if ui
+                    .selectable_value(&mut self.speed, speed, format!("×{}", speed))
+                    .on_hover_text("La hauteur reste la même")
+                    .clicked()
+                {
+                    self.player.set_speed(speed);
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            if ui.button("A").on_hover_text("Début de la boucle à la position actuelle").clicked()
+            {
+                self.loop_start = Some(self.cursor_secs);
+                changed = true;
+            }
+            if ui.button("B").on_hover_text("Fin de la boucle à la position actuelle").clicked() {
+                self.loop_end = Some(self.cursor_secs);
+                changed = true;
+            }
+            match self.loop_region() {
+                Some((a, b)) => {
+                    ui.label(format!("{} → {}", clock(a), clock(b)));
+                }
+                None => {
+                    ui.weak("Placez A et B pour boucler un passage");
+                }
+            }
+            changed |= ui
+                .add_enabled(
+                    self.loop_region().is_some(),
+                    egui::Checkbox::new(&mut self.looping, "🔁 Boucler"),
+                )
+                .changed();
+            if ui.button("Effacer").clicked() {
+                self.loop_start = None;
+                self.loop_end = None;
+                self.looping = false;
+                changed = true;
+            }
+            if changed {
+                self.apply_loop();
+            }
+        });
+        ui.small("Clic sur la courbe: lecture à partir de cet instant.");
+    }
+
+    fn show_plot(
+        &mut self,
+        ui: &mut egui::Ui,
+        position: Option<f32>,
+        scale: PitchScale,
+        (target_min, target_max): (f32, f32),
+    ) {
+        let Some(loaded) = &self.loaded else {
+            return;
+        };
+        let runs = loaded.runs();
+        let plot = Plot::new("session_review_plot")
+            .height(240.0)
+            .allow_drag(false)
+            .include_x(0.0)
+            .include_x(loaded.duration_secs())
+            .x_axis_label("Temps (s)");
+        let response = scale.y_axis(plot).show(ui, |plot_ui| {
+            plot_ui.hline(HLine::new("Cible min", target_min).color(egui::Color32::GREEN));
+            plot_ui.hline(HLine::new("Cible max", target_max).color(egui::Color32::GREEN));
+            for (name, at) in [("A", self.loop_start), ("B", self.loop_end)] {
+                if let Some(at) = at {
+                    plot_ui.vline(VLine::new(name, at).color(LOOP_COLOR).width(1.5));
+                }
+            }
+            for run in runs {
+                plot_ui.line(
+                    Line::new("Hauteur", PlotPoints::from(run))
+                        .color(egui::Color32::from_rgb(255, 0, 255))
+                        .width(2.0),
+                );
+            }
+            for marker in &self.markers {
+                plot_ui.vline(
+                    VLine::new(format!("📍 {}", marker.text), marker.at_secs)
+                        .color(egui::Color32::GRAY)
+                        .style(egui_plot::LineStyle::dashed_loose()),
+                );
+            }
+            let color = if position.is_some() {
+                PLAYHEAD_COLOR
+            } else {
+                PLAYHEAD_COLOR.gamma_multiply(0.5)
+            };
+            plot_ui.vline(VLine::new("Lecture", self.cursor_secs).color(color).width(1.5));
+            plot_ui.pointer_coordinate()
+        });
+
+        if response.response.clicked()
+            && let Some(pointer) = response.inner
+        {
+            self.cursor_secs = (pointer.x as f32).clamp(0.0, loaded.duration_secs());
+            if position.is_some() {
+                self.player.seek(self.cursor_secs);
+            } else {
+                self.play();
+            }
+        }
+    }
+}
+
+fn clock(secs: f32) -> String {
+    let secs = secs.max(0.0) as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use feminizer_voice_core::{AnalysisConfig, VadConfig};
+
+use crate::accessibility::AccessibilitySettings;
+use crate::auto_capture::AutoCaptureSettings;
+use crate::backup::BackupSettings;
+use crate::broadcast::BroadcastSettings;
+use crate::calibration::LevelCalibration;
+use crate::floor_cue::FloorCueSettings;
+use crate::goal::PracticeGoal;
+use crate::ladder::LadderSettings;
+use crate::good_moments::GoodMomentSettings;
+use crate::layout::LayoutSettings;
+use crate::midi::MidiSettings;
+use crate::paths;
+use crate::peer::PeerSettings;
+use crate::perception::PerceptionSettings;
+use crate::permissions::NetworkPermissions;
+use crate::pitch_unit::PitchScale;
+use crate::power::PowerSettings;
+use crate::schema::{self, Schema};
+use crate::session_end::SessionEndSettings;
+use crate::strain::StrainSettings;
+use crate::sustain::SustainThresholds;
+use crate::theme::ThemeSettings;
+use crate::tray::TraySettings;
+use crate::{MIN_CONFIDENCE, TARGET_MAX_HZ, TARGET_MIN_HZ};
+
+pub const SETTINGS_SCHEMA: Schema = Schema {
+    name: "Réglages",
+    // v0 → v1: aucun changement de contenu, seul le champ de version apparaît
+    migrations: &[|_| Ok(()), migrate_settings_v1, migrate_settings_v2],
+};
+
+// v1 → v2: permissions réseau. Les diffusions déjà activées restent autorisées,
+// limitées à cette machine comme avant
+fn migrate_settings_v1(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let enabled = |key: &str| {
+        map.get("broadcast")
+            .and_then(|b| b.get(key))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    };
+    let network = serde_json::json!({
+        "websocket": { "allowed": enabled("websocket_enabled"), "scope": "localhost" },
+        "osc": { "allowed": enabled("osc_enabled"), "scope": "localhost" },
+    });
+    schema::default_field(map, "network", network);
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Settings {
+    pub broadcast: BroadcastSettings,
+    pub analysis: AnalysisConfig,
+    /// Conserver l'audio des sessions pour pouvoir les réanalyser.
+    pub keep_session_audio: bool,
+    pub goal: PracticeGoal,
+    pub network: NetworkPermissions,
+    pub pitch_scale: PitchScale,
+    pub backup: BackupSettings,
+    pub level: LevelCalibration,
+    pub strain: StrainSettings,
+    pub floor_cue: FloorCueSettings,
+    pub sustain: SustainThresholds,
+    pub ladder: LadderSettings,
+    pub target: TargetRange,
+    pub thresholds: Thresholds,
+    /// Pilote audio choisi (JACK, ASIO…), `None` pour celui du système.
+    pub audio_host: Option<String>,
+    /// Périphérique d'entrée choisi, `None` pour celui du système.
+    pub input_device: Option<String>,
+    pub auto_capture: AutoCaptureSettings,
+    pub tray: TraySettings,
+    pub midi: MidiSettings,
+    pub perception: PerceptionSettings,
+    pub theme: ThemeSettings,
+    pub accessibility: AccessibilitySettings,
+    pub layout: LayoutSettings,
+    pub power: PowerSettings,
+    pub good_moments: GoodMomentSettings,
+    pub session_end: SessionEndSettings,
+    pub peer: PeerSettings,
+}
+
+/// Seuils de détection de la voix, réglés dans l'onglet « Seuils ».
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Thresholds {
+    pub vad: VadConfig,
+    pub min_confidence: f32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            vad: VadConfig::default(),
+            min_confidence: MIN_CONFIDENCE,
+        }
+    }
+}
+
+/// Plage de hauteurs visée.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TargetRange {
+    pub min_hz: f32,
+    pub max_hz: f32,
+}
+
+impl Default for TargetRange {
+    fn default() -> Self {
+        Self {
+            min_hz: TARGET_MIN_HZ,
+            max_hz: TARGET_MAX_HZ,
+        }
+    }
+}
+
+impl TargetRange {
+    const MIN_WIDTH_HZ: f32 = 10.0;
+
+    pub fn contains(&self, frequency: f32) -> bool {
+        (self.min_hz..=self.max_hz).contains(&frequency)
+    }
+
+    pub fn center(&self) -> f32 {
+        (self.min_hz * self.max_hz).sqrt()
+    }
+
+    /// Décale la plage de `shift_hz`, puis l'élargit de `widen_hz` de chaque côté.
+    pub fn nudge(&mut self, shift_hz: f32, widen_hz: f32) {
+        let min_hz = (self.min_hz + shift_hz - widen_hz).max(50.0);
+        let max_hz = (self.max_hz + shift_hz + widen_hz).min(1200.0);
+        if max_hz - min_hz >= Self::MIN_WIDTH_HZ {
+            self.min_hz = min_hz;
+            self.max_hz = max_hz;
+        }
+    }
+}
+
+// v2 → v3: l'unité d'affichage des hauteurs rejoint le diapason
+fn migrate_settings_v2(map: &mut serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let unit = map.remove("pitch_unit").unwrap_or_else(|| "hertz".into());
+    schema::default_field(map, "pitch_scale", serde_json::json!({ "unit": unit, "a4_hz": 440.0 }));
+    Ok(())
+}
+
+impl Settings {
+    fn path() -> Result<PathBuf> {
+        Ok(paths::profile_dir()?.join("settings.json"))
+    }
+
+    /// Faux pour un profil qui n'a encore jamais enregistré ses réglages.
+    pub fn exists() -> bool {
+        Self::path().is_ok_and(|path| path.exists())
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(SETTINGS_SCHEMA.decode(&fs::read_to_string(path)?)?.value)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(Self::path()?, SETTINGS_SCHEMA.encode(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::permissions::NetworkScope;
+    use crate::pitch_unit::PitchUnit;
+
+    #[test]
+    fn v0_settings_keep_their_broadcast_and_unit() {
+        let json = r#"{
+            "broadcast": { "websocket_enabled": true },
+            "pitch_unit": "note"
+        }"#;
+        let loaded = SETTINGS_SCHEMA.decode::<Settings>(json).unwrap();
+        assert_eq!(loaded.migrated_from, Some(0));
+        let settings = loaded.value;
+        assert!(settings.network.websocket.allowed);
+        assert_eq!(settings.network.websocket.scope, NetworkScope::Localhost);
+        assert!(!settings.network.osc.allowed);
+        assert_eq!(settings.pitch_scale.unit, PitchUnit::Note);
+        assert_eq!(settings.pitch_scale.a4_hz, 440.0);
+    }
+
+    #[test]
+    fn current_settings_round_trip() {
+        let json = SETTINGS_SCHEMA.encode(&Settings::default()).unwrap();
+        let loaded = SETTINGS_SCHEMA.decode::<Settings>(&json).unwrap();
+        assert_eq!(loaded.migrated_from, None);
+        assert_eq!(loaded.value.pitch_scale, PitchScale::default());
+    }
+}
@@ -0,0 +1,335 @@
+use eframe::egui;
+
+use crate::calibration::to_dbfs;
+use crate::pitch_unit::{PitchScale, PitchUnit};
+use crate::settings::TargetRange;
+
+const NOISE_SECS: f32 = 3.0;
+const BASELINE_SECS: f32 = 30.0;
+/// En dessous, la moyenne de la voix n'est pas assez sûre pour proposer une cible.
+const MIN_BASELINE_VOICED_SECS: f32 = 5.0;
+/// Seuil d'énergie de la détection de voix, en multiple du bruit mesuré.
+const NOISE_TO_THRESHOLD: f32 = 3.0;
+const ENERGY_THRESHOLD_RANGE: std::ops::RangeInclusive<f32> = 0.002..=0.08;
+/// Pièce assez bruyante pour activer la réduction de bruit d'office.
+const NOISY_ROOM_DBFS: f32 = -50.0;
+/// Premier objectif proposé: de 3 à 10 demi-tons au-dessus de la moyenne
+/// actuelle, sans dépasser la cible par défaut.
+const FIRST_STEP_SEMITONES: f32 = 3.0;
+const TARGET_WIDTH_SEMITONES: f32 = 7.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Language,
+    Microphone,
+    Noise,
+    Baseline,
+    Target,
+}
+
+impl Step {
+    const ALL: [Step; 5] = [
+        Step::Language,
+        Step::Microphone,
+        Step::Noise,
+        Step::Baseline,
+        Step::Target,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Step::Language => "Langue",
+            Step::Microphone => "Micro",
+            Step::Noise => "Bruit de fond",
+            Step::Baseline => "Voix actuelle",
+            Step::Target => "Cible",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&step| step == self).unwrap_or(0)
+    }
+}
+
+/// Mesure en cours: le bruit de la pièce ou la voix de référence.
+#[derive(Default)]
+struct Measure {
+    elapsed_secs: f32,
+    energy: f32,
+    voiced_secs: f32,
+    pitches: Vec<f32>,
+}
+
+/// Ce que l'assistant a réglé, à reporter dans les réglages du profil.
+pub struct SetupResult {
+    pub unit: PitchUnit,
+    /// Niveau RMS du silence de la pièce.
+    pub noise_rms: Option<f32>,
+    pub denoise: bool,
+    pub target: TargetRange,
+}
+
+impl SetupResult {
+    /// Seuil d'énergie de la détection de voix adapté au bruit mesuré.
+    pub fn energy_threshold(&self) -> Option<f32> {
+        let noise = self.noise_rms?;
+        Some((noise * NOISE_TO_THRESHOLD).clamp(
+            *ENERGY_THRESHOLD_RANGE.start(),
+            *ENERGY_THRESHOLD_RANGE.end(),
+        ))
+    }
+}
+
+pub enum WizardAction {
+    StartRecording,
+    Finish(SetupResult),
+    /// Fermé sans rien appliquer.
+    Skip,
+}
+
+fn suggested_target(baseline_hz: f32) -> TargetRange {
+    let default = TargetRange::default();
+    let semitones = |hz: f32, steps: f32| hz * 2.0_f32.powf(steps / 12.0);
+    let min_hz = semitones(baseline_hz, FIRST_STEP_SEMITONES).min(default.min_hz);
+    TargetRange {
+        min_hz,
+        max_hz: semitones(min_hz, TARGET_WIDTH_SEMITONES).min(default.max_hz),
+    }
+}
+
+/// Assistant du premier lancement d'un profil: notation, micro, bruit de
+/// fond, voix de référence et première cible.
+pub struct SetupWizard {
+    step: Step,
+    unit: PitchUnit,
+    measuring: Option<Measure>,
+    noise_rms: Option<f32>,
+    denoise: bool,
+    baseline_hz: Option<f32>,
+    target: TargetRange,
+    error: Option<String>,
+}
+
+impl SetupWizard {
+    pub fn new(scale: PitchScale, target: TargetRange) -> Self {
+        Self {
+            step: Step::Language,
+            unit: scale.unit,
+            measuring: None,
+            noise_rms: None,
+            denoise: false,
+            baseline_hz: None,
+            target,
+            error: None,
+        }
+    }
+
+    /// Trame analysée: `rms` en amplitude linéaire, `frequency` à 0 hors voix.
+    pub fn push_frame(&mut self, rms: f32, frequency: f32, frame_duration: f32) {
+        let Some(measure) = &mut self.measuring else {
+            return;
+        };
+        measure.elapsed_secs += frame_duration;
+        measure.energy += rms * rms * frame_duration;
+        if frequency > 0.0 {
+            measure.voiced_secs += frame_duration;
+            measure.pitches.push(frequency);
+        }
+
+        match self.step {
+            Step::Noise if measure.elapsed_secs >= NOISE_SECS => {
+                let noise = (measure.energy / measure.elapsed_secs).sqrt();
+                self.measuring = None;
+                self.noise_rms = Some(noise);
+                self.denoise = to_dbfs(noise) > NOISY_ROOM_DBFS;
+            }
+            Step::Baseline if measure.elapsed_secs >= BASELINE_SECS => {
+                let mut measure = self.measuring.take().unwrap_or_default();
+                if measure.voiced_secs < MIN_BASELINE_VOICED_SECS {
+                    self.error = Some(format!(
+                        "Seulement {:.0} s de voix entendues: recommencez en lisant à voix haute",
+                        measure.voiced_secs
+                    ));
+                    return;
+                }
+                measure.pitches.sort_by(f32::total_cmp);
+                let baseline = measure.pitches[measure.pitches.len() / 2];
+                self.baseline_hz = Some(baseline);
+                self.target = suggested_target(baseline);
+            }
+            _ => {}
+        }
+    }
+
+    fn progress(&self, total_secs: f32) -> Option<f32> {
+        let measure = self.measuring.as_ref()?;
+        Some((measure.elapsed_secs / total_secs).min(1.0))
+    }
+
+    /// `microphone` dessine le choix du périphérique et le niveau d'entrée.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        is_recording: bool,
+        scale: PitchScale,
+        microphone: impl FnOnce(&mut egui::Ui),
+    ) -> Option<WizardAction> {
+        if !is_recording && self.measuring.take().is_some() {
+            self.error = Some("Enregistrement arrêté: mesure annulée".to_string());
+        }
+        let scale = scale.with_unit(self.unit);
+        let mut action = None;
+        egui::Window::new("🧭 Premier lancement")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for step in Step::ALL {
+                        let text = format!("{}. {}", step.index() + 1, step.label());
+                        if step == self.step {
+                            ui.strong(text);
+                        } else {
+                            ui.weak(text);
+                        }
+                    }
+                });
+                ui.separator();
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+                }
+
+                match self.step {
+                    Step::Language => self.show_language(ui),
+                    Step::Microphone => {
+                        ui.label(
+                            "Choisissez le micro, puis parlez: la barre doit bouger sans \
+                             jamais atteindre le rouge.",
+                        );
+                        microphone(ui);
+                    }
+                    Step::Noise => self.show_noise(ui, is_recording),
+                    Step::Baseline => self.show_baseline(ui, is_recording, scale),
+                    Step::Target => self.show_target(ui, scale),
+                }
+                let needs_stream =
+                    matches!(self.step, Step::Microphone | Step::Noise | Step::Baseline);
+                let start = needs_stream && !is_recording;
+                if start && ui.button("⏺ Démarrer l'enregistrement").clicked() {
+                    action = Some(WizardAction::StartRecording);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Passer l'assistant").clicked() {
+                        action = Some(WizardAction::Skip);
+                    }
+                    let measuring = self.measuring.is_some();
+                    let index = self.step.index();
+                    let back = egui::Button::new("⬅ Retour");
+                    if index > 0 && ui.add_enabled(!measuring, back).clicked() {
+                        self.step = Step::ALL[index - 1];
+                        self.error = None;
+                    }
+                    if let Some(&next) = Step::ALL.get(index + 1) {
+                        if ui.add_enabled(!measuring, egui::Button::new("Suivant ➡")).clicked() {
+                            self.step = next;
+                            self.error = None;
+                        }
+                    } else if ui.button("✔ Terminer").clicked() {
+                        action = Some(WizardAction::Finish(SetupResult {
+                            unit: self.unit,
+                            noise_rms: self.noise_rms,
+                            denoise: self.denoise,
+                            target: self.target,
+                        }));
+                    }
+                });
+            });
+        if self.measuring.is_some() {
+            ctx.request_repaint();
+        }
+        action
+    }
+
+    fn show_language(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Langue de l'interface:");
+            ui.strong("Français");
+        });
+        ui.weak("C'est pour l'instant la seule langue disponible.");
+        ui.add_space(6.0);
+        ui.label("Comment préférez-vous lire les hauteurs ?");
+        for unit in PitchUnit::ALL {
+            ui.radio_value(&mut self.unit, unit, unit.label());
+        }
+    }
+
+    fn show_noise(&mut self, ui: &mut egui::Ui, is_recording: bool) {
+        ui.label(
+            "Restez silencieux quelques secondes: le bruit de la pièce règle le seuil de \
+             détection de la voix.",
+        );
+        if let Some(progress) = self.progress(NOISE_SECS) {
+            ui.add(egui::ProgressBar::new(progress).text("Silence…").desired_width(240.0));
+            return;
+        }
+        let measure = egui::Button::new(format!("🤫 Mesurer {:.0} s", NOISE_SECS));
+        if ui.add_enabled(is_recording, measure).clicked() {
+            self.error = None;
+            self.measuring = Some(Measure::default());
+        }
+        if let Some(noise) = self.noise_rms {
+            ui.label(format!("Bruit de fond: {:.0} dBFS", to_dbfs(noise)));
+            ui.checkbox(&mut self.denoise, "Réduction de bruit")
+                .on_hover_text("Proposée d'office dans une pièce bruyante");
+        }
+    }
+
+    fn show_baseline(&mut self, ui: &mut egui::Ui, is_recording: bool, scale: PitchScale) {
+        ui.label(
+            "Lisez un texte à voix haute, avec votre voix de tous les jours, pendant \
+             30 secondes: sa hauteur moyenne sert de point de départ.",
+        );
+        if let Some(progress) = self.progress(BASELINE_SECS) {
+            let remaining = BASELINE_SECS * (1.0 - progress);
+            ui.add(
+                egui::ProgressBar::new(progress)
+                    .text(format!("Lisez… {:.0} s", remaining))
+                    .desired_width(240.0),
+            );
+            return;
+        }
+        let record = egui::Button::new(format!("⏺ Enregistrer {:.0} s", BASELINE_SECS));
+        if ui.add_enabled(is_recording, record).clicked() {
+            self.error = None;
+            self.measuring = Some(Measure::default());
+        }
+        if let Some(baseline) = self.baseline_hz {
+            ui.label(format!("Hauteur moyenne actuelle: {}", scale.format(baseline)));
+        }
+    }
+
+    fn show_target(&mut self, ui: &mut egui::Ui, scale: PitchScale) {
+        match self.baseline_hz {
+            Some(baseline) => {
+                ui.label(format!(
+                    "Depuis une moyenne de {}, une première cible à quelques demi-tons au-dessus \
+                     reste atteignable sans forcer. Elle se change à tout moment.",
+                    scale.format(baseline)
+                ));
+            }
+            None => {
+                ui.label("Sans voix de référence, la cible par défaut est proposée.");
+            }
+        }
+        ui.add(egui::Slider::new(&mut self.target.min_hz, 80.0..=300.0).text("Hz min"));
+        ui.add(egui::Slider::new(&mut self.target.max_hz, 120.0..=400.0).text("Hz max"));
+        self.target.max_hz = self.target.max_hz.max(self.target.min_hz + 10.0);
+        ui.label(format!(
+            "Cible: de {} à {}",
+            scale.format(self.target.min_hz),
+            scale.format(self.target.max_hz)
+        ));
+    }
+}
@@ -0,0 +1,73 @@
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+pub const HELP: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F1);
+
+/// Raccourci clavier et sa description pour l'aide.
+pub struct Shortcut<A> {
+    pub keys: KeyboardShortcut,
+    pub description: &'static str,
+    pub action: A,
+}
+
+impl<A> Shortcut<A> {
+    /// Touche seule, sans modificateur.
+    pub const fn key(key: Key, description: &'static str, action: A) -> Self {
+        Self {
+            keys: KeyboardShortcut::new(Modifiers::NONE, key),
+            description,
+            action,
+        }
+    }
+}
+
+/// Action du premier raccourci pressé. Rien tant qu'un champ de saisie a le
+/// focus: la barre d'espace doit rester une espace.
+pub fn pressed<A: Copy>(ctx: &egui::Context, shortcuts: &[Shortcut<A>]) -> Option<A> {
+    if ctx.wants_keyboard_input() {
+        return None;
+    }
+    ctx.input_mut(|i| {
+        shortcuts
+            .iter()
+            .find(|shortcut| i.consume_shortcut(&shortcut.keys))
+            .map(|shortcut| shortcut.action)
+    })
+}
+
+/// Fenêtre d'aide listant les raccourcis (F1).
+#[derive(Default)]
+pub struct ShortcutHelp {
+    pub open: bool,
+}
+
+impl ShortcutHelp {
+    pub fn show<A>(&mut self, ctx: &egui::Context, shortcuts: &[Shortcut<A>]) {
+        if !ctx.wants_keyboard_input() && ctx.input_mut(|i| i.consume_shortcut(&HELP)) {
+            self.open = !self.open;
+        }
+
+        egui::Window::new("⌨ Raccourcis clavier")
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let rows = shortcuts
+                            .iter()
+                            .map(|s| (ctx.format_shortcut(&s.keys), s.description))
+                            .chain([
+                                (ctx.format_shortcut(&HELP), "Afficher cette aide"),
+                                ("Ctrl+K".to_string(), "Palette de commandes"),
+                            ]);
+                        for (keys, description) in rows {
+                            ui.monospace(keys);
+                            ui.label(description);
+                            ui.end_row();
+                        }
+                    });
+                ui.small("Inactifs pendant la saisie dans un champ de texte.");
+            });
+    }
+}
@@ -0,0 +1,368 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Polygon};
+use feminizer_voice_core::FrequencyData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dates::DateTime;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+
+/// Voix moyennée dans un instantané.
+const CAPTURE_SECS: f32 = 1.0;
+/// Grille commune des instantanés: deux prises restent comparables même si
+/// la fenêtre d'analyse ou le périphérique ont changé entre-temps.
+const STEP_HZ: f32 = 25.0;
+const MAX_HZ: f32 = 5000.0;
+/// Plancher des niveaux, relatifs au pic de l'instantané.
+const FLOOR_DB: f32 = -60.0;
+const COLOR_A: egui::Color32 = egui::Color32::LIGHT_BLUE;
+const COLOR_B: egui::Color32 = egui::Color32::from_rgb(255, 0, 255);
+
+fn grid_len() -> usize {
+    (MAX_HZ / STEP_HZ) as usize
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Snapshot {
+    label: String,
+    /// Horodatage Unix de la capture.
+    at: u64,
+    /// Hauteur médiane pendant la capture.
+    f0_hz: f32,
+    /// Niveau moyen en dB tous les `STEP_HZ`, 0 dB au pic.
+    levels_db: Vec<f32>,
+}
+
+impl Snapshot {
+    /// Centre de gravité du spectre: monte quand les résonances s'éclaircissent.
+    fn centroid_hz(&self) -> f32 {
+        let (mut weighted, mut total) = (0.0, 0.0);
+        for (i, &db) in self.levels_db.iter().enumerate() {
+            let power = 10.0_f32.powf(db / 10.0);
+            weighted += power * i as f32 * STEP_HZ;
+            total += power;
+        }
+        if total > 0.0 { weighted / total } else { 0.0 }
+    }
+
+    fn points(&self) -> PlotPoints<'static> {
+        self.levels_db
+            .iter()
+            .enumerate()
+            .map(|(i, &db)| [(i as f32 * STEP_HZ) as f64, db as f64])
+            .collect()
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SnapshotFile {
+    snapshots: Vec<Snapshot>,
+}
+
+fn snapshots_path() -> Result<PathBuf> {
+    Ok(paths::profile_dir()?.join("spectrum_snapshots.json"))
+}
+
+fn load_snapshots() -> Result<Vec<Snapshot>> {
+    let path = snapshots_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file: SnapshotFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(file.snapshots)
+}
+
+/// Puissance moyenne des trames voisées, ramenée sur la grille commune.
+struct Capture {
+    label: String,
+    power: Vec<f32>,
+    voiced_secs: f32,
+    pitches: Vec<f32>,
+}
+
+impl Capture {
+    fn new(label: String) -> Self {
+        Self {
+            label,
+            power: vec![0.0; grid_len()],
+            voiced_secs: 0.0,
+            pitches: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, data: &FrequencyData, f0: f32) {
+        let spectrum = &data.spectrum;
+        if spectrum.len() < 2 {
+            return;
+        }
+        let bin_hz = data.sample_rate / (2.0 * spectrum.len() as f32);
+        for (i, power) in self.power.iter_mut().enumerate() {
+            // Interpolation entre les deux raies qui encadrent le point
+            let position = (i as f32 * STEP_HZ / bin_hz).min((spectrum.len() - 1) as f32);
+            let below = position as usize;
+            let above = (below + 1).min(spectrum.len() - 1);
+            let fraction = position - below as f32;
+            let magnitude = spectrum[below] + fraction * (spectrum[above] - spectrum[below]);
+            *power += magnitude * magnitude;
+        }
+        self.voiced_secs += data.frame_duration;
+        self.pitches.push(f0);
+    }
+
+    fn finish(mut self) -> Snapshot {
+        let peak = self.power.iter().copied().fold(0.0_f32, f32::max).max(1e-12);
+        let levels_db = self
+            .power
+            .iter()
+            .map(|&power| (10.0 * (power / peak).max(1e-12).log10()).max(FLOOR_DB))
+            .collect();
+        self.pitches.sort_by(f32::total_cmp);
+        Snapshot {
+            label: self.label,
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            f0_hz: self.pitches.get(self.pitches.len() / 2).copied().unwrap_or(0.0),
+            levels_db,
+        }
+    }
+}
+
+/// Instantanés nommés du spectre d'une voyelle, superposés deux à deux pour
+/// voir comment la résonance a changé.
+pub struct SpectrumSnapshots {
+    snapshots: Vec<Snapshot>,
+    loaded: bool,
+    label: String,
+    capture: Option<Capture>,
+    /// Instantanés comparés, par indice.
+    a: usize,
+    b: usize,
+    error: Option<String>,
+}
+
+impl Default for SpectrumSnapshots {
+    fn default() -> Self {
+        Self {
+            snapshots: Vec::new(),
+            loaded: false,
+            label: "ii".to_string(),
+            capture: None,
+            a: 0,
+            b: 0,
+            error: None,
+        }
+    }
+}
+
+impl SpectrumSnapshots {
+    pub fn is_capturing(&self) -> bool {
+        self.capture.is_some()
+    }
+
+    /// Trame analysée; `f0` vaut 0 hors voix ou hors plage acceptée.
+    pub fn push_frame(&mut self, data: &FrequencyData, f0: f32) {
+        let Some(capture) = &mut self.capture else {
+            return;
+        };
+        if f0 <= 0.0 {
+            return;
+        }
+        capture.push(data, f0);
+        if capture.voiced_secs < CAPTURE_SECS {
+            return;
+        }
+        if let Some(capture) = self.capture.take() {
+            self.snapshots.push(capture.finish());
+            self.select_latest();
+            self.save();
+        }
+    }
+
+    fn select_latest(&mut self) {
+        self.b = self.snapshots.len().saturating_sub(1);
+        self.a = self.b.saturating_sub(1);
+    }
+
+    fn save(&mut self) {
+        let file = SnapshotFile {
+            snapshots: self.snapshots.clone(),
+        };
+        let saved = snapshots_path().and_then(|path| {
+            fs::write(path, serde_json::to_string_pretty(&file)?)?;
+            Ok(())
+        });
+        if let Err(e) = saved {
+            self.error = Some(format!("Enregistrement des instantanés: {}", e));
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, is_recording: bool, scale: PitchScale) {
+        if !self.loaded {
+            self.loaded = true;
+            match load_snapshots() {
+                Ok(snapshots) => {
+                    self.snapshots = snapshots;
+                    self.select_latest();
+                }
+                Err(e) => self.error = Some(format!("Instantanés illisibles: {}", e)),
+            }
+        }
+        if self.capture.is_some() && !is_recording {
+            self.capture = None;
+            self.error = Some("Enregistrement arrêté: capture annulée".to_string());
+        }
+
+        ui.heading("📸 Instantanés de spectre");
+        ui.label(
+            "Tenez une voyelle (« ii », « aa »…) pendant la capture: son spectre moyen est \
+             gardé sous le nom choisi. Comparez ensuite deux instantanés pour voir où la \
+             résonance a gagné ou perdu de l'énergie.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Nom:");
+            ui.add_enabled(
+                self.capture.is_none(),
+                egui::TextEdit::singleline(&mut self.label).desired_width(120.0),
+            );
+            if let Some(capture) = &self.capture {
+                let progress = capture.voiced_secs / CAPTURE_SECS;
+                ui.add(
+                    egui::ProgressBar::new(progress)
+                        .text("Tenez la voyelle…")
+                        .desired_width(200.0),
+                );
+                if ui.button("Annuler").clicked() {
+                    self.capture = None;
+                }
+            } else if ui
+                .add_enabled(
+                    is_recording && !self.label.trim().is_empty(),
+                    egui::Button::new(format!("📸 Capturer {:.0} s de voix", CAPTURE_SECS)),
+                )
+                .on_disabled_hover_text("Démarrez l'enregistrement et nommez l'instantané")
+                .clicked()
+            {
+                self.error = None;
+                self.capture = Some(Capture::new(self.label.trim().to_string()));
+            }
+        });
+        ui.separator();
+
+        if self.snapshots.is_empty() {
+            ui.weak("Aucun instantané pour ce profil.");
+            return;
+        }
+        self.show_comparison(ui, scale);
+        ui.separator();
+        self.show_list(ui, scale);
+    }
+
+    fn show_comparison(&mut self, ui: &mut egui::Ui, scale: PitchScale) {
+        self.a = self.a.min(self.snapshots.len() - 1);
+        self.b = self.b.min(self.snapshots.len() - 1);
+        let name = |snapshot: &Snapshot| {
+            format!("{} — {}", snapshot.label, DateTime::from_unix(snapshot.at))
+        };
+        ui.horizontal(|ui| {
+            let pickers = [
+                ("snapshot_a", "A:", &mut self.a, COLOR_A),
+                ("snapshot_b", "B:", &mut self.b, COLOR_B),
+            ];
+            for (id, text, index, color) in pickers {
+                ui.colored_label(color, text);
+                egui::ComboBox::from_id_salt(id)
+                    .selected_text(name(&self.snapshots[*index]))
+                    .show_ui(ui, |ui| {
+                        for (i, snapshot) in self.snapshots.iter().enumerate().rev() {
+                            ui.selectable_value(index, i, name(snapshot));
+                        }
+                    });
+            }
+        });
+
+        let (a, b) = (&self.snapshots[self.a], &self.snapshots[self.b]);
+        ui.label(format!(
+            "Centre de gravité: {:.0} Hz → {:.0} Hz ({:+.0} Hz) · hauteur {} → {}",
+            a.centroid_hz(),
+            b.centroid_hz(),
+            b.centroid_hz() - a.centroid_hz(),
+            scale.format(a.f0_hz),
+            scale.format(b.f0_hz)
+        ));
+
+        Plot::new("spectrum_snapshots")
+            .height(260.0)
+            .legend(Legend::default())
+            .include_y(FLOOR_DB)
+            .include_y(0.0)
+            .x_axis_label("Fréquence (Hz)")
+            .y_axis_label("dB (0 au pic)")
+            .show(ui, |plot_ui| {
+                // Un quadrilatère par pas de la grille, teinté selon le signe de l'écart
+                let steps = a.levels_db.len().min(b.levels_db.len());
+                for i in 1..steps {
+                    let x = |i: usize| (i as f32 * STEP_HZ) as f64;
+                    let gain = (b.levels_db[i - 1] + b.levels_db[i])
+                        - (a.levels_db[i - 1] + a.levels_db[i]);
+                    let (name, color) = if gain >= 0.0 {
+                        ("B plus fort", egui::Color32::from_rgba_unmultiplied(0, 200, 0, 60))
+                    } else {
+                        ("B plus faible", egui::Color32::from_rgba_unmultiplied(255, 140, 0, 60))
+                    };
+                    plot_ui.polygon(
+                        Polygon::new(
+                            name,
+                            PlotPoints::from(vec![
+                                [x(i - 1), a.levels_db[i - 1] as f64],
+                                [x(i), a.levels_db[i] as f64],
+                                [x(i), b.levels_db[i] as f64],
+                                [x(i - 1), b.levels_db[i - 1] as f64],
+                            ]),
+                        )
+                        .fill_color(color)
+                        .stroke(egui::Stroke::NONE),
+                    );
+                }
+                plot_ui.line(Line::new(format!("A: {}", a.label), a.points()).color(COLOR_A));
+                plot_ui.line(Line::new(format!("B: {}", b.label), b.points()).color(COLOR_B));
+            });
+    }
+
+    fn show_list(&mut self, ui: &mut egui::Ui, scale: PitchScale) {
+        let mut removed = None;
+        egui::Grid::new("spectrum_snapshot_list")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Date", "Nom", "Hauteur", "Centre de gravité", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (i, snapshot) in self.snapshots.iter().enumerate().rev() {
+                    ui.label(DateTime::from_unix(snapshot.at).to_string());
+                    ui.label(&snapshot.label);
+                    ui.label(scale.format(snapshot.f0_hz));
+                    ui.label(format!("{:.0} Hz", snapshot.centroid_hz()));
+                    if ui.small_button("🗑").on_hover_text("Supprimer").clicked() {
+                        removed = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.snapshots.remove(i);
+            self.select_latest();
+            self.save();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::calibration::{LevelCalibration, to_dbfs};
+
+const COOLDOWN_SECS: f32 = 30.0;
+
+/// Seuils de l'alerte de forçage: voix à la fois forte et haute.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct StrainSettings {
+    pub enabled: bool,
+    /// Hauteur à partir de laquelle la voix compte comme haute.
+    pub min_pitch_hz: f32,
+    /// Niveau à partir duquel elle compte comme forte, en dBFS.
+    pub min_level_dbfs: f32,
+    /// Durée cumulée avant l'alerte.
+    pub sustain_secs: f32,
+}
+
+impl Default for StrainSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_pitch_hz: 330.0,
+            min_level_dbfs: -12.0,
+            sustain_secs: 5.0,
+        }
+    }
+}
+
+impl StrainSettings {
+    pub fn show(&mut self, ui: &mut egui::Ui, level: &LevelCalibration) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.enabled, "⚠ Alerte de forçage")
+                .on_hover_text(
+                    "Prévient quand la voix reste longtemps à la fois forte et haute: \
+                     un risque de fatigue vocale",
+                )
+                .changed();
+            ui.add_enabled_ui(self.enabled, |ui| {
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.min_pitch_hz, 200.0..=600.0)
+                            .text("Hz et plus"),
+                    )
+                    .changed();
+                changed |= ui
+                    .add(
+                        egui::Slider::new(&mut self.min_level_dbfs, -40.0..=-3.0)
+                            .custom_formatter(|dbfs, _| level.format(dbfs as f32))
+                            .text("et plus"),
+                    )
+                    .changed();
+                changed |= ui
+                    .add(egui::Slider::new(&mut self.sustain_secs, 2.0..=30.0).text("s"))
+                    .changed();
+            });
+        });
+        changed
+    }
+}
+
+/// Suivi en direct du temps passé en zone de forçage.
+#[derive(Default)]
+pub struct StrainMonitor {
+    strained_secs: f32,
+    since_warning: Option<f32>,
+    warnings: usize,
+}
+
+impl StrainMonitor {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_strained(settings: &StrainSettings, frequency: f32, amplitude: f32) -> bool {
+        settings.enabled
+            && frequency >= settings.min_pitch_hz
+            && to_dbfs(amplitude) >= settings.min_level_dbfs
+    }
+
+    /// Trame analysée, `frequency` à 0 hors voix: le repos fait redescendre le
+    /// compteur. Renvoie la durée de l'épisode quand il faut alerter.
+    pub fn push(
+        &mut self,
+        settings: &StrainSettings,
+        frequency: f32,
+        amplitude: f32,
+        frame_duration: f32,
+    ) -> Option<f32> {
+        if let Some(since) = &mut self.since_warning {
+            *since += frame_duration;
+        }
+        if Self::is_strained(settings, frequency, amplitude) {
+            self.strained_secs += frame_duration;
+        } else {
+            // Une respiration ne remet pas le compteur à zéro
+            self.strained_secs = (self.strained_secs - 0.5 * frame_duration).max(0.0);
+        }
+
+        let cooled_down = self.since_warning.is_none_or(|since| since >= COOLDOWN_SECS);
+        if self.strained_secs >= settings.sustain_secs && cooled_down {
+            let duration = self.strained_secs;
+            self.strained_secs = 0.0;
+            self.since_warning = Some(0.0);
+            self.warnings += 1;
+            return Some(duration);
+        }
+        None
+    }
+
+    /// Alerte visible quelques secondes après son déclenchement.
+    pub fn show(&self, ui: &mut egui::Ui, color: egui::Color32) {
+        if self.since_warning.is_some_and(|since| since < 8.0) {
+            ui.colored_label(
+                color,
+                "⚠ Voix forte et haute depuis un moment: baissez le volume ou faites une pause",
+            );
+        } else if self.warnings > 0 {
+            ui.small(format!("{} alerte(s) de forçage pendant la session", self.warnings));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.02;
+
+    /// Renvoie le nombre d'alertes données pendant `secs`.
+    fn push(monitor: &mut StrainMonitor, frequency: f32, secs: f32) -> usize {
+        let settings = StrainSettings::default();
+        (0..(secs / FRAME).round() as usize)
+            .filter(|_| monitor.push(&settings, frequency, 0.5, FRAME).is_some())
+            .count()
+    }
+
+    #[test]
+    fn sustained_strain_is_reported() {
+        let mut monitor = StrainMonitor::default();
+        assert_eq!(push(&mut monitor, 400.0, 4.9), 0);
+        assert_eq!(push(&mut monitor, 400.0, 0.2), 1);
+    }
+
+    #[test]
+    fn a_rest_lets_the_counter_come_down() {
+        let mut monitor = StrainMonitor::default();
+        assert_eq!(push(&mut monitor, 400.0, 4.0), 0);
+        // Silence: deux fois plus long que l'effort pour tout effacer
+        assert_eq!(push(&mut monitor, 0.0, 8.0), 0);
+        assert_eq!(push(&mut monitor, 400.0, 4.0), 0);
+        assert!(monitor.strained_secs < 4.1, "{}", monitor.strained_secs);
+    }
+
+    #[test]
+    fn a_short_breath_does_not_reset_it() {
+        let mut monitor = StrainMonitor::default();
+        assert_eq!(push(&mut monitor, 400.0, 3.0), 0);
+        assert_eq!(push(&mut monitor, 0.0, 0.4), 0);
+        assert_eq!(push(&mut monitor, 400.0, 2.3), 1);
+    }
+}
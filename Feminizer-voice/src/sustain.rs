@@ -0,0 +1,363 @@
+use anyhow::Result;
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+use feminizer_voice_core::{VoiceQuality, analyze_sustained};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dates::DateTime;
+use crate::paths;
+use crate::pitch_unit::PitchScale;
+
+const TAKE_SECS: f32 = 5.0;
+/// Attaque et relâchement, écartés de la mesure: seule la tenue compte.
+const TRIM_SECS: f32 = 0.5;
+const SHOWN_RESULTS: usize = 10;
+
+/// Seuils de réussite de l'exercice; les valeurs par défaut sont les limites
+/// usuelles de Praat pour une voix sans trouble.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SustainThresholds {
+    pub max_jitter_percent: f32,
+    pub max_shimmer_percent: f32,
+    pub min_hnr_db: f32,
+}
+
+impl Default for SustainThresholds {
+    fn default() -> Self {
+        Self {
+            max_jitter_percent: 1.04,
+            max_shimmer_percent: 3.81,
+            min_hnr_db: 20.0,
+        }
+    }
+}
+
+impl SustainThresholds {
+    fn passes(&self, result: &SustainResult) -> [bool; 3] {
+        [
+            result.jitter_percent <= self.max_jitter_percent,
+            result.shimmer_percent <= self.max_shimmer_percent,
+            result.hnr_db >= self.min_hnr_db,
+        ]
+    }
+
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Réussite si:");
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.max_jitter_percent, 0.2..=5.0)
+                        .text("% de jitter max"),
+                )
+                .changed();
+            changed |= ui
+                .add(
+                    egui::Slider::new(&mut self.max_shimmer_percent, 1.0..=15.0)
+                        .text("% de shimmer max"),
+                )
+                .changed();
+            changed |= ui
+                .add(egui::Slider::new(&mut self.min_hnr_db, 5.0..=30.0).text("dB de HNR min"))
+                .changed();
+            if ui.button("Par défaut").clicked() {
+                *self = Self::default();
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SustainResult {
+    /// Horodatage Unix de la prise.
+    at: u64,
+    mean_f0: f32,
+    jitter_percent: f32,
+    shimmer_percent: f32,
+    hnr_db: f32,
+}
+
+impl SustainResult {
+    fn new(quality: VoiceQuality) -> Self {
+        Self {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            mean_f0: quality.mean_f0,
+            jitter_percent: quality.jitter_percent,
+            shimmer_percent: quality.shimmer_percent,
+            hnr_db: quality.hnr_db,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SustainHistory {
+    results: Vec<SustainResult>,
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(paths::profile_dir()?.join("sustained_vowel.json"))
+}
+
+fn load_history() -> Result<Vec<SustainResult>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let history: SustainHistory = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(history.results)
+}
+
+fn verdict(passed: bool) -> &'static str {
+    if passed { "✅" } else { "❌" }
+}
+
+/// Exercice de la voyelle tenue: un /a/ de quelques secondes, mesuré cycle
+/// par cycle (jitter, shimmer, HNR), avec l'historique du profil.
+#[derive(Default)]
+pub struct SustainedVowel {
+    history: Vec<SustainResult>,
+    loaded: bool,
+    capturing: bool,
+    captured: Vec<f32>,
+    captured_rate: f32,
+    error: Option<String>,
+}
+
+impl SustainedVowel {
+    /// Vrai tant qu'une prise est en cours: l'audio brut doit lui parvenir.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    pub fn push_samples(&mut self, samples: &[f32], sample_rate: f32) {
+        if !self.capturing {
+            return;
+        }
+        if !self.captured.is_empty() && sample_rate != self.captured_rate {
+            self.capturing = false;
+            self.captured.clear();
+            self.error = Some("Fréquence d'échantillonnage modifiée: prise annulée".to_string());
+            return;
+        }
+        self.captured_rate = sample_rate;
+        let wanted = (TAKE_SECS * sample_rate) as usize;
+        let missing = wanted.saturating_sub(self.captured.len());
+        self.captured
+            .extend_from_slice(&samples[..missing.min(samples.len())]);
+    }
+
+    fn progress(&self) -> f32 {
+        self.captured.len() as f32 / (TAKE_SECS * self.captured_rate.max(1.0))
+    }
+
+    fn finish_capture(&mut self, search: RangeInclusive<f32>) {
+        self.capturing = false;
+        let samples = std::mem::take(&mut self.captured);
+        let trim = ((TRIM_SECS * self.captured_rate) as usize).min(samples.len() / 2);
+        let held = &samples[trim..samples.len() - trim];
+        let Some(quality) =
+            analyze_sustained(held, self.captured_rate, *search.start(), *search.end())
+        else {
+            self.error = Some(
+                "Pas assez de cycles voisés: tenez un /a/ régulier pendant toute la prise"
+                    .to_string(),
+            );
+            return;
+        };
+
+        self.history.push(SustainResult::new(quality));
+        if let Err(e) = self.save_history() {
+            self.error = Some(format!("Enregistrement de l'historique: {}", e));
+        }
+    }
+
+    fn save_history(&self) -> Result<()> {
+        let history = SustainHistory {
+            results: self.history.clone(),
+        };
+        fs::write(history_path()?, serde_json::to_string_pretty(&history)?)?;
+        Ok(())
+    }
+
+    /// Renvoie `true` si les seuils ont changé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        thresholds: &mut SustainThresholds,
+        search: RangeInclusive<f32>,
+        scale: PitchScale,
+    ) -> bool {
+        if !self.loaded {
+            self.loaded = true;
+            match load_history() {
+                Ok(history) => self.history = history,
+                Err(e) => self.error = Some(format!("Historique illisible: {}", e)),
+            }
+        }
+        if self.capturing && !is_recording {
+            self.capturing = false;
+            self.captured.clear();
+            self.error = Some("Enregistrement arrêté: prise annulée".to_string());
+        }
+        if self.capturing && self.progress() >= 1.0 {
+            self.finish_capture(search);
+        }
+
+        ui.heading("🅰 Voyelle tenue");
+        ui.label(
+            "Tenez un /a/ confortable, à hauteur et volume constants, pendant toute la prise. \
+             Le jitter mesure les variations de période d'un cycle à l'autre, le shimmer celles \
+             d'amplitude; le HNR compare la part périodique de la voix à son souffle.",
+        );
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, format!("❌ {}", error));
+        }
+
+        ui.horizontal(|ui| {
+            if self.capturing {
+                let remaining = TAKE_SECS * (1.0 - self.progress());
+                ui.add(
+                    egui::ProgressBar::new(self.progress())
+                        .text(format!("Tenez le /a/… {:.0} s", remaining))
+                        .desired_width(240.0),
+                );
+                if ui.button("Annuler").clicked() {
+                    self.capturing = false;
+                    self.captured.clear();
+                }
+            } else if ui
+                .add_enabled(
+                    is_recording,
+                    egui::Button::new(format!("⏺ Tenir un /a/ ({:.0} s)", TAKE_SECS)),
+                )
+                .on_disabled_hover_text("Démarrez l'enregistrement pour faire une prise")
+                .clicked()
+            {
+                self.error = None;
+                self.captured.clear();
+                self.capturing = true;
+            }
+        });
+        let changed = thresholds.show(ui);
+        ui.separator();
+
+        if let Some(last) = self.history.last() {
+            let passed = thresholds.passes(last);
+            egui::Grid::new("sustain_last")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Dernière prise");
+                    ui.strong("Mesure");
+                    ui.strong("Seuil");
+                    ui.end_row();
+
+                    ui.label("Hauteur moyenne");
+                    ui.label(scale.format(last.mean_f0));
+                    ui.label("");
+                    ui.end_row();
+
+                    ui.label("Jitter");
+                    ui.label(format!(
+                        "{} {:.2} %",
+                        verdict(passed[0]),
+                        last.jitter_percent
+                    ));
+                    ui.label(format!("≤ {:.2} %", thresholds.max_jitter_percent));
+                    ui.end_row();
+
+                    ui.label("Shimmer");
+                    ui.label(format!(
+                        "{} {:.2} %",
+                        verdict(passed[1]),
+                        last.shimmer_percent
+                    ));
+                    ui.label(format!("≤ {:.2} %", thresholds.max_shimmer_percent));
+                    ui.end_row();
+
+                    ui.label("HNR");
+                    ui.label(format!("{} {:.1} dB", verdict(passed[2]), last.hnr_db));
+                    ui.label(format!("≥ {:.1} dB", thresholds.min_hnr_db));
+                    ui.end_row();
+                });
+            if passed.iter().all(|&ok| ok) {
+                ui.colored_label(egui::Color32::GREEN, "Prise réussie");
+            }
+        } else {
+            ui.weak("Aucune prise pour ce profil.");
+        }
+
+        if self.history.len() >= 2 {
+            self.show_history(ui, thresholds, scale);
+        }
+        changed
+    }
+
+    fn show_history(&self, ui: &mut egui::Ui, thresholds: &SustainThresholds, scale: PitchScale) {
+        ui.separator();
+        ui.label("📈 Progression");
+        let points = |value: fn(&SustainResult) -> f32| -> PlotPoints {
+            self.history
+                .iter()
+                .enumerate()
+                .map(|(i, result)| [i as f64 + 1.0, value(result) as f64])
+                .collect()
+        };
+        Plot::new("sustain_history")
+            .height(180.0)
+            .legend(Legend::default())
+            .x_axis_label("Prise")
+            .y_axis_label("%")
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new("Jitter", points(|r| r.jitter_percent))
+                        .color(egui::Color32::from_rgb(255, 0, 255)),
+                );
+                plot_ui.line(
+                    Line::new("Shimmer", points(|r| r.shimmer_percent))
+                        .color(egui::Color32::LIGHT_BLUE),
+                );
+                plot_ui.hline(
+                    HLine::new("Jitter max", thresholds.max_jitter_percent)
+                        .color(egui::Color32::from_rgb(255, 0, 255))
+                        .style(egui_plot::LineStyle::dashed_dense()),
+                );
+                plot_ui.hline(
+                    HLine::new("Shimmer max", thresholds.max_shimmer_percent)
+                        .color(egui::Color32::LIGHT_BLUE)
+                        .style(egui_plot::LineStyle::dashed_dense()),
+                );
+            });
+
+        egui::Grid::new("sustain_history_table")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Date", "Hauteur", "Jitter", "Shimmer", "HNR", ""] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for result in self.history.iter().rev().take(SHOWN_RESULTS) {
+                    let passed = thresholds.passes(result).iter().all(|&ok| ok);
+                    ui.label(DateTime::from_unix(result.at).to_string());
+                    ui.label(scale.format(result.mean_f0));
+                    ui.label(format!("{:.2} %", result.jitter_percent));
+                    ui.label(format!("{:.2} %", result.shimmer_percent));
+                    ui.label(format!("{:.1} dB", result.hnr_db));
+                    ui.label(verdict(passed));
+                    ui.end_row();
+                }
+            });
+    }
+}
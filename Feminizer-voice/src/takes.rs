@@ -0,0 +1,337 @@
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints};
+
+use crate::accessibility;
+use crate::calibration::{LevelCalibration, to_dbfs};
+use crate::pitch_unit::PitchScale;
+use crate::session::{self, PitchStats};
+use crate::settings::TargetRange;
+
+const MAX_TAKES: usize = 8;
+const COLORS: [egui::Color32; MAX_TAKES] = [
+    egui::Color32::from_rgb(255, 0, 255),
+    egui::Color32::from_rgb(80, 200, 255),
+    egui::Color32::from_rgb(255, 200, 60),
+    egui::Color32::from_rgb(100, 230, 120),
+    egui::Color32::from_rgb(255, 110, 90),
+    egui::Color32::from_rgb(180, 140, 255),
+    egui::Color32::from_rgb(240, 240, 240),
+    egui::Color32::from_rgb(255, 150, 200),
+];
+
+/// Prise nommée: contour de hauteur et niveau trame par trame.
+struct Take {
+    name: String,
+    /// Hauteur par trame, 0 hors voix.
+    frequencies: Vec<f32>,
+    amplitudes: Vec<f32>,
+    frame_secs: f32,
+    color: egui::Color32,
+    visible: bool,
+}
+
+impl Take {
+    fn voiced(&self) -> Vec<f32> {
+        self.frequencies
+            .iter()
+            .copied()
+            .filter(|&f| f > 0.0)
+            .collect()
+    }
+
+    fn duration_secs(&self) -> f32 {
+        self.frequencies.len() as f32 * self.frame_secs
+    }
+
+    fn mean_amplitude(&self) -> f32 {
+        let voiced: Vec<f32> = self
+            .frequencies
+            .iter()
+            .zip(&self.amplitudes)
+            .filter(|&(&f, _)| f > 0.0)
+            .map(|(_, &a)| a)
+            .collect();
+        if voiced.is_empty() {
+            return 0.0;
+        }
+        (voiced.iter().map(|a| a * a).sum::<f32>() / voiced.len() as f32).sqrt()
+    }
+
+    /// Segments voisés, en secondes depuis le début de la prise.
+    fn segments(&self) -> Vec<Vec<[f64; 2]>> {
+        let mut segments = Vec::new();
+        let mut current: Vec<[f64; 2]> = Vec::new();
+        for (i, &frequency) in self.frequencies.iter().enumerate() {
+            if frequency > 0.0 {
+                current.push([(i as f32 * self.frame_secs) as f64, frequency as f64]);
+            } else if !current.is_empty() {
+                segments.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            segments.push(current);
+        }
+        segments
+    }
+}
+
+/// Prises gardées en mémoire le temps de la session, pour comparer par
+/// exemple « ancienne voix » et les essais successifs.
+pub struct TakeManager {
+    takes: Vec<Take>,
+    recording: Option<Take>,
+    name_input: String,
+    renaming: Option<usize>,
+}
+
+impl Default for TakeManager {
+    fn default() -> Self {
+        Self {
+            takes: Vec::new(),
+            recording: None,
+            name_input: "Prise 1".to_string(),
+            renaming: None,
+        }
+    }
+}
+
+impl TakeManager {
+    /// Trame analysée; `frequency` vaut 0 hors voix.
+    pub fn push_frame(&mut self, frequency: f32, amplitude: f32, frame_duration: f32) {
+        if let Some(take) = &mut self.recording {
+            take.frequencies.push(frequency);
+            take.amplitudes.push(amplitude);
+            if frame_duration > 0.0 {
+                take.frame_secs = frame_duration;
+            }
+        }
+    }
+
+    fn next_color(&self) -> egui::Color32 {
+        COLORS
+            .iter()
+            .copied()
+            .find(|color| self.takes.iter().all(|take| take.color != *color))
+            .unwrap_or(COLORS[0])
+    }
+
+    fn new_take(&mut self) -> Take {
+        let name = match self.name_input.trim() {
+            "" => format!("Prise {}", self.takes.len() + 1),
+            name => name.to_string(),
+        };
+        Take {
+            name,
+            frequencies: Vec::new(),
+            amplitudes: Vec::new(),
+            frame_secs: 0.0,
+            color: self.next_color(),
+            visible: true,
+        }
+    }
+
+    fn keep(&mut self, take: Take) {
+        if take.frequencies.is_empty() {
+            return;
+        }
+        if self.takes.len() >= MAX_TAKES {
+            self.takes.remove(0);
+        }
+        self.takes.push(take);
+        self.name_input = format!("Prise {}", self.takes.len() + 1);
+    }
+
+    /// Fige l'historique affiché en direct sous forme de prise.
+    pub fn snapshot(&mut self, frequencies: &[f32], amplitudes: &[f32], frame_secs: f32) {
+        let take = Take {
+            frequencies: frequencies.to_vec(),
+            amplitudes: amplitudes.to_vec(),
+            frame_secs,
+            ..self.new_take()
+        };
+        self.keep(take);
+    }
+
+    fn finish(&mut self) {
+        if let Some(take) = self.recording.take() {
+            self.keep(take);
+        }
+    }
+
+    /// Renvoie `true` quand l'historique en direct doit être capturé.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        is_recording: bool,
+        target: TargetRange,
+        scale: PitchScale,
+        level: &LevelCalibration,
+    ) -> bool {
+        if !is_recording {
+            self.finish();
+        }
+
+        ui.heading("🎞 Comparaison de prises");
+        ui.label(
+            "Enregistrez plusieurs prises nommées pendant la session (ancienne voix, essais…) \
+             puis comparez leurs contours et leurs statistiques.",
+        );
+
+        let mut snapshot = false;
+        ui.horizontal(|ui| match &self.recording {
+            Some(take) => {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    format!("⏺ {} — {:.1} s", take.name, take.duration_secs()),
+                );
+                if ui.button("⏹ Terminer la prise").clicked() {
+                    self.finish();
+                }
+            }
+            None => {
+                ui.label("Nom:");
+                ui.add(egui::TextEdit::singleline(&mut self.name_input).desired_width(160.0));
+                if ui
+                    .add_enabled(is_recording, egui::Button::new("⏺ Nouvelle prise"))
+                    .on_disabled_hover_text("Démarrez l'enregistrement d'abord")
+                    .clicked()
+                {
+                    self.recording = Some(self.new_take());
+                }
+                if ui
+                    .button("📸 Capturer l'historique")
+                    .on_hover_text("Garde comme prise les dernières secondes affichées en direct")
+                    .clicked()
+                {
+                    snapshot = true;
+                }
+            }
+        });
+
+        if self.takes.is_empty() {
+            ui.label("Aucune prise pour l'instant.");
+            return snapshot;
+        }
+
+        ui.separator();
+        self.show_table(ui, target, scale, level);
+        ui.separator();
+
+        let plot = Plot::new("takes_plot")
+            .height(300.0)
+            .legend(Legend::default())
+            .x_axis_label("Temps depuis le début de la prise (s)");
+        scale
+            .y_axis(plot)
+            .include_y(target.min_hz)
+            .include_y(target.max_hz)
+            .show(ui, |plot_ui| {
+                for take in self.takes.iter().filter(|take| take.visible) {
+                    for segment in take.segments() {
+                        plot_ui.line(
+                            Line::new(take.name.as_str(), PlotPoints::from(segment))
+                                .color(take.color)
+                                .width(2.0),
+                        );
+                    }
+                }
+                for hz in [target.min_hz, target.max_hz] {
+                    plot_ui.hline(HLine::new("Cible", hz).color(egui::Color32::RED).width(1.0));
+                }
+            });
+        snapshot
+    }
+
+    fn show_table(
+        &mut self,
+        ui: &mut egui::Ui,
+        target: TargetRange,
+        scale: PitchScale,
+        level: &LevelCalibration,
+    ) {
+        let mut removed = None;
+        egui::Grid::new("takes_grid").striped(true).show(ui, |ui| {
+            for header in [
+                "",
+                "Prise",
+                "Durée",
+                "Voisé",
+                "Médiane",
+                "Q1 – Q3",
+                "Variabilité",
+                "Dans la cible",
+                "Niveau",
+                "",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for (index, take) in self.takes.iter_mut().enumerate() {
+                let voiced = take.voiced();
+                let PitchStats {
+                    median,
+                    q1,
+                    q3,
+                    variability_st,
+                    ..
+                } = session::pitch_stats(&voiced);
+                let in_range = voiced.iter().filter(|&&f| target.contains(f)).count();
+
+                let visible = ui
+                    .checkbox(&mut take.visible, "")
+                    .on_hover_text("Afficher le contour");
+                accessibility::describe(
+                    &visible,
+                    egui::WidgetType::Checkbox,
+                    &format!("Afficher le contour de {}", take.name),
+                );
+                if self.renaming == Some(index) {
+                    let response = ui.text_edit_singleline(&mut take.name);
+                    if response.lost_focus() {
+                        self.renaming = None;
+                    }
+                } else if ui
+                    .add(
+                        egui::Label::new(egui::RichText::new(&take.name).color(take.color))
+                            .sense(egui::Sense::click()),
+                    )
+                    .on_hover_text("Double-clic pour renommer")
+                    .double_clicked()
+                {
+                    self.renaming = Some(index);
+                }
+                ui.label(format!("{:.1} s", take.duration_secs()));
+                ui.label(format!("{:.1} s", voiced.len() as f32 * take.frame_secs));
+                if voiced.is_empty() {
+                    for _ in 0..4 {
+                        ui.label("—");
+                    }
+                } else {
+                    ui.label(scale.format(median));
+                    ui.label(format!("{} – {}", scale.format(q1), scale.format(q3)));
+                    ui.label(format!("{:.1} dt", variability_st));
+                    ui.label(format!(
+                        "{:.0} %",
+                        100.0 * in_range as f32 / voiced.len() as f32
+                    ));
+                }
+                ui.label(level.format(to_dbfs(take.mean_amplitude())));
+                let delete = ui.small_button("🗑").on_hover_text("Supprimer la prise");
+                accessibility::describe(
+                    &delete,
+                    egui::WidgetType::Button,
+                    &format!("Supprimer {}", take.name),
+                );
+                if delete.clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = removed {
+            self.takes.remove(index);
+            self.renaming = None;
+        }
+    }
+}
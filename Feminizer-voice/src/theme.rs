@@ -0,0 +1,138 @@
+use eframe::egui::{self, Color32};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// Suit le thème clair ou sombre du système.
+    System,
+}
+
+impl ThemeMode {
+    const ALL: [ThemeMode; 3] = [ThemeMode::Dark, ThemeMode::Light, ThemeMode::System];
+
+    fn label(self) -> &'static str {
+        match self {
+            ThemeMode::Dark => "Sombre",
+            ThemeMode::Light => "Clair",
+            ThemeMode::System => "Système",
+        }
+    }
+
+    pub fn apply(self, ctx: &egui::Context) {
+        ctx.set_theme(match self {
+            ThemeMode::Dark => egui::ThemePreference::Dark,
+            ThemeMode::Light => egui::ThemePreference::Light,
+            ThemeMode::System => egui::ThemePreference::System,
+        });
+    }
+}
+
+/// Couleurs des mesures, passées aux vues qui les dessinent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Palette {
+    pub pitch: Color32,
+    pub target: Color32,
+    pub in_range: Color32,
+    pub low_range: Color32,
+    pub warning: Color32,
+}
+
+/// Thème et couleurs, en RVB pour rester lisibles dans le fichier de réglages.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub mode: ThemeMode,
+    /// Courbe de hauteur.
+    pub pitch: [u8; 3],
+    /// Bornes de la plage cible.
+    pub target: [u8; 3],
+    /// Hauteur dans la cible (jauge).
+    pub in_range: [u8; 3],
+    /// Sous la cible, et repères de la plage grave.
+    pub low_range: [u8; 3],
+    /// Au-dessus de la cible, forçage et alertes.
+    pub warning: [u8; 3],
+}
+
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::Dark,
+            pitch: [255, 0, 255],
+            target: [255, 0, 0],
+            in_range: [60, 220, 110],
+            low_range: [80, 140, 255],
+            warning: [255, 120, 60],
+        }
+    }
+}
+
+impl ThemeSettings {
+    /// Palette Okabe-Ito: distinguable avec les daltonismes courants.
+    fn color_blind_safe(mode: ThemeMode) -> Self {
+        Self {
+            mode,
+            pitch: [0, 114, 178],
+            target: [230, 159, 0],
+            in_range: [0, 158, 115],
+            low_range: [86, 180, 233],
+            warning: [213, 94, 0],
+        }
+    }
+
+    pub fn palette(&self) -> Palette {
+        let color = |[r, g, b]: [u8; 3]| Color32::from_rgb(r, g, b);
+        Palette {
+            pitch: color(self.pitch),
+            target: color(self.target),
+            in_range: color(self.in_range),
+            low_range: color(self.low_range),
+            warning: color(self.warning),
+        }
+    }
+
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+        ui.horizontal(|ui| {
+            ui.label("Thème:");
+            for mode in ThemeMode::ALL {
+                ui.selectable_value(&mut self.mode, mode, mode.label());
+            }
+        });
+        egui::Grid::new("theme_colors").show(ui, |ui| {
+            for (label, color) in [
+                ("Courbe de hauteur", &mut self.pitch),
+                ("Plage cible", &mut self.target),
+                ("Dans la cible", &mut self.in_range),
+                ("Sous la cible / graves", &mut self.low_range),
+                ("Au-dessus / alertes", &mut self.warning),
+            ] {
+                ui.label(label);
+                ui.color_edit_button_srgb(color);
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Couleurs par défaut").clicked() {
+                *self = Self {
+                    mode: self.mode,
+                    ..Self::default()
+                };
+            }
+            if ui
+                .button("Adaptées au daltonisme")
+                .on_hover_text("Palette Okabe-Ito, sans opposition rouge / vert")
+                .clicked()
+            {
+                *self = Self::color_blind_safe(self.mode);
+            }
+        });
+        *self != before
+    }
+}
@@ -0,0 +1,305 @@
+use eframe::egui;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// L'icône demande la fonction Cargo `tray` (GTK sous Linux).
+pub const AVAILABLE: bool = cfg!(feature = "tray");
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TraySettings {
+    pub enabled: bool,
+    /// Réduire la fenêtre la cache: plus de barre des tâches ni d'Alt+Tab.
+    pub minimize_to_tray: bool,
+}
+
+impl TraySettings {
+    /// Renvoie `true` si un réglage a changé.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if !AVAILABLE {
+            ui.weak(
+                "Cette version a été compilée sans l'icône de notification \
+                 (fonction « tray »).",
+            );
+            return false;
+        }
+        let mut changed = ui
+            .checkbox(&mut self.enabled, "Icône dans la zone de notification")
+            .changed();
+        ui.add_enabled_ui(self.enabled, |ui| {
+            changed |= ui
+                .checkbox(
+                    &mut self.minimize_to_tray,
+                    "Réduire dans la zone de notification",
+                )
+                .on_hover_text("La fenêtre réduite disparaît de la barre des tâches")
+                .changed();
+        });
+        changed
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "tray"), allow(dead_code))]
+pub enum TrayCommand {
+    ToggleRecording,
+    ToggleGauge,
+    Show,
+    Quit,
+}
+
+/// Ce que l'icône affiche; n'est transmis que lorsqu'il change.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrayState {
+    pub recording: bool,
+    pub gauge_open: bool,
+    pub tooltip: String,
+}
+
+#[derive(Default)]
+pub struct Tray {
+    #[cfg(feature = "tray")]
+    icon: Option<imp::TrayHandle>,
+    #[cfg(feature = "tray")]
+    state: Option<TrayState>,
+    pub error: Option<String>,
+}
+
+#[cfg(not(feature = "tray"))]
+impl Tray {
+    pub fn start(&mut self, _ctx: &egui::Context) {}
+
+    pub fn stop(&mut self) {}
+
+    pub fn is_running(&self) -> bool {
+        false
+    }
+
+    pub fn update(&mut self, _state: TrayState) {}
+
+    pub fn poll(&mut self) -> Option<TrayCommand> {
+        None
+    }
+}
+
+#[cfg(feature = "tray")]
+impl Tray {
+    pub fn start(&mut self, ctx: &egui::Context) {
+        if self.icon.is_some() || self.error.is_some() {
+            return;
+        }
+        match imp::TrayHandle::spawn(ctx.clone()) {
+            Ok(icon) => {
+                self.icon = Some(icon);
+                self.state = None;
+            }
+            Err(e) => self.error = Some(format!("Icône de notification: {}", e)),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.icon = None;
+        self.error = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.icon.is_some()
+    }
+
+    pub fn update(&mut self, state: TrayState) {
+        if self.state.as_ref() == Some(&state) {
+            return;
+        }
+        if let Some(icon) = &self.icon {
+            icon.apply(&state);
+        }
+        self.state = Some(state);
+    }
+
+    pub fn poll(&mut self) -> Option<TrayCommand> {
+        self.icon.as_ref()?.commands.try_recv().ok()
+    }
+}
+
+#[cfg(feature = "tray")]
+mod imp {
+    use anyhow::Result;
+    use eframe::egui;
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use tray_icon::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{
+        Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    };
+
+    use super::{TrayCommand, TrayState};
+
+    const RECORD_ID: &str = "record";
+    const GAUGE_ID: &str = "gauge";
+    const SHOW_ID: &str = "show";
+    const QUIT_ID: &str = "quit";
+    const ICON_SIZE: u32 = 32;
+
+    fn record_label(recording: bool) -> &'static str {
+        if recording {
+            "⏹ Arrêter"
+        } else {
+            "⏺ Démarrer"
+        }
+    }
+
+    /// Disque magenta, comme la courbe de hauteur.
+    fn icon() -> Result<Icon> {
+        let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+        let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+        for y in 0..ICON_SIZE {
+            for x in 0..ICON_SIZE {
+                let distance = (x as f32 - center).hypot(y as f32 - center);
+                let alpha = ((center + 0.5 - distance).clamp(0.0, 1.0) * 255.0) as u8;
+                rgba.extend_from_slice(&[255, 0, 255, alpha]);
+            }
+        }
+        Ok(Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE)?)
+    }
+
+    /// Icône et entrées de menu, à modifier sur le fil qui les a créées.
+    struct TrayIconMenu {
+        tray: TrayIcon,
+        record: MenuItem,
+        gauge: CheckMenuItem,
+    }
+
+    impl TrayIconMenu {
+        fn build() -> Result<Self> {
+            let record = MenuItem::with_id(RECORD_ID, record_label(false), true, None);
+            let gauge = CheckMenuItem::with_id(GAUGE_ID, "Mini jauge", true, false, None);
+            let show = MenuItem::with_id(SHOW_ID, "Afficher la fenêtre", true, None);
+            let quit = MenuItem::with_id(QUIT_ID, "Quitter", true, None);
+            let menu = Menu::new();
+            menu.append_items(&[
+                &record,
+                &gauge,
+                &PredefinedMenuItem::separator(),
+                &show,
+                &quit,
+            ])?;
+            let tray = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip(crate::APP_TITLE)
+                .with_icon(icon()?)
+                .build()?;
+            Ok(Self {
+                tray,
+                record,
+                gauge,
+            })
+        }
+
+        fn apply(&self, state: &TrayState) {
+            self.record.set_text(record_label(state.recording));
+            self.gauge.set_checked(state.gauge_open);
+            let _ = self.tray.set_tooltip(Some(&state.tooltip));
+        }
+    }
+
+    /// Relaie les clics du menu et de l'icône, et réveille l'interface même
+    /// quand la fenêtre est cachée.
+    fn forward_events(ctx: egui::Context, commands: Sender<TrayCommand>) {
+        let menu_commands = commands.clone();
+        let menu_ctx = ctx.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            let command = match event.id.as_ref() {
+                RECORD_ID => TrayCommand::ToggleRecording,
+                GAUGE_ID => TrayCommand::ToggleGauge,
+                SHOW_ID => TrayCommand::Show,
+                QUIT_ID => TrayCommand::Quit,
+                _ => return,
+            };
+            let _ = menu_commands.send(command);
+            menu_ctx.request_repaint();
+        }));
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let _ = commands.send(TrayCommand::Show);
+                ctx.request_repaint();
+            }
+        }));
+    }
+
+    pub struct TrayHandle {
+        pub commands: Receiver<TrayCommand>,
+        #[cfg(target_os = "linux")]
+        states: Sender<TrayState>,
+        #[cfg(not(target_os = "linux"))]
+        icon: TrayIconMenu,
+    }
+
+    impl TrayHandle {
+        /// Sous Linux l'icône vit dans une boucle GTK sur son propre fil;
+        /// ailleurs, sur le fil de l'interface dont la boucle la fait vivre.
+        #[cfg(target_os = "linux")]
+        pub fn spawn(ctx: egui::Context) -> Result<Self> {
+            let (command_tx, commands) = mpsc::channel();
+            let (states, state_rx) = mpsc::channel::<TrayState>();
+            let (ready_tx, ready_rx) = mpsc::channel();
+            std::thread::Builder::new()
+                .name("tray".to_string())
+                .spawn(move || {
+                    let icon = gtk::init()
+                        .map_err(anyhow::Error::from)
+                        .and_then(|()| TrayIconMenu::build());
+                    let icon = match icon {
+                        Ok(icon) => {
+                            let _ = ready_tx.send(Ok(()));
+                            icon
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                            return;
+                        }
+                    };
+                    gtk::glib::timeout_add_local(
+                        std::time::Duration::from_millis(100),
+                        move || {
+                            loop {
+                                match state_rx.try_recv() {
+                                    Ok(state) => icon.apply(&state),
+                                    Err(mpsc::TryRecvError::Empty) => {
+                                        return gtk::glib::ControlFlow::Continue;
+                                    }
+                                    // Tray arrêté: l'icône disparaît avec la boucle
+                                    Err(mpsc::TryRecvError::Disconnected) => {
+                                        gtk::main_quit();
+                                        return gtk::glib::ControlFlow::Break;
+                                    }
+                                }
+                            }
+                        },
+                    );
+                    gtk::main();
+                })?;
+            ready_rx.recv()??;
+            forward_events(ctx, command_tx);
+            Ok(Self { commands, states })
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        pub fn spawn(ctx: egui::Context) -> Result<Self> {
+            let (command_tx, commands) = mpsc::channel();
+            let icon = TrayIconMenu::build()?;
+            forward_events(ctx, command_tx);
+            Ok(Self { commands, icon })
+        }
+
+        pub fn apply(&self, state: &TrayState) {
+            #[cfg(target_os = "linux")]
+            let _ = self.states.send(state.clone());
+            #[cfg(not(target_os = "linux"))]
+            self.icon.apply(state);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints, Points, Polygon};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::pitch_unit::PitchScale;
+use crate::session::SessionSummary;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    fn days(self) -> i64 {
+        match self {
+            Period::Day => 1,
+            Period::Week => 7,
+        }
+    }
+
+    // Le 1er janvier 1970 était un jeudi: décalage pour des semaines du lundi
+    fn start_day(self, day: i64) -> i64 {
+        match self {
+            Period::Day => day,
+            Period::Week => (day + 3).div_euclid(7) * 7 - 3,
+        }
+    }
+}
+
+/// Quartiles d'une période, moyennés entre ses sessions selon leur temps de voix.
+struct PeriodBand {
+    day: i64,
+    q1: f64,
+    median: f64,
+    q3: f64,
+}
+
+fn bands(sessions: &[SessionSummary], period: Period) -> Vec<PeriodBand> {
+    let mut bands: Vec<(PeriodBand, f64)> = Vec::new();
+    for session in sessions.iter().filter(|s| s.median_pitch > 0.0) {
+        let day = period.start_day((session.started_at / SECS_PER_DAY) as i64);
+        let weight = session.voiced_secs.max(1.0) as f64;
+
+        if bands.last().is_none_or(|(band, _)| band.day != day) {
+            bands.push((
+                PeriodBand {
+                    day,
+                    q1: 0.0,
+                    median: 0.0,
+                    q3: 0.0,
+                },
+                0.0,
+            ));
+        }
+        if let Some((band, total)) = bands.last_mut() {
+            band.q1 += session.pitch_q1 as f64 * weight;
+            band.median += session.median_pitch as f64 * weight;
+            band.q3 += session.pitch_q3 as f64 * weight;
+            *total += weight;
+        }
+    }
+
+    bands
+        .into_iter()
+        .map(|(band, total)| PeriodBand {
+            day: band.day,
+            q1: band.q1 / total,
+            median: band.median / total,
+            q3: band.q3 / total,
+        })
+        .collect()
+}
+
+pub struct TrendChart {
+    period: Period,
+}
+
+impl Default for TrendChart {
+    fn default() -> Self {
+        Self {
+            period: Period::Day,
+        }
+    }
+}
+
+impl TrendChart {
+    /// `sessions` doit être trié par date de début.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        sessions: &[SessionSummary],
+        target_min: f32,
+        target_max: f32,
+        scale: PitchScale,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("📈 Tendance de la hauteur médiane, par");
+            ui.selectable_value(&mut self.period, Period::Day, "jour");
+            ui.selectable_value(&mut self.period, Period::Week, "semaine");
+        });
+        ui.small(
+            "La bande couvre le quartile inférieur au quartile supérieur: \
+             un écart qui reste dans la bande relève des variations normales.",
+        );
+
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| (d.as_secs() / SECS_PER_DAY) as i64)
+            .unwrap_or(0);
+        let bands = bands(sessions, self.period);
+        let half = self.period.days() as f64 / 2.0;
+        let x = |band: &PeriodBand| (band.day - today) as f64 + half;
+
+        let band_color = egui::Color32::from_rgba_unmultiplied(255, 0, 255, 50);
+        let plot = Plot::new("pitch_trend")
+            .height(220.0)
+            .legend(Legend::default())
+            .x_axis_label("Jours (0 = aujourd'hui)");
+        scale.y_axis(plot)
+            .show(ui, |plot_ui| {
+                plot_ui.hline(HLine::new("Cible min", target_min).color(egui::Color32::GREEN));
+                plot_ui.hline(HLine::new("Cible max", target_max).color(egui::Color32::GREEN));
+
+                // Un trapèze par intervalle: un seul polygone concave serait mal rempli
+                for pair in bands.windows(2) {
+                    let (a, b) = (&pair[0], &pair[1]);
+                    let points = vec![[x(a), a.q1], [x(b), b.q1], [x(b), b.q3], [x(a), a.q3]];
+                    plot_ui.polygon(
+                        Polygon::new("Écart interquartile", PlotPoints::from(points))
+                            .fill_color(band_color)
+                            .stroke(egui::Stroke::NONE),
+                    );
+                }
+                if let [only] = bands.as_slice() {
+                    let points = vec![
+                        [x(only) - half, only.q1],
+                        [x(only) + half, only.q1],
+                        [x(only) + half, only.q3],
+                        [x(only) - half, only.q3],
+                    ];
+                    plot_ui.polygon(
+                        Polygon::new("Écart interquartile", PlotPoints::from(points))
+                            .fill_color(band_color)
+                            .stroke(egui::Stroke::NONE),
+                    );
+                }
+
+                let medians: PlotPoints = bands.iter().map(|band| [x(band), band.median]).collect();
+                plot_ui.line(
+                    Line::new("Médiane", medians)
+                        .color(egui::Color32::from_rgb(255, 0, 255))
+                        .width(2.0),
+                );
+
+                let sessions: PlotPoints = sessions
+                    .iter()
+                    .filter(|s| s.median_pitch > 0.0)
+                    .map(|s| {
+                        let day = s.started_at as f64 / SECS_PER_DAY as f64 - today as f64;
+                        [day, s.median_pitch as f64]
+                    })
+                    .collect();
+                plot_ui.points(
+                    Points::new("Sessions", sessions)
+                        .color(egui::Color32::from_gray(160))
+                        .radius(2.0),
+                );
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::test_summary;
+
+    /// 2024-01-01, un lundi.
+    const MONDAY: u64 = 19_723 * SECS_PER_DAY;
+
+    fn session(started_at: u64, quartiles: [f32; 3], voiced_secs: f32) -> SessionSummary {
+        let mut session = test_summary(started_at);
+        [session.pitch_q1, session.median_pitch, session.pitch_q3] = quartiles;
+        session.voiced_secs = voiced_secs;
+        session
+    }
+
+    #[test]
+    fn a_single_session_is_its_own_band() {
+        let bands = bands(&[session(MONDAY + 3600, [170.0, 185.0, 200.0], 60.0)], Period::Week);
+        assert_eq!(bands.len(), 1);
+        let band = &bands[0];
+        assert_eq!(band.day, (MONDAY / SECS_PER_DAY) as i64);
+        assert_eq!([band.q1, band.median, band.q3], [170.0, 185.0, 200.0]);
+    }
+
+    #[test]
+    fn few_sessions_are_weighted_by_voiced_time() {
+        let sessions = [
+            session(MONDAY, [160.0, 180.0, 200.0], 30.0),
+            session(MONDAY + 6 * SECS_PER_DAY, [190.0, 210.0, 230.0], 90.0),
+            // Lundi suivant: nouvelle semaine
+            session(MONDAY + 7 * SECS_PER_DAY, [150.0, 170.0, 190.0], 10.0),
+        ];
+        let bands = bands(&sessions, Period::Week);
+        assert_eq!(bands.len(), 2);
+        assert_eq!([bands[0].q1, bands[0].median, bands[0].q3], [182.5, 202.5, 222.5]);
+        assert_eq!(bands[1].median, 170.0);
+        assert!(bands.iter().all(|b| b.q1 <= b.median && b.median <= b.q3));
+
+        assert_eq!(super::bands(&sessions, Period::Day).len(), 3);
+    }
+
+    #[test]
+    fn sessions_without_voice_are_left_out() {
+        let sessions = [
+            session(MONDAY, [0.0, 0.0, 0.0], 0.0),
+            session(MONDAY + SECS_PER_DAY, [170.0, 185.0, 200.0], 0.0),
+        ];
+        let bands = bands(&sessions, Period::Day);
+        assert_eq!(bands.len(), 1);
+        // Un temps de voix nul ne donne pas une moyenne indéfinie
+        assert_eq!(bands[0].median, 185.0);
+    }
+}
@@ -0,0 +1,241 @@
+use eframe::egui;
+use egui_plot::{HLine, Legend, Line, Plot, PlotPoints, Points, VLine};
+use feminizer_voice_core::{FrequencyData, VadConfig};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+const MAX_FRAMES: usize = 400;
+
+struct TuningFrame {
+    amplitude: f32,
+    flatness: f32,
+    frequency: f32,
+    confidence: f32,
+    vad_voiced: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Accepted,
+    TooQuiet,
+    TooNoisy,
+    Unreliable,
+    OutOfRange,
+}
+
+impl Verdict {
+    const ALL: [Verdict; 5] = [
+        Verdict::Accepted,
+        Verdict::TooQuiet,
+        Verdict::TooNoisy,
+        Verdict::Unreliable,
+        Verdict::OutOfRange,
+    ];
+
+    // Ni la confiance ni la plage de fréquences n'interviennent dans la détection de voix
+    fn passes_vad(self) -> bool {
+        matches!(self, Verdict::Accepted | Verdict::Unreliable | Verdict::OutOfRange)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Verdict::Accepted => "Acceptée",
+            Verdict::TooQuiet => "Trop faible",
+            Verdict::TooNoisy => "Trop bruitée",
+            Verdict::Unreliable => "Peu fiable",
+            Verdict::OutOfRange => "Hors plage",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Verdict::Accepted => egui::Color32::from_rgb(60, 220, 110),
+            Verdict::TooQuiet => egui::Color32::from_gray(110),
+            Verdict::TooNoisy => egui::Color32::from_rgb(255, 170, 60),
+            Verdict::Unreliable => egui::Color32::from_rgb(200, 110, 220),
+            Verdict::OutOfRange => egui::Color32::from_rgb(80, 140, 255),
+        }
+    }
+}
+
+fn amplitude_db(amplitude: f32) -> f32 {
+    20.0 * amplitude.max(1e-5).log10()
+}
+
+/// Trames récentes recolorées à chaque image selon les seuils courants.
+#[derive(Default)]
+pub struct ThresholdTuner {
+    frames: VecDeque<TuningFrame>,
+}
+
+impl ThresholdTuner {
+    pub fn push(&mut self, data: &FrequencyData) {
+        self.frames.push_back(TuningFrame {
+            amplitude: data.amplitude,
+            flatness: data.spectral_flatness,
+            frequency: data.dominant_frequency,
+            confidence: data.confidence,
+            vad_voiced: data.is_voiced,
+        });
+        if self.frames.len() > MAX_FRAMES {
+            self.frames.pop_front();
+        }
+    }
+
+    fn verdict(
+        frame: &TuningFrame,
+        config: &VadConfig,
+        min_hz: f32,
+        max_hz: f32,
+        min_confidence: f32,
+    ) -> Verdict {
+        if frame.amplitude < config.energy_threshold {
+            Verdict::TooQuiet
+        } else if frame.flatness > config.flatness_threshold {
+            Verdict::TooNoisy
+        } else if frame.confidence < min_confidence {
+            Verdict::Unreliable
+        } else if !(min_hz..=max_hz).contains(&frame.frequency) {
+            Verdict::OutOfRange
+        } else {
+            Verdict::Accepted
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &mut VadConfig,
+        min_hz: &mut f32,
+        max_hz: &mut f32,
+        min_confidence: &mut f32,
+        search: RangeInclusive<f32>,
+    ) {
+        ui.heading("🎚 Réglage des seuils");
+        ui.label(
+            "Parlez puis restez silencieux: chaque trame est colorée selon les seuils actuels. \
+             Ajustez-les jusqu'à ce que la voix soit verte et le bruit gris ou orange.",
+        );
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::Slider::new(&mut config.energy_threshold, 0.001..=0.1)
+                    .logarithmic(true)
+                    .text("Amplitude min"),
+            );
+            ui.add(
+                egui::Slider::new(&mut config.flatness_threshold, 0.05..=1.0)
+                    .text("Planéité max"),
+            );
+        });
+        ui.horizontal(|ui| {
+            // Au-delà des bornes de recherche, aucune trame ne peut arriver
+            ui.add(egui::Slider::new(min_hz, search.clone()).text("Hz min"));
+            ui.add(egui::Slider::new(max_hz, search).text("Hz max"));
+            ui.add(egui::Slider::new(min_confidence, 0.0..=1.0).text("Confiance min"))
+                .on_hover_text(
+                    "Émergence du pic de hauteur au-dessus du bruit: en dessous, \
+                     la trame est affichée en pâle et ignorée des statistiques",
+                );
+            if ui.button("Effacer").clicked() {
+                self.frames.clear();
+            }
+        });
+
+        let verdicts: Vec<Verdict> = self
+            .frames
+            .iter()
+            .map(|frame| Self::verdict(frame, config, *min_hz, *max_hz, *min_confidence))
+            .collect();
+
+        let accepted = verdicts.iter().filter(|&&v| v == Verdict::Accepted).count();
+        let agree = self
+            .frames
+            .iter()
+            .zip(&verdicts)
+            .filter(|(frame, verdict)| frame.vad_voiced == verdict.passes_vad())
+            .count();
+        ui.small(format!(
+            "{} / {} trames acceptées — le détecteur adaptatif (plancher de bruit, maintien) \
+             est d'accord sur {} d'entre elles",
+            accepted,
+            verdicts.len(),
+            agree
+        ));
+
+        self.draw_timeline(ui, &verdicts);
+
+        Plot::new("tuning_scatter")
+            .height(320.0)
+            .legend(Legend::default())
+            .x_axis_label("Amplitude (dB)")
+            .y_axis_label("Planéité spectrale")
+            .include_x(-80.0)
+            .include_x(0.0)
+            .include_y(0.0)
+            .include_y(1.0)
+            .show(ui, |plot_ui| {
+                for verdict in Verdict::ALL {
+                    let points: PlotPoints = self
+                        .frames
+                        .iter()
+                        .zip(&verdicts)
+                        .filter(|&(_, &v)| v == verdict)
+                        .map(|(frame, _)| {
+                            [amplitude_db(frame.amplitude) as f64, frame.flatness as f64]
+                        })
+                        .collect();
+                    plot_ui.points(
+                        Points::new(verdict.label(), points)
+                            .color(verdict.color())
+                            .radius(2.5),
+                    );
+                }
+
+                plot_ui.vline(
+                    VLine::new("Seuil d'amplitude", amplitude_db(config.energy_threshold))
+                        .color(egui::Color32::WHITE),
+                );
+                plot_ui.hline(
+                    HLine::new("Seuil de planéité", config.flatness_threshold)
+                        .color(egui::Color32::WHITE),
+                );
+            });
+
+        let pitch: PlotPoints = self
+            .frames
+            .iter()
+            .enumerate()
+            .filter(|(_, frame)| frame.frequency > 0.0)
+            .map(|(i, frame)| [i as f64, frame.frequency as f64])
+            .collect();
+        Plot::new("tuning_pitch")
+            .height(140.0)
+            .y_axis_label("Fréquence (Hz)")
+            .allow_drag(false)
+            .allow_zoom(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Fréquence brute", pitch).color(egui::Color32::GRAY));
+                plot_ui.hline(HLine::new("Hz min", *min_hz).color(Verdict::OutOfRange.color()));
+                plot_ui.hline(HLine::new("Hz max", *max_hz).color(Verdict::OutOfRange.color()));
+            });
+    }
+
+    fn draw_timeline(&self, ui: &mut egui::Ui, verdicts: &[Verdict]) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 18.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, egui::Color32::from_gray(20));
+
+        let width = rect.width() / MAX_FRAMES as f32;
+        let offset = MAX_FRAMES - verdicts.len();
+        for (i, verdict) in verdicts.iter().enumerate() {
+            let left = rect.left() + (offset + i) as f32 * width;
+            let cell = egui::Rect::from_min_max(
+                egui::pos2(left, rect.top()),
+                egui::pos2(left + width.max(1.0), rect.bottom()),
+            );
+            painter.rect_filled(cell, 0.0, verdict.color());
+        }
+    }
+}
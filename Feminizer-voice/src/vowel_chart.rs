@@ -0,0 +1,119 @@
+use eframe::egui;
+use egui_plot::{Line, Plot, PlotPoint, PlotPoints, Points, Text};
+use std::collections::VecDeque;
+
+const MAX_POINTS: usize = 300;
+
+// Moyennes de Peterson & Barney (1952): (voyelle, F1, F2)
+const MASCULINE_VOWELS: [(&str, f32, f32); 6] = [
+    ("i", 270.0, 2290.0),
+    ("ɛ", 530.0, 1840.0),
+    ("æ", 660.0, 1720.0),
+    ("ɑ", 730.0, 1090.0),
+    ("ɔ", 570.0, 840.0),
+    ("u", 300.0, 870.0),
+];
+
+const FEMININE_VOWELS: [(&str, f32, f32); 6] = [
+    ("i", 310.0, 2790.0),
+    ("ɛ", 610.0, 2330.0),
+    ("æ", 860.0, 2050.0),
+    ("ɑ", 850.0, 1220.0),
+    ("ɔ", 590.0, 920.0),
+    ("u", 370.0, 950.0),
+];
+
+#[derive(Default)]
+pub struct VowelChart {
+    points: VecDeque<(f32, f32)>,
+}
+
+impl VowelChart {
+    pub fn push(&mut self, f1: f32, f2: f32) {
+        self.points.push_back((f1, f2));
+        if self.points.len() > MAX_POINTS {
+            self.points.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("🗣 Espace vocalique (F1/F2)");
+            ui.colored_label(egui::Color32::LIGHT_BLUE, "■ masculin");
+            ui.colored_label(egui::Color32::from_rgb(255, 130, 200), "■ féminin");
+            if ui.small_button("Effacer").clicked() {
+                self.clear();
+            }
+        });
+
+        // Axes inversés comme dans un trapèze vocalique classique
+        let recent: Vec<[f64; 2]> = self
+            .points
+            .iter()
+            .map(|&(f1, f2)| [-(f2 as f64), -(f1 as f64)])
+            .collect();
+        let latest = recent.last().copied();
+
+        Plot::new("vowel_chart")
+            .height(ui.available_height().max(300.0))
+            .x_axis_label("F2 (Hz)")
+            .y_axis_label("F1 (Hz)")
+            .x_axis_formatter(|mark, _| format!("{:.0}", -mark.value))
+            .y_axis_formatter(|mark, _| format!("{:.0}", -mark.value))
+            .include_x(-3200.0)
+            .include_x(-600.0)
+            .include_y(-1000.0)
+            .include_y(-200.0)
+            .show(ui, |plot_ui| {
+                for (vowels, color) in [
+                    (&MASCULINE_VOWELS, egui::Color32::LIGHT_BLUE),
+                    (&FEMININE_VOWELS, egui::Color32::from_rgb(255, 130, 200)),
+                ] {
+                    for &(vowel, f1, f2) in vowels.iter() {
+                        plot_ui.line(
+                            Line::new(vowel, ellipse(f1, f2))
+                                .color(color.gamma_multiply(0.7))
+                                .width(1.5),
+                        );
+                        plot_ui.text(
+                            Text::new(vowel, PlotPoint::new(-(f2 as f64), -(f1 as f64)), vowel)
+                                .color(color),
+                        );
+                    }
+                }
+
+                plot_ui.points(
+                    Points::new("formants", recent)
+                        .radius(2.0)
+                        .color(egui::Color32::from_rgba_unmultiplied(255, 0, 255, 120)),
+                );
+
+                if let Some(latest) = latest {
+                    plot_ui.points(
+                        Points::new("formant_latest", vec![latest])
+                            .radius(6.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                }
+            });
+    }
+}
+
+fn ellipse(f1: f32, f2: f32) -> PlotPoints<'static> {
+    let radius_f1 = f1 as f64 * 0.12;
+    let radius_f2 = f2 as f64 * 0.10;
+
+    (0..=40)
+        .map(|i| {
+            let angle = i as f64 / 40.0 * std::f64::consts::TAU;
+            [
+                -(f2 as f64) + radius_f2 * angle.cos(),
+                -(f1 as f64) + radius_f1 * angle.sin(),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,203 @@
+use eframe::egui;
+use feminizer_voice_core::FrequencyData;
+use std::collections::VecDeque;
+
+use crate::accessibility;
+
+const MAX_HZ: f32 = 1500.0;
+const HISTORY: usize = 300;
+/// Dynamique affichée: en dessous, noir.
+const RANGE_DB: f32 = 60.0;
+const FREQ_MARKS: [f32; 6] = [250.0, 500.0, 750.0, 1000.0, 1250.0, 1500.0];
+
+struct Column {
+    /// Niveaux en dB relatifs au maximum du bloc, de 0 Hz à `MAX_HZ`.
+    levels: Vec<f32>,
+    f0: f32,
+    reliable: bool,
+}
+
+/// Bas du spectre (0–1,5 kHz) qui défile, avec la fondamentale détectée par
+/// dessus: on voit si le détecteur suit la fondamentale ou une harmonique.
+pub struct HarmonicWaterfall {
+    columns: VecDeque<Column>,
+    bin_hz: f32,
+    texture: Option<egui::TextureHandle>,
+    dirty: bool,
+    show_harmonics: bool,
+}
+
+impl Default for HarmonicWaterfall {
+    fn default() -> Self {
+        Self {
+            columns: VecDeque::with_capacity(HISTORY),
+            bin_hz: 0.0,
+            texture: None,
+            dirty: false,
+            show_harmonics: true,
+        }
+    }
+}
+
+impl HarmonicWaterfall {
+    /// `f0` vaut 0 hors voix ou hors plage acceptée.
+    pub fn push(&mut self, data: &FrequencyData, f0: f32, reliable: bool) {
+        if data.spectrum.is_empty() {
+            return;
+        }
+        let bin_hz = data.sample_rate / (2.0 * data.spectrum.len() as f32);
+        if bin_hz != self.bin_hz {
+            // Autre résolution: les anciennes colonnes ne s'alignent plus
+            self.columns.clear();
+            self.bin_hz = bin_hz;
+        }
+        let bins = ((MAX_HZ / bin_hz) as usize).min(data.spectrum.len());
+        let levels = data.spectrum[..bins]
+            .iter()
+            .map(|&m| 20.0 * m.max(1e-6).log10())
+            .collect();
+
+        self.columns.push_back(Column {
+            levels,
+            f0,
+            reliable,
+        });
+        if self.columns.len() > HISTORY {
+            self.columns.pop_front();
+        }
+        self.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.columns.clear();
+        self.dirty = true;
+    }
+
+    fn image(&self) -> egui::ColorImage {
+        let rows = self.columns.front().map_or(1, |c| c.levels.len()).max(1);
+        let mut image = egui::ColorImage::filled([HISTORY, rows], egui::Color32::BLACK);
+        let offset = HISTORY - self.columns.len();
+        for (x, column) in self.columns.iter().enumerate() {
+            for (bin, &db) in column.levels.iter().enumerate().take(rows) {
+                let level = (1.0 + db / RANGE_DB).clamp(0.0, 1.0);
+                // Les aigus en haut de l'image
+                image[(offset + x, rows - 1 - bin)] = heat(level);
+            }
+        }
+        image
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.heading("🌈 Harmoniques");
+        ui.label(
+            "Spectre de 0 à 1,5 kHz au fil du temps. La ligne blanche est la fondamentale \
+             détectée: elle doit suivre la bande claire la plus basse.",
+        );
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.show_harmonics, "Repères d'harmoniques (2×, 3×, 4×…)");
+            if ui.button("Effacer").clicked() {
+                self.clear();
+            }
+        });
+
+        if self.dirty || self.texture.is_none() {
+            let image = self.image();
+            match &mut self.texture {
+                Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+                None => {
+                    self.texture = Some(ui.ctx().load_texture(
+                        "harmonic_waterfall",
+                        image,
+                        egui::TextureOptions::LINEAR,
+                    ))
+                }
+            }
+            self.dirty = false;
+        }
+
+        let size = egui::vec2(ui.available_width(), ui.available_height().clamp(260.0, 600.0));
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+        accessibility::describe(
+            &response,
+            egui::WidgetType::Image,
+            "Cascade des harmoniques",
+        );
+        let painter = ui.painter_at(rect);
+        if let Some(texture) = &self.texture {
+            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+            painter.image(texture.id(), rect, uv, egui::Color32::WHITE);
+        }
+
+        let x_of = |i: usize| {
+            let offset = HISTORY - self.columns.len();
+            rect.left() + (offset + i) as f32 / HISTORY as f32 * rect.width()
+        };
+        let y_of = |hz: f32| rect.bottom() - hz / MAX_HZ * rect.height();
+
+        for hz in FREQ_MARKS {
+            let y = y_of(hz);
+            painter.line_segment(
+                [egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)],
+                egui::Stroke::new(0.5, egui::Color32::from_white_alpha(60)),
+            );
+            painter.text(
+                egui::pos2(rect.left() + 2.0, y - 6.0),
+                egui::Align2::LEFT_CENTER,
+                format!("{} Hz", hz as i32),
+                egui::FontId::monospace(10.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let last_harmonic = if self.show_harmonics { 8 } else { 1 };
+        for (i, column) in self.columns.iter().enumerate() {
+            if column.f0 <= 0.0 {
+                continue;
+            }
+            let x = x_of(i);
+            let width = (rect.width() / HISTORY as f32).max(1.5);
+            let alpha = if column.reliable { 255 } else { 90 };
+            let trace = egui::Rect::from_center_size(
+                egui::pos2(x + width / 2.0, y_of(column.f0)),
+                egui::vec2(width, 3.0),
+            );
+            painter.rect_filled(trace, 0.0, egui::Color32::from_white_alpha(alpha));
+            for multiple in 2..=last_harmonic {
+                let hz = column.f0 * multiple as f32;
+                if hz > MAX_HZ {
+                    break;
+                }
+                let mark = egui::Rect::from_center_size(
+                    egui::pos2(x + width / 2.0, y_of(hz)),
+                    egui::vec2(width, 1.0),
+                );
+                painter.rect_filled(mark, 0.0, egui::Color32::from_white_alpha(alpha / 3));
+            }
+        }
+
+        if let Some(pointer) = response.hover_pos() {
+            let hz = (rect.bottom() - pointer.y) / rect.height() * MAX_HZ;
+            let column = ((pointer.x - rect.left()) / rect.width() * HISTORY as f32) as usize;
+            let f0 = column
+                .checked_sub(HISTORY - self.columns.len())
+                .and_then(|i| self.columns.get(i))
+                .map_or(0.0, |c| c.f0);
+            let text = if f0 > 0.0 {
+                format!("{:.0} Hz ≈ {:.1} × F0 ({:.0} Hz)", hz, hz / f0, f0)
+            } else {
+                format!("{:.0} Hz", hz)
+            };
+            response.on_hover_text_at_pointer(text);
+        }
+    }
+}
+
+/// Noir → violet → rouge → jaune → blanc.
+fn heat(level: f32) -> egui::Color32 {
+    let channel = |start: f32| ((level - start) * 3.0).clamp(0.0, 1.0);
+    egui::Color32::from_rgb(
+        (255.0 * channel(0.0)) as u8,
+        (255.0 * channel(0.4)) as u8,
+        (255.0 * (channel(0.0) * 0.6 * (1.0 - channel(0.35)) + channel(0.7))) as u8,
+    )
+}
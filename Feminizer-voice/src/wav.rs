@@ -0,0 +1,27 @@
+use anyhow::Result;
+use std::path::Path;
+
+/// Lit un fichier WAV et mélange ses canaux en mono. Renvoie aussi la
+/// fréquence d'échantillonnage.
+pub fn read_mono(path: &Path) -> Result<(Vec<f32>, f32)> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    Ok((samples, spec.sample_rate as f32))
+}
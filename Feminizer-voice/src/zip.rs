@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use std::io::{Read, Write};
+
+use crate::dates::DateTime;
+
+// Sous-ensemble du format ZIP: entrées « stockées » ou « compressées »
+// (deflate), sans ZIP64 ni chiffrement. Suffisant pour nos sauvegardes et
+// lisible par n'importe quel outil d'archive.
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL: u32 = 0x0605_4b50;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+const VERSION: u16 = 20;
+/// Noms de fichiers en UTF-8.
+const FLAG_UTF8: u16 = 0x0800;
+
+pub struct Entry {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+struct Written {
+    name: String,
+    crc: u32,
+    compressed: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// Archive écrite au fil des entrées: seule celle en cours est en mémoire.
+pub struct ZipWriter<W: Write> {
+    out: W,
+    /// Octets déjà écrits, pour situer chaque entrée.
+    position: usize,
+    entries: Vec<Written>,
+    /// Date et heure DOS de toutes les entrées.
+    dos_time: (u16, u16),
+}
+
+fn u32_of(value: usize, what: &str) -> Result<u32> {
+    u32::try_from(value).map_err(|_| anyhow::anyhow!("{} trop volumineux pour une archive", what))
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W, unix_secs: u64) -> Self {
+        Self {
+            out,
+            position: 0,
+            entries: Vec::new(),
+            dos_time: dos_date_time(unix_secs),
+        }
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> Result<()> {
+        self.out.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    /// Ajoute un fichier; un nom terminé par `/` désigne un dossier.
+    pub fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let mut crc = flate2::Crc::new();
+        crc.update(data);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data)?;
+        let compressed = encoder.finish()?;
+
+        let written = Written {
+            name: name.to_string(),
+            crc: crc.sum(),
+            compressed: u32_of(compressed.len(), name)?,
+            size: u32_of(data.len(), name)?,
+            offset: u32_of(self.position, "L'archive")?,
+        };
+        let (time, date) = self.dos_time;
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend(LOCAL_HEADER.to_le_bytes());
+        header.extend(VERSION.to_le_bytes());
+        header.extend(FLAG_UTF8.to_le_bytes());
+        header.extend(METHOD_DEFLATE.to_le_bytes());
+        header.extend(time.to_le_bytes());
+        header.extend(date.to_le_bytes());
+        header.extend(written.crc.to_le_bytes());
+        header.extend(written.compressed.to_le_bytes());
+        header.extend(written.size.to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0_u16.to_le_bytes());
+        header.extend(name.as_bytes());
+        self.put(&header)?;
+        self.put(&compressed)?;
+        self.entries.push(written);
+        Ok(())
+    }
+
+    /// Écrit le répertoire central et rend la destination.
+    pub fn finish(mut self) -> Result<W> {
+        let central_offset = u32_of(self.position, "L'archive")?;
+        let (time, date) = self.dos_time;
+        let mut central = Vec::new();
+        for entry in &self.entries {
+            central.extend(CENTRAL_HEADER.to_le_bytes());
+            central.extend(VERSION.to_le_bytes());
+            central.extend(VERSION.to_le_bytes());
+            central.extend(FLAG_UTF8.to_le_bytes());
+            central.extend(METHOD_DEFLATE.to_le_bytes());
+            central.extend(time.to_le_bytes());
+            central.extend(date.to_le_bytes());
+            central.extend(entry.crc.to_le_bytes());
+            central.extend(entry.compressed.to_le_bytes());
+            central.extend(entry.size.to_le_bytes());
+            central.extend((entry.name.len() as u16).to_le_bytes());
+            // Extra, commentaire, disque, attributs internes et externes
+            central.extend([0_u8; 12]);
+            central.extend(entry.offset.to_le_bytes());
+            central.extend(entry.name.as_bytes());
+        }
+        let central_size = u32_of(central.len(), "Le répertoire de l'archive")?;
+        let count = u16::try_from(self.entries.len())
+            .map_err(|_| anyhow::anyhow!("Trop de fichiers pour une archive"))?;
+
+        central.extend(END_OF_CENTRAL.to_le_bytes());
+        central.extend([0_u8; 4]);
+        central.extend(count.to_le_bytes());
+        central.extend(count.to_le_bytes());
+        central.extend(central_size.to_le_bytes());
+        central.extend(central_offset.to_le_bytes());
+        central.extend(0_u16.to_le_bytes());
+        self.put(&central)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// `bytes[at..at + len]`, sans débordement sur des tailles forgées.
+fn slice_at(bytes: &[u8], at: usize, len: usize) -> Option<&[u8]> {
+    bytes.get(at..at.checked_add(len)?)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16> {
+    let slice = slice_at(bytes, at, 2).context("Archive tronquée")?;
+    Ok(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32> {
+    let slice = slice_at(bytes, at, 4).context("Archive tronquée")?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// Lit toutes les entrées d'une archive et vérifie leur somme de contrôle.
+pub fn read_all(bytes: &[u8]) -> Result<Vec<Entry>> {
+    // Le répertoire central est annoncé à la fin, suivi d'un éventuel commentaire
+    let end = (0..=bytes.len().saturating_sub(22))
+        .rev()
+        .find(|&at| read_u32(bytes, at).is_ok_and(|sig| sig == END_OF_CENTRAL))
+        .context("Ce fichier n'est pas une archive ZIP")?;
+    let count = read_u16(bytes, end + 10)? as usize;
+    let mut at = read_u32(bytes, end + 16)? as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        if read_u32(bytes, at)? != CENTRAL_HEADER {
+            anyhow::bail!("Répertoire de l'archive corrompu");
+        }
+        let method = read_u16(bytes, at + 10)?;
+        let crc = read_u32(bytes, at + 16)?;
+        let compressed = read_u32(bytes, at + 20)? as usize;
+        let size = read_u32(bytes, at + 24)? as usize;
+        let name_len = read_u16(bytes, at + 28)? as usize;
+        let extra_len = read_u16(bytes, at + 30)? as usize;
+        let comment_len = read_u16(bytes, at + 32)? as usize;
+        let offset = read_u32(bytes, at + 42)? as usize;
+        let name = slice_at(bytes, at + 46, name_len).context("Archive tronquée")?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        at += 46 + name_len + extra_len + comment_len;
+
+        if read_u32(bytes, offset)? != LOCAL_HEADER {
+            anyhow::bail!("Entrée corrompue: {}", name);
+        }
+        let start = offset
+            + 30
+            + read_u16(bytes, offset + 26)? as usize
+            + read_u16(bytes, offset + 28)? as usize;
+        let raw = slice_at(bytes, start, compressed)
+            .with_context(|| format!("Entrée tronquée: {}", name))?;
+
+        let data = match method {
+            METHOD_STORED => raw.to_vec(),
+            METHOD_DEFLATE => {
+                let mut data = Vec::with_capacity(size);
+                DeflateDecoder::new(raw).read_to_end(&mut data)?;
+                data
+            }
+            other => anyhow::bail!("Compression non prise en charge ({}): {}", other, name),
+        };
+        let mut check = flate2::Crc::new();
+        check.update(&data);
+        if check.sum() != crc || data.len() != size {
+            anyhow::bail!("Entrée endommagée: {}", name);
+        }
+        entries.push(Entry { name, data });
+    }
+    Ok(entries)
+}
+
+/// Date et heure au format DOS (heure UTC, précision de deux secondes).
+fn dos_date_time(unix_secs: u64) -> (u16, u16) {
+    let t = DateTime::from_unix(unix_secs);
+    let time = (t.hour << 11) | (t.minute << 5) | (t.second / 2);
+    let date = (((t.year - 1980).clamp(0, 127) as u32) << 9) | (t.month << 5) | t.day;
+    (time as u16, date as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Vec::new(), 1_700_000_000);
+        for (name, data) in entries {
+            writer.add(name, data).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let repeated = "séance ".repeat(2000);
+        let entries: [(&str, &[u8]); 5] = [
+            ("settings.json", b"{}"),
+            ("profiles/", b""),
+            ("profiles/Élodie/sessions/1700000000.json", repeated.as_bytes()),
+            ("profiles/Élodie/notes d'été ✨.txt", "Voix plus légère".as_bytes()),
+            ("empty.txt", b""),
+        ];
+        let read = read_all(&archive(&entries)).unwrap();
+        assert_eq!(read.len(), entries.len());
+        for (entry, (name, data)) in read.iter().zip(entries) {
+            assert_eq!(entry.name, name);
+            assert_eq!(entry.data, data);
+        }
+    }
+
+    #[test]
+    fn damaged_entries_are_refused() {
+        let mut bytes = archive(&[("a.txt", "texte à compresser".repeat(50).as_bytes())]);
+        bytes[40] ^= 0xff;
+        assert!(read_all(&bytes).is_err());
+        assert!(read_all(b"pas une archive").is_err());
+    }
+
+    #[test]
+    fn forged_sizes_do_not_overflow() {
+        let mut bytes = archive(&[("a.txt", b"abc")]);
+        // Taille compressée de l'entrée dans le répertoire central
+        let end = bytes.len() - 22;
+        let central = read_u32(&bytes, end + 16).unwrap() as usize;
+        bytes[central + 20..central + 24].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_all(&bytes).is_err());
+
+        bytes[end + 16..end + 20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_all(&bytes).is_err());
+    }
+}